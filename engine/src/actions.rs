@@ -0,0 +1,82 @@
+use crate::state::Position;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameAction {
+    /// Move card(s) from one position to another
+    MoveCard { from: Position, to: Position },
+    /// Deal cards from stock to waste pile
+    DealFromStock,
+    /// Start a new game
+    NewGame,
+    /// Start the current deal again from the beginning, using its seed
+    ReplaySeed,
+    /// Undo the last move
+    Undo,
+    /// Re-apply the last undone move
+    Redo,
+    /// Move every card that is safe to collect up to the foundations, as
+    /// one step
+    CollectAll,
+}
+
+impl GameAction {
+    /// Short name of the kind of action, as used in transcripts
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameAction::MoveCard { .. } => "move",
+            GameAction::DealFromStock => "deal",
+            GameAction::NewGame => "new",
+            GameAction::ReplaySeed => "replay",
+            GameAction::Undo => "undo",
+            GameAction::Redo => "redo",
+            GameAction::CollectAll => "collect",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrawCount {
+    One, // Deal 1 card at a time from stock (easier)
+    #[default]
+    Three, // Deal 3 cards at a time from stock (harder)
+}
+
+/// How a game is scored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scoring {
+    /// Points for playing cards and turning them over, less penalties for
+    /// going round the stock, as Windows scores it
+    #[default]
+    Standard,
+    /// A dollar staked on every card dealt, and five won back for each one
+    /// reaching the foundations
+    Vegas,
+    /// No score kept
+    None,
+}
+
+impl Scoring {
+    pub fn all() -> [Scoring; 3] {
+        [Scoring::Standard, Scoring::Vegas, Scoring::None]
+    }
+
+    /// Name used in the settings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scoring::Standard => "standard",
+            Scoring::Vegas => "vegas",
+            Scoring::None => "none",
+        }
+    }
+
+    /// Name shown in settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Scoring::Standard => "Standard",
+            Scoring::Vegas => "Vegas",
+            Scoring::None => "None",
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::actions::{DrawCount, Scoring};
+use crate::daily::Date;
+use crate::deck::Card;
+use crate::history::{History, HistoryLimits};
+use crate::rules::GameRules;
+use crate::settings::Accommodations;
+use crate::state::GameState;
+use crate::storage::{self, Checked};
+use crate::transcript::{action_notation, parse_action};
+use crate::variant::Variant;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A game in progress kept on disk after every move, so it can be resumed
+/// at the next launch with its piles, clock, score and undo history.
+///
+/// The undo history's base position is stored pile by pile and its action
+/// log replayed on loading, which rebuilds every later position and checks
+/// the file against the rules at the same time.
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    pub game_state: GameState,
+    pub history: History,
+    /// Date of the daily challenge, if the game is one
+    pub daily: Option<Date>,
+}
+
+impl SavedGame {
+    /// Default location, alongside `stats.toml`
+    pub fn default_path() -> PathBuf {
+        storage::data_dir().join("autosave.toml")
+    }
+
+    /// Load at startup; no file means no game to resume. A damaged file is
+    /// quarantined with a notice for the player.
+    pub fn load_checked(path: &Path, limits: HistoryLimits) -> Checked<Option<Self>> {
+        storage::load_checked(path, |text| Self::parse(text, limits).map(Some))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        storage::save(path, &self.to_file())
+    }
+
+    pub fn parse(text: &str, limits: HistoryLimits) -> Result<Self, String> {
+        Self::from_file(storage::parse(text)?, limits)
+    }
+
+    /// The game in the autosave format
+    pub fn to_text(&self) -> Result<String, String> {
+        storage::to_text(&self.to_file())
+    }
+
+    /// Remove the saved game, once it is finished or abandoned
+    pub fn clear(path: &Path) -> Result<(), String> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn to_file(&self) -> SaveFile {
+        let base = self.history.base();
+        SaveFile {
+            game: GameSection {
+                elapsed_ms: self.game_state.elapsed().as_millis() as u64,
+                practice: self.game_state.practice,
+                accommodations: self.game_state.accommodations.to_flags(),
+                daily: self.daily,
+            },
+            base: BaseSection {
+                variant: base.rules,
+                seed: base.seed,
+                imported: base.imported,
+                draw_count: base.draw_count,
+                score: base.score,
+                scoring: base.scoring,
+                moves: base.move_count,
+                recycles: base.recycles,
+                tableau: base.tableau.iter().map(|pile| pile_text(pile)).collect(),
+                foundations: base
+                    .foundations
+                    .iter()
+                    .map(|pile| pile_text(pile))
+                    .collect(),
+                stock: pile_text(&base.stock),
+                waste: pile_text(&base.waste),
+            },
+            history: HistorySection {
+                cursor: self.history.cursor(),
+                actions: self
+                    .history
+                    .entries()
+                    .iter()
+                    .map(|command| action_notation(&command.action))
+                    .collect(),
+            },
+        }
+    }
+
+    pub(crate) fn from_file(file: SaveFile, limits: HistoryLimits) -> Result<Self, String> {
+        let elapsed = Duration::from_millis(file.game.elapsed_ms);
+
+        // Every position in the history shares the restored clock
+        let mut base = file.base.into_game_state()?;
+        base.start_time = SystemTime::now()
+            .checked_sub(elapsed)
+            .unwrap_or_else(SystemTime::now);
+
+        let actions = file
+            .history
+            .actions
+            .iter()
+            .map(|text| parse_action(text))
+            .collect::<Result<Vec<_>, _>>()?;
+        let history = History::restore(base, actions, file.history.cursor, limits)?;
+
+        let mut game_state = history.current();
+        if game_state.game_won {
+            return Err("the saved game is already won".to_string());
+        }
+        game_state.practice = file.game.practice;
+        game_state.accommodations = Accommodations::from_flags(file.game.accommodations);
+
+        Ok(SavedGame {
+            game_state,
+            history,
+            daily: file.game.daily,
+        })
+    }
+}
+
+/// Layout of `autosave.toml`: the clock and flags, the undo history's base
+/// position pile by pile, and the actions played on it
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SaveFile {
+    game: GameSection,
+    base: BaseSection,
+    history: HistorySection,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GameSection {
+    #[serde(default)]
+    elapsed_ms: u64,
+    #[serde(default)]
+    practice: bool,
+    #[serde(default)]
+    accommodations: u8,
+    daily: Option<Date>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaseSection {
+    variant: Variant,
+    #[serde(with = "storage::seed")]
+    seed: u64,
+    #[serde(default)]
+    imported: bool,
+    #[serde(default)]
+    draw_count: DrawCount,
+    #[serde(default)]
+    score: i32,
+    #[serde(default)]
+    scoring: Scoring,
+    #[serde(default)]
+    moves: u32,
+    #[serde(default)]
+    recycles: u32,
+    tableau: Vec<String>,
+    foundations: Vec<String>,
+    #[serde(default)]
+    stock: String,
+    #[serde(default)]
+    waste: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HistorySection {
+    #[serde(default)]
+    cursor: usize,
+    actions: Vec<String>,
+}
+
+impl BaseSection {
+    /// Rebuild the base position, checking it holds exactly the variant's
+    /// cards
+    fn into_game_state(self) -> Result<GameState, String> {
+        let variant = self.variant;
+        let mut game_state = GameState::with_rules(variant, self.seed);
+        game_state.imported = self.imported;
+        game_state.draw_count = self.draw_count;
+        game_state.score = self.score;
+        game_state.scoring = self.scoring;
+        game_state.move_count = self.moves;
+        game_state.recycles = self.recycles;
+
+        let tableau = parse_piles(&self.tableau)?;
+        let foundations = parse_piles(&self.foundations)?;
+        if tableau.len() != game_state.tableau.len()
+            || foundations.len() != game_state.foundations.len()
+        {
+            return Err(format!("wrong number of piles for {}", variant.name()));
+        }
+        game_state.tableau = tableau;
+        game_state.foundations = foundations;
+        game_state.stock = parse_pile(&self.stock)?;
+        game_state.waste = parse_pile(&self.waste)?;
+
+        let mut cards: Vec<u8> = game_state
+            .tableau
+            .iter()
+            .chain(&game_state.foundations)
+            .chain([&game_state.stock, &game_state.waste])
+            .flatten()
+            .map(Card::index)
+            .collect();
+        let mut deck: Vec<u8> = variant.deck().iter().map(Card::index).collect();
+        cards.sort_unstable();
+        deck.sort_unstable();
+        if cards != deck {
+            return Err(format!("the piles do not hold a {} deck", variant.name()));
+        }
+        Ok(game_state)
+    }
+}
+
+/// Cards bottom to top, face-down ones in lower case, e.g. `qs 4h 7D`
+fn pile_text(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| {
+            if card.face_up {
+                card.code()
+            } else {
+                card.code().to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_pile(text: &str) -> Result<Vec<Card>, String> {
+    text.split_whitespace()
+        .map(|code| {
+            let mut card = Card::parse(code)?;
+            card.face_up = code.ends_with(|c: char| c.is_ascii_uppercase());
+            Ok(card)
+        })
+        .collect()
+}
+
+fn parse_piles(texts: &[String]) -> Result<Vec<Vec<Card>>, String> {
+    texts.iter().map(|text| parse_pile(text)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::command::GameCommand;
+    use crate::spider::{Spider, SpiderSuits};
+
+    /// A game with a few deals played and the last one undone
+    fn game_in_progress(variant: Variant) -> SavedGame {
+        let mut game_state = GameState::with_rules(variant, 2065);
+        game_state.draw_count = DrawCount::One;
+        game_state.set_scoring(Scoring::Vegas);
+        let mut history = History::new(game_state.clone(), HistoryLimits::default());
+        for _ in 0..3 {
+            let command = GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap();
+            history.record(command, &game_state);
+        }
+        let mut game_state = history.undo().unwrap();
+        game_state.practice = true;
+        SavedGame {
+            game_state,
+            history,
+            daily: Some(Date::new(2026, 10, 15).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_saved_game_round_trip() {
+        for variant in [
+            Variant::default(),
+            Variant::new(Spider::new(SpiderSuits::Two)),
+        ] {
+            let saved = game_in_progress(variant);
+            let text = saved.to_text().unwrap();
+            let loaded = SavedGame::parse(&text, HistoryLimits::default()).unwrap();
+
+            assert_eq!(loaded.game_state.rules, variant);
+            assert_eq!(loaded.game_state.tableau, saved.game_state.tableau);
+            assert_eq!(loaded.game_state.stock, saved.game_state.stock);
+            assert_eq!(loaded.game_state.waste, saved.game_state.waste);
+            assert_eq!(loaded.game_state.move_count, saved.game_state.move_count);
+            assert_eq!(loaded.game_state.score, saved.game_state.score);
+            assert_eq!(loaded.game_state.scoring, Scoring::Vegas);
+            assert!(loaded.game_state.practice);
+            assert_eq!(loaded.daily, saved.daily);
+            assert_eq!(loaded.history.cursor(), 2);
+            assert!(loaded.history.can_redo());
+        }
+    }
+
+    #[test]
+    fn test_saved_game_keeps_the_clock() {
+        let mut saved = game_in_progress(Variant::default());
+        saved.game_state.start_time = SystemTime::now() - Duration::from_secs(95);
+        let loaded = SavedGame::from_file(saved.to_file(), HistoryLimits::default()).unwrap();
+        let elapsed = loaded.game_state.elapsed().as_secs();
+        assert!((95..100).contains(&elapsed), "elapsed {}", elapsed);
+    }
+
+    #[test]
+    fn test_tampered_saves_are_rejected() {
+        let saved = game_in_progress(Variant::default());
+
+        // An extra card makes it no longer a deck
+        let mut file = saved.to_file();
+        file.base.stock.push_str(" ks");
+        let error = SavedGame::from_file(file, HistoryLimits::default()).unwrap_err();
+        assert!(error.contains("deck"), "{}", error);
+
+        let mut file = saved.to_file();
+        file.history.actions = vec!["move F0 T0.1".to_string()];
+        assert!(SavedGame::from_file(file, HistoryLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_save_load_and_clear() {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "vibe-solitaire-test-{}-autosave",
+                std::process::id()
+            ))
+            .join("autosave.toml");
+        let _ = fs::remove_file(&path);
+        assert!(
+            SavedGame::load_checked(&path, HistoryLimits::default())
+                .value
+                .is_none()
+        );
+
+        game_in_progress(Variant::default()).save(&path).unwrap();
+        let checked = SavedGame::load_checked(&path, HistoryLimits::default());
+        assert!(checked.notice.is_none());
+        assert!(checked.value.is_some());
+
+        SavedGame::clear(&path).unwrap();
+        assert!(!path.exists());
+        SavedGame::clear(&path).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+/// Source of elapsed time for anything that animates or is timed.
+///
+/// Interactive play uses the wall clock; replay playback and exports use a
+/// `FrameClock` so the same input always produces the same frames, whatever
+/// the machine's speed.
+pub trait Clock {
+    /// Time elapsed since the clock started
+    fn now(&self) -> Duration;
+}
+
+/// Wall-clock time, for interactive play
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Simulation time that only moves when advanced one frame at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameClock {
+    fps: u32,
+    frame: u64,
+}
+
+impl FrameClock {
+    pub fn new(fps: u32) -> Self {
+        FrameClock {
+            fps: fps.max(1),
+            frame: 0,
+        }
+    }
+
+    /// A clock positioned at `frame`
+    pub fn at_frame(fps: u32, frame: u64) -> Self {
+        FrameClock {
+            frame,
+            ..Self::new(fps)
+        }
+    }
+
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Index of the current frame
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn advance(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Number of whole frames covering `duration`, rounded up
+    pub fn frames_for(&self, duration: Duration) -> u64 {
+        let nanos = duration.as_nanos() * self.fps as u128;
+        nanos.div_ceil(1_000_000_000) as u64
+    }
+}
+
+impl Clock for FrameClock {
+    /// Exact start time of the current frame, computed in integer nanoseconds
+    /// so it never drifts with floating-point rounding
+    fn now(&self) -> Duration {
+        let nanos = self.frame as u128 * 1_000_000_000 / self.fps as u128;
+        Duration::from_nanos(nanos as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_clock_is_exact() {
+        let mut clock = FrameClock::new(30);
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        for _ in 0..30 {
+            clock.advance();
+        }
+        assert_eq!(clock.frame(), 30);
+        assert_eq!(clock.now(), Duration::from_secs(1));
+
+        clock.advance();
+        assert_eq!(clock.now(), Duration::from_nanos(1_033_333_333));
+    }
+
+    #[test]
+    fn test_frames_for_duration() {
+        let clock = FrameClock::new(60);
+        assert_eq!(clock.frames_for(Duration::ZERO), 0);
+        assert_eq!(clock.frames_for(Duration::from_millis(250)), 15);
+        // A partial frame still needs a whole frame to show
+        assert_eq!(clock.frames_for(Duration::from_millis(251)), 16);
+    }
+}
@@ -0,0 +1,358 @@
+use crate::actions::GameAction;
+use crate::deck::Card;
+use crate::events::GameEvent;
+use crate::state::{GameState, Pile};
+use std::time::SystemTime;
+
+/// A move played on the board as a command object. It holds the piles and
+/// counters the move changed, as they were before and after, so it can be
+/// taken back and played again exactly, side effects such as cards turned
+/// up, runs completed or the clock stopping on a win included.
+///
+/// Commands cover moves on the board: dealing, moving cards and collecting.
+/// Starting a new game replaces the whole state and is never taken back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameCommand {
+    pub action: GameAction,
+    before: Effects,
+    after: Effects,
+}
+
+/// The piles a move changed and the counters it may have changed
+#[derive(Debug, Clone, PartialEq)]
+struct Effects {
+    piles: Vec<(Pile, Vec<Card>)>,
+    move_count: u32,
+    score: i32,
+    recycles: u32,
+    game_won: bool,
+    end_time: Option<SystemTime>,
+}
+
+impl GameCommand {
+    /// Play `action` on `game_state`, capturing what it changes
+    pub fn apply(game_state: &mut GameState, action: GameAction) -> Result<Self, String> {
+        match action {
+            GameAction::NewGame | GameAction::ReplaySeed => {
+                return Err("A new deal starts over and cannot be taken back".to_string());
+            }
+            GameAction::Undo | GameAction::Redo => {
+                return Err("Undo and redo are handled by the game history".to_string());
+            }
+            _ => {}
+        }
+        let before = game_state.clone();
+        game_state.handle_action(action.clone())?;
+        let changed: Vec<Pile> = game_state
+            .diff(&before)
+            .into_iter()
+            .map(|change| change.pile)
+            .collect();
+        Ok(GameCommand {
+            action,
+            before: Effects::of(&before, &changed),
+            after: Effects::of(game_state, &changed),
+        })
+    }
+
+    /// Take the move back, putting the piles and counters it changed as
+    /// they were
+    pub fn invert(&self, game_state: &mut GameState) {
+        self.before.restore(game_state);
+    }
+
+    /// Play the move again after it was taken back
+    pub fn reapply(&self, game_state: &mut GameState) {
+        self.after.restore(game_state);
+    }
+
+    /// Piles the move changed
+    pub fn piles(&self) -> impl Iterator<Item = Pile> + '_ {
+        self.after.piles.iter().map(|(pile, _)| *pile)
+    }
+
+    /// What the move did, in order: cards dealt or moved, cards turned up
+    /// where they lie, then the game being won
+    pub fn events(&self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let stock_before = self.before.cards(Pile::Stock).len();
+        let stock_after = self.after.cards(Pile::Stock).len();
+        if stock_after > stock_before {
+            events.push(GameEvent::WasteTurnedOver);
+        } else if stock_after < stock_before {
+            events.push(GameEvent::StockDealt {
+                count: stock_before - stock_after,
+            });
+        } else {
+            events.extend(self.moves());
+        }
+        for ((pile, before), (_, after)) in self.before.piles.iter().zip(&self.after.piles) {
+            // Cards still in place that now face up were turned over there
+            events.extend(
+                before
+                    .iter()
+                    .zip(after)
+                    .take_while(|(was, is)| same_card(was, is))
+                    .filter(|(was, is)| !was.face_up && is.face_up)
+                    .map(|(_, card)| GameEvent::CardFlipped {
+                        card: *card,
+                        pile: *pile,
+                    }),
+            );
+        }
+        if self.after.game_won && !self.before.game_won {
+            events.push(GameEvent::GameWon);
+        }
+        events
+    }
+
+    /// Cards that landed on a pile, each matched to the pile it left
+    fn moves(&self) -> Vec<GameEvent> {
+        let mut left: Vec<(Pile, Vec<Card>)> = self
+            .before
+            .piles
+            .iter()
+            .zip(&self.after.piles)
+            .map(|((pile, before), (_, after))| (*pile, before[kept(before, after)..].to_vec()))
+            .collect();
+        let mut moves = Vec::new();
+        for ((pile, before), (_, after)) in self.before.piles.iter().zip(&self.after.piles) {
+            for card in &after[kept(before, after)..] {
+                let from = left.iter_mut().find_map(|(from, cards)| {
+                    let index = cards.iter().position(|left| same_card(left, card))?;
+                    cards.remove(index);
+                    Some(*from)
+                });
+                if let Some(from) = from {
+                    moves.push(GameEvent::CardMoved {
+                        card: *card,
+                        from,
+                        to: *pile,
+                    });
+                }
+            }
+        }
+        moves
+    }
+}
+
+impl Effects {
+    /// `game_state`'s counters with the cards of `piles`
+    fn of(game_state: &GameState, piles: &[Pile]) -> Self {
+        Effects {
+            piles: piles
+                .iter()
+                .map(|pile| (*pile, game_state.cards(*pile).to_vec()))
+                .collect(),
+            move_count: game_state.move_count,
+            score: game_state.score,
+            recycles: game_state.recycles,
+            game_won: game_state.game_won,
+            end_time: game_state.end_time,
+        }
+    }
+
+    /// Cards of `pile` as captured, or none if the move left it alone
+    fn cards(&self, pile: Pile) -> &[Card] {
+        self.piles
+            .iter()
+            .find(|(changed, _)| *changed == pile)
+            .map_or(&[], |(_, cards)| cards.as_slice())
+    }
+
+    fn restore(&self, game_state: &mut GameState) {
+        for (pile, pile_cards) in &self.piles {
+            *cards_mut(game_state, *pile) = pile_cards.clone();
+        }
+        game_state.move_count = self.move_count;
+        game_state.score = self.score;
+        game_state.recycles = self.recycles;
+        game_state.game_won = self.game_won;
+        game_state.end_time = self.end_time;
+    }
+}
+
+/// How many cards at the bottom of a pile a move left where they were
+fn kept(before: &[Card], after: &[Card]) -> usize {
+    before
+        .iter()
+        .zip(after)
+        .take_while(|(was, is)| same_card(was, is))
+        .count()
+}
+
+/// Whether two cards are the same suit and rank, whichever way up
+fn same_card(a: &Card, b: &Card) -> bool {
+    a.suit == b.suit && a.rank == b.rank
+}
+
+fn cards_mut(game_state: &mut GameState, pile: Pile) -> &mut Vec<Card> {
+    match pile {
+        Pile::Tableau(col) => &mut game_state.tableau[col],
+        Pile::Foundation(foundation) => &mut game_state.foundations[foundation],
+        Pile::Stock => &mut game_state.stock,
+        Pile::Waste => &mut game_state.waste,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawCount;
+    use crate::deck::{Rank, Suit};
+    use crate::state::Position;
+
+    #[test]
+    fn test_invert_takes_back_the_flip() {
+        let mut game_state = GameState::new_with_seed(7);
+        // Clear a column's covering cards so the move turns one up
+        game_state.tableau[1] = vec![
+            Card::new(Suit::Spades, Rank::Nine, false),
+            Card::new(Suit::Hearts, Rank::Eight, true),
+        ];
+        game_state.tableau[2] = vec![Card::new(Suit::Clubs, Rank::Nine, true)];
+        let before = game_state.clone();
+
+        let action = GameAction::MoveCard {
+            from: Position::Tableau(1, 1),
+            to: Position::Tableau(2, 1),
+        };
+        let command = GameCommand::apply(&mut game_state, action).unwrap();
+        assert!(game_state.tableau[1][0].face_up);
+        assert_eq!(
+            command.piles().collect::<Vec<_>>(),
+            [Pile::Tableau(1), Pile::Tableau(2)]
+        );
+        let after = game_state.clone();
+
+        command.invert(&mut game_state);
+        assert_eq!(game_state.tableau, before.tableau);
+        assert!(!game_state.tableau[1][0].face_up);
+        assert_eq!(game_state.score, before.score);
+        assert_eq!(game_state.move_count, before.move_count);
+
+        command.reapply(&mut game_state);
+        assert_eq!(game_state.tableau, after.tableau);
+        assert_eq!(game_state.score, after.score);
+    }
+
+    #[test]
+    fn test_invert_takes_back_turning_the_waste_over() {
+        let mut game_state = GameState::new_with_seed(11);
+        game_state.draw_count = DrawCount::One;
+        let fresh = game_state.clone();
+        let mut commands = Vec::new();
+        while !game_state.stock.is_empty() {
+            commands.push(GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap());
+        }
+        let dealt = game_state.clone();
+        let recycle = GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap();
+        assert_eq!(game_state.recycles, 1);
+
+        recycle.invert(&mut game_state);
+        assert_eq!(game_state.stock, dealt.stock);
+        assert_eq!(game_state.waste, dealt.waste);
+        assert_eq!(game_state.recycles, 0);
+        assert_eq!(game_state.score, dealt.score);
+
+        for command in commands.iter().rev() {
+            command.invert(&mut game_state);
+        }
+        assert_eq!(game_state.stock, fresh.stock);
+        assert_eq!(game_state.move_count, 0);
+    }
+
+    #[test]
+    fn test_events_report_the_move_then_the_flip() {
+        let mut game_state = GameState::new_with_seed(7);
+        let nine = Card::new(Suit::Spades, Rank::Nine, false);
+        let eight = Card::new(Suit::Hearts, Rank::Eight, true);
+        game_state.tableau[1] = vec![nine, eight];
+        game_state.tableau[2] = vec![Card::new(Suit::Clubs, Rank::Nine, true)];
+
+        let action = GameAction::MoveCard {
+            from: Position::Tableau(1, 1),
+            to: Position::Tableau(2, 1),
+        };
+        let command = GameCommand::apply(&mut game_state, action).unwrap();
+        assert_eq!(
+            command.events(),
+            [
+                GameEvent::CardMoved {
+                    card: eight,
+                    from: Pile::Tableau(1),
+                    to: Pile::Tableau(2),
+                },
+                GameEvent::CardFlipped {
+                    card: Card {
+                        face_up: true,
+                        ..nine
+                    },
+                    pile: Pile::Tableau(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_report_dealing_and_turning_the_waste_over() {
+        let mut game_state = GameState::new_with_seed(11);
+        game_state.draw_count = DrawCount::Three;
+        let deal = GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap();
+        assert_eq!(deal.events(), [GameEvent::StockDealt { count: 3 }]);
+
+        while !game_state.stock.is_empty() {
+            game_state.handle_action(GameAction::DealFromStock).unwrap();
+        }
+        let recycle = GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap();
+        assert_eq!(recycle.events(), [GameEvent::WasteTurnedOver]);
+    }
+
+    #[test]
+    fn test_events_end_with_the_win() {
+        let mut game_state = GameState::new_with_seed(5);
+        game_state.stock.clear();
+        game_state.waste.clear();
+        game_state.tableau = vec![Vec::new(); 7];
+        for (i, suit) in Suit::all().into_iter().enumerate() {
+            game_state.foundations[i] = Rank::all()
+                .iter()
+                .map(|rank| Card::new(suit, *rank, true))
+                .collect();
+        }
+        let king = game_state.foundations[0].pop().unwrap();
+        game_state.tableau[0].push(king);
+
+        let action = GameAction::MoveCard {
+            from: Position::Tableau(0, 0),
+            to: Position::Foundation(0),
+        };
+        let command = GameCommand::apply(&mut game_state, action).unwrap();
+        assert_eq!(
+            command.events(),
+            [
+                GameEvent::CardMoved {
+                    card: king,
+                    from: Pile::Tableau(0),
+                    to: Pile::Foundation(0),
+                },
+                GameEvent::GameWon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_refused_and_whole_game_actions_make_no_command() {
+        let mut game_state = GameState::new_with_seed(3);
+        let before = game_state.clone();
+        let illegal = GameAction::MoveCard {
+            from: Position::Foundation(0),
+            to: Position::Tableau(0, 1),
+        };
+        assert!(GameCommand::apply(&mut game_state, illegal).is_err());
+        assert!(GameCommand::apply(&mut game_state, GameAction::NewGame).is_err());
+        assert!(GameCommand::apply(&mut game_state, GameAction::Undo).is_err());
+        assert_eq!(game_state.tableau, before.tableau);
+        assert_eq!(game_state.seed, before.seed);
+    }
+}
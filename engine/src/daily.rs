@@ -0,0 +1,204 @@
+use crate::deck::splitmix64;
+use crate::settings::Accommodations;
+use crate::state::GameState;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A calendar date (UTC), used to key the daily challenge. Stored in the
+/// `YYYY-MM-DD` form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, String> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(format!("invalid date {}-{}-{}", year, month, day));
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /// Today's date in UTC, so every player shares the same daily deal
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_days_since_epoch((seconds / 86_400) as i64)
+    }
+
+    /// Convert a day count since 1970-01-01 to a civil date
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days algorithm
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Day count since 1970-01-01
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Day of the week, 0 = Monday through 6 = Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.days_since_epoch() + 3).rem_euclid(7) as u32
+    }
+
+    /// First day of this date's month
+    pub fn first_of_month(&self) -> Date {
+        Date { day: 1, ..*self }
+    }
+
+    /// Parse the `YYYY-MM-DD` form used in storage
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = text.split('-').collect();
+        let [year, month, day] = parts.as_slice() else {
+            return Err(format!("invalid date `{}`", text));
+        };
+        let number = |part: &str| {
+            part.parse::<u32>()
+                .map_err(|_| format!("invalid date `{}`", text))
+        };
+        Self::new(number(year)? as i32, number(month)?, number(day)?)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        Date::parse(&text)
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> String {
+        date.to_string()
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Seed of the daily challenge deal for `date`.
+///
+/// A fixed mix of the date (SplitMix64) rather than anything random, so every
+/// player and every version of the game deals the same cards that day.
+pub fn daily_seed(date: Date) -> u64 {
+    splitmix64(&mut (date.days_since_epoch() as u64))
+}
+
+/// Outcome of the daily challenge on one date, kept apart from single games
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyResult {
+    pub date: Date,
+    pub won: bool,
+    pub score: i32,
+    pub moves: u32,
+    pub time: Duration,
+    pub accommodations: Accommodations,
+    /// Practice attempts are never recorded
+    pub practice: bool,
+}
+
+impl DailyResult {
+    pub fn from_game(date: Date, game_state: &GameState) -> Self {
+        DailyResult {
+            date,
+            won: game_state.game_won,
+            score: game_state.score,
+            moves: game_state.move_count,
+            // Stored with millisecond precision
+            time: Duration::from_millis(game_state.elapsed().as_millis() as u64),
+            accommodations: game_state.accommodations,
+            practice: game_state.practice,
+        }
+    }
+
+    /// Whether this result should replace `other` for the same date: a win
+    /// beats a loss, then the higher score wins
+    pub fn is_better_than(&self, other: &DailyResult) -> bool {
+        (self.won, self.score) > (other.won, other.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_conversions() {
+        assert_eq!(
+            Date::from_days_since_epoch(0),
+            Date::new(1970, 1, 1).unwrap()
+        );
+        let leap_day = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(leap_day.days_since_epoch(), 19_782);
+        assert_eq!(Date::from_days_since_epoch(19_782), leap_day);
+        assert_eq!(leap_day.weekday(), 3); // Thursday
+
+        for days in [-1, 59, 365, 10_957, 20_000, 30_000] {
+            assert_eq!(Date::from_days_since_epoch(days).days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn test_date_parse_and_display() {
+        let date = Date::parse("2025-03-07").unwrap();
+        assert_eq!(date, Date::new(2025, 3, 7).unwrap());
+        assert_eq!(date.to_string(), "2025-03-07");
+
+        assert!(Date::parse("2025-02-29").is_err());
+        assert!(Date::parse("2025-13-01").is_err());
+        assert!(Date::parse("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_daily_seed_is_fixed_per_date() {
+        let date = Date::new(2025, 6, 1).unwrap();
+        assert_eq!(
+            daily_seed(date),
+            daily_seed(Date::parse("2025-06-01").unwrap())
+        );
+        assert_ne!(daily_seed(date), daily_seed(Date::new(2025, 6, 2).unwrap()));
+        // Pinned so the daily deal never changes between releases
+        assert_eq!(
+            daily_seed(Date::new(1970, 1, 1).unwrap()),
+            0xE220_A839_7B1D_CDAF
+        );
+    }
+}
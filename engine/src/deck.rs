@@ -71,6 +71,56 @@ impl Card {
     pub fn id(&self) -> String {
         format!("{}{}", self.rank.display(), self.suit.symbol())
     }
+
+    /// Position of the card in a fresh deck (suit by suit, Ace to King), 0..52
+    pub fn index(&self) -> u8 {
+        let suit = Suit::all()
+            .iter()
+            .position(|suit| *suit == self.suit)
+            .unwrap_or_default() as u8;
+        suit * 13 + self.rank as u8 - 1
+    }
+
+    /// Face-down card at `index` in a fresh deck
+    pub fn from_index(index: u8) -> Option<Card> {
+        let suit = *Suit::all().get(index as usize / 13)?;
+        let rank = Rank::all()[index as usize % 13];
+        Some(Card::new(suit, rank, false))
+    }
+
+    /// Name of the card in words, e.g. "Queen of Spades", for text and speech
+    pub fn name(&self) -> String {
+        format!("{} of {}", self.rank.name(), self.suit.name())
+    }
+
+    /// Plain-text code for the card, e.g. `10H` or `QS`
+    pub fn code(&self) -> String {
+        format!("{}{}", self.rank.display(), self.suit.letter())
+    }
+
+    /// Parse a face-down card from its code. Accepts `T` for ten, suit
+    /// letters or symbols and any letter case, e.g. `10h`, `TH` or `10♥`.
+    pub fn parse(text: &str) -> Result<Card, String> {
+        let invalid = || format!("`{}` is not a card", text);
+        let suit_char = text.chars().last().ok_or_else(invalid)?;
+        let rank_text = &text[..text.len() - suit_char.len_utf8()];
+
+        let suit = match suit_char.to_ascii_uppercase() {
+            'H' | '♥' => Suit::Hearts,
+            'D' | '♦' => Suit::Diamonds,
+            'C' | '♣' => Suit::Clubs,
+            'S' | '♠' => Suit::Spades,
+            _ => return Err(invalid()),
+        };
+        let rank = match rank_text.to_ascii_uppercase().as_str() {
+            "T" => Rank::Ten,
+            rank_text => Rank::all()
+                .into_iter()
+                .find(|rank| rank.display() == rank_text)
+                .ok_or_else(invalid)?,
+        };
+        Ok(Card::new(suit, rank, false))
+    }
 }
 
 impl fmt::Display for Card {
@@ -106,6 +156,26 @@ impl Suit {
             Suit::Spades => "♠",
         }
     }
+
+    /// Name of the suit in words
+    pub fn name(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "Hearts",
+            Suit::Diamonds => "Diamonds",
+            Suit::Clubs => "Clubs",
+            Suit::Spades => "Spades",
+        }
+    }
+
+    /// Single-letter name used in plain-text card codes
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+            Suit::Spades => 'S',
+        }
+    }
 }
 
 impl fmt::Display for Suit {
@@ -169,6 +239,17 @@ impl Rank {
             Rank::King => "K",
         }
     }
+
+    /// Name of the rank in words, spelling out the court cards and Ace
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rank::Ace => "Ace",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            rank => rank.display(),
+        }
+    }
 }
 
 impl fmt::Display for Rank {
@@ -190,16 +271,94 @@ pub fn create_deck() -> Vec<Card> {
     deck
 }
 
+/// Shuffle `deck` with a Fisher-Yates pass driven entirely by `seed`.
+///
+/// The random stream is generated here rather than by a library RNG, so a
+/// seed (which doubles as the game number players share) deals the same cards
+/// on every platform and in every release.
+pub fn shuffle_with_seed(deck: &mut [Card], seed: u64) {
+    let mut state = seed;
+    for i in (1..deck.len()).rev() {
+        let j = next_below(&mut state, i as u64 + 1) as usize;
+        deck.swap(i, j);
+    }
+}
+
+/// Next value of a SplitMix64 stream
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Uniform value in `0..bound`, rejecting draws that would bias the modulo
+fn next_below(state: &mut u64, bound: u64) -> u64 {
+    let zone = u64::MAX - u64::MAX % bound;
+    loop {
+        let value = splitmix64(state);
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_shuffle_with_seed_is_a_stable_permutation() {
+        let mut first = create_deck();
+        shuffle_with_seed(&mut first, 1);
+        let mut again = create_deck();
+        shuffle_with_seed(&mut again, 1);
+        assert_eq!(first, again);
+
+        let mut other = create_deck();
+        shuffle_with_seed(&mut other, 2);
+        assert_ne!(first, other);
+
+        let mut ids: Vec<String> = first.iter().map(Card::id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 52);
+
+        // Pinned so shared game numbers keep dealing the same cards
+        let top: Vec<String> = first[..3].iter().map(Card::id).collect();
+        assert_eq!(top, vec!["7♦", "9♥", "4♦"]);
+    }
+
+    #[test]
+    fn test_card_index_matches_fresh_deck() {
+        for (index, card) in create_deck().into_iter().enumerate() {
+            assert_eq!(card.index() as usize, index);
+            assert_eq!(Card::from_index(index as u8), Some(card));
+        }
+        assert_eq!(Card::from_index(52), None);
+    }
+
+    #[test]
+    fn test_card_codes_round_trip() {
+        for card in create_deck() {
+            assert_eq!(Card::parse(&card.code()), Ok(card));
+        }
+        let ten = Card::new(Suit::Hearts, Rank::Ten, false);
+        assert_eq!(Card::parse("th"), Ok(ten));
+        assert_eq!(Card::parse("10♥"), Ok(ten));
+        assert!(Card::parse("").is_err());
+        assert!(Card::parse("1H").is_err());
+        assert!(Card::parse("KX").is_err());
+        assert!(Card::parse("H").is_err());
+    }
+
     #[test]
     fn test_card_creation() {
         let card = Card::new(Suit::Hearts, Rank::Ace, true);
         assert_eq!(card.suit, Suit::Hearts);
         assert_eq!(card.rank, Rank::Ace);
-        assert_eq!(card.face_up, true);
+        assert!(card.face_up);
     }
 
     #[test]
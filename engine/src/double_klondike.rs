@@ -0,0 +1,104 @@
+use crate::deck::{Card, create_deck};
+use crate::registry::{VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, Klondike, deal_columns};
+use crate::variant::Variant;
+
+/// Double Klondike: two decks shuffled together into nine columns of one to
+/// nine cards, with eight foundations, one Ace to King for each suit of each
+/// deck. Building and foundation play follow Klondike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DoubleKlondike;
+
+impl GameRules for DoubleKlondike {
+    fn name(&self) -> &'static str {
+        "Double Klondike"
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        let mut deck = create_deck();
+        deck.extend(create_deck());
+        deck
+    }
+
+    fn foundation_count(&self) -> usize {
+        8
+    }
+
+    /// Forty-five cards on the table, leaving fifty-nine in the stock
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        deal_columns(deck, 9, |col| col + 1)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        Klondike.is_movable_run(cards)
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        Klondike.accepts_on_tableau(card, top)
+    }
+
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool {
+        Klondike.accepts_on_foundation(card, top)
+    }
+}
+
+/// Offer Double Klondike to players
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    registry.register(VariantEntry::new(Variant::new(DoubleKlondike)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::deck::{Rank, Suit};
+    use crate::state::{GameState, Position};
+
+    #[test]
+    fn test_double_klondike_deal() {
+        let game_state = GameState::with_rules(DoubleKlondike, 2060);
+        assert_eq!(game_state.tableau.len(), 9);
+        for (col, column) in game_state.tableau.iter().enumerate() {
+            assert_eq!(column.len(), col + 1);
+            assert_eq!(column.iter().filter(|card| card.face_up).count(), 1);
+        }
+        assert_eq!(game_state.stock.len(), 59);
+        assert_eq!(game_state.foundations.len(), 8);
+
+        // Every card twice over
+        let mut cards: Vec<u8> = game_state
+            .tableau
+            .iter()
+            .flatten()
+            .chain(&game_state.stock)
+            .map(Card::index)
+            .collect();
+        cards.sort();
+        assert_eq!(cards.len(), 104);
+        assert!(cards.chunks(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    fn test_both_aces_of_a_suit_start_foundations() {
+        let mut game_state = GameState::with_rules(DoubleKlondike, 0);
+        game_state.tableau = vec![Vec::new(); 9];
+        game_state.tableau[0] = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+        game_state.tableau[8] = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Tableau(0, 0),
+                to: Position::Foundation(2),
+            })
+            .unwrap();
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Tableau(8, 0),
+                to: Position::Foundation(7),
+            })
+            .unwrap();
+        assert_eq!(game_state.foundations[2].len(), 1);
+        assert_eq!(game_state.foundations[7].len(), 1);
+        assert!(!game_state.game_won);
+    }
+}
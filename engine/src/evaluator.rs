@@ -0,0 +1,288 @@
+use crate::actions::GameAction;
+use crate::solver::{self, Solution, Solver};
+use crate::state::{GameState, Position};
+use rand::Rng;
+
+/// Node limit for the exact search tried before falling back to rollouts
+const HINT_SOLVER_NODES: usize = 20_000;
+/// Win chance a move may give up against the best move and still be best
+const BEST_MARGIN: f32 = 0.05;
+/// Win chance given up beyond which a move is a blunder
+const BLUNDER_MARGIN: f32 = 0.25;
+
+/// A candidate move with its estimated chance of leading to a win
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveScore {
+    pub action: GameAction,
+    /// Share of rollouts from the resulting position that were won, 0 to 1
+    pub win_rate: f32,
+}
+
+/// How a played move compares with the best move available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveGrade {
+    Best,
+    Ok,
+    Blunder,
+}
+
+impl MoveGrade {
+    /// Grade a move from its win chance and the best move's
+    pub fn from_win_rates(best: f32, played: f32) -> Self {
+        let lost = best - played;
+        if lost <= BEST_MARGIN {
+            MoveGrade::Best
+        } else if lost <= BLUNDER_MARGIN {
+            MoveGrade::Ok
+        } else {
+            MoveGrade::Blunder
+        }
+    }
+
+    /// Name shown by the trainer
+    pub fn label(&self) -> &'static str {
+        match self {
+            MoveGrade::Best => "Best",
+            MoveGrade::Ok => "Ok",
+            MoveGrade::Blunder => "Blunder",
+        }
+    }
+}
+
+/// Grade given to a played move, with the move the evaluator liked best
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradedMove {
+    pub grade: MoveGrade,
+    pub played: MoveScore,
+    pub best: MoveScore,
+}
+
+/// Monte Carlo evaluation for positions too deep to solve exactly: every
+/// candidate move is played out many times with a simple randomized policy
+/// and scored by how often those games were won. Candidates are the
+/// solver's Klondike moves, so only variants where `Variant::is_solvable`
+/// are evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Evaluator {
+    /// Playouts per candidate move
+    pub rollouts: usize,
+    /// Actions per playout before it counts as a loss
+    pub max_depth: usize,
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Evaluator {
+            rollouts: 64,
+            max_depth: 250,
+        }
+    }
+}
+
+impl Evaluator {
+    /// Score every legal move, best first. Ties keep the solver's move
+    /// ordering, so a foundation play wins a tie over a deal.
+    pub fn evaluate<R: Rng + ?Sized>(&self, game_state: &GameState, rng: &mut R) -> Vec<MoveScore> {
+        let mut scores: Vec<MoveScore> = solver::legal_moves(game_state)
+            .into_iter()
+            .filter_map(|action| {
+                let mut next = game_state.clone();
+                next.handle_action(action.clone()).ok()?;
+                let wins = (0..self.rollouts)
+                    .filter(|_| self.rollout(next.clone(), rng))
+                    .count();
+                Some(MoveScore {
+                    action,
+                    win_rate: wins as f32 / self.rollouts.max(1) as f32,
+                })
+            })
+            .collect();
+        scores.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate));
+        scores
+    }
+
+    /// Grade `action` played from `before` against every other legal move.
+    /// Returns `None` if the action is not legal there.
+    pub fn grade<R: Rng + ?Sized>(
+        &self,
+        before: &GameState,
+        action: &GameAction,
+        rng: &mut R,
+    ) -> Option<GradedMove> {
+        let mut after = before.clone();
+        after.handle_action(action.clone()).ok()?;
+
+        let scores = self.evaluate(before, rng);
+        // Moves the solver never suggests, like a King between empty columns,
+        // are scored on their own
+        let played = match scores.iter().find(|score| score.action == *action) {
+            Some(score) => score.clone(),
+            None => {
+                let wins = (0..self.rollouts)
+                    .filter(|_| self.rollout(after.clone(), rng))
+                    .count();
+                MoveScore {
+                    action: action.clone(),
+                    win_rate: wins as f32 / self.rollouts.max(1) as f32,
+                }
+            }
+        };
+        let best = scores
+            .into_iter()
+            .next()
+            .filter(|best| best.win_rate > played.win_rate)
+            .unwrap_or_else(|| played.clone());
+
+        Some(GradedMove {
+            grade: MoveGrade::from_win_rates(best.win_rate, played.win_rate),
+            played,
+            best,
+        })
+    }
+
+    /// Play on from `game_state`: always take a foundation play when there
+    /// is one, otherwise a random legal move. Returns whether the game was won.
+    fn rollout<R: Rng + ?Sized>(&self, mut game_state: GameState, rng: &mut R) -> bool {
+        for _ in 0..self.max_depth {
+            if game_state.game_won {
+                return true;
+            }
+            let moves = solver::legal_moves(&game_state);
+            let Some(action) = moves
+                .iter()
+                .find(|action| {
+                    matches!(
+                        action,
+                        GameAction::MoveCard {
+                            to: Position::Foundation(_),
+                            ..
+                        }
+                    )
+                })
+                .or_else(|| moves.get(rng.gen_range(0..moves.len().max(1))))
+            else {
+                return false;
+            };
+            if game_state.handle_action(action.clone()).is_err() {
+                return false;
+            }
+        }
+        game_state.game_won
+    }
+}
+
+/// Best move to suggest: the first move of a proven win when a short exact
+/// search finds one, otherwise the move with the best rollout score. Only
+/// the solver's Klondike moves are tried, so it is only asked about variants
+/// where `Variant::is_solvable`.
+pub fn hint<R: Rng + ?Sized>(game_state: &GameState, rng: &mut R) -> Option<MoveScore> {
+    if let Solution::Winnable(line) = Solver::new(HINT_SOLVER_NODES).solve(game_state) {
+        return line.into_iter().next().map(|action| MoveScore {
+            action,
+            win_rate: 1.0,
+        });
+    }
+    Evaluator::default()
+        .evaluate(game_state, rng)
+        .into_iter()
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawCount;
+    use crate::deck::{Card, Rank, Suit};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// Everything home except the Kings, which sit face up in the tableau
+    fn kings_left() -> GameState {
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.draw_count = DrawCount::One;
+        game_state.stock.clear();
+        game_state.tableau = vec![Vec::new(); 7];
+        for (i, suit) in Suit::all().into_iter().enumerate() {
+            game_state.foundations[i] = Rank::all()[..12]
+                .iter()
+                .map(|rank| Card::new(suit, *rank, true))
+                .collect();
+            game_state.tableau[i].push(Card::new(suit, Rank::King, true));
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_rollouts_score_finished_lines() {
+        let game_state = kings_left();
+        let scores = Evaluator::default().evaluate(&game_state, &mut StdRng::seed_from_u64(1));
+        assert_eq!(scores.len(), 4);
+        assert!(scores.iter().all(|score| score.win_rate == 1.0));
+    }
+
+    #[test]
+    fn test_evaluation_covers_every_legal_move() {
+        let game_state = GameState::new_with_seed(2043);
+        let evaluator = Evaluator {
+            rollouts: 4,
+            max_depth: 50,
+        };
+        let scores = evaluator.evaluate(&game_state, &mut StdRng::seed_from_u64(2));
+        assert_eq!(scores.len(), solver::legal_moves(&game_state).len());
+        assert!(
+            scores
+                .windows(2)
+                .all(|pair| pair[0].win_rate >= pair[1].win_rate)
+        );
+    }
+
+    #[test]
+    fn test_grade_thresholds() {
+        assert_eq!(MoveGrade::from_win_rates(0.5, 0.5), MoveGrade::Best);
+        assert_eq!(MoveGrade::from_win_rates(0.5, 0.46), MoveGrade::Best);
+        assert_eq!(MoveGrade::from_win_rates(0.5, 0.3), MoveGrade::Ok);
+        assert_eq!(MoveGrade::from_win_rates(0.5, 0.2), MoveGrade::Blunder);
+    }
+
+    #[test]
+    fn test_grading_played_moves() {
+        let game_state = kings_left();
+        let mut rng = StdRng::seed_from_u64(4);
+        let evaluator = Evaluator::default();
+
+        let home = GameAction::MoveCard {
+            from: Position::Tableau(0, 0),
+            to: Position::Foundation(0),
+        };
+        let graded = evaluator.grade(&game_state, &home, &mut rng).unwrap();
+        assert_eq!(graded.grade, MoveGrade::Best);
+        assert_eq!(graded.played.action, home);
+
+        let illegal = GameAction::MoveCard {
+            from: Position::Tableau(0, 0),
+            to: Position::Foundation(1),
+        };
+        assert_eq!(evaluator.grade(&game_state, &illegal, &mut rng), None);
+    }
+
+    #[test]
+    fn test_hints_lead_to_a_win() {
+        let mut game_state = kings_left();
+        // The Queen of Hearts waits in the stock, blocking the King of Hearts
+        let queen = game_state.foundations[0].pop().unwrap();
+        game_state.stock.push(Card {
+            face_up: false,
+            ..queen
+        });
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..10 {
+            let Some(hint) = hint(&game_state, &mut rng) else {
+                break;
+            };
+            assert_eq!(hint.win_rate, 1.0);
+            game_state.handle_action(hint.action).unwrap();
+        }
+        assert!(game_state.game_won);
+    }
+}
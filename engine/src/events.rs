@@ -0,0 +1,19 @@
+use crate::deck::Card;
+use crate::state::Pile;
+
+/// Something a move did on the board. Each played move reports its events
+/// once it is on the board, so sound, animation and statistics can listen
+/// for what they care about instead of inspecting the state themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A card went from one pile to another
+    CardMoved { card: Card, from: Pile, to: Pile },
+    /// A face-down card was turned up where it lies
+    CardFlipped { card: Card, pile: Pile },
+    /// `count` cards were dealt out of the stock
+    StockDealt { count: usize },
+    /// The waste was turned back over into the stock
+    WasteTurnedOver,
+    /// The last card went home
+    GameWon,
+}
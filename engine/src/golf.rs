@@ -0,0 +1,144 @@
+use crate::deck::Card;
+use crate::registry::{BoardLayout, VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, StockDeal, deal_columns};
+use crate::variant::Variant;
+
+/// Golf: seven columns of five cards, all face up. The top card of any
+/// column can be played onto the waste if it is one rank above or below the
+/// waste's top card, Aces and Kings not turning the corner. Nothing is built
+/// on the columns, the stock is dealt one card at a time and never turned
+/// over again, and clearing every column wins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Golf;
+
+impl GameRules for Golf {
+    fn name(&self) -> &'static str {
+        "Golf"
+    }
+
+    /// Cards leave the table onto the waste instead
+    fn foundation_count(&self) -> usize {
+        0
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        let (mut tableau, stock) = deal_columns(deck, 7, |_| 5);
+        for card in tableau.iter_mut().flatten() {
+            card.face_up = true;
+        }
+        (tableau, stock)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        cards.len() == 1 && cards[0].face_up
+    }
+
+    fn accepts_on_tableau(&self, _card: &Card, _top: Option<&Card>) -> bool {
+        false
+    }
+
+    fn accepts_on_foundation(&self, _card: &Card, _top: Option<&Card>) -> bool {
+        false
+    }
+
+    fn accepts_on_waste(&self, card: &Card, top: Option<&Card>) -> bool {
+        top.is_some_and(|top| (card.rank as u8).abs_diff(top.rank as u8) == 1)
+    }
+
+    fn stock_deal(&self) -> StockDeal {
+        StockDeal::OneToWaste
+    }
+
+    fn recycle_limit(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn is_won(&self, tableau: &[Vec<Card>], _foundations: &[Vec<Card>]) -> bool {
+        tableau.iter().all(Vec::is_empty)
+    }
+}
+
+/// Offer Golf to players, its stock and waste below the columns
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    registry.register(VariantEntry {
+        layout: BoardLayout::StockBelow,
+        ..VariantEntry::new(Variant::new(Golf))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::deck::{Rank, Suit};
+    use crate::state::{GameState, Position};
+
+    fn play_to_waste(col: usize, game_state: &GameState<Golf>) -> GameAction {
+        GameAction::MoveCard {
+            from: Position::Tableau(col, game_state.tableau[col].len() - 1),
+            to: Position::Waste(game_state.waste.len().saturating_sub(1)),
+        }
+    }
+
+    #[test]
+    fn test_golf_deal() {
+        let game_state = GameState::with_rules(Golf, 2056);
+        assert_eq!(game_state.tableau.len(), 7);
+        assert!(game_state.tableau.iter().all(|column| column.len() == 5));
+        assert!(game_state.tableau.iter().flatten().all(|card| card.face_up));
+        assert_eq!(game_state.stock.len(), 17);
+        assert!(game_state.foundations.is_empty());
+        assert!(!game_state.game_won);
+    }
+
+    #[test]
+    fn test_golf_plays_one_rank_either_way() {
+        let mut game_state = GameState::with_rules(Golf, 0);
+        game_state.stock = vec![Card::new(Suit::Clubs, Rank::Seven, false)];
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[0] = vec![Card::new(Suit::Hearts, Rank::Six, true)];
+        game_state.tableau[1] = vec![
+            Card::new(Suit::Spades, Rank::Nine, true),
+            Card::new(Suit::Spades, Rank::Seven, true),
+        ];
+
+        // Nothing to play onto before the first deal
+        assert!(
+            game_state
+                .handle_action(play_to_waste(0, &game_state))
+                .is_err()
+        );
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+
+        // A Seven cannot go on a Seven, but a Six can
+        assert!(
+            game_state
+                .handle_action(play_to_waste(1, &game_state))
+                .is_err()
+        );
+        game_state
+            .handle_action(play_to_waste(0, &game_state))
+            .unwrap();
+        game_state
+            .handle_action(play_to_waste(1, &game_state))
+            .unwrap();
+        assert_eq!(game_state.waste.len(), 3);
+
+        // The Nine is left, with no way back through the stock
+        assert!(game_state.handle_action(GameAction::DealFromStock).is_err());
+        assert!(game_state.is_stuck());
+        assert!(!game_state.game_won);
+    }
+
+    #[test]
+    fn test_golf_won_when_columns_clear() {
+        let mut game_state = GameState::with_rules(Golf, 0);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[3] = vec![Card::new(Suit::Hearts, Rank::King, true)];
+        game_state.waste = vec![Card::new(Suit::Clubs, Rank::Queen, true)];
+        game_state
+            .handle_action(play_to_waste(3, &game_state))
+            .unwrap();
+        assert!(game_state.game_won);
+    }
+}
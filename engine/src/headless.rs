@@ -0,0 +1,163 @@
+use crate::actions::{DrawCount, GameAction};
+use crate::command::GameCommand;
+use crate::deck::Card;
+use crate::events::GameEvent;
+use crate::history::{History, HistoryLimits};
+use crate::state::GameState;
+use crate::variant::Variant;
+
+/// A game played from code instead of the window, for bots, fuzzers,
+/// simulations and tests. Actions go in through `apply` and the board comes
+/// out through `observe`, which shows only what a player could see.
+///
+/// `Game` and `Observation` are the engine's stable interface: they keep
+/// working across minor versions, however the modules behind them change.
+#[derive(Debug, Clone)]
+pub struct Game {
+    state: GameState,
+    history: History,
+}
+
+/// The board as a player sees it, face-down cards hidden
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    /// Tableau columns, bottom first, with `None` for each face-down card
+    pub tableau: Vec<Vec<Option<Card>>>,
+    /// Foundations, bottom first
+    pub foundations: Vec<Vec<Card>>,
+    /// Number of cards left in the stock, which is dealt face down
+    pub stock: usize,
+    /// Waste pile, bottom first
+    pub waste: Vec<Card>,
+    pub move_count: u32,
+    pub score: i32,
+    pub won: bool,
+}
+
+impl Game {
+    /// Deal `variant` from `seed`, turning `draw_count` cards at a time from
+    /// the stock in variants that deal to a waste
+    pub fn new(variant: Variant, seed: u64, draw_count: DrawCount) -> Self {
+        let mut state = GameState::with_rules(variant, seed);
+        state.draw_count = draw_count;
+        Game {
+            history: History::new(state.clone(), HistoryLimits::default()),
+            state,
+        }
+    }
+
+    /// Play `action`, returning what it did on the board. Undo and redo step
+    /// through the moves played and report no events. A new deal is a new
+    /// `Game`, so `NewGame` and `ReplaySeed` are refused.
+    pub fn apply(&mut self, action: GameAction) -> Result<Vec<GameEvent>, String> {
+        let stepped = match action {
+            GameAction::Undo => self.history.undo().ok_or("Nothing to undo")?,
+            GameAction::Redo => self.history.redo().ok_or("Nothing to redo")?,
+            action => {
+                let command = GameCommand::apply(&mut self.state, action)?;
+                let events = command.events();
+                self.history.record(command, &self.state);
+                return Ok(events);
+            }
+        };
+        self.state = stepped;
+        Ok(Vec::new())
+    }
+
+    /// What a player sees of the board
+    pub fn observe(&self) -> Observation {
+        Observation {
+            tableau: self
+                .state
+                .tableau
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .map(|card| card.face_up.then_some(*card))
+                        .collect()
+                })
+                .collect(),
+            foundations: self.state.foundations.clone(),
+            stock: self.state.stock.len(),
+            waste: self.state.waste.clone(),
+            move_count: self.state.move_count,
+            score: self.state.score,
+            won: self.state.game_won,
+        }
+    }
+
+    /// Every move the rules allow now, dealing from the stock first; empty
+    /// once the game is won
+    pub fn legal_actions(&self) -> Vec<GameAction> {
+        self.state.available_moves()
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.state.game_won
+    }
+
+    /// The full state, face-down cards included, for tests and tools that
+    /// need more than a player sees. Unlike `observe`, its shape follows
+    /// the engine's internals and may change.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observation_hides_face_down_cards() {
+        let game = Game::new(Variant::default(), 42, DrawCount::One);
+        let observation = game.observe();
+        assert_eq!(observation.tableau.len(), 7);
+        for (col, column) in observation.tableau.iter().enumerate() {
+            assert_eq!(column.len(), col + 1);
+            assert!(column[..col].iter().all(Option::is_none));
+            assert!(column[col].is_some());
+        }
+        assert_eq!(observation.stock, 24);
+        assert!(observation.waste.is_empty());
+        assert!(!observation.won);
+    }
+
+    #[test]
+    fn test_apply_plays_undoes_and_redoes() {
+        let mut game = Game::new(Variant::default(), 42, DrawCount::One);
+        let dealt = game.observe();
+        assert!(game.legal_actions().contains(&GameAction::DealFromStock));
+
+        let events = game.apply(GameAction::DealFromStock).unwrap();
+        assert_eq!(events, [GameEvent::StockDealt { count: 1 }]);
+        let played = game.observe();
+        assert_eq!(played.waste.len(), 1);
+
+        assert_eq!(game.apply(GameAction::Undo), Ok(Vec::new()));
+        assert_eq!(game.observe(), dealt);
+        game.apply(GameAction::Redo).unwrap();
+        assert_eq!(game.observe(), played);
+        assert!(game.apply(GameAction::Redo).is_err());
+        assert!(game.apply(GameAction::NewGame).is_err());
+    }
+
+    #[test]
+    fn test_random_legal_play_stays_legal() {
+        let mut game = Game::new(Variant::default(), 7, DrawCount::Three);
+        for step in 0..200 {
+            let actions = game.legal_actions();
+            let Some(action) = actions.get(step % actions.len().max(1)) else {
+                break;
+            };
+            game.apply(action.clone()).unwrap();
+        }
+        let observation = game.observe();
+        let cards = observation.tableau.iter().map(Vec::len).sum::<usize>()
+            + observation.foundations.iter().map(Vec::len).sum::<usize>()
+            + observation.stock
+            + observation.waste.len();
+        assert_eq!(cards, 52);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::actions::{DrawCount, Scoring};
+use crate::state::GameState;
+use crate::storage::{self, Checked};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of entries kept in each table
+pub const MAX_ENTRIES: usize = 10;
+
+/// A single winning game on a high-score table
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScoreEntry {
+    pub score: i32,
+    pub time: Duration,
+    pub moves: u32,
+    /// Seconds since the Unix epoch when the game was won
+    pub achieved_at: u64,
+}
+
+/// Top-10 tables for one draw mode
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModeHighScores {
+    /// Highest scores first
+    pub best_scores: Vec<HighScoreEntry>,
+    /// Shortest times first
+    pub fastest_wins: Vec<HighScoreEntry>,
+}
+
+/// Where a newly recorded win landed (0-based ranks)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Placement {
+    pub score_rank: Option<usize>,
+    pub time_rank: Option<usize>,
+}
+
+impl Placement {
+    pub fn is_new_entry(&self) -> bool {
+        self.score_rank.is_some() || self.time_rank.is_some()
+    }
+}
+
+/// Local high-score tables, one set per draw mode, stored next to the stats file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HighScores {
+    pub draw_one: ModeHighScores,
+    pub draw_three: ModeHighScores,
+}
+
+impl HighScores {
+    /// Default location, alongside `stats.toml`
+    pub fn default_path() -> PathBuf {
+        storage::data_dir().join("highscores.toml")
+    }
+
+    pub fn mode(&self, draw_count: DrawCount) -> &ModeHighScores {
+        match draw_count {
+            DrawCount::One => &self.draw_one,
+            DrawCount::Three => &self.draw_three,
+        }
+    }
+
+    fn mode_mut(&mut self, draw_count: DrawCount) -> &mut ModeHighScores {
+        match draw_count {
+            DrawCount::One => &mut self.draw_one,
+            DrawCount::Three => &mut self.draw_three,
+        }
+    }
+
+    /// Enter a won game into the tables for its draw mode. Lost games, practice
+    /// games and games played with accommodations are never ranked, and only
+    /// scores kept the standard way are ranked by score.
+    pub fn record(&mut self, game_state: &GameState) -> Placement {
+        if !game_state.game_won || !game_state.is_ranked() {
+            return Placement::default();
+        }
+
+        let entry = HighScoreEntry {
+            score: game_state.score,
+            // Stored with millisecond precision
+            time: Duration::from_millis(game_state.elapsed().as_millis() as u64),
+            moves: game_state.move_count,
+            achieved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+
+        let mode = self.mode_mut(game_state.draw_count);
+        Placement {
+            score_rank: (game_state.scoring == Scoring::Standard)
+                .then(|| {
+                    insert_ranked(&mut mode.best_scores, entry.clone(), |a, b| {
+                        a.score > b.score
+                    })
+                })
+                .flatten(),
+            time_rank: insert_ranked(&mut mode.fastest_wins, entry, |a, b| a.time < b.time),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        storage::load::<HighScoresFile>(path)?.try_into()
+    }
+
+    /// Load at startup. A damaged file is quarantined and replaced by an empty
+    /// record, with a notice for the player.
+    pub fn load_checked(path: &Path) -> Checked<Self> {
+        storage::load_checked(path, Self::parse)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        storage::save(path, &HighScoresFile::from(self))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        storage::parse::<HighScoresFile>(text)?.try_into()
+    }
+}
+
+/// Insert `entry` keeping `entries` sorted by `better` and capped at
+/// `MAX_ENTRIES`. Ties go to the earlier entry. Returns the new rank, if any.
+fn insert_ranked(
+    entries: &mut Vec<HighScoreEntry>,
+    entry: HighScoreEntry,
+    better: impl Fn(&HighScoreEntry, &HighScoreEntry) -> bool,
+) -> Option<usize> {
+    let rank = entries
+        .iter()
+        .position(|existing| better(&entry, existing))
+        .unwrap_or(entries.len());
+    if rank >= MAX_ENTRIES {
+        return None;
+    }
+
+    entries.insert(rank, entry);
+    entries.truncate(MAX_ENTRIES);
+    Some(rank)
+}
+
+/// Layout of `highscores.toml`: a `[[best_score]]` or `[[fastest_win]]`
+/// table per entry, each naming its draw mode
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct HighScoresFile {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    best_score: Vec<EntryFile>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fastest_win: Vec<EntryFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntryFile {
+    draw_count: DrawCount,
+    score: i32,
+    time_ms: u64,
+    moves: u32,
+    achieved_at: u64,
+}
+
+impl From<&HighScores> for HighScoresFile {
+    fn from(high_scores: &HighScores) -> Self {
+        let mut file = HighScoresFile::default();
+        for draw_count in [DrawCount::One, DrawCount::Three] {
+            let mode = high_scores.mode(draw_count);
+            for (entries, stored) in [
+                (&mode.best_scores, &mut file.best_score),
+                (&mode.fastest_wins, &mut file.fastest_win),
+            ] {
+                stored.extend(entries.iter().map(|entry| EntryFile {
+                    draw_count,
+                    score: entry.score,
+                    time_ms: entry.time.as_millis() as u64,
+                    moves: entry.moves,
+                    achieved_at: entry.achieved_at,
+                }));
+            }
+        }
+        file
+    }
+}
+
+impl TryFrom<HighScoresFile> for HighScores {
+    type Error = String;
+
+    fn try_from(file: HighScoresFile) -> Result<Self, String> {
+        let mut high_scores = HighScores::default();
+        for stored in file.best_score {
+            let (draw_count, entry) = stored.into_entry()?;
+            let mode = high_scores.mode_mut(draw_count);
+            insert_ranked(&mut mode.best_scores, entry, |a, b| a.score > b.score);
+        }
+        for stored in file.fastest_win {
+            let (draw_count, entry) = stored.into_entry()?;
+            let mode = high_scores.mode_mut(draw_count);
+            insert_ranked(&mut mode.fastest_wins, entry, |a, b| a.time < b.time);
+        }
+        Ok(high_scores)
+    }
+}
+
+impl EntryFile {
+    fn into_entry(self) -> Result<(DrawCount, HighScoreEntry), String> {
+        if self.score < 0 {
+            return Err("high score entry has invalid score".to_string());
+        }
+        let entry = HighScoreEntry {
+            score: self.score,
+            time: Duration::from_millis(self.time_ms),
+            moves: self.moves,
+            achieved_at: self.achieved_at,
+        };
+        Ok((self.draw_count, entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golf::Golf;
+    use crate::variant::Variant;
+
+    fn won_game(draw_count: DrawCount, score: i32) -> GameState {
+        let mut game_state = GameState::new_with_seed(score as u64);
+        game_state.draw_count = draw_count;
+        game_state.score = score;
+        game_state.game_won = true;
+        game_state
+    }
+
+    #[test]
+    fn test_record_ranks_by_score_per_mode() {
+        let mut high_scores = HighScores::default();
+
+        assert_eq!(
+            high_scores
+                .record(&won_game(DrawCount::Three, 100))
+                .score_rank,
+            Some(0)
+        );
+        assert_eq!(
+            high_scores
+                .record(&won_game(DrawCount::Three, 300))
+                .score_rank,
+            Some(0)
+        );
+        assert_eq!(
+            high_scores
+                .record(&won_game(DrawCount::Three, 200))
+                .score_rank,
+            Some(1)
+        );
+
+        let scores: Vec<i32> = high_scores
+            .mode(DrawCount::Three)
+            .best_scores
+            .iter()
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(scores, vec![300, 200, 100]);
+        assert!(high_scores.mode(DrawCount::One).best_scores.is_empty());
+    }
+
+    #[test]
+    fn test_tables_are_capped() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_ENTRIES as i32 {
+            high_scores.record(&won_game(DrawCount::One, score * 10));
+        }
+
+        // Lower than everything on a full table: not placed
+        let placement = high_scores.record(&won_game(DrawCount::One, 5));
+        assert_eq!(placement.score_rank, None);
+        assert_eq!(high_scores.draw_one.best_scores.len(), MAX_ENTRIES);
+
+        let placement = high_scores.record(&won_game(DrawCount::One, 1000));
+        assert_eq!(placement.score_rank, Some(0));
+        assert_eq!(high_scores.draw_one.best_scores.len(), MAX_ENTRIES);
+        assert_eq!(high_scores.draw_one.best_scores.last().unwrap().score, 20);
+    }
+
+    #[test]
+    fn test_unranked_games_are_ignored() {
+        let mut high_scores = HighScores::default();
+
+        let mut lost = won_game(DrawCount::Three, 50);
+        lost.game_won = false;
+        assert!(!high_scores.record(&lost).is_new_entry());
+
+        let mut accommodated = won_game(DrawCount::Three, 50);
+        accommodated.accommodations.untimed = true;
+        assert!(!high_scores.record(&accommodated).is_new_entry());
+
+        let mut practice = won_game(DrawCount::Three, 50);
+        practice.practice = true;
+        assert!(!high_scores.record(&practice).is_new_entry());
+
+        let mut golf = won_game(DrawCount::Three, 50);
+        golf.rules = Variant::new(Golf);
+        assert!(!high_scores.record(&golf).is_new_entry());
+
+        assert_eq!(high_scores, HighScores::default());
+    }
+
+    #[test]
+    fn test_high_scores_round_trip() {
+        let mut high_scores = HighScores::default();
+        high_scores.record(&won_game(DrawCount::One, 420));
+        high_scores.record(&won_game(DrawCount::Three, 380));
+        high_scores.record(&won_game(DrawCount::Three, 510));
+
+        let text = storage::to_text(&HighScoresFile::from(&high_scores)).unwrap();
+        assert_eq!(HighScores::parse(&text).unwrap(), high_scores);
+    }
+
+    #[test]
+    fn test_corrupt_fixtures_are_rejected() {
+        let entry = "score = 1\ntime_ms = 2\nmoves = 3\nachieved_at = 4";
+        let fixtures = [
+            format!("[[best_score]]\n{}", entry),
+            format!("[[best_score]]\ndraw_count = \"Five\"\n{}", entry),
+            format!(
+                "[[fastest_win]]\ndraw_count = \"One\"\n{}\nmoves = -3",
+                entry
+            ),
+            "[[fastest_win]]\ndraw_count = \"One\"\nscore = ".to_string(),
+            "[[best_score]]\ndraw_count = \"One\"\nscore = -1\ntime_ms = 2\nmoves = 3\nachieved_at = 4"
+                .to_string(),
+        ];
+
+        for fixture in &fixtures {
+            assert!(HighScores::parse(fixture).is_err(), "{}", fixture);
+        }
+
+        let valid = format!("[[best_score]]\ndraw_count = \"One\"\n{}", entry);
+        assert_eq!(
+            HighScores::parse(&valid)
+                .unwrap()
+                .draw_one
+                .best_scores
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use crate::actions::GameAction;
+use crate::command::GameCommand;
+use crate::state::GameState;
+use crate::transcript::action_notation;
+use serde::{Deserialize, Serialize};
+
+/// Bounds on how much undo history is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryLimits {
+    /// Most actions kept in the log; older ones are folded into the base snapshot
+    pub max_entries: usize,
+    /// A full state snapshot is kept every this many actions, where the log
+    /// can be cut when it grows past `max_entries`
+    pub snapshot_interval: usize,
+}
+
+impl Default for HistoryLimits {
+    fn default() -> Self {
+        HistoryLimits {
+            max_entries: 5_000,
+            snapshot_interval: 50,
+        }
+    }
+}
+
+/// Undo/redo history stored as a log of command objects with periodic
+/// snapshots.
+///
+/// Each command knows how to take itself back and play itself again, so
+/// undo and redo step the current state directly. Memory stays flat during
+/// long sessions: recording after an undo drops the redo branch, and once
+/// the log grows past `max_entries` the oldest actions are folded into a new
+/// base at a snapshot.
+#[derive(Debug, Clone)]
+pub struct History {
+    /// State before the first logged action
+    base: GameState,
+    /// Applied commands; `entries[cursor..]` is the redo branch
+    entries: Vec<GameCommand>,
+    /// States after `index` actions, sorted by index
+    snapshots: Vec<(usize, GameState)>,
+    /// Number of actions currently applied
+    cursor: usize,
+    /// State with the first `cursor` commands applied
+    current: GameState,
+    limits: HistoryLimits,
+}
+
+impl History {
+    pub fn new(initial: GameState, limits: HistoryLimits) -> Self {
+        History {
+            base: initial.clone(),
+            entries: Vec::new(),
+            snapshots: Vec::new(),
+            cursor: 0,
+            current: initial,
+            limits: HistoryLimits {
+                max_entries: limits.max_entries.max(1),
+                snapshot_interval: limits.snapshot_interval.max(1),
+            },
+        }
+    }
+
+    /// Rebuild a history from its base state and action log, replaying every
+    /// action to check it and to lay the snapshots down again. The first
+    /// `cursor` actions end up applied; the rest are the redo branch.
+    pub fn restore(
+        base: GameState,
+        entries: Vec<GameAction>,
+        cursor: usize,
+        limits: HistoryLimits,
+    ) -> Result<Self, String> {
+        if cursor > entries.len() {
+            return Err(format!(
+                "cursor {} is past the {} logged actions",
+                cursor,
+                entries.len()
+            ));
+        }
+
+        let redo_len = entries.len() - cursor;
+        let mut history = History::new(base.clone(), limits);
+        let mut state = base;
+        for (step, action) in entries.into_iter().enumerate() {
+            let command = GameCommand::apply(&mut state, action.clone()).map_err(|error| {
+                format!(
+                    "action {} (`{}`) cannot be replayed: {}",
+                    step + 1,
+                    action_notation(&action),
+                    error
+                )
+            })?;
+            history.record(command, &state);
+        }
+        for _ in 0..redo_len {
+            history.undo();
+        }
+        Ok(history)
+    }
+
+    /// State before the first logged action
+    pub fn base(&self) -> &GameState {
+        &self.base
+    }
+
+    /// Logged commands, including the redo branch
+    pub fn entries(&self) -> &[GameCommand] {
+        &self.entries
+    }
+
+    /// Logged actions up to the cursor, leaving out the redo branch
+    pub fn applied_actions(&self) -> impl Iterator<Item = &GameAction> {
+        self.entries[..self.cursor]
+            .iter()
+            .map(|command| &command.action)
+    }
+
+    /// Number of actions currently applied
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The state with every applied action played
+    pub fn current(&self) -> GameState {
+        self.current.clone()
+    }
+
+    pub fn limits(&self) -> HistoryLimits {
+        self.limits
+    }
+
+    /// Number of actions in the log, including the redo branch
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of full state snapshots currently held
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    /// Record a command that was just applied, producing `state_after`
+    pub fn record(&mut self, command: GameCommand, state_after: &GameState) {
+        // A new action after undoing abandons the redo branch
+        self.entries.truncate(self.cursor);
+        self.snapshots.retain(|(index, _)| *index <= self.cursor);
+
+        self.entries.push(command);
+        self.cursor = self.entries.len();
+        self.current = state_after.clone();
+        if self.cursor.is_multiple_of(self.limits.snapshot_interval) {
+            self.snapshots.push((self.cursor, state_after.clone()));
+        }
+
+        self.compact();
+    }
+
+    /// Step back one action, returning the restored state
+    pub fn undo(&mut self) -> Option<GameState> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.cursor -= 1;
+        self.entries[self.cursor].invert(&mut self.current);
+        Some(self.current.clone())
+    }
+
+    /// Re-apply the most recently undone action, returning the restored state
+    pub fn redo(&mut self) -> Option<GameState> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.entries[self.cursor].reapply(&mut self.current);
+        self.cursor += 1;
+        Some(self.current.clone())
+    }
+
+    /// Fold the oldest actions into the base once the log exceeds its limit.
+    /// The cut is made at a snapshot, which becomes the base, so compaction
+    /// runs at most once per snapshot interval.
+    fn compact(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.limits.max_entries);
+        if excess == 0 {
+            return;
+        }
+
+        let Some(position) = self
+            .snapshots
+            .iter()
+            .position(|(index, _)| *index >= excess)
+        else {
+            return;
+        };
+
+        let (cut, new_base) = self.snapshots.remove(position);
+        self.snapshots.drain(..position);
+        for (index, _) in &mut self.snapshots {
+            *index -= cut;
+        }
+        self.entries.drain(..cut);
+        self.cursor -= cut;
+        self.base = new_base;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawCount;
+    use crate::state::Position;
+
+    fn new_game() -> GameState {
+        let mut game_state = GameState::new_with_seed(2032);
+        game_state.draw_count = DrawCount::One;
+        game_state
+    }
+
+    /// Deal `count` times, recording each action
+    fn deal_recorded(game_state: &mut GameState, history: &mut History, count: usize) {
+        for _ in 0..count {
+            let command = GameCommand::apply(game_state, GameAction::DealFromStock).unwrap();
+            history.record(command, game_state);
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut game_state = new_game();
+        let initial = game_state.clone();
+        let mut history = History::new(game_state.clone(), HistoryLimits::default());
+        assert!(!history.can_undo());
+
+        deal_recorded(&mut game_state, &mut history, 2);
+        let after_two = game_state.clone();
+
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.waste.len(), 1);
+        assert_eq!(undone.move_count, 1);
+        assert!(history.can_redo());
+
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.waste, initial.waste);
+        assert_eq!(undone.stock, initial.stock);
+        assert!(history.undo().is_none());
+
+        history.redo().unwrap();
+        let redone = history.redo().unwrap();
+        assert_eq!(redone.waste, after_two.waste);
+        assert_eq!(redone.move_count, after_two.move_count);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn test_recording_drops_redo_branch() {
+        let mut game_state = new_game();
+        let mut history = History::new(game_state.clone(), HistoryLimits::default());
+
+        deal_recorded(&mut game_state, &mut history, 5);
+        history.undo();
+        history.undo();
+        game_state = history.undo().unwrap();
+        assert_eq!(history.len(), 5);
+
+        deal_recorded(&mut game_state, &mut history, 1);
+        assert_eq!(history.len(), 3);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_undo_across_snapshots() {
+        let limits = HistoryLimits {
+            max_entries: 1_000,
+            snapshot_interval: 4,
+        };
+        let mut game_state = new_game();
+        let mut history = History::new(game_state.clone(), limits);
+
+        let mut states = vec![game_state.clone()];
+        for _ in 0..30 {
+            deal_recorded(&mut game_state, &mut history, 1);
+            states.push(game_state.clone());
+        }
+        assert_eq!(history.snapshot_count(), 7);
+
+        for expected in states.iter().rev().skip(1) {
+            let undone = history.undo().unwrap();
+            assert_eq!(undone.stock, expected.stock);
+            assert_eq!(undone.waste, expected.waste);
+            assert_eq!(undone.move_count, expected.move_count);
+        }
+    }
+
+    #[test]
+    fn test_restore_replays_the_log() {
+        let mut game_state = new_game();
+        let mut history = History::new(game_state.clone(), HistoryLimits::default());
+        deal_recorded(&mut game_state, &mut history, 3);
+        let after_two = history.undo().unwrap();
+
+        let actions = history
+            .entries()
+            .iter()
+            .map(|command| command.action.clone())
+            .collect();
+        let restored = History::restore(
+            history.base().clone(),
+            actions,
+            history.cursor(),
+            history.limits(),
+        )
+        .unwrap();
+        assert_eq!(restored.cursor(), 2);
+        assert!(restored.can_redo());
+        assert_eq!(restored.current().waste, after_two.waste);
+        assert_eq!(restored.current().move_count, after_two.move_count);
+
+        // A log that does not replay is rejected
+        let illegal = vec![GameAction::MoveCard {
+            from: Position::Foundation(0),
+            to: Position::Tableau(0, 1),
+        }];
+        let error = History::restore(new_game(), illegal, 1, HistoryLimits::default());
+        assert!(error.unwrap_err().contains("action 1"));
+        assert!(History::restore(new_game(), Vec::new(), 1, HistoryLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_history_stays_bounded() {
+        let limits = HistoryLimits {
+            max_entries: 20,
+            snapshot_interval: 5,
+        };
+        let mut game_state = new_game();
+        let mut history = History::new(game_state.clone(), limits);
+
+        // The draw-one stock cycles forever, so this is a marathon session
+        for _ in 0..500 {
+            deal_recorded(&mut game_state, &mut history, 1);
+            assert!(history.len() <= limits.max_entries + limits.snapshot_interval);
+            assert!(history.snapshot_count() <= history.len() / limits.snapshot_interval + 1);
+        }
+
+        // Undo still works all the way back to the compacted base
+        let mut expected_moves = game_state.move_count;
+        while let Some(undone) = history.undo() {
+            expected_moves -= 1;
+            assert_eq!(undone.move_count, expected_moves);
+        }
+        assert!(game_state.move_count - expected_moves >= limits.max_entries as u32);
+    }
+}
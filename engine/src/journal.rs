@@ -0,0 +1,299 @@
+use crate::actions::GameAction;
+use crate::autosave::SavedGame;
+use crate::command::GameCommand;
+use crate::history::HistoryLimits;
+use crate::settings::Accommodations;
+use crate::state::GameState;
+use crate::storage::{self, Checked};
+use crate::transcript::{action_notation, parse_action};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Line between the starting game and the actions played on it
+const ACTIONS_MARKER: &str = "%% actions";
+
+/// Append-only record of the game being played, for recovering it after a
+/// crash.
+///
+/// The journal opens with the game as it stood when the journal was started,
+/// in the autosave format, followed by one line per action as it is played:
+///
+/// ```text
+/// %% actions
+/// 4210 0 0 deal
+/// 9876 1 0 move W0 T3.4
+/// 12034 1 0 undo
+/// ```
+///
+/// Each line gives the game clock in milliseconds, whether the game is a
+/// practice game, its accommodation flags, then the action. Lines are only
+/// ever appended, so a crash loses at most the line being written, which is
+/// skipped on recovery.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    /// Open while a game is being journaled
+    file: Option<File>,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Journal { path, file: None }
+    }
+
+    /// Default location, alongside `stats.toml`
+    pub fn default_path() -> PathBuf {
+        storage::data_dir().join("journal.txt")
+    }
+
+    /// Recover the game a journal left behind at startup, if it was still
+    /// being played. A damaged journal is quarantined with a notice.
+    pub fn recover(path: &Path, limits: HistoryLimits) -> Checked<Option<SavedGame>> {
+        storage::load_checked(path, |text| Self::parse(text, limits))
+    }
+
+    /// Replay a journal's actions on its starting game. Finished games and
+    /// games with nothing played yield `None`.
+    pub fn parse(text: &str, limits: HistoryLimits) -> Result<Option<SavedGame>, String> {
+        let (start, actions) = text
+            .split_once(ACTIONS_MARKER)
+            .ok_or_else(|| "journal has no actions marker".to_string())?;
+        let mut saved = SavedGame::parse(start, limits)?;
+        let mut elapsed = saved.game_state.elapsed();
+
+        let lines: Vec<&str> = actions
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        for (i, line) in lines.iter().enumerate() {
+            let entry = match parse_line(line) {
+                Ok(entry) => entry,
+                // The last line may have been cut short by the crash
+                Err(_) if i + 1 == lines.len() => break,
+                Err(error) => return Err(format!("journal line {}: {}", i + 1, error)),
+            };
+            replay(&mut saved, &entry)
+                .map_err(|error| format!("journal line {}: {}", i + 1, error))?;
+            elapsed = entry.elapsed;
+            saved.game_state.practice = entry.practice;
+            saved.game_state.accommodations = entry.accommodations;
+        }
+
+        if saved.game_state.game_won || saved.history.is_empty() {
+            return Ok(None);
+        }
+        // Round trip through the autosave format so every position in the
+        // history shares the recovered clock
+        saved.game_state.start_time = SystemTime::now()
+            .checked_sub(elapsed)
+            .unwrap_or_else(SystemTime::now);
+        SavedGame::from_file(saved.to_file(), limits).map(Some)
+    }
+
+    /// Start journaling `saved`, replacing any earlier journal
+    pub fn start(&mut self, saved: &SavedGame) -> Result<(), String> {
+        self.file = None;
+        let start = saved.to_text()?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut file = File::create(&self.path)
+            .map_err(|e| format!("Failed to create {}: {}", self.path.display(), e))?;
+        writeln!(file, "{}{}", start, ACTIONS_MARKER)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        drop(file);
+
+        self.file = Some(
+            OpenOptions::new()
+                .append(true)
+                .open(&self.path)
+                .map_err(|e| format!("Failed to open {}: {}", self.path.display(), e))?,
+        );
+        Ok(())
+    }
+
+    /// Append an action just applied to `game_state`, or an undo or redo.
+    /// Does nothing while no journal is started.
+    pub fn append(&mut self, action: &GameAction, game_state: &GameState) -> Result<(), String> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        writeln!(
+            file,
+            "{} {} {} {}",
+            game_state.elapsed().as_millis(),
+            game_state.practice as u8,
+            game_state.accommodations.to_flags(),
+            action_notation(action)
+        )
+        .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+
+    /// Stop journaling and remove the journal, once the game is safely
+    /// saved, finished or not worth recovering
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.file = None;
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", self.path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One journaled action with the game's clock and flags after it
+struct Entry {
+    elapsed: Duration,
+    practice: bool,
+    accommodations: Accommodations,
+    action: GameAction,
+}
+
+fn parse_line(line: &str) -> Result<Entry, String> {
+    let mut fields = line.trim().splitn(4, ' ');
+    let mut next = |name: &str| {
+        fields
+            .next()
+            .ok_or_else(|| format!("missing {} in `{}`", name, line))
+    };
+    let number = |text: &str| {
+        text.parse::<u64>()
+            .map_err(|_| format!("invalid number `{}`", text))
+    };
+    Ok(Entry {
+        elapsed: Duration::from_millis(number(next("clock")?)?),
+        practice: number(next("practice flag")?)? != 0,
+        accommodations: Accommodations::from_flags(number(next("accommodations")?)? as u8),
+        action: parse_action(next("action")?)?,
+    })
+}
+
+/// Apply a journaled action to the game and its history, as the app did
+fn replay(saved: &mut SavedGame, entry: &Entry) -> Result<(), String> {
+    let restored = match entry.action {
+        GameAction::Undo => saved.history.undo(),
+        GameAction::Redo => saved.history.redo(),
+        ref action => {
+            let command = GameCommand::apply(&mut saved.game_state, action.clone())?;
+            saved.history.record(command, &saved.game_state);
+            return Ok(());
+        }
+    };
+    let mut restored = restored.ok_or_else(|| "nothing to undo or redo".to_string())?;
+    // Stepping through the history never drops the game's flags
+    restored.practice |= saved.game_state.practice;
+    restored.accommodations = restored
+        .accommodations
+        .union(saved.game_state.accommodations);
+    saved.game_state = restored;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawCount;
+    use crate::history::History;
+
+    fn journal_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "vibe-solitaire-test-{}-journal",
+                std::process::id()
+            ))
+            .join(format!("{}.txt", name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn fresh_game() -> SavedGame {
+        let mut game_state = GameState::new_with_seed(2068);
+        game_state.draw_count = DrawCount::One;
+        SavedGame {
+            history: History::new(game_state.clone(), HistoryLimits::default()),
+            game_state,
+            daily: None,
+        }
+    }
+
+    /// Play `action` as the app does, journaling it
+    fn play(saved: &mut SavedGame, journal: &mut Journal, action: GameAction) {
+        let entry = Entry {
+            elapsed: Duration::ZERO,
+            practice: saved.game_state.practice,
+            accommodations: saved.game_state.accommodations,
+            action: action.clone(),
+        };
+        replay(saved, &entry).unwrap();
+        journal.append(&action, &saved.game_state).unwrap();
+    }
+
+    #[test]
+    fn test_journal_recovers_the_game() {
+        let path = journal_path("recover");
+        let mut journal = Journal::new(path.clone());
+        let mut saved = fresh_game();
+        journal.start(&saved).unwrap();
+        for _ in 0..4 {
+            play(&mut saved, &mut journal, GameAction::DealFromStock);
+        }
+        play(&mut saved, &mut journal, GameAction::Undo);
+        saved.game_state.practice = true;
+        play(&mut saved, &mut journal, GameAction::Undo);
+        play(&mut saved, &mut journal, GameAction::Redo);
+
+        let recovered = Journal::recover(&path, HistoryLimits::default())
+            .value
+            .unwrap();
+        assert_eq!(recovered.game_state.waste, saved.game_state.waste);
+        assert_eq!(recovered.game_state.move_count, 3);
+        assert!(recovered.game_state.practice);
+        assert_eq!(recovered.history.cursor(), 3);
+        assert!(recovered.history.can_redo());
+
+        journal.clear().unwrap();
+        assert!(!path.exists());
+        assert!(
+            Journal::recover(&path, HistoryLimits::default())
+                .value
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_torn_last_line_is_skipped() {
+        let path = journal_path("torn");
+        let mut journal = Journal::new(path.clone());
+        let mut saved = fresh_game();
+        journal.start(&saved).unwrap();
+        play(&mut saved, &mut journal, GameAction::DealFromStock);
+        play(&mut saved, &mut journal, GameAction::DealFromStock);
+
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("{}1200 0 0 mov", text)).unwrap();
+        let recovered = Journal::recover(&path, HistoryLimits::default());
+        assert!(recovered.notice.is_none());
+        assert_eq!(recovered.value.unwrap().game_state.move_count, 2);
+
+        // Damage before the last line is not a crash, and sets the journal aside
+        fs::write(&path, text.replace(" deal\n", " dela\n")).unwrap();
+        let recovered = Journal::recover(&path, HistoryLimits::default());
+        assert!(recovered.value.is_none());
+        assert!(recovered.notice.unwrap().contains("journal line 1"));
+    }
+
+    #[test]
+    fn test_untouched_game_needs_no_recovery() {
+        let text = format!("{}{}\n", fresh_game().to_text().unwrap(), ACTIONS_MARKER);
+        assert!(
+            Journal::parse(&text, HistoryLimits::default())
+                .unwrap()
+                .is_none()
+        );
+        assert!(Journal::parse("no marker", HistoryLimits::default()).is_err());
+    }
+}
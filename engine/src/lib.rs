@@ -0,0 +1,55 @@
+//! The rules, solver and persistence of the solitaire games, with no user
+//! interface. The gpui app in the root crate is one frontend; the CLI and
+//! tests drive the same engine directly.
+//!
+//! Cards are defined in `deck`, places on the board in `state` and the
+//! variants in `variant`; all are re-exported here.
+//!
+//! Bots, fuzzers and simulations should play through `Game`, which takes
+//! actions and reports what a player can see. It and the types it uses are
+//! kept stable across minor versions; the other modules serve the app and
+//! may change with it.
+
+pub mod actions;
+pub mod autosave;
+pub mod clock;
+pub mod command;
+pub mod daily;
+pub mod deck;
+pub mod double_klondike;
+pub mod evaluator;
+pub mod events;
+pub mod golf;
+pub mod headless;
+pub mod highscores;
+pub mod history;
+pub mod journal;
+pub mod locale;
+pub mod mirror;
+pub mod packed;
+pub mod playback;
+pub mod pyramid;
+pub mod registry;
+pub mod replay;
+pub mod report;
+pub mod reroll;
+pub mod rules;
+pub mod scripted;
+pub mod settings;
+pub mod simulation;
+pub mod solver;
+pub mod spider;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod thoughtful;
+pub mod tournament;
+pub mod transcript;
+pub mod variant;
+
+pub use actions::{DrawCount, GameAction};
+pub use deck::{Card, Rank, Suit};
+pub use events::GameEvent;
+pub use headless::{Game, Observation};
+pub use state::{Pile, Position};
+pub use variant::Variant;
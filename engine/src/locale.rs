@@ -0,0 +1,1327 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fmt;
+
+/// Languages the game can be shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "ar")]
+    Arabic,
+}
+
+impl Language {
+    pub fn all() -> [Language; 3] {
+        [Language::English, Language::Spanish, Language::Arabic]
+    }
+
+    /// Code used in the settings file
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::Arabic => "ar",
+        }
+    }
+
+    /// Name of the language in itself, for the language picker
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::Arabic => "العربية",
+        }
+    }
+
+    /// Whether the language is written from right to left, so the menus
+    /// and dialogs are laid out mirrored
+    pub fn is_rtl(&self) -> bool {
+        matches!(self, Language::Arabic)
+    }
+
+    /// English text with its translation. English needs none.
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::Spanish => SPANISH,
+            Language::Arabic => ARABIC,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Language> = const { Cell::new(Language::English) };
+}
+
+/// Show text in `language` from here on; set once per frame
+pub fn set_language(language: Language) {
+    CURRENT.with(|current| current.set(language));
+}
+
+/// Language text is being shown in
+pub fn language() -> Language {
+    CURRENT.with(Cell::get)
+}
+
+/// Whether the current language is written from right to left
+pub fn is_rtl() -> bool {
+    language().is_rtl()
+}
+
+/// Marks around text of its own direction set into a translation, so a
+/// name or number keeps its reading order inside right-to-left text
+const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+/// Mark giving a line a right-to-left base direction whatever it starts
+/// with
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+
+/// `text` as a line in the current language's direction. A right-to-left
+/// line that starts with a name or number still reads from the right.
+pub fn directed(text: &str) -> String {
+    if is_rtl() {
+        format!("{}{}", RIGHT_TO_LEFT_MARK, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// `text` in the current language. The English text is the key, so text
+/// without a translation is shown as it is.
+pub fn tr(text: &str) -> &str {
+    language()
+        .catalog()
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// `template` in the current language with each `{}` filled from `args`
+/// in turn, e.g. `trf("Deal {} cards", &[&3])`. In right-to-left languages
+/// each argument is isolated so its own direction is kept.
+pub fn trf(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let isolate = is_rtl();
+    let mut parts = tr(template).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            if isolate {
+                text.push(FIRST_STRONG_ISOLATE);
+                text.push_str(&arg.to_string());
+                text.push(POP_DIRECTIONAL_ISOLATE);
+            } else {
+                text.push_str(&arg.to_string());
+            }
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+const SPANISH: &[(&str, &str)] = &[
+    // Menu bar
+    ("Klondike Solitaire", "Solitario Klondike"),
+    ("New Game", "Nueva partida"),
+    ("Forfeit Deal", "Abandonar reparto"),
+    ("Undo", "Deshacer"),
+    ("Redo", "Rehacer"),
+    ("Auto-complete", "Autocompletar"),
+    ("Collect All", "Recoger todo"),
+    ("Take Over", "Tomar el control"),
+    ("Show Winning Line", "Mostrar jugada ganadora"),
+    ("Hint", "Pista"),
+    ("Quick Stats", "Estadísticas rápidas"),
+    ("Next Move", "Siguiente movimiento"),
+    ("Previous Move", "Movimiento anterior"),
+    ("Trainer: On", "Entrenador: sí"),
+    ("Trainer: Off", "Entrenador: no"),
+    ("Practice Game", "Partida de práctica"),
+    ("Replay Deal", "Repetir reparto"),
+    ("Tournament", "Torneo"),
+    ("Play Game #…", "Jugar partida n.º…"),
+    ("Import Deal", "Importar reparto"),
+    ("Share Position", "Compartir posición"),
+    ("Export Replay", "Exportar repetición"),
+    ("Watch Replay", "Ver repetición"),
+    ("Daily Challenge", "Reto diario"),
+    ("High Scores", "Récords"),
+    ("Settings", "Ajustes"),
+    ("Deal a new game", "Repartir una partida nueva"),
+    ("Take back the last move", "Deshacer el último movimiento"),
+    (
+        "Make the move taken back again",
+        "Volver a hacer el movimiento deshecho",
+    ),
+    (
+        "Play every card that can go to the foundations",
+        "Subir a las fundaciones todas las cartas que se pueda",
+    ),
+    (
+        "Move the cards no longer needed on the tableau to the foundations",
+        "Subir a las fundaciones las cartas que ya no hacen falta en la mesa",
+    ),
+    ("Show a good move", "Mostrar un buen movimiento"),
+    (
+        "Grade every move against the best one",
+        "Comparar cada movimiento con el mejor",
+    ),
+    // Status bar
+    ("Score", "Puntos"),
+    ("Time", "Tiempo"),
+    ("Moves", "Movimientos"),
+    ("Stock", "Mazo"),
+    ("Waste", "Descarte"),
+    ("Pass", "Pasada"),
+    ("Last pass", "Última pasada"),
+    ("Plays", "Jugadas"),
+    ("{} of {}", "{} de {}"),
+    (
+        "Points for cards played, less the penalties",
+        "Puntos por las cartas jugadas, menos las penalizaciones",
+    ),
+    ("Vegas", "Vegas"),
+    (
+        "Dollars won back on cards played, less the stake",
+        "Dólares recuperados por las cartas jugadas, menos la apuesta",
+    ),
+    (
+        "Moves made this game, deals included",
+        "Movimientos de esta partida, repartos incluidos",
+    ),
+    ("Cards left to deal", "Cartas por repartir"),
+    (
+        "Cards dealt and not yet played",
+        "Cartas repartidas aún sin jugar",
+    ),
+    ("Time since the cards were dealt", "Tiempo desde el reparto"),
+    (
+        "Times through the stock, counting this one",
+        "Vueltas al mazo, contando esta",
+    ),
+    (
+        "Cards that can be played now, not counting the stock",
+        "Cartas que se pueden jugar ahora, sin contar el mazo",
+    ),
+    (
+        "Move {} of {}: {} (Enter to make it)",
+        "Movimiento {} de {}: {} (Intro para hacerlo)",
+    ),
+    // The stock and foundations
+    (
+        "Deal a card onto every column",
+        "Repartir una carta en cada columna",
+    ),
+    ("The stock is empty", "El mazo está vacío"),
+    ("Deal 1 card", "Repartir 1 carta"),
+    ("Deal {} cards", "Repartir {} cartas"),
+    (
+        "The stock can't be turned over again",
+        "Ya no se puede dar la vuelta al mazo",
+    ),
+    (
+        "Turn the waste over ({} left)",
+        "Dar la vuelta al descarte (quedan {})",
+    ),
+    ("Turn the waste over", "Dar la vuelta al descarte"),
+    (
+        "Cards adding up to {} are removed here",
+        "Aquí se retiran las cartas que suman {}",
+    ),
+    (
+        "Runs from King down to Ace collect here",
+        "Aquí se recogen las escaleras del Rey al As",
+    ),
+    (
+        "Build each suit up from Ace to King",
+        "Sube cada palo del As al Rey",
+    ),
+    // Refused moves
+    (
+        "Both stock and waste are empty",
+        "El mazo y el descarte están vacíos",
+    ),
+    (
+        "The stock cannot be turned over again",
+        "Ya no se puede dar la vuelta al mazo",
+    ),
+    (
+        "Every column needs a card before dealing",
+        "Cada columna necesita una carta antes de repartir",
+    ),
+    ("No cards to move", "No hay cartas que mover"),
+    (
+        "A card cannot pair with itself",
+        "Una carta no puede emparejarse consigo misma",
+    ),
+    ("Those cards do not add up to {}", "Esas cartas no suman {}"),
+    (
+        "There is no pile to discard to",
+        "No hay montón donde descartar",
+    ),
+    (
+        "No cards are safe to collect",
+        "No hay cartas que se puedan recoger sin riesgo",
+    ),
+    ("That card is covered", "Esa carta está tapada"),
+    (
+        "Only a King can go on an empty column",
+        "Solo un Rey puede ir en una columna vacía",
+    ),
+    (
+        "Foundations start with an Ace",
+        "Las fundaciones empiezan con un As",
+    ),
+    ("{} can't go on {}", "{} no puede ir sobre {}"),
+    ("{} can't go there", "{} no puede ir ahí"),
+    (
+        "Only one card at a time can go to a foundation",
+        "Solo se puede subir una carta cada vez a una fundación",
+    ),
+    (
+        "Only one card at a time can go to the waste",
+        "Solo se puede poner una carta cada vez en el descarte",
+    ),
+    (
+        "Cards cannot be moved to the stock",
+        "No se pueden mover cartas al mazo",
+    ),
+    (
+        "An imported deal has no seed to replay",
+        "Un reparto importado no tiene semilla que repetir",
+    ),
+    ("No moves left", "No quedan movimientos"),
+    (
+        "No cards can go up to the foundations",
+        "Ninguna carta puede subir a las fundaciones",
+    ),
+    (
+        "Only Klondike positions can be shared",
+        "Solo se pueden compartir posiciones de Klondike",
+    ),
+    (
+        "Position code copied to the clipboard",
+        "Código de posición copiado al portapapeles",
+    ),
+    (
+        "Take over from the winning line first",
+        "Toma primero el control de la jugada ganadora",
+    ),
+    ("Close the replay first", "Cierra primero la repetición"),
+    (
+        "Deals cannot be replayed during a tournament",
+        "No se pueden repetir repartos durante un torneo",
+    ),
+    ("The game is already won", "La partida ya está ganada"),
+    ("Nothing to undo", "Nada que deshacer"),
+    ("Nothing to redo", "Nada que rehacer"),
+    (
+        "Drag the card onto the one to pair it with",
+        "Arrastra la carta sobre aquella con la que quieras emparejarla",
+    ),
+    (
+        "That card has nowhere to go",
+        "Esa carta no tiene adónde ir",
+    ),
+    // Settings
+    ("Game", "Partida"),
+    (
+        "Draw three cards at a time (from the next game)",
+        "Robar tres cartas cada vez (desde la próxima partida)",
+    ),
+    (
+        "Scoring (from the next game):",
+        "Puntuación (desde la próxima partida):",
+    ),
+    (
+        "Ask before abandoning a game in progress",
+        "Preguntar antes de abandonar una partida en curso",
+    ),
+    (
+        "Count the cards that can be played",
+        "Contar las cartas que se pueden jugar",
+    ),
+    ("Animate cards", "Animar las cartas"),
+    ("Card motion:", "Movimiento de las cartas:"),
+    ("Theme:", "Tema:"),
+    ("Language:", "Idioma:"),
+    ("Text size:", "Tamaño del texto:"),
+    (
+        "Four-color deck (blue diamonds, green clubs)",
+        "Baraja de cuatro colores (diamantes azules, tréboles verdes)",
+    ),
+    (
+        "Large index cards (big corner rank and suit, plain center)",
+        "Cartas de índice grande (número y palo grandes en la esquina, centro sencillo)",
+    ),
+    (
+        "Count the cards in the stock and face down in each column",
+        "Contar las cartas del mazo y las boca abajo de cada columna",
+    ),
+    (
+        "Compact layout (for small windows)",
+        "Disposición compacta (para ventanas pequeñas)",
+    ),
+    ("Accessibility", "Accesibilidad"),
+    (
+        "Large drop targets (drop anywhere over a column)",
+        "Zonas de soltar grandes (en cualquier punto de una columna)",
+    ),
+    ("Untimed play", "Jugar sin reloj"),
+    (
+        "Sound cues (moves, flips, deals, wins and refused moves)",
+        "Sonidos (movimientos, volteos, repartos, victorias y movimientos rechazados)",
+    ),
+    ("Background music", "Música de fondo"),
+    ("Sounds:", "Sonidos:"),
+    ("Mute", "Silenciar"),
+    ("Unmute", "Activar sonido"),
+    ("Muted", "Silenciado"),
+    (
+        "Turn all sound off or back on",
+        "Apagar o volver a encender todo el sonido",
+    ),
+    ("All sound is off", "Todo el sonido está apagado"),
+    ("Built-in tones", "Tonos integrados"),
+    (
+        "Drop a folder of .wav or .ogg files named after the cues (move, invalid, flip, deal, win, record_win, no_undo_win) into {} and reopen settings",
+        "Coloca una carpeta de archivos .wav u .ogg con el nombre de cada sonido (move, invalid, flip, deal, win, record_win, no_undo_win) en {} y vuelve a abrir los ajustes",
+    ),
+    ("Volume:", "Volumen:"),
+    ("Effects:", "Efectos:"),
+    ("Music:", "Música:"),
+    (
+        "Pause music while the window is in the background",
+        "Pausar la música mientras la ventana está en segundo plano",
+    ),
+    (
+        "Plays .ogg and .wav tracks from {} in name order",
+        "Reproduce las pistas .ogg y .wav de {} por orden de nombre",
+    ),
+    (
+        "Reduce motion (cards jump into place, hints hold still)",
+        "Reducir el movimiento (las cartas saltan a su sitio, las pistas quedan fijas)",
+    ),
+    (
+        "Games played with accommodations count in your totals but are not ranked.",
+        "Las partidas con adaptaciones cuentan en tus totales pero no en las clasificaciones.",
+    ),
+    (
+        "Text board (one line per pile, for screen readers)",
+        "Tablero en texto (una línea por montón, para lectores de pantalla)",
+    ),
+    ("Input:", "Control:"),
+    ("Standard", "Normal"),
+    ("Dwell click", "Clic por permanencia"),
+    ("Scanning", "Barrido"),
+    ("Rest on a pile for:", "Descansar sobre un montón durante:"),
+    ("Stay on each pile for:", "Quedarse en cada montón durante:"),
+    ("Keyboard shortcuts", "Atajos de teclado"),
+    ("Deal", "Repartir"),
+    ("None", "Ninguno"),
+    ("Change", "Cambiar"),
+    ("Press a key…", "Pulsa una tecla…"),
+    (
+        "Restore Default Shortcuts",
+        "Restaurar atajos predeterminados",
+    ),
+    ("Close", "Cerrar"),
+    // Abandoning and dead ends
+    ("Abandon current game?", "¿Abandonar la partida actual?"),
+    (
+        "This forfeits the current tournament deal.",
+        "Así pierdes el reparto actual del torneo.",
+    ),
+    (
+        "Practice games are not counted in your statistics.",
+        "Las partidas de práctica no cuentan en tus estadísticas.",
+    ),
+    (
+        "It will count as a loss in your statistics.",
+        "Contará como derrota en tus estadísticas.",
+    ),
+    ("Abandon", "Abandonar"),
+    ("Keep Playing", "Seguir jugando"),
+    (
+        "Don't ask again (turn back on in Settings)",
+        "No volver a preguntar (se reactiva en Ajustes)",
+    ),
+    ("No legal moves remain", "No quedan movimientos posibles"),
+    (
+        "No card can be played and the stock is used up.",
+        "No se puede jugar ninguna carta y el mazo se ha agotado.",
+    ),
+    (
+        "No card can be played and the stock can't be dealt again.",
+        "No se puede jugar ninguna carta y el mazo no se puede volver a repartir.",
+    ),
+    ("Restart Deal", "Reiniciar reparto"),
+    ("Look at the Board", "Ver el tablero"),
+    // Dialogs
+    ("OK", "Aceptar"),
+    ("Cancel", "Cancelar"),
+    (
+        "Some saved data was damaged",
+        "Algunos datos guardados estaban dañados",
+    ),
+    (
+        "Could not import the deal",
+        "No se pudo importar el reparto",
+    ),
+    ("Could not load the theme", "No se pudo cargar el tema"),
+    (
+        "Could not load the sound pack",
+        "No se pudo cargar el paquete de sonidos",
+    ),
+    (
+        "Sound pack could not be loaded: {}",
+        "No se pudo cargar el paquete de sonidos: {}",
+    ),
+    (
+        "Keyboard shortcuts could not be loaded: {}",
+        "No se pudieron cargar los atajos de teclado: {}",
+    ),
+    (
+        "Settings could not be reloaded: {}",
+        "No se pudieron volver a cargar los ajustes: {}",
+    ),
+    (
+        "Theme file could not be loaded: {}",
+        "No se pudo cargar el archivo de tema: {}",
+    ),
+    (
+        "Finish the tournament before importing a deal",
+        "Termina el torneo antes de importar un reparto",
+    ),
+    (
+        "Finish the tournament before choosing a game",
+        "Termina el torneo antes de elegir una partida",
+    ),
+    (
+        "Finish the tournament before playing the daily challenge",
+        "Termina el torneo antes de jugar el reto diario",
+    ),
+    (
+        "Finish the tournament before starting another",
+        "Termina el torneo antes de empezar otro",
+    ),
+    (
+        "The clipboard has no text to import",
+        "El portapapeles no tiene texto que importar",
+    ),
+    (
+        "The clipboard has no replay to watch",
+        "El portapapeles no tiene ninguna repetición que ver",
+    ),
+    (
+        "Saved to {} and copied to the clipboard. Paste it into Import Deal to watch it again.",
+        "Guardada en {} y copiada al portapapeles. Pégala en Importar reparto para volver a verla.",
+    ),
+    ("◀ Step", "◀ Paso"),
+    ("Step ▶", "Paso ▶"),
+    ("Play", "Reproducir"),
+    ("Pause", "Pausa"),
+    ("Speed", "Velocidad"),
+    ("Close Replay", "Cerrar repetición"),
+    (
+        "No replays exported yet",
+        "Aún no se ha exportado ninguna repetición",
+    ),
+    ("From Clipboard", "Desde el portapapeles"),
+    ("Type a game number", "Escribe un número de partida"),
+    (
+        "The same number always deals the same cards. Enter to deal, Esc to cancel.",
+        "El mismo número reparte siempre las mismas cartas. Intro para repartir, Esc para cancelar.",
+    ),
+    ("New Game #{}", "Nueva partida n.º {}"),
+    ("Re-roll", "Otra"),
+    (
+        "Recently seen — click one to go back to it",
+        "Vistas hace poco: pulsa una para volver a ella",
+    ),
+    ("Mo", "Lu"),
+    ("Tu", "Ma"),
+    ("We", "Mi"),
+    ("Th", "Ju"),
+    ("Fr", "Vi"),
+    ("Sa", "Sá"),
+    ("Su", "Do"),
+    ("Daily Challenge: {}", "Reto diario: {}"),
+    (
+        "Today's deal won: Score {} | Moves {} | {}",
+        "Reparto de hoy ganado: Puntos {} | Movimientos {} | {}",
+    ),
+    (
+        "Today's deal attempted, not yet won",
+        "Reparto de hoy jugado, aún sin ganar",
+    ),
+    (
+        "Today's deal not played yet",
+        "Reparto de hoy aún sin jugar",
+    ),
+    ("Play Today's Deal", "Jugar el reparto de hoy"),
+    ("Theme files:", "Archivos de tema:"),
+    (
+        "Drop .toml theme files into {} and reopen settings",
+        "Coloca archivos de tema .toml en {} y vuelve a abrir los ajustes",
+    ),
+    ("High Scores: Draw One", "Récords: robar una"),
+    ("High Scores: Draw Three", "Récords: robar tres"),
+    ("Show Draw One", "Ver robar una"),
+    ("Show Draw Three", "Ver robar tres"),
+    ("New high score!", "¡Nuevo récord!"),
+    ("Best Scores", "Mejores puntuaciones"),
+    ("Fastest Wins", "Victorias más rápidas"),
+    ("No wins yet", "Aún no hay victorias"),
+    (
+        "{}. {} pts | {} | {} moves",
+        "{}. {} ptos. | {} | {} movimientos",
+    ),
+    ("Won", "Ganada"),
+    ("Not played", "Sin jugar"),
+    (
+        "{} | Score {} | Moves {} | {}",
+        "{} | Puntos {} | Movimientos {} | {}",
+    ),
+    ("Deal {} (game #{}): {}", "Reparto {} (partida n.º {}): {}"),
+    (
+        "Tournament: {} of {} deals played",
+        "Torneo: {} de {} repartos jugados",
+    ),
+    (
+        "Total: {} won | Score {} | Moves {} | {}",
+        "Total: {} ganados | Puntos {} | Movimientos {} | {}",
+    ),
+    ("Next Deal", "Siguiente reparto"),
+    ("Finish Tournament", "Terminar torneo"),
+    (
+        "Pace {} vs best {} ({})",
+        "Ritmo {} frente al mejor {} ({})",
+    ),
+    ("ahead", "por delante"),
+    ("behind", "por detrás"),
+    ("Pace {} (no best yet)", "Ritmo {} (aún sin mejor tiempo)"),
+    (
+        "Pace: no foundation cards yet",
+        "Ritmo: aún no hay cartas en las fundaciones",
+    ),
+    ("Streak {} (best {})", "Racha {} (mejor {})"),
+    ("Today {} played, {} won", "Hoy {} jugadas, {} ganadas"),
+    ("Diagnostics", "Diagnóstico"),
+    (
+        "Latency in ms over the most recent samples; budget {} ms",
+        "Latencia en ms de las muestras más recientes; límite {} ms",
+    ),
+    ("Resume your game?", "¿Continuar tu partida?"),
+    ("Resume", "Continuar"),
+    ("Daily Challenge {}", "Reto diario {}"),
+    // Status line and trainer
+    ("Draw: One", "Robar: una"),
+    ("Draw: Three", "Robar: tres"),
+    ("Game #{}", "Partida n.º {}"),
+    ("Imported deal", "Reparto importado"),
+    ("Tournament deal {}/{}", "Reparto del torneo {}/{}"),
+    ("Practice", "Práctica"),
+    ("Checking…", "Comprobando…"),
+    ("Winnable", "Se puede ganar"),
+    ("Unknown", "Desconocido"),
+    ("Lost", "Perdida"),
+    (
+        "Winning line: move {} of {}",
+        "Jugada ganadora: movimiento {} de {}",
+    ),
+    (
+        "Replay of {} #{} | Move {} of {}",
+        "Repetición de {} n.º {} | Movimiento {} de {}",
+    ),
+    ("Hint: thinking…", "Pista: pensando…"),
+    (
+        "Hint: {} ({}% win chance)",
+        "Pista: {} ({} % de probabilidad de ganar)",
+    ),
+    ("Hint: no moves left", "Pista: no quedan movimientos"),
+    (
+        "Trainer: {} | Best {}, Ok {}, Blunders {}",
+        "Entrenador: {} | Óptimos {}, Aceptables {}, Errores graves {}",
+    ),
+    ("make a move", "haz un movimiento"),
+    ("Best", "Óptimo"),
+    ("Ok", "Aceptable"),
+    ("Blunder", "Error grave"),
+    (" (better: {})", " (mejor: {})"),
+    // The board in words
+    (
+        "Drag and drop cards to move them! Foundation piles and tableau columns are drop targets.",
+        "¡Arrastra y suelta las cartas para moverlas! Las fundaciones y las columnas de la mesa admiten cartas.",
+    ),
+    ("Stock, next first:", "Mazo, la siguiente primero:"),
+    ("Stock: {}", "Mazo: {}"),
+    ("Waste: {}, top {}", "Descarte: {}, arriba {}"),
+    ("Waste: empty", "Descarte: vacío"),
+    ("Foundation {}: up to {}", "Fundación {}: hasta {}"),
+    ("Foundation {}: empty", "Fundación {}: vacía"),
+    ("Column {}: {}", "Columna {}: {}"),
+    ("cards", "cartas"),
+    ("to the foundation", "a la fundación"),
+    ("onto {} in column {}", "sobre {} en la columna {}"),
+    ("to empty column {}", "a la columna vacía {}"),
+    ("to {}", "a {}"),
+    ("Move {} {}", "Mover {} {}"),
+    ("Turn the waste back over", "Dar la vuelta al descarte"),
+    ("Deal from the stock", "Repartir del mazo"),
+    ("Start a new game", "Empezar una partida nueva"),
+    ("Replay this deal", "Repetir este reparto"),
+    (
+        "Collect every safe card onto the foundations",
+        "Subir a las fundaciones todas las cartas sin riesgo",
+    ),
+    ("empty", "vacío"),
+    ("1 card", "1 carta"),
+    ("{} cards", "{} cartas"),
+    ("{} face down", "{} boca abajo"),
+    ("{} face down, then {}", "{} boca abajo, luego {}"),
+    ("Ace", "As"),
+    ("Jack", "Sota"),
+    ("Queen", "Reina"),
+    ("King", "Rey"),
+    ("Hearts", "Corazones"),
+    ("Diamonds", "Diamantes"),
+    ("Clubs", "Tréboles"),
+    ("Spades", "Picas"),
+    // Error reports
+    ("Something went wrong", "Algo salió mal"),
+    (
+        "Something went wrong with that move",
+        "Algo salió mal con ese movimiento",
+    ),
+    (
+        "Could not write a report: {}",
+        "No se pudo escribir un informe: {}",
+    ),
+    (
+        "A report was saved with the game's version, the deal, the position and the last moves played. Attach it to a bug report so the problem can be found.",
+        "Se guardó un informe con la versión del juego, el reparto, la posición y los últimos movimientos. Adjúntalo a un informe de errores para que se pueda encontrar el problema.",
+    ),
+    ("Open Report", "Abrir informe"),
+    (
+        "Save a report for bug reports when something goes wrong",
+        "Guardar un informe para los informes de errores cuando algo salga mal",
+    ),
+];
+
+const ARABIC: &[(&str, &str)] = &[
+    // Menu bar
+    ("Klondike Solitaire", "سوليتير كلونديك"),
+    ("New Game", "لعبة جديدة"),
+    ("Forfeit Deal", "التخلي عن التوزيعة"),
+    ("Undo", "تراجع"),
+    ("Redo", "إعادة"),
+    ("Auto-complete", "إكمال تلقائي"),
+    ("Collect All", "جمع الكل"),
+    ("Take Over", "تولي اللعب"),
+    ("Show Winning Line", "عرض طريق الفوز"),
+    ("Hint", "تلميح"),
+    ("Quick Stats", "إحصاءات سريعة"),
+    ("Next Move", "الحركة التالية"),
+    ("Previous Move", "الحركة السابقة"),
+    ("Trainer: On", "المدرب: يعمل"),
+    ("Trainer: Off", "المدرب: متوقف"),
+    ("Practice Game", "لعبة تدريبية"),
+    ("Replay Deal", "إعادة التوزيعة"),
+    ("Tournament", "بطولة"),
+    ("Play Game #…", "لعب اللعبة رقم…"),
+    ("Import Deal", "استيراد توزيعة"),
+    ("Share Position", "مشاركة الوضع"),
+    ("Export Replay", "تصدير الإعادة"),
+    ("Watch Replay", "مشاهدة إعادة"),
+    ("Daily Challenge", "التحدي اليومي"),
+    ("High Scores", "أعلى النتائج"),
+    ("Settings", "الإعدادات"),
+    ("Deal a new game", "توزيع لعبة جديدة"),
+    ("Take back the last move", "التراجع عن آخر حركة"),
+    (
+        "Make the move taken back again",
+        "إعادة الحركة التي تم التراجع عنها",
+    ),
+    (
+        "Play every card that can go to the foundations",
+        "لعب كل ورقة يمكن رفعها إلى الأسس",
+    ),
+    (
+        "Move the cards no longer needed on the tableau to the foundations",
+        "نقل الأوراق التي لم تعد لازمة على الطاولة إلى الأسس",
+    ),
+    ("Show a good move", "عرض حركة جيدة"),
+    (
+        "Grade every move against the best one",
+        "تقييم كل حركة مقارنة بأفضلها",
+    ),
+    // Status bar
+    ("Score", "النقاط"),
+    ("Time", "الوقت"),
+    ("Moves", "الحركات"),
+    ("Stock", "الرصيد"),
+    ("Waste", "المهملات"),
+    ("Pass", "الدورة"),
+    ("Last pass", "الدورة الأخيرة"),
+    ("Plays", "لعبات ممكنة"),
+    ("{} of {}", "{} من {}"),
+    (
+        "Points for cards played, less the penalties",
+        "نقاط الأوراق الملعوبة بعد خصم العقوبات",
+    ),
+    ("Vegas", "فيغاس"),
+    (
+        "Dollars won back on cards played, less the stake",
+        "الدولارات المستردة عن الأوراق الملعوبة بعد خصم الرهان",
+    ),
+    (
+        "Moves made this game, deals included",
+        "الحركات في هذه اللعبة، ومنها التوزيعات",
+    ),
+    ("Cards left to deal", "أوراق متبقية للتوزيع"),
+    ("Cards dealt and not yet played", "أوراق موزعة لم تُلعب بعد"),
+    ("Time since the cards were dealt", "الوقت منذ توزيع الأوراق"),
+    (
+        "Times through the stock, counting this one",
+        "مرات المرور على الرصيد، ومنها هذه المرة",
+    ),
+    (
+        "Cards that can be played now, not counting the stock",
+        "الأوراق التي يمكن لعبها الآن، دون احتساب الرصيد",
+    ),
+    (
+        "Move {} of {}: {} (Enter to make it)",
+        "الحركة {} من {}: {} (Enter لتنفيذها)",
+    ),
+    // The stock and foundations
+    ("Deal a card onto every column", "توزيع ورقة على كل عمود"),
+    ("The stock is empty", "الرصيد فارغ"),
+    ("Deal 1 card", "توزيع ورقة واحدة"),
+    ("Deal {} cards", "توزيع {} أوراق"),
+    (
+        "The stock can't be turned over again",
+        "لا يمكن قلب الرصيد مجدداً",
+    ),
+    ("Turn the waste over ({} left)", "قلب المهملات (متبقٍ {})"),
+    ("Turn the waste over", "قلب المهملات"),
+    (
+        "Cards adding up to {} are removed here",
+        "تُزال هنا الأوراق التي مجموعها {}",
+    ),
+    (
+        "Runs from King down to Ace collect here",
+        "تُجمع هنا السلاسل من الملك نزولاً إلى الآس",
+    ),
+    (
+        "Build each suit up from Ace to King",
+        "رتّب كل نوع تصاعدياً من الآس إلى الملك",
+    ),
+    // Refused moves
+    ("Both stock and waste are empty", "الرصيد والمهملات فارغان"),
+    (
+        "The stock cannot be turned over again",
+        "لا يمكن قلب الرصيد مجدداً",
+    ),
+    (
+        "Every column needs a card before dealing",
+        "يحتاج كل عمود إلى ورقة قبل التوزيع",
+    ),
+    ("No cards to move", "لا أوراق لتحريكها"),
+    (
+        "A card cannot pair with itself",
+        "لا يمكن أن تقترن الورقة بنفسها",
+    ),
+    ("Those cards do not add up to {}", "مجموع هذه الأوراق ليس {}"),
+    (
+        "There is no pile to discard to",
+        "لا توجد كومة للتخلص من الأوراق فيها",
+    ),
+    ("No cards are safe to collect", "لا أوراق يمكن جمعها بأمان"),
+    ("That card is covered", "هذه الورقة مغطاة"),
+    (
+        "Only a King can go on an empty column",
+        "لا يوضع في العمود الفارغ إلا الملك",
+    ),
+    ("Foundations start with an Ace", "تبدأ الأسس بالآس"),
+    ("{} can't go on {}", "لا يمكن وضع {} على {}"),
+    ("{} can't go there", "لا يمكن وضع {} هناك"),
+    (
+        "Only one card at a time can go to a foundation",
+        "لا ترفع إلى الأساس إلا ورقة واحدة في كل مرة",
+    ),
+    (
+        "Only one card at a time can go to the waste",
+        "لا توضع في المهملات إلا ورقة واحدة في كل مرة",
+    ),
+    (
+        "Cards cannot be moved to the stock",
+        "لا يمكن نقل الأوراق إلى الرصيد",
+    ),
+    (
+        "An imported deal has no seed to replay",
+        "التوزيعة المستوردة ليس لها رقم لإعادتها",
+    ),
+    ("No moves left", "لم تبق حركات"),
+    (
+        "No cards can go up to the foundations",
+        "لا يمكن رفع أي ورقة إلى الأسس",
+    ),
+    (
+        "Only Klondike positions can be shared",
+        "يمكن مشاركة أوضاع كلونديك فقط",
+    ),
+    (
+        "Position code copied to the clipboard",
+        "نُسخ رمز الوضع إلى الحافظة",
+    ),
+    (
+        "Take over from the winning line first",
+        "تولَّ اللعب من طريق الفوز أولاً",
+    ),
+    ("Close the replay first", "أغلق الإعادة أولاً"),
+    (
+        "Deals cannot be replayed during a tournament",
+        "لا يمكن إعادة التوزيعات أثناء البطولة",
+    ),
+    ("The game is already won", "انتهت اللعبة بالفوز"),
+    ("Nothing to undo", "لا شيء للتراجع عنه"),
+    ("Nothing to redo", "لا شيء لإعادته"),
+    (
+        "Drag the card onto the one to pair it with",
+        "اسحب الورقة إلى الورقة التي تريد أن تقرنها بها",
+    ),
+    ("That card has nowhere to go", "لا مكان لهذه الورقة"),
+    // Settings
+    ("Game", "اللعبة"),
+    (
+        "Draw three cards at a time (from the next game)",
+        "سحب ثلاث أوراق في كل مرة (من اللعبة التالية)",
+    ),
+    (
+        "Scoring (from the next game):",
+        "طريقة الحساب (من اللعبة التالية):",
+    ),
+    (
+        "Ask before abandoning a game in progress",
+        "السؤال قبل ترك لعبة جارية",
+    ),
+    (
+        "Count the cards that can be played",
+        "عدّ الأوراق التي يمكن لعبها",
+    ),
+    ("Animate cards", "تحريك الأوراق"),
+    ("Card motion:", "حركة الأوراق:"),
+    ("Theme:", "المظهر:"),
+    ("Language:", "اللغة:"),
+    ("Text size:", "حجم النص:"),
+    (
+        "Four-color deck (blue diamonds, green clubs)",
+        "أوراق بأربعة ألوان (ديناري أزرق، سباتي أخضر)",
+    ),
+    (
+        "Large index cards (big corner rank and suit, plain center)",
+        "أوراق بفهرس كبير (رتبة ونوع كبيران في الزاوية، ووسط بسيط)",
+    ),
+    (
+        "Count the cards in the stock and face down in each column",
+        "عدّ الأوراق في الرصيد والمقلوبة في كل عمود",
+    ),
+    (
+        "Compact layout (for small windows)",
+        "تخطيط مضغوط (للنوافذ الصغيرة)",
+    ),
+    ("Accessibility", "إمكانية الوصول"),
+    (
+        "Large drop targets (drop anywhere over a column)",
+        "أماكن إفلات كبيرة (الإفلات في أي مكان فوق العمود)",
+    ),
+    ("Untimed play", "لعب بلا توقيت"),
+    (
+        "Sound cues (moves, flips, deals, wins and refused moves)",
+        "إشارات صوتية (الحركات والقلب والتوزيع والفوز والحركات المرفوضة)",
+    ),
+    ("Background music", "موسيقى الخلفية"),
+    ("Sounds:", "الأصوات:"),
+    ("Mute", "كتم الصوت"),
+    ("Unmute", "إلغاء الكتم"),
+    ("Muted", "الصوت مكتوم"),
+    (
+        "Turn all sound off or back on",
+        "إيقاف كل الأصوات أو إعادة تشغيلها",
+    ),
+    ("All sound is off", "كل الأصوات متوقفة"),
+    ("Built-in tones", "النغمات المدمجة"),
+    (
+        "Drop a folder of .wav or .ogg files named after the cues (move, invalid, flip, deal, win, record_win, no_undo_win) into {} and reopen settings",
+        "ضع مجلداً من ملفات .wav أو .ogg مسماة بأسماء الأصوات (move وinvalid وflip وdeal وwin وrecord_win وno_undo_win) في {} وأعد فتح الإعدادات",
+    ),
+    ("Volume:", "مستوى الصوت:"),
+    ("Effects:", "المؤثرات:"),
+    ("Music:", "الموسيقى:"),
+    (
+        "Pause music while the window is in the background",
+        "إيقاف الموسيقى مؤقتاً عندما تكون النافذة في الخلفية",
+    ),
+    (
+        "Plays .ogg and .wav tracks from {} in name order",
+        "تشغّل مقاطع .ogg و.wav من {} بترتيب الأسماء",
+    ),
+    (
+        "Reduce motion (cards jump into place, hints hold still)",
+        "تقليل الحركة (تقفز الأوراق إلى مكانها، وتثبت التلميحات)",
+    ),
+    (
+        "Games played with accommodations count in your totals but are not ranked.",
+        "الألعاب الملعوبة بتسهيلات تُحسب في مجموعك لكنها لا تدخل الترتيب.",
+    ),
+    (
+        "Text board (one line per pile, for screen readers)",
+        "لوحة نصية (سطر لكل كومة، لقارئات الشاشة)",
+    ),
+    ("Input:", "الإدخال:"),
+    ("Standard", "عادي"),
+    ("Dwell click", "النقر بالتوقف"),
+    ("Scanning", "المسح"),
+    ("Rest on a pile for:", "التوقف على كومة لمدة:"),
+    ("Stay on each pile for:", "البقاء على كل كومة لمدة:"),
+    ("Keyboard shortcuts", "اختصارات لوحة المفاتيح"),
+    ("Deal", "توزيع"),
+    ("None", "لا شيء"),
+    ("Change", "تغيير"),
+    ("Press a key…", "اضغط مفتاحاً…"),
+    ("Restore Default Shortcuts", "استعادة الاختصارات الافتراضية"),
+    ("Close", "إغلاق"),
+    // Abandoning and dead ends
+    ("Abandon current game?", "هل تريد ترك اللعبة الحالية؟"),
+    (
+        "This forfeits the current tournament deal.",
+        "بهذا تخسر توزيعة البطولة الحالية.",
+    ),
+    (
+        "Practice games are not counted in your statistics.",
+        "الألعاب التدريبية لا تُحسب في إحصاءاتك.",
+    ),
+    (
+        "It will count as a loss in your statistics.",
+        "ستُحسب خسارة في إحصاءاتك.",
+    ),
+    ("Abandon", "ترك"),
+    ("Keep Playing", "متابعة اللعب"),
+    (
+        "Don't ask again (turn back on in Settings)",
+        "لا تسأل مجدداً (يمكن إعادة التفعيل من الإعدادات)",
+    ),
+    ("No legal moves remain", "لم تبق حركات مسموحة"),
+    (
+        "No card can be played and the stock is used up.",
+        "لا يمكن لعب أي ورقة وقد نفد الرصيد.",
+    ),
+    (
+        "No card can be played and the stock can't be dealt again.",
+        "لا يمكن لعب أي ورقة ولا يمكن التوزيع من الرصيد مجدداً.",
+    ),
+    ("Restart Deal", "بدء التوزيعة من جديد"),
+    ("Look at the Board", "النظر إلى اللوحة"),
+    // Dialogs
+    ("OK", "موافق"),
+    ("Cancel", "إلغاء"),
+    ("Some saved data was damaged", "بعض البيانات المحفوظة تالفة"),
+    ("Could not import the deal", "تعذر استيراد التوزيعة"),
+    ("Could not load the theme", "تعذر تحميل المظهر"),
+    ("Could not load the sound pack", "تعذر تحميل حزمة الأصوات"),
+    (
+        "Sound pack could not be loaded: {}",
+        "تعذر تحميل حزمة الأصوات: {}",
+    ),
+    (
+        "Keyboard shortcuts could not be loaded: {}",
+        "تعذر تحميل اختصارات لوحة المفاتيح: {}",
+    ),
+    (
+        "Settings could not be reloaded: {}",
+        "تعذرت إعادة تحميل الإعدادات: {}",
+    ),
+    (
+        "Theme file could not be loaded: {}",
+        "تعذر تحميل ملف المظهر: {}",
+    ),
+    (
+        "Finish the tournament before importing a deal",
+        "أنهِ البطولة قبل استيراد توزيعة",
+    ),
+    (
+        "Finish the tournament before choosing a game",
+        "أنهِ البطولة قبل اختيار لعبة",
+    ),
+    (
+        "Finish the tournament before playing the daily challenge",
+        "أنهِ البطولة قبل لعب التحدي اليومي",
+    ),
+    (
+        "Finish the tournament before starting another",
+        "أنهِ البطولة قبل بدء أخرى",
+    ),
+    (
+        "The clipboard has no text to import",
+        "لا يوجد في الحافظة نص لاستيراده",
+    ),
+    (
+        "The clipboard has no replay to watch",
+        "لا توجد في الحافظة إعادة لمشاهدتها",
+    ),
+    (
+        "Saved to {} and copied to the clipboard. Paste it into Import Deal to watch it again.",
+        "حُفظت في {} ونُسخت إلى الحافظة. الصقها في استيراد توزيعة لمشاهدتها مجدداً.",
+    ),
+    ("◀ Step", "◀ خطوة"),
+    ("Step ▶", "خطوة ▶"),
+    ("Play", "تشغيل"),
+    ("Pause", "إيقاف مؤقت"),
+    ("Speed", "السرعة"),
+    ("Close Replay", "إغلاق الإعادة"),
+    ("No replays exported yet", "لم تُصدَّر أي إعادة بعد"),
+    ("From Clipboard", "من الحافظة"),
+    ("Type a game number", "اكتب رقم لعبة"),
+    (
+        "The same number always deals the same cards. Enter to deal, Esc to cancel.",
+        "الرقم نفسه يوزع دائماً الأوراق نفسها. Enter للتوزيع، Esc للإلغاء.",
+    ),
+    ("New Game #{}", "لعبة جديدة رقم {}"),
+    ("Re-roll", "رقم آخر"),
+    (
+        "Recently seen — click one to go back to it",
+        "شوهدت مؤخراً — انقر على واحدة للعودة إليها",
+    ),
+    ("Mo", "ن"),
+    ("Tu", "ث"),
+    ("We", "ر"),
+    ("Th", "خ"),
+    ("Fr", "ج"),
+    ("Sa", "س"),
+    ("Su", "ح"),
+    ("Daily Challenge: {}", "التحدي اليومي: {}"),
+    (
+        "Today's deal won: Score {} | Moves {} | {}",
+        "فزت بتوزيعة اليوم: النقاط {} | الحركات {} | {}",
+    ),
+    (
+        "Today's deal attempted, not yet won",
+        "لُعبت توزيعة اليوم ولم تفز بها بعد",
+    ),
+    ("Today's deal not played yet", "لم تُلعب توزيعة اليوم بعد"),
+    ("Play Today's Deal", "لعب توزيعة اليوم"),
+    ("Theme files:", "ملفات المظاهر:"),
+    (
+        "Drop .toml theme files into {} and reopen settings",
+        "ضع ملفات مظاهر .toml في {} وأعد فتح الإعدادات",
+    ),
+    ("High Scores: Draw One", "أعلى النتائج: سحب ورقة"),
+    ("High Scores: Draw Three", "أعلى النتائج: سحب ثلاث"),
+    ("Show Draw One", "عرض سحب ورقة"),
+    ("Show Draw Three", "عرض سحب ثلاث"),
+    ("New high score!", "نتيجة قياسية جديدة!"),
+    ("Best Scores", "أفضل النتائج"),
+    ("Fastest Wins", "أسرع الانتصارات"),
+    ("No wins yet", "لا انتصارات بعد"),
+    ("{}. {} pts | {} | {} moves", "{}. {} نقطة | {} | {} حركة"),
+    ("Won", "فوز"),
+    ("Not played", "لم تُلعب"),
+    (
+        "{} | Score {} | Moves {} | {}",
+        "{} | النقاط {} | الحركات {} | {}",
+    ),
+    ("Deal {} (game #{}): {}", "التوزيعة {} (اللعبة رقم {}): {}"),
+    (
+        "Tournament: {} of {} deals played",
+        "البطولة: لُعبت {} من {} توزيعات",
+    ),
+    (
+        "Total: {} won | Score {} | Moves {} | {}",
+        "المجموع: {} فوز | النقاط {} | الحركات {} | {}",
+    ),
+    ("Next Deal", "التوزيعة التالية"),
+    ("Finish Tournament", "إنهاء البطولة"),
+    ("Pace {} vs best {} ({})", "الوتيرة {} مقابل الأفضل {} ({})"),
+    ("ahead", "متقدم"),
+    ("behind", "متأخر"),
+    ("Pace {} (no best yet)", "الوتيرة {} (لا أفضل بعد)"),
+    (
+        "Pace: no foundation cards yet",
+        "الوتيرة: لا أوراق في الأسس بعد",
+    ),
+    ("Streak {} (best {})", "السلسلة {} (الأفضل {})"),
+    ("Today {} played, {} won", "اليوم لُعبت {} وفزت في {}"),
+    ("Diagnostics", "التشخيص"),
+    (
+        "Latency in ms over the most recent samples; budget {} ms",
+        "زمن الاستجابة بالملي ثانية لأحدث العينات؛ الحد {} ملي ثانية",
+    ),
+    ("Resume your game?", "هل تريد متابعة لعبتك؟"),
+    ("Resume", "متابعة"),
+    ("Daily Challenge {}", "التحدي اليومي {}"),
+    // Status line and trainer
+    ("Draw: One", "السحب: ورقة"),
+    ("Draw: Three", "السحب: ثلاث"),
+    ("Game #{}", "اللعبة رقم {}"),
+    ("Imported deal", "توزيعة مستوردة"),
+    ("Tournament deal {}/{}", "توزيعة البطولة {}/{}"),
+    ("Practice", "تدريب"),
+    ("Checking…", "جارٍ التحقق…"),
+    ("Winnable", "يمكن الفوز"),
+    ("Unknown", "غير معروف"),
+    ("Lost", "خسارة"),
+    ("Winning line: move {} of {}", "طريق الفوز: الحركة {} من {}"),
+    (
+        "Replay of {} #{} | Move {} of {}",
+        "إعادة {} رقم {} | الحركة {} من {}",
+    ),
+    ("Hint: thinking…", "تلميح: جارٍ التفكير…"),
+    ("Hint: {} ({}% win chance)", "تلميح: {} (فرصة الفوز {}٪)"),
+    ("Hint: no moves left", "تلميح: لم تبق حركات"),
+    (
+        "Trainer: {} | Best {}, Ok {}, Blunders {}",
+        "المدرب: {} | الأفضل {}، مقبول {}، أخطاء فادحة {}",
+    ),
+    ("make a move", "قم بحركة"),
+    ("Best", "الأفضل"),
+    ("Ok", "مقبول"),
+    ("Blunder", "خطأ فادح"),
+    (" (better: {})", " (الأفضل: {})"),
+    // The board in words
+    (
+        "Drag and drop cards to move them! Foundation piles and tableau columns are drop targets.",
+        "اسحب الأوراق وأفلتها لتحريكها! الأسس وأعمدة الطاولة أماكن للإفلات.",
+    ),
+    ("Stock, next first:", "الرصيد، التالية أولاً:"),
+    ("Stock: {}", "الرصيد: {}"),
+    ("Waste: {}, top {}", "المهملات: {}، في الأعلى {}"),
+    ("Waste: empty", "المهملات: فارغة"),
+    ("Foundation {}: up to {}", "الأساس {}: حتى {}"),
+    ("Foundation {}: empty", "الأساس {}: فارغ"),
+    ("Column {}: {}", "العمود {}: {}"),
+    ("cards", "أوراق"),
+    ("to the foundation", "إلى الأساس"),
+    ("onto {} in column {}", "على {} في العمود {}"),
+    ("to empty column {}", "إلى العمود الفارغ {}"),
+    ("to {}", "إلى {}"),
+    ("Move {} {}", "تحريك {} {}"),
+    ("Turn the waste back over", "قلب المهملات من جديد"),
+    ("Deal from the stock", "التوزيع من الرصيد"),
+    ("Start a new game", "بدء لعبة جديدة"),
+    ("Replay this deal", "إعادة هذه التوزيعة"),
+    (
+        "Collect every safe card onto the foundations",
+        "جمع كل الأوراق الآمنة على الأسس",
+    ),
+    ("empty", "فارغ"),
+    ("1 card", "ورقة واحدة"),
+    ("{} cards", "{} أوراق"),
+    ("{} face down", "{} مقلوبة"),
+    ("{} face down, then {}", "{} مقلوبة، ثم {}"),
+    ("Ace", "الآس"),
+    ("Jack", "الشاب"),
+    ("Queen", "الملكة"),
+    ("King", "الملك"),
+    ("Hearts", "القلوب"),
+    ("Diamonds", "الديناري"),
+    ("Clubs", "السباتي"),
+    ("Spades", "البستوني"),
+    // Error reports
+    ("Something went wrong", "حدث خطأ ما"),
+    (
+        "Something went wrong with that move",
+        "حدث خطأ ما في تلك الحركة",
+    ),
+    ("Could not write a report: {}", "تعذرت كتابة تقرير: {}"),
+    (
+        "A report was saved with the game's version, the deal, the position and the last moves played. Attach it to a bug report so the problem can be found.",
+        "حُفظ تقرير يتضمن إصدار اللعبة والتوزيعة والوضع وآخر الحركات. أرفقه ببلاغ عن خطأ ليمكن العثور على المشكلة.",
+    ),
+    ("Open Report", "فتح التقرير"),
+    (
+        "Save a report for bug reports when something goes wrong",
+        "حفظ تقرير لبلاغات الأخطاء عند حدوث خطأ",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_keep_placeholders() {
+        for language in Language::all() {
+            let catalog = language.catalog();
+            for (i, (english, translated)) in catalog.iter().enumerate() {
+                assert_eq!(
+                    english.matches("{}").count(),
+                    translated.matches("{}").count(),
+                    "{}",
+                    english
+                );
+                assert!(
+                    !catalog[..i].iter().any(|(earlier, _)| earlier == english),
+                    "{} is translated twice",
+                    english
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalogs_translate_the_same_text() {
+        let english = |language: Language| {
+            let mut keys: Vec<&str> = language
+                .catalog()
+                .iter()
+                .map(|(english, _)| *english)
+                .collect();
+            keys.sort_unstable();
+            keys
+        };
+        for language in Language::all() {
+            if language != Language::English {
+                assert_eq!(
+                    english(language),
+                    english(Language::Spanish),
+                    "{}",
+                    language.code()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_with_fallback() {
+        assert_eq!(tr("Undo"), "Undo");
+        set_language(Language::Spanish);
+        assert_eq!(tr("Undo"), "Deshacer");
+        assert_eq!(tr("Not in the catalog"), "Not in the catalog");
+        assert_eq!(trf("Deal {} cards", &[&3]), "Repartir 3 cartas");
+        assert_eq!(
+            trf("{} can't go on {}", &[&"J♦", &"Q♥"]),
+            "J♦ no puede ir sobre Q♥"
+        );
+        set_language(Language::English);
+        assert_eq!(trf("Deal {} cards", &[&3]), "Deal 3 cards");
+        assert_eq!(directed("Deal 3 cards"), "Deal 3 cards");
+    }
+
+    #[test]
+    fn test_right_to_left_keeps_arguments_apart() {
+        set_language(Language::Arabic);
+        assert!(is_rtl());
+        assert_eq!(tr("Undo"), "تراجع");
+        assert_eq!(
+            trf("{} of {}", &[&2, &3]),
+            "\u{2068}2\u{2069} من \u{2068}3\u{2069}"
+        );
+        assert_eq!(directed("Klondike"), "\u{200F}Klondike");
+        set_language(Language::English);
+        assert!(!is_rtl());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::actions::GameAction;
+use crate::deck::Card;
+use crate::locale::{tr, trf};
+use crate::state::{GameState, Position};
+
+/// The board as plain text, one line per pile, for screen readers and
+/// magnifiers. Cards are named in words and face-down cards only counted, so
+/// the text never reveals more than the graphical board. The text is in the
+/// current language.
+pub fn board_lines(game_state: &GameState) -> Vec<String> {
+    let mut lines = vec![
+        trf("Stock: {}", &[&count(game_state.stock.len())]),
+        match game_state.waste.last() {
+            Some(top) => trf(
+                "Waste: {}, top {}",
+                &[&count(game_state.waste.len()), &name(top)],
+            ),
+            None => tr("Waste: empty").to_string(),
+        },
+    ];
+
+    for (i, pile) in game_state.foundations.iter().enumerate() {
+        lines.push(match pile.last() {
+            Some(top) => trf("Foundation {}: up to {}", &[&(i + 1), &name(top)]),
+            None => trf("Foundation {}: empty", &[&(i + 1)]),
+        });
+    }
+
+    for (col, column) in game_state.tableau.iter().enumerate() {
+        lines.push(trf(
+            "Column {}: {}",
+            &[&(col + 1), &describe_column(column)],
+        ));
+    }
+    lines
+}
+
+/// An action in words as it applies to `game_state`, e.g. "Move 7 of
+/// Diamonds onto 8 of Clubs in column 3", for hints and announcements
+pub fn describe_action(game_state: &GameState, action: &GameAction) -> String {
+    match action {
+        GameAction::MoveCard { from, to } => {
+            let card = game_state
+                .get_cards_at_position(*from)
+                .ok()
+                .and_then(|cards| cards.first().map(name))
+                .unwrap_or_else(|| tr("cards").to_string());
+            let destination = match *to {
+                Position::Foundation(_) => tr("to the foundation").to_string(),
+                Position::Tableau(col, _) => {
+                    match game_state.tableau.get(col).and_then(|c| c.last()) {
+                        Some(top) => trf("onto {} in column {}", &[&name(top), &(col + 1)]),
+                        None => trf("to empty column {}", &[&(col + 1)]),
+                    }
+                }
+                other => trf("to {}", &[&other]),
+            };
+            trf("Move {} {}", &[&card, &destination])
+        }
+        GameAction::DealFromStock if game_state.stock.is_empty() => {
+            tr("Turn the waste back over").to_string()
+        }
+        GameAction::DealFromStock => tr("Deal from the stock").to_string(),
+        GameAction::NewGame => tr("Start a new game").to_string(),
+        GameAction::ReplaySeed => tr("Replay this deal").to_string(),
+        GameAction::Undo => tr("Undo").to_string(),
+        GameAction::Redo => tr("Redo").to_string(),
+        GameAction::CollectAll => tr("Collect every safe card onto the foundations").to_string(),
+    }
+}
+
+/// A card's name in words in the current language, e.g. "7 of Hearts"
+fn name(card: &Card) -> String {
+    trf("{} of {}", &[&tr(card.rank.name()), &tr(card.suit.name())])
+}
+
+fn count(cards: usize) -> String {
+    match cards {
+        0 => tr("empty").to_string(),
+        1 => tr("1 card").to_string(),
+        n => trf("{} cards", &[&n]),
+    }
+}
+
+/// Face-down count followed by the face-up cards, bottom to top
+fn describe_column(column: &[Card]) -> String {
+    if column.is_empty() {
+        return tr("empty").to_string();
+    }
+    let hidden = column.iter().filter(|card| !card.face_up).count();
+    let shown: Vec<String> = column
+        .iter()
+        .filter(|card| card.face_up)
+        .map(name)
+        .collect();
+    match (hidden, shown.is_empty()) {
+        (0, _) => shown.join(", "),
+        (_, true) => trf("{} face down", &[&hidden]),
+        (_, false) => trf("{} face down, then {}", &[&hidden, &shown.join(", ")]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::deck::{Rank, Suit};
+
+    #[test]
+    fn test_one_line_per_pile() {
+        let game_state = GameState::new_with_seed(2038);
+        let lines = board_lines(&game_state);
+        assert_eq!(lines.len(), 2 + 4 + 7);
+        assert_eq!(lines[0], "Stock: 24 cards");
+        assert_eq!(lines[1], "Waste: empty");
+        assert_eq!(lines[2], "Foundation 1: empty");
+
+        let top = game_state.tableau[0][0];
+        assert_eq!(lines[6], format!("Column 1: {}", top.name()));
+        let top = game_state.tableau[6][6];
+        assert_eq!(
+            lines[12],
+            format!("Column 7: 6 face down, then {}", top.name())
+        );
+    }
+
+    #[test]
+    fn test_mirror_follows_moves() {
+        let mut game_state = GameState::new_with_seed(2038);
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        let top = *game_state.waste.last().unwrap();
+        let lines = board_lines(&game_state);
+        assert!(
+            lines[1].ends_with(&format!("top {}", top.name())),
+            "{}",
+            lines[1]
+        );
+
+        game_state.foundations[0].push(Card::new(Suit::Hearts, Rank::Ace, true));
+        game_state.tableau[0].clear();
+        let lines = board_lines(&game_state);
+        assert_eq!(lines[2], "Foundation 1: up to Ace of Hearts");
+        assert_eq!(lines[6], "Column 1: empty");
+    }
+
+    #[test]
+    fn test_describe_action() {
+        let mut game_state = GameState::new_with_seed(2043);
+        game_state.tableau[0] = vec![Card::new(Suit::Clubs, Rank::Eight, true)];
+        game_state.tableau[1] = vec![Card::new(Suit::Diamonds, Rank::Seven, true)];
+        game_state.tableau[2].clear();
+
+        let onto_eight = GameAction::MoveCard {
+            from: Position::Tableau(1, 0),
+            to: Position::Tableau(0, 1),
+        };
+        assert_eq!(
+            describe_action(&game_state, &onto_eight),
+            "Move 7 of Diamonds onto 8 of Clubs in column 1"
+        );
+        let to_empty = GameAction::MoveCard {
+            from: Position::Tableau(1, 0),
+            to: Position::Tableau(2, 0),
+        };
+        assert!(describe_action(&game_state, &to_empty).ends_with("to empty column 3"));
+        assert_eq!(
+            describe_action(&game_state, &GameAction::DealFromStock),
+            "Deal from the stock"
+        );
+    }
+}
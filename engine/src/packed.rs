@@ -0,0 +1,188 @@
+use crate::actions::DrawCount;
+use crate::deck::{Card, Rank};
+use crate::state::GameState;
+
+/// Marks an unused slot or an empty foundation
+const EMPTY: u8 = u8::MAX;
+/// Bit set on a packed card that is face up
+const FACE_UP: u8 = 1 << 6;
+/// Index of the stock and waste lengths after the seven columns
+const STOCK: usize = 7;
+const WASTE: usize = 8;
+
+/// A Klondike position packed into 66 bytes for search.
+///
+/// Each card is a `u8` (its `Card::index`, plus a face-up bit). The tableau
+/// columns, stock and waste are laid end to end in one fixed array with their
+/// lengths alongside, and each foundation keeps only its top card, since the
+/// cards below it follow from it. Positions are `Copy` and hash by value, so
+/// millions of them can go in a transposition table. Score, move count and
+/// timing are not kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedState {
+    cards: [u8; 52],
+    /// Lengths of the seven columns, then the stock and the waste
+    lengths: [u8; 9],
+    foundations: [u8; 4],
+    draw_three: bool,
+}
+
+impl PackedState {
+    pub fn from_game(game_state: &GameState) -> Self {
+        let mut cards = [EMPTY; 52];
+        let mut lengths = [0; 9];
+        let piles = game_state
+            .tableau
+            .iter()
+            .chain([&game_state.stock, &game_state.waste]);
+        let mut next = 0;
+        for (pile, cards_in_pile) in piles.enumerate() {
+            lengths[pile] = cards_in_pile.len() as u8;
+            for card in cards_in_pile {
+                cards[next] = pack(card);
+                next += 1;
+            }
+        }
+
+        PackedState {
+            cards,
+            lengths,
+            foundations: std::array::from_fn(|pile| {
+                game_state.foundations[pile].last().map_or(EMPTY, pack)
+            }),
+            draw_three: game_state.draw_count == DrawCount::Three,
+        }
+    }
+
+    /// Unpack into a playable position with a zero score and move count
+    pub fn to_game(&self) -> GameState {
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.imported = true;
+        game_state.draw_count = if self.draw_three {
+            DrawCount::Three
+        } else {
+            DrawCount::One
+        };
+
+        let mut cards = self.cards.iter().map(|packed| unpack(*packed));
+        let mut take = |pile: usize| cards.by_ref().take(self.lengths[pile] as usize).collect();
+        for col in 0..7 {
+            game_state.tableau[col] = take(col);
+        }
+        game_state.stock = take(STOCK);
+        game_state.waste = take(WASTE);
+
+        for (pile, top) in self.foundations.iter().enumerate() {
+            if *top == EMPTY {
+                continue;
+            }
+            let top = unpack(*top);
+            game_state.foundations[pile] = Rank::all()[..top.rank as usize]
+                .iter()
+                .map(|rank| Card::new(top.suit, *rank, true))
+                .collect();
+        }
+        game_state.game_won = game_state.foundation_card_count() == 52;
+        game_state
+    }
+
+    /// Number of cards on the foundations
+    pub fn foundation_card_count(&self) -> usize {
+        self.foundations
+            .iter()
+            .filter(|top| **top != EMPTY)
+            .map(|top| (top & !FACE_UP) as usize % 13 + 1)
+            .sum()
+    }
+
+    /// Stable 64-bit hash (FNV-1a over the packed bytes), the same on every
+    /// platform and run, for keying transposition tables
+    pub fn hash64(&self) -> u64 {
+        const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01B3;
+        self.cards
+            .iter()
+            .chain(&self.lengths)
+            .chain(&self.foundations)
+            .chain([&(self.draw_three as u8)])
+            .fold(OFFSET, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(PRIME)
+            })
+    }
+}
+
+fn pack(card: &Card) -> u8 {
+    card.index() | if card.face_up { FACE_UP } else { 0 }
+}
+
+fn unpack(packed: u8) -> Card {
+    let mut card = Card::from_index(packed & !FACE_UP).unwrap_or_else(|| unreachable!());
+    card.face_up = packed & FACE_UP != 0;
+    card
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use std::collections::HashSet;
+
+    fn played(seed: u64, deals: usize) -> GameState {
+        let mut game_state = GameState::new_with_seed(seed);
+        for _ in 0..deals {
+            game_state.handle_action(GameAction::DealFromStock).unwrap();
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let game_state = played(2048, 3);
+        let packed = PackedState::from_game(&game_state);
+        let unpacked = packed.to_game();
+        assert_eq!(unpacked.tableau, game_state.tableau);
+        assert_eq!(unpacked.stock, game_state.stock);
+        assert_eq!(unpacked.waste, game_state.waste);
+        assert_eq!(unpacked.draw_count, game_state.draw_count);
+        assert_eq!(PackedState::from_game(&unpacked), packed);
+    }
+
+    #[test]
+    fn test_foundations_keep_only_the_top_card() {
+        let mut game_state = played(1, 0);
+        let mut all: Vec<Card> = game_state
+            .tableau
+            .iter_mut()
+            .flat_map(std::mem::take)
+            .collect();
+        all.append(&mut game_state.stock);
+
+        // Move the Hearts from Ace to Three home
+        let hearts: Vec<Card> = (0..3).map(|i| Card::from_index(i).unwrap()).collect();
+        all.retain(|card| card.index() >= 3);
+        game_state.foundations[2] = hearts
+            .into_iter()
+            .map(|card| Card {
+                face_up: true,
+                ..card
+            })
+            .collect();
+        game_state.stock = all;
+
+        let packed = PackedState::from_game(&game_state);
+        assert_eq!(packed.foundation_card_count(), 3);
+        assert_eq!(packed.to_game().foundations, game_state.foundations);
+    }
+
+    #[test]
+    fn test_hashing_tells_positions_apart() {
+        let first = PackedState::from_game(&played(5, 1));
+        let again = PackedState::from_game(&played(5, 1));
+        let later = PackedState::from_game(&played(5, 2));
+        assert_eq!(first.hash64(), again.hash64());
+        assert_ne!(first.hash64(), later.hash64());
+
+        let table: HashSet<PackedState> = [first, again, later].into_iter().collect();
+        assert_eq!(table.len(), 2);
+    }
+}
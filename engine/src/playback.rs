@@ -0,0 +1,340 @@
+use crate::actions::GameAction;
+use crate::clock::{Clock, FrameClock};
+use crate::state::GameState;
+use crate::transcript::{Transcript, action_notation};
+use std::time::Duration;
+
+/// How fast a replay plays back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaybackTiming {
+    pub fps: u32,
+    /// Time each action's animation takes
+    pub move_duration: Duration,
+    /// Rest between actions, showing the settled board
+    pub pause: Duration,
+}
+
+impl Default for PlaybackTiming {
+    fn default() -> Self {
+        PlaybackTiming {
+            fps: 30,
+            move_duration: Duration::from_millis(300),
+            pause: Duration::from_millis(200),
+        }
+    }
+}
+
+/// What to draw for one frame of playback
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    pub index: u64,
+    /// Simulation time of the frame, independent of the wall clock
+    pub time: Duration,
+    /// Number of actions fully applied to `state`
+    pub step: usize,
+    /// Board to draw; while an action animates, the board before it
+    pub state: &'a GameState,
+    /// Action in flight, if any
+    pub moving: Option<&'a GameAction>,
+    /// Frames of the current action's animation shown so far, out of
+    /// `move_frames`
+    pub move_frame: u64,
+    pub move_frames: u64,
+}
+
+impl Frame<'_> {
+    /// Animation progress of the moving action, from 0 up to (not including) 1
+    pub fn progress(&self) -> f32 {
+        if self.move_frames == 0 {
+            return 0.0;
+        }
+        self.move_frame as f32 / self.move_frames as f32
+    }
+}
+
+/// Frame-by-frame playback of a transcript, driven by simulation time.
+///
+/// Every frame is a pure function of the transcript, the timing and the
+/// frame index, so replays and exported media come out identical across
+/// machines and runs no matter how long each frame takes to render.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    /// Board before each action, followed by the final board
+    states: Vec<GameState>,
+    actions: Vec<GameAction>,
+    move_frames: u64,
+    pause_frames: u64,
+    clock: FrameClock,
+}
+
+impl Playback {
+    /// Prepare playback, applying every action up front. Fails on an action
+    /// that cannot be replayed deterministically or is illegal.
+    pub fn new(transcript: &Transcript, timing: PlaybackTiming) -> Result<Self, String> {
+        Self::from_state(
+            transcript.initial_state(),
+            transcript.actions.clone(),
+            timing,
+        )
+    }
+
+    /// Prepare playback of `actions` from any position, such as a solver's
+    /// winning line from the board in play
+    pub fn from_state(
+        mut state: GameState,
+        actions: Vec<GameAction>,
+        timing: PlaybackTiming,
+    ) -> Result<Self, String> {
+        let mut states = vec![state.clone()];
+        for (i, action) in actions.iter().enumerate() {
+            if *action == GameAction::NewGame {
+                return Err(format!(
+                    "step {}: `new` deals a random game and cannot be replayed",
+                    i + 1
+                ));
+            }
+            state.handle_action(action.clone()).map_err(|error| {
+                format!("step {} ({}): {}", i + 1, action_notation(action), error)
+            })?;
+            states.push(state.clone());
+        }
+
+        let clock = FrameClock::new(timing.fps);
+        Ok(Playback {
+            states,
+            actions,
+            // Every action gets at least one frame so none is skipped
+            move_frames: clock.frames_for(timing.move_duration).max(1),
+            pause_frames: clock.frames_for(timing.pause),
+            clock,
+        })
+    }
+
+    fn frames_per_step(&self) -> u64 {
+        self.move_frames + self.pause_frames
+    }
+
+    /// Total number of frames, ending on one frame of the final board
+    pub fn total_frames(&self) -> u64 {
+        self.actions.len() as u64 * self.frames_per_step() + 1
+    }
+
+    /// Frame at `index`, clamped to the last frame. Random access lets an
+    /// exporter render frames in any order or in parallel.
+    pub fn frame(&self, index: u64) -> Frame<'_> {
+        let index = index.min(self.total_frames() - 1);
+        let clock = FrameClock::at_frame(self.clock.fps(), index);
+
+        let step = (index / self.frames_per_step()) as usize;
+        let within = index % self.frames_per_step();
+        let (state, moving, move_frame) = if step < self.actions.len() && within < self.move_frames
+        {
+            (&self.states[step], Some(&self.actions[step]), within)
+        } else {
+            (&self.states[(step + 1).min(self.actions.len())], None, 0)
+        };
+
+        Frame {
+            index,
+            time: clock.now(),
+            step: if moving.is_some() {
+                step
+            } else {
+                (step + 1).min(self.actions.len())
+            },
+            state,
+            moving,
+            move_frame,
+            move_frames: self.move_frames,
+        }
+    }
+
+    /// Frame for the current playback position
+    pub fn current_frame(&self) -> Frame<'_> {
+        self.frame(self.clock.frame())
+    }
+
+    /// Move playback on by one frame; returns false once the end is reached
+    pub fn advance(&mut self) -> bool {
+        if self.clock.frame() + 1 >= self.total_frames() {
+            return false;
+        }
+        self.clock.advance();
+        true
+    }
+
+    /// Jump to frame `index`, clamped to the last frame
+    pub fn seek(&mut self, index: u64) {
+        self.clock = FrameClock::at_frame(self.clock.fps(), index.min(self.total_frames() - 1));
+    }
+
+    /// Jump to the settled board after the first `step` actions
+    pub fn seek_step(&mut self, step: usize) {
+        let step = step.min(self.actions.len());
+        // The board before any action is only ever shown as the first
+        // action starts moving, and the final board ends playback
+        self.seek(match step {
+            0 => 0,
+            step if step == self.actions.len() => self.total_frames() - 1,
+            step => (step as u64 - 1) * self.frames_per_step() + self.move_frames,
+        });
+    }
+
+    /// Jump past the action being played or up next
+    pub fn step_forward(&mut self) {
+        let step = self.current_frame().step;
+        self.seek_step(step + 1);
+    }
+
+    /// Jump back to the start of the action being played, or before the
+    /// last one applied when none is moving
+    pub fn step_back(&mut self) {
+        let frame = self.current_frame();
+        let step = if frame.moving.is_some() && frame.move_frame > 0 {
+            frame.step
+        } else {
+            frame.step.saturating_sub(1)
+        };
+        self.seek_step(step);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.clock.frame() + 1 >= self.total_frames()
+    }
+
+    /// Actions being played back
+    pub fn actions(&self) -> &[GameAction] {
+        &self.actions
+    }
+
+    /// Board after the first `step` actions
+    pub fn state_after(&self, step: usize) -> &GameState {
+        &self.states[step.min(self.actions.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawCount;
+
+    fn transcript() -> Transcript {
+        let mut transcript = Transcript::new(2036, DrawCount::One);
+        transcript.actions = vec![GameAction::DealFromStock; 3];
+        transcript
+    }
+
+    fn timing() -> PlaybackTiming {
+        PlaybackTiming {
+            fps: 10,
+            move_duration: Duration::from_millis(300),
+            pause: Duration::from_millis(200),
+        }
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        let playback = Playback::new(&transcript(), timing()).unwrap();
+        // 3 move frames + 2 pause frames per action, plus the final frame
+        assert_eq!(playback.total_frames(), 16);
+
+        let first = playback.frame(0);
+        assert_eq!(first.moving, Some(&GameAction::DealFromStock));
+        assert_eq!(first.step, 0);
+        assert!(first.state.waste.is_empty());
+
+        let mid_move = playback.frame(2);
+        assert_eq!(mid_move.move_frame, 2);
+        assert!((mid_move.progress() - 2.0 / 3.0).abs() < 1e-6);
+
+        let pause = playback.frame(3);
+        assert!(pause.moving.is_none());
+        assert_eq!(pause.step, 1);
+        assert_eq!(pause.state.waste.len(), 1);
+        assert_eq!(pause.time, Duration::from_millis(300));
+
+        let last = playback.frame(15);
+        assert_eq!(last.step, 3);
+        assert_eq!(last.state.waste.len(), 3);
+        assert_eq!(playback.frame(1_000).index, 15);
+    }
+
+    #[test]
+    fn test_playback_is_deterministic() {
+        let describe = |playback: &mut Playback| {
+            let mut frames = Vec::new();
+            loop {
+                let frame = playback.current_frame();
+                frames.push((
+                    frame.index,
+                    frame.time,
+                    frame.step,
+                    frame.move_frame,
+                    frame.state.waste.clone(),
+                ));
+                if !playback.advance() {
+                    break;
+                }
+            }
+            frames
+        };
+
+        let mut first = Playback::new(&transcript(), timing()).unwrap();
+        let mut second = Playback::new(&transcript(), timing()).unwrap();
+        let frames = describe(&mut first);
+        assert_eq!(frames, describe(&mut second));
+        assert_eq!(frames.len() as u64, first.total_frames());
+        assert!(first.is_finished());
+    }
+
+    #[test]
+    fn test_playback_from_position() {
+        let mut start = transcript().initial_state();
+        start.handle_action(GameAction::DealFromStock).unwrap();
+        let playback =
+            Playback::from_state(start.clone(), vec![GameAction::DealFromStock; 2], timing())
+                .unwrap();
+
+        assert_eq!(playback.frame(0).state.waste, start.waste);
+        assert_eq!(playback.actions().len(), 2);
+        assert_eq!(playback.state_after(2).waste.len(), 3);
+        assert_eq!(playback.state_after(9).waste.len(), 3);
+    }
+
+    #[test]
+    fn test_stepping_through_playback() {
+        let mut playback = Playback::new(&transcript(), timing()).unwrap();
+        playback.step_forward();
+        assert_eq!(playback.current_frame().index, 3);
+        assert_eq!(playback.current_frame().step, 1);
+        assert!(playback.current_frame().moving.is_none());
+
+        playback.step_forward();
+        playback.step_forward();
+        assert!(playback.is_finished());
+        assert_eq!(playback.current_frame().step, 3);
+
+        // Part way through a move, stepping back returns to its start
+        playback.seek(11);
+        assert!(playback.current_frame().moving.is_some());
+        playback.step_back();
+        assert_eq!(playback.current_frame().step, 2);
+        assert!(playback.current_frame().moving.is_none());
+        playback.step_back();
+        playback.step_back();
+        assert_eq!(playback.current_frame().index, 0);
+        playback.step_back();
+        assert_eq!(playback.current_frame().index, 0);
+    }
+
+    #[test]
+    fn test_unreplayable_transcripts_are_rejected() {
+        let mut transcript = transcript();
+        transcript.actions.push(GameAction::NewGame);
+        assert!(Playback::new(&transcript, timing()).is_err());
+
+        let mut transcript = Transcript::new(1, DrawCount::One);
+        transcript.actions.push(GameAction::Undo);
+        assert!(Playback::new(&transcript, timing()).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::deck::{Card, Rank};
+use crate::registry::{BoardLayout, VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, StockDeal, deal_columns};
+use crate::state::GameState;
+use crate::variant::Variant;
+
+/// Rows in the pyramid, the top row holding one card and the bottom seven
+pub const PYRAMID_ROWS: usize = 7;
+/// Times the waste can be turned back over, for three passes through the stock
+const RECYCLES: u32 = 2;
+
+/// Pyramid: 28 cards dealt face up in a triangle, each half covering the
+/// two below it. Uncovered cards and the top of the waste are removed in
+/// pairs adding up to 13 (Jacks count 11, Queens 12), Kings on their own.
+/// Clearing the pyramid wins.
+///
+/// Each card of the pyramid is its own tableau column, numbered row by row
+/// from the top, so a removed card leaves an empty column behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pyramid;
+
+impl GameRules for Pyramid {
+    fn name(&self) -> &'static str {
+        "Pyramid"
+    }
+
+    /// A single discard pile for removed cards
+    fn foundation_count(&self) -> usize {
+        1
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        deal_columns(deck, slot(PYRAMID_ROWS, 0), |_| 1)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        cards.len() == 1 && cards[0].face_up
+    }
+
+    fn accepts_on_tableau(&self, _card: &Card, _top: Option<&Card>) -> bool {
+        false
+    }
+
+    /// Kings are removed on their own
+    fn accepts_on_foundation(&self, card: &Card, _top: Option<&Card>) -> bool {
+        card.rank == Rank::King
+    }
+
+    fn stock_deal(&self) -> StockDeal {
+        StockDeal::OneToWaste
+    }
+
+    fn recycle_limit(&self) -> Option<u32> {
+        Some(RECYCLES)
+    }
+
+    fn is_covered(&self, tableau: &[Vec<Card>], col: usize) -> bool {
+        let (row, place) = row_and_place(col);
+        row + 1 < PYRAMID_ROWS
+            && [slot(row + 1, place), slot(row + 1, place + 1)]
+                .iter()
+                .any(|below| tableau.get(*below).is_some_and(|column| !column.is_empty()))
+    }
+
+    fn pair_total(&self) -> Option<u8> {
+        Some(13)
+    }
+
+    fn is_won(&self, tableau: &[Vec<Card>], _foundations: &[Vec<Card>]) -> bool {
+        tableau.iter().all(Vec::is_empty)
+    }
+}
+
+/// Column of the card at `place` in `row`, both counted from 0 at the top left
+pub fn slot(row: usize, place: usize) -> usize {
+    row * (row + 1) / 2 + place
+}
+
+/// Row and place in the row of pyramid column `col`
+pub fn row_and_place(col: usize) -> (usize, usize) {
+    let mut row = 0;
+    while slot(row + 1, 0) <= col {
+        row += 1;
+    }
+    (row, col - slot(row, 0))
+}
+
+/// Offer Pyramid to players, previewed by the pyramid's bottom row
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    registry.register(VariantEntry {
+        layout: BoardLayout::Pyramid { rows: PYRAMID_ROWS },
+        preview: |variant, seed| {
+            GameState::with_rules(variant, seed).tableau[slot(PYRAMID_ROWS - 1, 0)..]
+                .iter()
+                .filter_map(|column| column.last().copied())
+                .collect()
+        },
+        ..VariantEntry::new(Variant::new(Pyramid))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{DrawCount, GameAction};
+    use crate::deck::Suit;
+    use crate::state::{GameState, Position};
+
+    /// An empty pyramid with `cards` placed by (row, place)
+    fn pyramid_with(cards: &[(usize, usize, Rank)]) -> GameState<Pyramid> {
+        let mut game_state = GameState::with_rules(Pyramid, 0);
+        game_state.stock.clear();
+        game_state.tableau = vec![Vec::new(); slot(PYRAMID_ROWS, 0)];
+        for (row, place, rank) in cards {
+            game_state.tableau[slot(*row, *place)] = vec![Card::new(Suit::Clubs, *rank, true)];
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_pyramid_slots() {
+        assert_eq!(slot(0, 0), 0);
+        assert_eq!(slot(6, 6), 27);
+        for col in 0..28 {
+            let (row, place) = row_and_place(col);
+            assert!(place <= row);
+            assert_eq!(slot(row, place), col);
+        }
+
+        let game_state = GameState::with_rules(Pyramid, 2054);
+        assert_eq!(game_state.tableau.len(), 28);
+        assert!(game_state.tableau.iter().all(|column| column[0].face_up));
+        assert_eq!(game_state.stock.len(), 24);
+        // Only the bottom row starts uncovered
+        for col in 0..28 {
+            let covered = Pyramid.is_covered(&game_state.tableau, col);
+            assert_eq!(covered, row_and_place(col).0 < 6, "column {}", col);
+        }
+    }
+
+    #[test]
+    fn test_pairs_adding_to_13_are_removed() {
+        let mut game_state =
+            pyramid_with(&[(5, 0, Rank::Two), (6, 0, Rank::Eight), (6, 1, Rank::Five)]);
+        let covered = GameAction::MoveCard {
+            from: Position::Tableau(slot(5, 0), 0),
+            to: Position::Tableau(slot(6, 0), 1),
+        };
+        assert!(game_state.handle_action(covered).is_err());
+
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Tableau(slot(6, 1), 0),
+                to: Position::Tableau(slot(6, 0), 1),
+            })
+            .unwrap();
+        assert_eq!(game_state.foundations[0].len(), 2);
+        // The Two is uncovered now, but has no partner
+        assert!(!Pyramid.is_covered(&game_state.tableau, slot(5, 0)));
+        assert!(game_state.is_stuck());
+        assert!(!game_state.game_won);
+    }
+
+    #[test]
+    fn test_kings_and_waste_clear_the_pyramid() {
+        let mut game_state = pyramid_with(&[(6, 0, Rank::King), (6, 1, Rank::Jack)]);
+        game_state.stock = vec![Card::new(Suit::Hearts, Rank::Two, false)];
+
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Tableau(slot(6, 0), 0),
+                to: Position::Foundation(0),
+            })
+            .unwrap();
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Waste(0),
+                to: Position::Tableau(slot(6, 1), 1),
+            })
+            .unwrap();
+        assert!(game_state.game_won);
+    }
+
+    #[test]
+    fn test_stock_turns_over_twice() {
+        let mut game_state = GameState::with_rules(Pyramid, 1);
+        game_state.draw_count = DrawCount::Three;
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        assert_eq!(game_state.waste.len(), 1);
+
+        assert_eq!(game_state.recycles_left(), Some(2));
+        assert_eq!(game_state.stock_passes(), Some(3));
+        assert!(!game_state.is_last_pass());
+
+        let mut passes = 1;
+        while game_state.handle_action(GameAction::DealFromStock).is_ok() {
+            if game_state.waste.is_empty() {
+                passes += 1;
+            }
+        }
+        assert_eq!(passes, 3);
+        assert_eq!(game_state.stock_pass(), 3);
+        assert!(game_state.is_last_pass());
+        assert!(game_state.stock.is_empty());
+        assert_eq!(game_state.recycles_left(), Some(0));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::deck::Card;
+use crate::reroll;
+use crate::rules::GameRules;
+use crate::variant::Variant;
+use crate::{double_klondike, golf, pyramid, rules, spider, thoughtful};
+use std::sync::OnceLock;
+
+/// Variants offered to players, filled in once at startup
+static REGISTRY: OnceLock<VariantRegistry> = OnceLock::new();
+
+/// How a variant's board is arranged on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardLayout {
+    /// Stock, waste and foundations in a row above the tableau columns
+    Columns,
+    /// The tableau as a pyramid `rows` deep, each card its own column
+    Pyramid { rows: usize },
+    /// Tableau columns with the stock and waste centred below them and no
+    /// foundations shown
+    StockBelow,
+}
+
+/// A variant as the app offers it: its rules, how its board is laid out and
+/// how a deal is previewed before it is chosen
+#[derive(Debug, Clone, Copy)]
+pub struct VariantEntry {
+    pub variant: Variant,
+    pub layout: BoardLayout,
+    /// Cards shown on the new game screen for a deal of the variant from a
+    /// seed
+    pub preview: fn(Variant, u64) -> Vec<Card>,
+}
+
+impl VariantEntry {
+    /// `variant` laid out in columns, previewed by the top card of each
+    pub fn new(variant: Variant) -> Self {
+        VariantEntry {
+            variant,
+            layout: BoardLayout::Columns,
+            preview: reroll::preview,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.variant.name()
+    }
+}
+
+/// Every variant the app offers, in the order they are listed. Each variant
+/// registers itself, so the app shell and menus find new ones here.
+#[derive(Debug, Clone, Default)]
+pub struct VariantRegistry {
+    entries: Vec<VariantEntry>,
+}
+
+impl VariantRegistry {
+    /// The variants that come with the engine
+    pub fn builtin() -> Self {
+        let mut registry = VariantRegistry::default();
+        let registers: [fn(&mut VariantRegistry) -> Result<(), String>; 6] = [
+            rules::register,
+            double_klondike::register,
+            thoughtful::register,
+            spider::register,
+            pyramid::register,
+            golf::register,
+        ];
+        registers
+            .iter()
+            .try_for_each(|register| register(&mut registry))
+            .expect("the built-in variants have different names");
+        registry
+    }
+
+    /// Add `entry` to the end of the list. A name already taken is refused,
+    /// so nothing can stand in for a variant registered before it.
+    pub fn register(&mut self, entry: VariantEntry) -> Result<(), String> {
+        if self.find(entry.name()).is_some() {
+            return Err(format!(
+                "a variant named {} is already registered",
+                entry.name()
+            ));
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[VariantEntry] {
+        &self.entries
+    }
+
+    /// The variant registered under `name`
+    pub fn find(&self, name: &str) -> Option<&VariantEntry> {
+        self.entries.iter().find(|entry| entry.name() == name)
+    }
+
+    /// How `variant`'s board is laid out, in columns if it isn't registered
+    pub fn layout(&self, variant: Variant) -> BoardLayout {
+        self.entry(variant)
+            .map_or(BoardLayout::Columns, |entry| entry.layout)
+    }
+
+    /// Cards previewing a deal of `variant` from `seed`, the top of each
+    /// column if it isn't registered
+    pub fn preview(&self, variant: Variant, seed: u64) -> Vec<Card> {
+        match self.entry(variant) {
+            Some(entry) => (entry.preview)(variant, seed),
+            None => reroll::preview(variant, seed),
+        }
+    }
+
+    fn entry(&self, variant: Variant) -> Option<&VariantEntry> {
+        self.entries.iter().find(|entry| entry.variant == variant)
+    }
+}
+
+/// Use `registry` in place of the built-in variants. It must be installed
+/// before any variant is looked up.
+pub fn install(registry: VariantRegistry) -> Result<(), String> {
+    REGISTRY
+        .set(registry)
+        .map_err(|_| "The variants were already registered".to_string())
+}
+
+/// The variants on offer, the built-in ones unless others were installed
+pub fn variants() -> &'static VariantRegistry {
+    REGISTRY.get_or_init(VariantRegistry::builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golf::Golf;
+    use crate::rules::Klondike;
+
+    #[test]
+    fn test_builtin_variants_register_in_menu_order() {
+        let registry = VariantRegistry::builtin();
+        let names: Vec<&str> = registry.entries().iter().map(VariantEntry::name).collect();
+        assert_eq!(names.first(), Some(&"Klondike"));
+        assert_eq!(names.last(), Some(&"Golf"));
+        assert_eq!(names.len(), 8);
+        assert_eq!(registry.layout(Variant::new(Golf)), BoardLayout::StockBelow);
+        let pyramid = registry.find("Pyramid").unwrap().variant;
+        assert_eq!(registry.preview(pyramid, 2053).len(), 7);
+        assert!(registry.find("Canfield").is_none());
+    }
+
+    #[test]
+    fn test_registering_a_name_again_is_refused() {
+        let mut registry = VariantRegistry::default();
+        registry
+            .register(VariantEntry::new(Variant::new(Klondike)))
+            .unwrap();
+        let again = registry.register(VariantEntry {
+            layout: BoardLayout::StockBelow,
+            ..VariantEntry::new(Variant::new(Klondike))
+        });
+        assert!(again.is_err());
+        assert_eq!(registry.entries().len(), 1);
+        assert_eq!(
+            registry.layout(Variant::new(Klondike)),
+            BoardLayout::Columns
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use crate::actions::{DrawCount, GameAction};
+use crate::history::History;
+use crate::rules::GameRules;
+use crate::state::GameState;
+use crate::storage;
+use crate::transcript::{action_notation, parse_action};
+use crate::variant::Variant;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File extension of exported replays
+pub const REPLAY_EXTENSION: &str = "vsreplay";
+/// Version of the replay format written by this build
+const REPLAY_FORMAT: u32 = 1;
+
+/// A game recorded for sharing: the deal's variant, seed and draw mode,
+/// and every move played on it from the start. Anyone with the same build
+/// can replay it move for move.
+///
+/// The file form is a small TOML document:
+///
+/// ```text
+/// # vibe-solitaire replay
+/// format = 1
+/// variant = "Klondike"
+/// seed = "1234"
+/// draw_count = "Three"
+/// recorded_at = 1760486400
+/// actions = ["deal", "move W0 T3.4", "move T3.4 F2"]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub variant: Variant,
+    pub seed: u64,
+    pub draw_count: DrawCount,
+    /// Moves from the deal, in the order played
+    pub actions: Vec<GameAction>,
+    /// Seconds since the Unix epoch when the replay was recorded
+    pub recorded_at: u64,
+}
+
+impl Replay {
+    /// Record the moves that led to the current position of a game, from
+    /// its undo history
+    pub fn from_game(history: &History) -> Result<Self, String> {
+        let deal = history.base();
+        if deal.imported {
+            return Err("Imported deals have no seed to replay from".to_string());
+        }
+        if deal.move_count > 0 {
+            return Err("The game is too long to record from the deal".to_string());
+        }
+
+        Ok(Replay {
+            variant: deal.rules,
+            seed: deal.seed,
+            draw_count: deal.draw_count,
+            actions: history.applied_actions().cloned().collect(),
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+    }
+
+    /// Folder exported replays are written to
+    pub fn default_dir() -> PathBuf {
+        storage::data_dir().join("replays")
+    }
+
+    /// File name for the replay, e.g. `klondike-1234-1760486400.vsreplay`
+    pub fn file_name(&self) -> String {
+        let variant: String = self
+            .variant
+            .name()
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                c if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect();
+        format!(
+            "{}-{}-{}.{}",
+            variant, self.seed, self.recorded_at, REPLAY_EXTENSION
+        )
+    }
+
+    /// The deal before any move
+    pub fn initial_state(&self) -> GameState {
+        let mut game_state = GameState::with_rules(self.variant, self.seed);
+        game_state.draw_count = self.draw_count;
+        game_state
+    }
+
+    /// Play every move on the deal, failing at the first illegal one
+    pub fn final_state(&self) -> Result<GameState, String> {
+        let mut game_state = self.initial_state();
+        for (i, action) in self.actions.iter().enumerate() {
+            if matches!(
+                action,
+                GameAction::NewGame | GameAction::ReplaySeed | GameAction::Undo | GameAction::Redo
+            ) {
+                return Err(format!(
+                    "step {}: `{}` cannot be replayed",
+                    i + 1,
+                    action_notation(action)
+                ));
+            }
+            game_state.handle_action(action.clone()).map_err(|error| {
+                format!("step {} ({}): {}", i + 1, action_notation(action), error)
+            })?;
+        }
+        Ok(game_state)
+    }
+
+    /// Parse a replay and check that every move in it is legal
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let header: ReplayHeader = storage::parse(text)?;
+        match header.format {
+            Some(REPLAY_FORMAT) => {}
+            Some(format) => return Err(format!("unsupported replay format {}", format)),
+            None => return Err("not a replay: missing `format`".to_string()),
+        }
+        let replay = Self::try_from(storage::parse::<ReplayFile>(text)?)?;
+        replay.final_state()?;
+        Ok(replay)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Replay files in `dir`, most recently written first
+    pub fn saved_in(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<(SystemTime, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == REPLAY_EXTENSION))
+            .map(|path| {
+                let modified = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(UNIX_EPOCH);
+                (modified, path)
+            })
+            .collect();
+        files.sort_by(|a, b| b.cmp(a));
+        files.into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Write the replay into `dir` under its file name, returning the path
+    pub fn save_in(&self, dir: &Path) -> Result<PathBuf, String> {
+        let path = dir.join(self.file_name());
+        storage::save(&path, &ReplayFile::from(self))?;
+        Ok(path)
+    }
+}
+
+/// Just the format version, read first so a replay from a newer build is
+/// refused as such rather than for a key it renamed
+#[derive(Deserialize)]
+struct ReplayHeader {
+    format: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReplayFile {
+    format: u32,
+    variant: Variant,
+    #[serde(with = "storage::seed")]
+    seed: u64,
+    #[serde(default)]
+    draw_count: DrawCount,
+    #[serde(default)]
+    recorded_at: u64,
+    actions: Vec<String>,
+}
+
+impl From<&Replay> for ReplayFile {
+    fn from(replay: &Replay) -> Self {
+        ReplayFile {
+            format: REPLAY_FORMAT,
+            variant: replay.variant,
+            seed: replay.seed,
+            draw_count: replay.draw_count,
+            recorded_at: replay.recorded_at,
+            actions: replay.actions.iter().map(action_notation).collect(),
+        }
+    }
+}
+
+impl TryFrom<ReplayFile> for Replay {
+    type Error = String;
+
+    fn try_from(file: ReplayFile) -> Result<Self, String> {
+        Ok(Replay {
+            variant: file.variant,
+            seed: file.seed,
+            draw_count: file.draw_count,
+            actions: file
+                .actions
+                .iter()
+                .map(|text| parse_action(text))
+                .collect::<Result<Vec<_>, _>>()?,
+            recorded_at: file.recorded_at,
+        })
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = storage::to_text(&ReplayFile::from(self)).map_err(|_| fmt::Error)?;
+        writeln!(f, "# vibe-solitaire replay")?;
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::GameCommand;
+    use crate::history::HistoryLimits;
+    use crate::pyramid::Pyramid;
+    use crate::state::Position;
+
+    /// A Pyramid game with four deals played and the last one taken back
+    fn recorded_history() -> (GameState, History) {
+        let mut game_state = GameState::with_rules(Variant::new(Pyramid), 2070);
+        let mut history = History::new(game_state.clone(), HistoryLimits::default());
+        for _ in 0..4 {
+            let command = GameCommand::apply(&mut game_state, GameAction::DealFromStock).unwrap();
+            history.record(command, &game_state);
+        }
+        let game_state = history.undo().unwrap();
+        (game_state, history)
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let (game_state, history) = recorded_history();
+        let replay = Replay::from_game(&history).unwrap();
+        assert_eq!(replay.actions.len(), 3);
+
+        let parsed = Replay::parse(&replay.to_string()).unwrap();
+        assert_eq!(parsed, replay);
+        let replayed = parsed.final_state().unwrap();
+        assert_eq!(replayed.waste, game_state.waste);
+        assert_eq!(replayed.move_count, game_state.move_count);
+        assert!(replay.file_name().starts_with("pyramid-2070-"));
+        assert!(replay.file_name().ends_with(".vsreplay"));
+    }
+
+    #[test]
+    fn test_replays_are_checked() {
+        let (_, history) = recorded_history();
+        let mut replay = Replay::from_game(&history).unwrap();
+        replay.actions.push(GameAction::MoveCard {
+            from: Position::Foundation(0),
+            to: Position::Tableau(27, 1),
+        });
+        let error = Replay::parse(&replay.to_string()).unwrap_err();
+        assert!(error.starts_with("step 4"), "{}", error);
+
+        replay.actions = vec![GameAction::Undo];
+        assert!(Replay::parse(&replay.to_string()).is_err());
+        assert!(Replay::parse("format = 2").is_err());
+        assert!(Replay::parse("T1: 7D").is_err());
+
+        let mut imported = GameState::new_with_seed(1);
+        imported.imported = true;
+        let history = History::new(imported, HistoryLimits::default());
+        assert!(Replay::from_game(&history).is_err());
+    }
+
+    #[test]
+    fn test_replay_saved_and_loaded() {
+        let dir = std::env::temp_dir().join(format!(
+            "vibe-solitaire-test-{}-replays",
+            std::process::id()
+        ));
+        let (_, history) = recorded_history();
+        let replay = Replay::from_game(&history).unwrap();
+        let path = replay.save_in(&dir).unwrap();
+        assert_eq!(Replay::load(&path).unwrap(), replay);
+        assert!(Replay::saved_in(&dir).contains(&path));
+        assert!(Replay::saved_in(&dir.join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::actions::GameAction;
+use crate::rules::GameRules;
+use crate::state::GameState;
+use crate::storage;
+use crate::transcript::action_notation;
+use std::collections::VecDeque;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// Most recent actions kept for a report
+pub const RECENT_ACTIONS: usize = 50;
+
+/// File in the reports folder naming the report the last crash left, until
+/// the app has offered it to the player
+const PENDING_CRASH: &str = "last_crash.txt";
+
+/// What went wrong, with enough of the game around it for a bug report to
+/// be followed up: the app's version, the deal, the position and the last
+/// actions played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub reason: String,
+    pub version: &'static str,
+    pub variant: &'static str,
+    pub seed: u64,
+    /// The position as `GameState::encode` writes it, which only reads back
+    /// for Klondike
+    pub position: String,
+    /// Actions played, oldest first, in transcript notation
+    pub actions: Vec<String>,
+}
+
+impl Report {
+    /// Folder reports are written to
+    pub fn default_dir() -> PathBuf {
+        storage::data_dir().join("reports")
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "reason: {}\nversion: {}\nvariant: {}\nseed: {}\nposition: {}\n\nrecent actions:\n",
+            self.reason, self.version, self.variant, self.seed, self.position
+        );
+        for action in &self.actions {
+            text.push_str(action);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Write the report into `dir` under a name of its own, returning where
+    pub fn save_in(&self, dir: &Path) -> Result<PathBuf, String> {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+        let stamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis());
+        let path = dir.join(format!("report-{}.txt", stamp));
+        fs::write(&path, self.to_text()).map_err(|error| error.to_string())?;
+        Ok(path)
+    }
+}
+
+/// The game as it stood after the last action, kept for a report
+#[derive(Debug, Default)]
+struct Recent {
+    /// Whether the player has chosen to have reports written
+    enabled: bool,
+    variant: &'static str,
+    seed: u64,
+    position: String,
+    actions: VecDeque<String>,
+}
+
+/// Keeps what a report needs as a game is played, so one can be written
+/// even from a panic, when the app itself can't be asked. Reports are
+/// opt-in: nothing is written until `set_enabled` turns them on.
+#[derive(Debug, Clone)]
+pub struct Reporter {
+    version: &'static str,
+    dir: PathBuf,
+    recent: Arc<Mutex<Recent>>,
+}
+
+impl Reporter {
+    /// A reporter writing reports for `version` of the app into `dir`
+    pub fn new(version: &'static str, dir: PathBuf) -> Self {
+        Reporter {
+            version,
+            dir,
+            recent: Arc::default(),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.with_recent(|recent| recent.enabled = enabled);
+    }
+
+    /// A new game began, or was resumed, as `game_state`
+    pub fn start(&self, game_state: &GameState) {
+        self.with_recent(|recent| {
+            recent.variant = game_state.rules.name();
+            recent.seed = game_state.seed;
+            recent.position = game_state.encode();
+            recent.actions.clear();
+        });
+    }
+
+    /// `action` was played, leaving `game_state`
+    pub fn record(&self, action: &GameAction, game_state: &GameState) {
+        self.with_recent(|recent| {
+            if recent.actions.len() == RECENT_ACTIONS {
+                recent.actions.pop_front();
+            }
+            recent.actions.push_back(action_notation(action));
+            recent.position = game_state.encode();
+        });
+    }
+
+    /// A report of `reason` about the game as last recorded
+    pub fn report(&self, reason: &str) -> Report {
+        self.with_recent(|recent| Report {
+            reason: reason.to_string(),
+            version: self.version,
+            variant: recent.variant,
+            seed: recent.seed,
+            position: recent.position.clone(),
+            actions: recent.actions.iter().cloned().collect(),
+        })
+    }
+
+    /// Write a report of `reason` if the player opted in, returning where
+    pub fn write(&self, reason: &str) -> Option<Result<PathBuf, String>> {
+        if !self.with_recent(|recent| recent.enabled) {
+            return None;
+        }
+        Some(self.report(reason).save_in(&self.dir))
+    }
+
+    /// Write a report when the app panics, after the usual panic message,
+    /// and leave its path for `take_crash_report` on the next start
+    pub fn install_panic_hook(&self) {
+        let reporter = self.clone();
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            previous(info);
+            match reporter.write(&info.to_string()) {
+                Some(Ok(path)) => {
+                    eprintln!("Crash report written to {}", path.display());
+                    let _ = fs::write(
+                        reporter.dir.join(PENDING_CRASH),
+                        path.to_string_lossy().as_bytes(),
+                    );
+                }
+                Some(Err(error)) => eprintln!("Failed to write a crash report: {}", error),
+                None => {}
+            }
+        }));
+    }
+
+    /// The report the last crash left, once: it is only offered the first
+    /// time the app starts after the crash
+    pub fn take_crash_report(&self) -> Option<PathBuf> {
+        let pending = self.dir.join(PENDING_CRASH);
+        let path = PathBuf::from(fs::read_to_string(&pending).ok()?.trim());
+        let _ = fs::remove_file(&pending);
+        path.exists().then_some(path)
+    }
+
+    /// Run `f` on the recent game, even if a panic left it locked
+    fn with_recent<T>(&self, f: impl FnOnce(&mut Recent) -> T) -> T {
+        f(&mut self.recent.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// What a move from `before` broke in `after`, if anything: every card
+/// must still be on the board exactly once
+pub fn invariant_violation(before: &GameState, after: &GameState) -> Option<String> {
+    let cards = |game_state: &GameState| {
+        let mut cards: Vec<u8> = game_state
+            .piles()
+            .flat_map(|pile| game_state.cards(pile))
+            .map(|card| card.index())
+            .collect();
+        cards.sort_unstable();
+        cards
+    };
+    (cards(before) != cards(after)).then(|| {
+        format!(
+            "the cards on the board changed from {} to {}",
+            before.encode(),
+            after.encode()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "vibe-solitaire-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_reports_are_written_only_when_enabled() {
+        let dir = test_dir("reports");
+        let reporter = Reporter::new("1.2.3", dir.clone());
+        let mut game_state = GameState::new_with_seed(2145);
+        reporter.start(&game_state);
+        game_state.deal_from_stock().unwrap();
+        reporter.record(&GameAction::DealFromStock, &game_state);
+        assert_eq!(reporter.write("broken"), None);
+
+        reporter.set_enabled(true);
+        let path = reporter.write("broken").unwrap().unwrap();
+        let text = fs::read_to_string(path).unwrap();
+        assert!(
+            text.starts_with("reason: broken\nversion: 1.2.3\nvariant: Klondike\nseed: 2145\n")
+        );
+        assert!(text.contains(&game_state.encode()));
+        assert!(text.ends_with("recent actions:\ndeal\n"), "{}", text);
+        assert_eq!(reporter.take_crash_report(), None);
+    }
+
+    #[test]
+    fn test_lost_card_is_an_invariant_violation() {
+        let before = GameState::new_with_seed(7);
+        let mut after = before.clone();
+        after.deal_from_stock().unwrap();
+        assert_eq!(invariant_violation(&before, &after), None);
+
+        after.stock.pop();
+        assert!(invariant_violation(&before, &after).is_some());
+    }
+}
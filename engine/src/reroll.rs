@@ -0,0 +1,125 @@
+use crate::deck::Card;
+use crate::state::GameState;
+use crate::variant::Variant;
+use rand::{Rng, thread_rng};
+
+/// Number of recently seen seeds kept in the breadcrumb
+pub const SEED_BREADCRUMB_LEN: usize = 8;
+
+/// Seeds offered on the new-game screen before dealing.
+///
+/// Every re-roll shows a fresh seed and keeps the ones already seen, oldest
+/// first, so a player can go back to a deal they skipped.
+#[derive(Debug, Clone)]
+pub struct SeedRoll {
+    seeds: Vec<u64>,
+    current: usize,
+}
+
+impl SeedRoll {
+    pub fn new(seed: u64) -> Self {
+        SeedRoll {
+            seeds: vec![seed],
+            current: 0,
+        }
+    }
+
+    pub fn with_random_seed() -> Self {
+        Self::new(thread_rng().r#gen())
+    }
+
+    /// Seed currently on show
+    pub fn current(&self) -> u64 {
+        self.seeds[self.current]
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Recently seen seeds, oldest first
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Show a freshly rolled seed
+    pub fn reroll(&mut self) -> u64 {
+        self.show(thread_rng().r#gen())
+    }
+
+    /// Show `seed`, appending it to the breadcrumb and dropping the oldest
+    /// seed once the breadcrumb is full
+    pub fn show(&mut self, seed: u64) -> u64 {
+        self.seeds.retain(|seen| *seen != seed);
+        self.seeds.push(seed);
+        if self.seeds.len() > SEED_BREADCRUMB_LEN {
+            self.seeds.remove(0);
+        }
+        self.current = self.seeds.len() - 1;
+        seed
+    }
+
+    /// Go back to the seed at `index` in the breadcrumb
+    pub fn select(&mut self, index: usize) -> Option<u64> {
+        if index >= self.seeds.len() {
+            return None;
+        }
+        self.current = index;
+        Some(self.current())
+    }
+}
+
+/// Face-up cards along the first row of the deal for `seed` in `variant`,
+/// one per column
+pub fn preview(variant: Variant, seed: u64) -> Vec<Card> {
+    GameState::with_rules(variant, seed)
+        .tableau
+        .iter()
+        .filter_map(|column| column.last().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breadcrumb_keeps_recent_seeds() {
+        let mut roll = SeedRoll::new(1);
+        for seed in 2..=10 {
+            assert_eq!(roll.show(seed), seed);
+        }
+        assert_eq!(roll.seeds(), &[3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(roll.current(), 10);
+
+        // Going back keeps the breadcrumb intact
+        assert_eq!(roll.select(2), Some(5));
+        assert_eq!(roll.seeds().len(), SEED_BREADCRUMB_LEN);
+        assert_eq!(roll.select(SEED_BREADCRUMB_LEN), None);
+        assert_eq!(roll.current(), 5);
+
+        // A seed seen again moves to the end rather than appearing twice
+        roll.show(4);
+        assert_eq!(roll.seeds(), &[3, 5, 6, 7, 8, 9, 10, 4]);
+        assert_eq!(roll.current_index(), 7);
+    }
+
+    #[test]
+    fn test_reroll_shows_new_seed() {
+        let mut roll = SeedRoll::new(0);
+        let seed = roll.reroll();
+        assert_eq!(roll.current(), seed);
+        assert_eq!(roll.seeds().last(), Some(&seed));
+    }
+
+    #[test]
+    fn test_preview_matches_deal() {
+        let game_state = GameState::new_with_seed(2037);
+        let row = preview(Variant::default(), 2037);
+        assert_eq!(row.len(), 7);
+        for (card, column) in row.iter().zip(&game_state.tableau) {
+            assert_eq!(Some(card), column.last());
+            assert!(card.face_up);
+        }
+    }
+}
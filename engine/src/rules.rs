@@ -0,0 +1,198 @@
+use crate::deck::{Card, Rank, create_deck};
+use crate::registry::{VariantEntry, VariantRegistry};
+use crate::variant::Variant;
+use std::fmt;
+
+/// What dealing from the stock does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockDeal {
+    /// Turn cards over onto the waste, as many as the draw setting says,
+    /// recycling it when the stock runs out
+    ToWaste,
+    /// Turn one card over onto the waste whatever the draw setting
+    OneToWaste,
+    /// Deal one card face up onto every column, once none is empty
+    RowToTableau,
+}
+
+/// The rules that differ between solitaire variants: how the cards are
+/// dealt, what may be built on the tableau and foundations, and when the
+/// game is won. `GameState` is generic over its rules and asks them before
+/// every move.
+pub trait GameRules: fmt::Debug {
+    /// Name of the variant, e.g. "Klondike"
+    fn name(&self) -> &'static str;
+
+    /// The unshuffled cards the variant is played with
+    fn deck(&self) -> Vec<Card> {
+        create_deck()
+    }
+
+    /// Number of foundation piles
+    fn foundation_count(&self) -> usize {
+        4
+    }
+
+    /// Lay a shuffled deck out into tableau columns, returning the columns
+    /// and the cards left over for the stock (dealt from the end)
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>);
+
+    /// Whether `cards` (bottom to top) may be picked up together from a column
+    fn is_movable_run(&self, cards: &[Card]) -> bool;
+
+    /// Whether a run starting with `card` may be placed on `top`, or on an
+    /// empty column when `top` is `None`
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool;
+
+    /// Whether `card` may be placed on a foundation whose top card is `top`
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool;
+
+    /// Whether `card` may be played from the tableau onto the waste, whose
+    /// top card is `top`
+    fn accepts_on_waste(&self, _card: &Card, _top: Option<&Card>) -> bool {
+        false
+    }
+
+    /// What dealing from the stock does
+    fn stock_deal(&self) -> StockDeal {
+        StockDeal::ToWaste
+    }
+
+    /// Times the waste may be turned back over into the stock, or `None`
+    /// for no limit
+    fn recycle_limit(&self) -> Option<u32> {
+        None
+    }
+
+    /// Whether the stock is dealt and turned back over face up, leaving no
+    /// card hidden
+    fn open_stock(&self) -> bool {
+        false
+    }
+
+    /// Whether the cards of column `col` are pinned under cards in other
+    /// columns, as in Pyramid, and cannot be played yet
+    fn is_covered(&self, _tableau: &[Vec<Card>], _col: usize) -> bool {
+        false
+    }
+
+    /// When set, cards are not built on each other: two cards whose ranks
+    /// add up to this are removed together onto the first foundation
+    fn pair_total(&self) -> Option<u8> {
+        None
+    }
+
+    /// Whether a King-to-Ace run completed in a column leaves the tableau
+    /// for a foundation by itself
+    fn clears_complete_runs(&self) -> bool {
+        false
+    }
+
+    /// Whether the game is won with these piles
+    fn is_won(&self, _tableau: &[Vec<Card>], foundations: &[Vec<Card>]) -> bool {
+        foundations.iter().all(|pile| pile.len() == 13)
+    }
+    /// Whether the solver can search games played by these rules
+    fn is_solvable(&self) -> bool {
+        false
+    }
+}
+
+/// Classic Klondike: seven columns of one to seven cards with only the top
+/// card face up, building down in alternating colours, Kings alone filling
+/// empty columns, and suits built up from Ace to King to win.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Klondike;
+
+impl GameRules for Klondike {
+    fn name(&self) -> &'static str {
+        "Klondike"
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        deal_columns(deck, 7, |col| col + 1)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        !cards.is_empty()
+            && cards.iter().all(|card| card.face_up)
+            && cards
+                .windows(2)
+                .all(|pair| pair[1].can_place_on_tableau(&pair[0]))
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        match top {
+            Some(top) => card.can_place_on_tableau(top),
+            None => card.rank == Rank::King,
+        }
+    }
+
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool {
+        card.can_place_on_foundation(top)
+    }
+    fn is_solvable(&self) -> bool {
+        true
+    }
+}
+
+/// Deal `columns` columns left to right, `size(col)` cards each, with only
+/// the top card of each column face up. Returns the columns and the rest of
+/// the deck for the stock.
+pub fn deal_columns(
+    deck: Vec<Card>,
+    columns: usize,
+    size: impl Fn(usize) -> usize,
+) -> (Vec<Vec<Card>>, Vec<Card>) {
+    let mut cards = deck.into_iter();
+    let tableau = (0..columns)
+        .map(|col| {
+            let mut column: Vec<Card> = cards.by_ref().take(size(col)).collect();
+            if let Some(top) = column.last_mut() {
+                top.face_up = true;
+            }
+            column
+        })
+        .collect();
+    (tableau, cards.collect())
+}
+
+/// Offer Klondike to players
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    registry.register(VariantEntry::new(Variant::new(Klondike)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Suit;
+
+    #[test]
+    fn test_klondike_deal() {
+        let (tableau, stock) = Klondike.deal(create_deck());
+        assert_eq!(tableau.len(), 7);
+        for (col, column) in tableau.iter().enumerate() {
+            assert_eq!(column.len(), col + 1);
+            assert_eq!(column.iter().filter(|card| card.face_up).count(), 1);
+            assert!(column.last().unwrap().face_up);
+        }
+        assert_eq!(stock.len(), 24);
+        assert!(stock.iter().all(|card| !card.face_up));
+    }
+
+    #[test]
+    fn test_klondike_building() {
+        let king = Card::new(Suit::Spades, Rank::King, true);
+        let queen = Card::new(Suit::Hearts, Rank::Queen, true);
+        assert!(Klondike.accepts_on_tableau(&king, None));
+        assert!(!Klondike.accepts_on_tableau(&queen, None));
+        assert!(Klondike.accepts_on_tableau(&queen, Some(&king)));
+        assert!(Klondike.is_movable_run(&[king, queen]));
+        assert!(!Klondike.is_movable_run(&[queen, king]));
+        assert!(!Klondike.is_movable_run(&[]));
+
+        let ace = Card::new(Suit::Hearts, Rank::Ace, true);
+        assert!(Klondike.accepts_on_foundation(&ace, None));
+        assert!(!Klondike.accepts_on_foundation(&queen, None));
+    }
+}
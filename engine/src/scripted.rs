@@ -0,0 +1,363 @@
+use crate::deck::{Card, create_deck};
+use crate::registry::{VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, deal_columns};
+use crate::storage;
+use crate::variant::Variant;
+use rhai::{AST, Array, Dynamic, Engine, FuncArgs, Map, Scope};
+use std::fmt;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+/// File extension of rules scripts
+pub const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Functions every rules script must define
+const REQUIRED_FUNCTIONS: [&str; 4] = [
+    "name",
+    "columns",
+    "accepts_on_tableau",
+    "accepts_on_foundation",
+];
+
+/// Most operations one call into a script may take, so a script that
+/// loops forever is stopped instead of hanging the game
+const MAX_OPERATIONS: u64 = 10_000;
+
+/// How deep a script's function calls may nest
+const MAX_CALL_LEVELS: usize = 16;
+
+/// How deeply a script's expressions may nest
+const MAX_EXPR_DEPTH: usize = 32;
+
+/// Rules a player wrote as a Rhai script and dropped into the rules folder,
+/// offered as a variant of its own.
+///
+/// ```text
+/// // Name shown on the new game screen
+/// fn name() { "Easy Klondike" }
+///
+/// // Cards dealt to each column, left to right
+/// fn columns() { [1, 2, 3, 4, 5, 6, 7] }
+///
+/// // Optional: whether a card is dealt face up. Without it only the top
+/// // card of each column is.
+/// fn face_up(column, index, size) { index >= size - 2 }
+///
+/// // Whether `card` may go on `top`, which is () on an empty column
+/// fn accepts_on_tableau(card, top) {
+///     if type_of(top) == "()" { return true; }
+///     top.face_up && card.rank == top.rank - 1
+/// }
+///
+/// fn accepts_on_foundation(card, top) {
+///     if type_of(top) == "()" { return card.rank == 1; }
+///     card.suit == top.suit && card.rank == top.rank + 1
+/// }
+/// ```
+///
+/// Cards are maps of `suit` ("Hearts", "Diamonds", "Clubs" or "Spades"),
+/// `rank` (1 for an Ace to 13 for a King), `red` and `face_up`. A run moves
+/// together when each card is accepted on the one below it. The rest is
+/// Klondike's: the stock deals to the waste, and the game is won once the
+/// four foundations hold every card.
+pub struct ScriptedRules {
+    name: &'static str,
+    engine: Engine,
+    ast: AST,
+    columns: Vec<usize>,
+    /// Whether the script decides which cards are dealt face up
+    deals_face_up: bool,
+}
+
+/// A loaded rules script as a variant's rules. Scripts are loaded once and
+/// kept for the life of the app, so the rules can be copied like any other.
+#[derive(Clone, Copy)]
+pub struct Scripted(&'static ScriptedRules);
+
+impl ScriptedRules {
+    /// Folder players drop rules scripts into
+    pub fn default_dir() -> PathBuf {
+        storage::config_dir().join("rules")
+    }
+
+    /// Every rules script in `dir` by file name, in name order, with the
+    /// reason for any that failed to load
+    pub fn load_all(dir: &Path) -> Vec<(String, Result<ScriptedRules, String>)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut scripts: Vec<(String, Result<ScriptedRules, String>)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .filter_map(|path| {
+                let file_name = path.file_name()?.to_str()?.to_string();
+                Some((file_name, Self::load(&path)))
+            })
+            .collect();
+        scripts.sort_by(|a, b| a.0.cmp(&b.0));
+        scripts
+    }
+
+    pub fn load(path: &Path) -> Result<ScriptedRules, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Self::compile(&source)
+    }
+
+    /// Compile a rules script, checking it defines what a variant needs
+    pub fn compile(source: &str) -> Result<ScriptedRules, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH);
+        let ast = engine.compile(source).map_err(|error| error.to_string())?;
+        let defines = |function: &str| ast.iter_functions().any(|defined| defined.name == function);
+        if let Some(missing) = REQUIRED_FUNCTIONS
+            .iter()
+            .find(|function| !defines(function))
+        {
+            return Err(format!("the script has no `{}` function", missing));
+        }
+        let deals_face_up = defines("face_up");
+
+        let mut rules = ScriptedRules {
+            name: "",
+            engine,
+            ast,
+            columns: Vec::new(),
+            deals_face_up,
+        };
+        let name: String = rules.call("name", ())?;
+        if name.trim().is_empty() {
+            return Err("`name` gives no name".to_string());
+        }
+        let columns: Array = rules.call("columns", ())?;
+        rules.columns = columns
+            .into_iter()
+            .map(|size| match size.as_int() {
+                Ok(size @ 0..=52) => Ok(size as usize),
+                _ => Err(format!("`columns` lists {} as a column size", size)),
+            })
+            .collect::<Result<_, _>>()?;
+        if rules.columns.is_empty() || rules.columns.iter().sum::<usize>() > 52 {
+            return Err("`columns` must deal between 1 and 52 cards".to_string());
+        }
+        rules.check_accepts()?;
+        // The name lives as long as the rules, which are kept for good
+        rules.name = Box::leak(name.into_boxed_str());
+        Ok(rules)
+    }
+
+    fn call<T: rhai::Variant + Clone>(
+        &self,
+        function: &str,
+        args: impl FuncArgs,
+    ) -> Result<T, String> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, function, args)
+            .map_err(|error| format!("`{}` failed: {}", function, error))
+    }
+
+    /// Ask `function` whether `card` may go on `top`
+    fn judge(&self, function: &str, card: &Card, top: Option<&Card>) -> Result<bool, String> {
+        let top = top.map_or(Dynamic::UNIT, |top| Dynamic::from_map(card_map(top)));
+        self.call(function, (Dynamic::from_map(card_map(card)), top))
+    }
+
+    /// Try both `accepts_` functions on every card, on an empty pile and on
+    /// every other card, so a script that can't judge a move is refused
+    /// when it is loaded rather than failing in play
+    fn check_accepts(&self) -> Result<(), String> {
+        let deck: Vec<Card> = create_deck()
+            .into_iter()
+            .map(|card| Card::new(card.suit, card.rank, true))
+            .collect();
+        for function in ["accepts_on_tableau", "accepts_on_foundation"] {
+            for card in &deck {
+                for top in iter::once(None).chain(deck.iter().map(Some)) {
+                    self.judge(function, card, top)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `function` lets `card` go on `top`. Scripts are checked when
+    /// loaded, so one that still fails only refuses the move.
+    fn accepts(&self, function: &str, card: &Card, top: Option<&Card>) -> bool {
+        self.judge(function, card, top).unwrap_or(false)
+    }
+}
+
+impl Scripted {
+    /// Keep `rules` for the rest of the app's life
+    pub fn new(rules: ScriptedRules) -> Self {
+        Scripted(Box::leak(Box::new(rules)))
+    }
+}
+
+impl PartialEq for Scripted {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Scripted {}
+
+impl fmt::Debug for Scripted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Scripted").field(&self.0.name).finish()
+    }
+}
+
+impl GameRules for Scripted {
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        let sizes = &self.0.columns;
+        let (mut tableau, stock) = deal_columns(deck, sizes.len(), |col| sizes[col]);
+        if self.0.deals_face_up {
+            for (col, column) in tableau.iter_mut().enumerate() {
+                let size = column.len();
+                for (index, card) in column.iter_mut().enumerate() {
+                    card.face_up = self
+                        .0
+                        .call("face_up", (col as i64, index as i64, size as i64))
+                        .unwrap_or(index + 1 == size);
+                }
+            }
+        }
+        (tableau, stock)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        !cards.is_empty()
+            && cards.iter().all(|card| card.face_up)
+            && cards
+                .windows(2)
+                .all(|pair| self.accepts_on_tableau(&pair[1], Some(&pair[0])))
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        self.0.accepts("accepts_on_tableau", card, top)
+    }
+
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool {
+        self.0.accepts("accepts_on_foundation", card, top)
+    }
+}
+
+/// Offer every rules script in `dir` to players, returning why any could
+/// not be loaded
+pub fn register(registry: &mut VariantRegistry, dir: &Path) -> Vec<String> {
+    ScriptedRules::load_all(dir)
+        .into_iter()
+        .filter_map(|(file_name, rules)| {
+            rules
+                .and_then(|rules| {
+                    let variant = Variant::new(Scripted::new(rules));
+                    registry.register(VariantEntry::new(variant))
+                })
+                .err()
+                .map(|error| format!("Rules script {} could not be loaded: {}", file_name, error))
+        })
+        .collect()
+}
+
+/// A card as scripts see it
+fn card_map(card: &Card) -> Map {
+    let mut map = Map::new();
+    map.insert("suit".into(), Dynamic::from(card.suit.name().to_string()));
+    map.insert("rank".into(), Dynamic::from(card.rank as i64));
+    map.insert("red".into(), Dynamic::from(card.is_red()));
+    map.insert("face_up".into(), Dynamic::from(card.face_up));
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Rank, Suit, create_deck};
+
+    const SCRIPT: &str = r#"
+        fn name() { "Easy Klondike" }
+        fn columns() { [1, 2, 3, 4, 5, 6, 7] }
+        fn face_up(column, index, size) { index >= size - 2 }
+        fn accepts_on_tableau(card, top) {
+            if type_of(top) == "()" { return true; }
+            top.face_up && card.rank == top.rank - 1
+        }
+        fn accepts_on_foundation(card, top) {
+            if type_of(top) == "()" { return card.rank == 1; }
+            card.suit == top.suit && card.rank == top.rank + 1
+        }
+    "#;
+
+    #[test]
+    fn test_script_deals_and_judges_moves() {
+        let rules = Scripted::new(ScriptedRules::compile(SCRIPT).unwrap());
+        assert_eq!(rules.name(), "Easy Klondike");
+
+        let (tableau, stock) = rules.deal(create_deck());
+        assert_eq!(tableau.len(), 7);
+        assert_eq!(stock.len(), 24);
+        assert!(tableau[0][0].face_up);
+        assert!(tableau[6][5].face_up && !tableau[6][4].face_up);
+
+        let seven = Card::new(Suit::Hearts, Rank::Seven, true);
+        let six = Card::new(Suit::Hearts, Rank::Six, true);
+        assert!(rules.accepts_on_tableau(&six, Some(&seven)));
+        assert!(!rules.accepts_on_tableau(&seven, Some(&six)));
+        assert!(rules.accepts_on_tableau(&seven, None));
+        assert!(rules.is_movable_run(&[seven, six]));
+
+        let ace = Card::new(Suit::Clubs, Rank::Ace, true);
+        assert!(rules.accepts_on_foundation(&ace, None));
+        assert!(!rules.accepts_on_foundation(&six, None));
+    }
+
+    #[test]
+    fn test_broken_scripts_are_refused() {
+        assert!(ScriptedRules::compile("fn name( {").is_err());
+        let error = ScriptedRules::compile(r#"fn name() { "Half" }"#)
+            .err()
+            .unwrap();
+        assert!(error.contains("columns"), "{}", error);
+        let too_many = SCRIPT.replace("[1, 2, 3, 4, 5, 6, 7]", "[30, 30]");
+        assert!(ScriptedRules::compile(&too_many).is_err());
+        // Only fails on an empty column
+        let failing = SCRIPT.replace("return true;", "return top.rank;");
+        let error = ScriptedRules::compile(&failing).err().unwrap();
+        assert!(error.contains("accepts_on_tableau"), "{}", error);
+    }
+
+    #[test]
+    fn test_runaway_scripts_are_stopped() {
+        let looping = SCRIPT.replace("return true;", "loop {}");
+        assert!(ScriptedRules::compile(&looping).is_err());
+        let recursing = format!("{}\nfn deeper(n) {{ deeper(n + 1) }}", SCRIPT)
+            .replace("return true;", "return deeper(0);");
+        assert!(ScriptedRules::compile(&recursing).is_err());
+    }
+
+    #[test]
+    fn test_scripts_cannot_take_a_registered_name() {
+        let dir =
+            std::env::temp_dir().join(format!("vibe-solitaire-test-{}-rules", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let impostor = SCRIPT.replace("Easy Klondike", "Klondike");
+        fs::write(dir.join("klondike.rhai"), impostor).unwrap();
+
+        let mut registry = VariantRegistry::builtin();
+        let notices = register(&mut registry, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].contains("already registered"), "{}", notices[0]);
+        assert!(registry.find("Klondike").unwrap().variant.is_klondike());
+    }
+}
@@ -0,0 +1,686 @@
+use crate::actions::{DrawCount, Scoring};
+use crate::history::HistoryLimits;
+use crate::locale::Language;
+use crate::storage::{self, Checked};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Accessibility accommodations a player can opt into.
+///
+/// Any game played with an accommodation carries the flags on its result so
+/// records that rank players (best time, best score) only compare strict games.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accommodations {
+    /// Accept drops anywhere over a tableau column instead of only on its top card
+    pub large_drop_targets: bool,
+    /// Play without the clock counting: the game's time is never ranked
+    pub untimed: bool,
+}
+
+impl Accommodations {
+    const LARGE_DROP_TARGETS: u8 = 1 << 0;
+    const UNTIMED: u8 = 1 << 1;
+
+    /// True when no accommodation is enabled
+    pub fn is_strict(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Combine with another set, keeping every accommodation used by either
+    pub fn union(self, other: Accommodations) -> Accommodations {
+        Accommodations {
+            large_drop_targets: self.large_drop_targets || other.large_drop_targets,
+            untimed: self.untimed || other.untimed,
+        }
+    }
+
+    /// Pack into bit flags for storage
+    pub fn to_flags(self) -> u8 {
+        let mut flags = 0;
+        if self.large_drop_targets {
+            flags |= Self::LARGE_DROP_TARGETS;
+        }
+        if self.untimed {
+            flags |= Self::UNTIMED;
+        }
+        flags
+    }
+
+    /// Unpack from stored bit flags, ignoring unknown bits
+    pub fn from_flags(flags: u8) -> Self {
+        Accommodations {
+            large_drop_targets: flags & Self::LARGE_DROP_TARGETS != 0,
+            untimed: flags & Self::UNTIMED != 0,
+        }
+    }
+
+    /// Human-readable list of enabled accommodations, e.g. "large drop targets, untimed"
+    pub fn describe(&self) -> String {
+        let mut names = Vec::new();
+        if self.large_drop_targets {
+            names.push("large drop targets");
+        }
+        if self.untimed {
+            names.push("untimed");
+        }
+        names.join(", ")
+    }
+}
+
+/// Text sizes offered in the settings, as percentages of normal
+pub const TEXT_SCALES: [u32; 6] = [80, 100, 125, 150, 175, 200];
+/// Times offered for resting the pointer to click, and for scanning to
+/// stay on each pile, in milliseconds
+pub const DWELL_TIMES: [u64; 5] = [500, 1000, 1500, 2000, 3000];
+pub const SCAN_INTERVALS: [u64; 5] = [1000, 1500, 2000, 3000, 5000];
+
+/// How the player points at cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    /// Mouse and keyboard as usual
+    #[default]
+    Standard,
+    /// Resting the pointer on a pile clicks it, as Enter does on the board
+    /// cursor
+    Dwell,
+    /// The board cursor steps from pile to pile on its own, and a single
+    /// switch (Enter or Space) picks the pile it is on
+    Scan,
+}
+
+impl InputMode {
+    pub fn all() -> [InputMode; 3] {
+        [InputMode::Standard, InputMode::Dwell, InputMode::Scan]
+    }
+
+    /// Name used in the settings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputMode::Standard => "standard",
+            InputMode::Dwell => "dwell",
+            InputMode::Scan => "scan",
+        }
+    }
+
+    /// Name shown in settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputMode::Standard => "Standard",
+            InputMode::Dwell => "Dwell click",
+            InputMode::Scan => "Scanning",
+        }
+    }
+}
+
+/// Which palette the menus, dialogs and status bar use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeChoice {
+    /// Follow the operating system's light or dark preference
+    #[default]
+    System,
+    Dark,
+    Light,
+    /// Stronger contrast throughout, with heavier card borders and hollow
+    /// red suits so no suit is told apart by color alone
+    HighContrast,
+}
+
+impl ThemeChoice {
+    pub fn all() -> [ThemeChoice; 4] {
+        [
+            ThemeChoice::System,
+            ThemeChoice::Dark,
+            ThemeChoice::Light,
+            ThemeChoice::HighContrast,
+        ]
+    }
+
+    /// Name used in the settings file and menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeChoice::System => "system",
+            ThemeChoice::Dark => "dark",
+            ThemeChoice::Light => "light",
+            ThemeChoice::HighContrast => "high-contrast",
+        }
+    }
+}
+
+/// How a moving card's speed changes along the way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Fast off the mark, slowing into place
+    #[default]
+    EaseOut,
+    /// Overshoots its place a little and settles back
+    Spring,
+}
+
+impl Easing {
+    pub fn all() -> [Easing; 3] {
+        [Easing::Linear, Easing::EaseOut, Easing::Spring]
+    }
+
+    /// Name used in the settings file and menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Easing::Linear => "linear",
+            Easing::EaseOut => "ease-out",
+            Easing::Spring => "spring",
+        }
+    }
+}
+
+/// User preferences that apply to new games
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub draw_count: DrawCount,
+    /// How new games are scored
+    pub scoring: Scoring,
+    /// Accessibility section of the settings
+    pub accessibility: Accommodations,
+    /// How much undo history is kept per game
+    pub history_limits: HistoryLimits,
+    /// Show the text mirror of the board beside the graphical one
+    pub text_board: bool,
+    /// Ask before leaving a game in progress for a new one
+    pub confirm_abandon: bool,
+    /// Count the cards that can be played in the status bar
+    pub move_counter: bool,
+    /// Write a report to disk when the game crashes or a move breaks the
+    /// board, for attaching to a bug report
+    pub error_reports: bool,
+    /// Cards glide between piles and cascade off a won board; when off,
+    /// they jump straight into place
+    pub animations: bool,
+    /// How cards speed up and slow down as they move
+    pub easing: Easing,
+    /// Keep cards still whatever `animations` says, for players who find
+    /// motion uncomfortable; highlights and toasts still show
+    pub reduce_motion: bool,
+    /// Short sounds for moves, refusals, flips, deals and wins
+    pub sound_cues: bool,
+    /// Every sound silenced at once, whatever the volumes say
+    pub muted: bool,
+    /// Name of a sound pack from the sounds folder, used instead of the
+    /// built-in tones
+    pub sound_pack: Option<String>,
+    /// Loop the tracks in the music folder while the game is open
+    pub music: bool,
+    /// Loudness of all sound as a percentage, scaling the two below
+    pub master_volume: u32,
+    /// Loudness of the sound cues as a percentage
+    pub effects_volume: u32,
+    /// Loudness of the music as a percentage
+    pub music_volume: u32,
+    /// Pause the music while another window has focus
+    pub pause_music_in_background: bool,
+    /// Light or dark menus and dialogs
+    pub theme: ThemeChoice,
+    /// Id of a theme file from the themes folder, used instead of `theme`
+    pub theme_file: Option<String>,
+    /// Blue diamonds and green clubs, so every suit has its own color
+    pub four_color_deck: bool,
+    /// Cards with an oversized rank and suit in the corner
+    pub large_index: bool,
+    /// Tighter fans and a smaller top row, for small windows
+    pub compact_layout: bool,
+    /// Language the game is shown in
+    pub language: Language,
+    /// Size of all text, as a percentage of normal, for low vision
+    pub text_scale: u32,
+    /// Badges counting the cards left in the stock and face down in each
+    /// column
+    pub pile_counts: bool,
+    /// Keyboard shortcuts the player remapped: keystrokes by command name.
+    /// Commands left out keep their default keys.
+    pub key_bindings: BTreeMap<String, Vec<String>>,
+    pub input_mode: InputMode,
+    /// How long the pointer rests on a pile to click it
+    pub dwell_time: Duration,
+    /// How long scanning stays on each pile
+    pub scan_interval: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            draw_count: DrawCount::default(),
+            scoring: Scoring::default(),
+            accessibility: Accommodations::default(),
+            history_limits: HistoryLimits::default(),
+            text_board: false,
+            confirm_abandon: true,
+            move_counter: false,
+            error_reports: false,
+            animations: true,
+            easing: Easing::default(),
+            reduce_motion: false,
+            sound_cues: true,
+            muted: false,
+            sound_pack: None,
+            music: false,
+            master_volume: 100,
+            effects_volume: 100,
+            music_volume: 50,
+            pause_music_in_background: true,
+            theme: ThemeChoice::default(),
+            theme_file: None,
+            four_color_deck: false,
+            large_index: false,
+            compact_layout: false,
+            language: Language::default(),
+            text_scale: 100,
+            pile_counts: true,
+            key_bindings: BTreeMap::new(),
+            input_mode: InputMode::default(),
+            dwell_time: Duration::from_millis(1000),
+            scan_interval: Duration::from_millis(2000),
+        }
+    }
+}
+
+impl Settings {
+    /// Default location, in the platform config dir
+    pub fn default_path() -> PathBuf {
+        storage::config_dir().join("settings.toml")
+    }
+
+    /// Load at startup. A damaged file is quarantined and the defaults are
+    /// used, with a notice for the player.
+    pub fn load_checked(path: &Path) -> Checked<Self> {
+        storage::load_checked(path, Self::parse)
+    }
+
+    /// Read the file again while the app runs. Unlike `load_checked` a
+    /// damaged file is left where it is, as it may be half way through an
+    /// edit.
+    pub fn reload(path: &Path) -> Result<Self, String> {
+        storage::load::<SettingsFile>(path).map(Settings::from)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        storage::save(path, &SettingsFile::from(self))
+    }
+
+    /// Gain for the sound cues, from 0 to 1, after the master volume
+    pub fn effects_level(&self) -> f32 {
+        self.level(self.effects_volume)
+    }
+
+    /// Gain for the music, from 0 to 1, after the master volume
+    pub fn music_level(&self) -> f32 {
+        self.level(self.music_volume)
+    }
+
+    fn level(&self, volume: u32) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        (self.master_volume * volume) as f32 / 10_000.0
+    }
+
+    /// Read settings, keeping the default for anything left out so a file
+    /// from an older version still loads
+    pub fn parse(text: &str) -> Result<Self, String> {
+        storage::parse::<SettingsFile>(text).map(Settings::from)
+    }
+}
+
+/// Layout of `settings.toml`: a few keys at the top and a table per part
+/// of the settings window. Every key is optional.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsFile {
+    draw_count: DrawCount,
+    scoring: Scoring,
+    text_board: bool,
+    confirm_abandon: bool,
+    move_counter: bool,
+    error_reports: bool,
+    accessibility: Accommodations,
+    history: HistoryLimits,
+    animation: AnimationSection,
+    sound: SoundSection,
+    appearance: AppearanceSection,
+    input: InputSection,
+    keys: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct AnimationSection {
+    enabled: bool,
+    easing: Easing,
+    reduce_motion: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct SoundSection {
+    cues: bool,
+    muted: bool,
+    music: bool,
+    pack: Option<String>,
+    master_volume: u32,
+    effects_volume: u32,
+    music_volume: u32,
+    pause_music_in_background: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct AppearanceSection {
+    theme: ThemeChoice,
+    four_color_deck: bool,
+    large_index: bool,
+    compact_layout: bool,
+    language: Language,
+    text_scale: u32,
+    pile_counts: bool,
+    theme_file: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct InputSection {
+    mode: InputMode,
+    dwell_ms: u64,
+    scan_ms: u64,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        SettingsFile::from(&Settings::default())
+    }
+}
+
+impl Default for AnimationSection {
+    fn default() -> Self {
+        SettingsFile::default().animation
+    }
+}
+
+impl Default for SoundSection {
+    fn default() -> Self {
+        SettingsFile::default().sound
+    }
+}
+
+impl Default for AppearanceSection {
+    fn default() -> Self {
+        SettingsFile::default().appearance
+    }
+}
+
+impl Default for InputSection {
+    fn default() -> Self {
+        SettingsFile::default().input
+    }
+}
+
+impl From<&Settings> for SettingsFile {
+    fn from(settings: &Settings) -> Self {
+        SettingsFile {
+            draw_count: settings.draw_count,
+            scoring: settings.scoring,
+            text_board: settings.text_board,
+            confirm_abandon: settings.confirm_abandon,
+            move_counter: settings.move_counter,
+            error_reports: settings.error_reports,
+            accessibility: settings.accessibility,
+            history: settings.history_limits,
+            animation: AnimationSection {
+                enabled: settings.animations,
+                easing: settings.easing,
+                reduce_motion: settings.reduce_motion,
+            },
+            sound: SoundSection {
+                cues: settings.sound_cues,
+                muted: settings.muted,
+                music: settings.music,
+                pack: settings.sound_pack.clone(),
+                master_volume: settings.master_volume,
+                effects_volume: settings.effects_volume,
+                music_volume: settings.music_volume,
+                pause_music_in_background: settings.pause_music_in_background,
+            },
+            appearance: AppearanceSection {
+                theme: settings.theme,
+                four_color_deck: settings.four_color_deck,
+                large_index: settings.large_index,
+                compact_layout: settings.compact_layout,
+                language: settings.language,
+                text_scale: settings.text_scale,
+                pile_counts: settings.pile_counts,
+                theme_file: settings.theme_file.clone(),
+            },
+            input: InputSection {
+                mode: settings.input_mode,
+                dwell_ms: settings.dwell_time.as_millis() as u64,
+                scan_ms: settings.scan_interval.as_millis() as u64,
+            },
+            keys: settings.key_bindings.clone(),
+        }
+    }
+}
+
+/// Bring hand-edited values back within what the settings window offers
+impl From<SettingsFile> for Settings {
+    fn from(file: SettingsFile) -> Self {
+        Settings {
+            draw_count: file.draw_count,
+            scoring: file.scoring,
+            accessibility: file.accessibility,
+            history_limits: HistoryLimits {
+                max_entries: file.history.max_entries.max(1),
+                snapshot_interval: file.history.snapshot_interval.max(1),
+            },
+            text_board: file.text_board,
+            confirm_abandon: file.confirm_abandon,
+            move_counter: file.move_counter,
+            error_reports: file.error_reports,
+            animations: file.animation.enabled,
+            easing: file.animation.easing,
+            reduce_motion: file.animation.reduce_motion,
+            sound_cues: file.sound.cues,
+            muted: file.sound.muted,
+            sound_pack: file.sound.pack,
+            music: file.sound.music,
+            master_volume: file.sound.master_volume.min(100),
+            effects_volume: file.sound.effects_volume.min(100),
+            music_volume: file.sound.music_volume.min(100),
+            pause_music_in_background: file.sound.pause_music_in_background,
+            theme: file.appearance.theme,
+            theme_file: file.appearance.theme_file,
+            four_color_deck: file.appearance.four_color_deck,
+            large_index: file.appearance.large_index,
+            compact_layout: file.appearance.compact_layout,
+            language: file.appearance.language,
+            text_scale: file
+                .appearance
+                .text_scale
+                .clamp(TEXT_SCALES[0], TEXT_SCALES[TEXT_SCALES.len() - 1]),
+            pile_counts: file.appearance.pile_counts,
+            key_bindings: file.keys,
+            input_mode: file.input.mode,
+            dwell_time: clamp_millis(file.input.dwell_ms, &DWELL_TIMES),
+            scan_interval: clamp_millis(file.input.scan_ms, &SCAN_INTERVALS),
+        }
+    }
+}
+
+/// A time in milliseconds, brought within the shortest and longest of
+/// `offered`
+fn clamp_millis(millis: u64, offered: &[u64]) -> Duration {
+    let (shortest, longest) = (offered[0], offered[offered.len() - 1]);
+    Duration::from_millis(millis.clamp(shortest, longest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_accommodations_are_strict() {
+        assert!(Accommodations::default().is_strict());
+        assert!(Settings::default().accessibility.is_strict());
+
+        let untimed = Accommodations {
+            untimed: true,
+            ..Default::default()
+        };
+        assert!(!untimed.is_strict());
+        assert_eq!(untimed.describe(), "untimed");
+    }
+
+    #[test]
+    fn test_accommodation_flags_round_trip() {
+        for flags in 0..4 {
+            assert_eq!(Accommodations::from_flags(flags).to_flags(), flags);
+        }
+        // Unknown bits from newer versions are ignored
+        assert!(Accommodations::from_flags(0b1000).is_strict());
+    }
+
+    #[test]
+    fn test_accommodation_union_keeps_all_flags() {
+        let large = Accommodations {
+            large_drop_targets: true,
+            ..Default::default()
+        };
+        let untimed = Accommodations {
+            untimed: true,
+            ..Default::default()
+        };
+        let both = large.union(untimed);
+        assert!(both.large_drop_targets && both.untimed);
+        assert_eq!(both.union(Accommodations::default()), both);
+    }
+
+    #[test]
+    fn test_master_volume_scales_both_categories() {
+        let settings = Settings {
+            master_volume: 50,
+            effects_volume: 100,
+            music_volume: 40,
+            ..Settings::default()
+        };
+        assert_eq!(settings.effects_level(), 0.5);
+        assert_eq!(settings.music_level(), 0.2);
+
+        let silent = Settings {
+            master_volume: 0,
+            ..settings.clone()
+        };
+        assert_eq!(silent.effects_level(), 0.0);
+        assert_eq!(silent.music_level(), 0.0);
+
+        let muted = Settings {
+            muted: true,
+            ..settings
+        };
+        assert_eq!(muted.effects_level(), 0.0);
+        assert_eq!(muted.music_level(), 0.0);
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let key_bindings = BTreeMap::from([("hint".to_string(), vec!["secondary-h".to_string()])]);
+        let settings = Settings {
+            draw_count: DrawCount::One,
+            scoring: Scoring::Vegas,
+            accessibility: Accommodations {
+                large_drop_targets: true,
+                untimed: false,
+            },
+            history_limits: HistoryLimits {
+                max_entries: 200,
+                snapshot_interval: 10,
+            },
+            text_board: true,
+            confirm_abandon: false,
+            move_counter: true,
+            error_reports: true,
+            animations: false,
+            easing: Easing::Spring,
+            reduce_motion: true,
+            sound_cues: false,
+            muted: true,
+            sound_pack: Some("chimes".to_string()),
+            music: true,
+            master_volume: 80,
+            effects_volume: 30,
+            music_volume: 75,
+            pause_music_in_background: false,
+            theme: ThemeChoice::HighContrast,
+            theme_file: Some("midnight".to_string()),
+            four_color_deck: true,
+            large_index: true,
+            compact_layout: true,
+            language: Language::Spanish,
+            text_scale: 150,
+            pile_counts: false,
+            key_bindings,
+            input_mode: InputMode::Scan,
+            dwell_time: Duration::from_millis(1500),
+            scan_interval: Duration::from_millis(3000),
+        };
+        let text = storage::to_text(&SettingsFile::from(&settings)).unwrap();
+        assert_eq!(Settings::parse(&text).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_settings_missing_keys_keep_defaults() {
+        assert_eq!(
+            Settings::parse("text_board = true").unwrap(),
+            Settings {
+                text_board: true,
+                ..Settings::default()
+            }
+        );
+
+        for bad in [
+            "draw_count = \"Two\"",
+            "scoring = \"golf\"",
+            "text_board = 1",
+            "error_reports = \"yes\"",
+            "[history]\nmax_entries = -5",
+            "[animation]\neasing = \"wobbly\"",
+            "[animation]\nenabled = \"no\"",
+            "[sound]\ncues = \"on\"",
+            "[sound]\nmusic_volume = \"loud\"",
+            "[sound]\nmaster_volume = -10",
+            "[sound]\npack = 3",
+            "[sound]\nmuted = \"yes\"",
+            "[sound]\npause_music_in_background = 1",
+            "[appearance]\ntheme = \"purple\"",
+            "[appearance]\nfour_color_deck = \"yes\"",
+            "[appearance]\npile_counts = 0",
+            "[appearance]\nlarge_index = \"yes\"",
+            "[appearance]\nlanguage = \"xx\"",
+            "[keys]\nhint = \"h\"",
+            "[input]\nmode = \"telepathy\"",
+            "[input]\ndwell_ms = -1",
+            "[appearance]\ntext_scale = \"large\"",
+        ] {
+            assert!(Settings::parse(bad).is_err(), "{}", bad);
+        }
+
+        // Text sizes out of range are brought back into it
+        let huge = Settings::parse("[appearance]\ntext_scale = 500").unwrap();
+        assert_eq!(huge.text_scale, 200);
+        let loud = Settings::parse("[sound]\nmusic_volume = 400").unwrap();
+        assert_eq!(loud.music_volume, 100);
+        let quick = Settings::parse("[input]\ndwell_ms = 10").unwrap();
+        assert_eq!(quick.dwell_time, Duration::from_millis(500));
+    }
+}
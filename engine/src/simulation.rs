@@ -0,0 +1,175 @@
+use crate::actions::{DrawCount, GameAction};
+use crate::packed::PackedState;
+use crate::solver;
+use crate::state::GameState;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Actions a simulated game may take before it counts as lost
+const MAX_ACTIONS: u32 = 1_000;
+
+/// How a simulated player picks its moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Any legal move, uniformly at random
+    Random,
+    /// The solver's most promising move that leads somewhere new
+    Heuristic,
+}
+
+impl Policy {
+    pub fn parse(text: &str) -> Result<Policy, String> {
+        match text {
+            "random" => Ok(Policy::Random),
+            "heuristic" => Ok(Policy::Heuristic),
+            other => Err(format!(
+                "unknown policy `{}` (expected `random` or `heuristic`)",
+                other
+            )),
+        }
+    }
+}
+
+/// Totals over a batch of simulated games
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    pub games: u32,
+    pub wins: u32,
+    pub total_moves: u64,
+    pub elapsed: Duration,
+}
+
+impl SimulationReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+
+    pub fn average_moves(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.total_moves as f64 / self.games as f64
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Games:         {}", self.games)?;
+        writeln!(
+            f,
+            "Wins:          {} ({:.1}%)",
+            self.wins,
+            self.win_rate() * 100.0
+        )?;
+        writeln!(f, "Average moves: {:.1}", self.average_moves())?;
+        let per_game = self.elapsed.as_secs_f64() * 1000.0 / self.games.max(1) as f64;
+        write!(
+            f,
+            "Time:          {:.2} s ({:.2} ms per game)",
+            self.elapsed.as_secs_f64(),
+            per_game
+        )
+    }
+}
+
+/// Play `games` fresh deals with `policy`, drawing every seed and random
+/// choice from `rng` so a seeded generator reproduces the batch
+pub fn simulate<R: Rng + ?Sized>(
+    games: u32,
+    draw_count: DrawCount,
+    policy: Policy,
+    rng: &mut R,
+) -> SimulationReport {
+    let started = Instant::now();
+    let mut report = SimulationReport::default();
+    for _ in 0..games {
+        let mut game_state = GameState::new_with_rng(rng);
+        game_state.draw_count = draw_count;
+        play(&mut game_state, policy, rng);
+
+        report.games += 1;
+        report.wins += game_state.game_won as u32;
+        report.total_moves += game_state.move_count as u64;
+    }
+    report.elapsed = started.elapsed();
+    report
+}
+
+/// Play until the game is won, no move is left, or the action limit is hit
+pub fn play<R: Rng + ?Sized>(game_state: &mut GameState, policy: Policy, rng: &mut R) {
+    let mut seen = HashSet::new();
+    for _ in 0..MAX_ACTIONS {
+        if game_state.game_won {
+            return;
+        }
+        seen.insert(PackedState::from_game(game_state));
+
+        let moves = solver::legal_moves(game_state);
+        let action = match policy {
+            Policy::Random => moves.choose(rng).cloned(),
+            Policy::Heuristic => moves
+                .into_iter()
+                .find(|action| leads_somewhere_new(game_state, action, &seen)),
+        };
+        let Some(action) = action else {
+            return;
+        };
+        if game_state.handle_action(action).is_err() {
+            return;
+        }
+    }
+}
+
+fn leads_somewhere_new(
+    game_state: &GameState,
+    action: &GameAction,
+    seen: &HashSet<PackedState>,
+) -> bool {
+    let mut next = game_state.clone();
+    next.handle_action(action.clone()).is_ok() && !seen.contains(&PackedState::from_game(&next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_simulation_totals() {
+        for policy in [Policy::Random, Policy::Heuristic] {
+            let report = simulate(5, DrawCount::One, policy, &mut StdRng::seed_from_u64(2046));
+            assert_eq!(report.games, 5);
+            assert!(report.wins <= 5);
+            assert!(report.total_moves > 0);
+            assert!(report.to_string().contains("Games:         5"));
+        }
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible() {
+        let run = || {
+            let report = simulate(
+                3,
+                DrawCount::Three,
+                Policy::Heuristic,
+                &mut StdRng::seed_from_u64(7),
+            );
+            (report.wins, report.total_moves)
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_policy_parse() {
+        assert_eq!(Policy::parse("random"), Ok(Policy::Random));
+        assert_eq!(Policy::parse("heuristic"), Ok(Policy::Heuristic));
+        assert!(Policy::parse("clever").is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use crate::actions::{DrawCount, GameAction};
+use crate::deck::Card;
+use crate::packed::PackedState;
+use crate::rules::GameRules;
+use crate::state::{GameState, Position};
+use std::collections::HashMap;
+
+/// Positions the solver explores before giving up on a deal
+pub const DEFAULT_NODE_LIMIT: usize = 200_000;
+
+/// What the solver found out about a position
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution {
+    /// Actions that win from the position, in order
+    Winnable(Vec<GameAction>),
+    /// Every line of play was searched and none wins
+    Unwinnable,
+    /// The node limit ran out before the search finished
+    Unknown,
+}
+
+impl Solution {
+    pub fn is_winnable(&self) -> bool {
+        matches!(self, Solution::Winnable(_))
+    }
+}
+
+/// Depth-first search over Klondike positions.
+///
+/// Moves are tried most promising first (foundation plays, then moves that
+/// turn over a face-down card, then the rest, dealing last). Every position
+/// reached is remembered in a transposition table and never searched twice,
+/// and dealing round the whole stock without playing a card is cut off, so
+/// the search ends on unwinnable deals rather than cycling.
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    node_limit: usize,
+}
+
+impl Solver {
+    pub fn new(node_limit: usize) -> Self {
+        Solver { node_limit }
+    }
+
+    pub fn node_limit(&self) -> usize {
+        self.node_limit
+    }
+
+    /// Search for a winning line from `game_state`. Only Klondike and
+    /// Thoughtful are searched; other variants are always `Unknown`.
+    pub fn solve(&self, game_state: &GameState) -> Solution {
+        if game_state.game_won {
+            return Solution::Winnable(Vec::new());
+        }
+        if !game_state.rules.is_solvable() {
+            return Solution::Unknown;
+        }
+
+        let mut search = Search {
+            node_limit: self.node_limit,
+            nodes: 0,
+            line: Vec::new(),
+            visited: HashMap::new(),
+        };
+        match search.explore(game_state, 0) {
+            Some(true) => Solution::Winnable(search.line),
+            Some(false) => Solution::Unwinnable,
+            None => Solution::Unknown,
+        }
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new(DEFAULT_NODE_LIMIT)
+    }
+}
+
+struct Search {
+    node_limit: usize,
+    nodes: usize,
+    /// Actions leading from the root to the position being explored
+    line: Vec<GameAction>,
+    /// Positions already reached, either on the current line or searched to
+    /// the end without a win, with the fewest idle deals they were reached
+    /// after. Reached again after more, they have no more deals left to try.
+    visited: HashMap<PackedState, usize>,
+}
+
+impl Search {
+    /// `Some(true)` once won, `Some(false)` when every move from here was
+    /// exhausted, `None` when the node limit ran out. `idle_deals` counts the
+    /// deals made in a row since a card was last played.
+    fn explore(&mut self, game_state: &GameState, idle_deals: usize) -> Option<bool> {
+        if game_state.game_won {
+            return Some(true);
+        }
+        if idle_deals > deals_per_cycle(game_state) {
+            // Only going round the stock again, with nothing played
+            return Some(false);
+        }
+        let packed = PackedState::from_game(game_state);
+        if self
+            .visited
+            .get(&packed)
+            .is_some_and(|&reached| reached <= idle_deals)
+        {
+            return Some(false);
+        }
+        self.visited.insert(packed, idle_deals);
+        if self.nodes >= self.node_limit {
+            return None;
+        }
+        self.nodes += 1;
+
+        let mut exhausted = true;
+        for action in legal_moves(game_state) {
+            let mut next = game_state.clone();
+            if next.handle_action(action.clone()).is_err() {
+                continue;
+            }
+            let idle_deals = match action {
+                GameAction::DealFromStock => idle_deals + 1,
+                _ => 0,
+            };
+            self.line.push(action);
+            match self.explore(&next, idle_deals) {
+                Some(true) => return Some(true),
+                Some(false) => {}
+                None => exhausted = false,
+            }
+            self.line.pop();
+            if !exhausted {
+                break;
+            }
+        }
+        exhausted.then_some(false)
+    }
+}
+
+/// Deals in a row after which dealing alone can show nothing new: enough to
+/// finish the current pass through the stock, turn the waste over and go
+/// round once more
+fn deals_per_cycle(game_state: &GameState) -> usize {
+    let cards = game_state.stock.len() + game_state.waste.len();
+    let draw = match game_state.draw_count {
+        DrawCount::One => 1,
+        DrawCount::Three => 3,
+    };
+    2 * (cards.div_ceil(draw) + 1)
+}
+
+/// Legal moves from `game_state`, most promising first. Moves that cannot
+/// change the outcome, like shifting a King between empty columns, are left
+/// out.
+pub fn legal_moves(game_state: &GameState) -> Vec<GameAction> {
+    let mut to_foundation = Vec::new();
+    let mut reveals = Vec::new();
+    let mut others = Vec::new();
+
+    let sources = game_state
+        .waste
+        .last()
+        .map(|card| (Position::Waste(game_state.waste.len() - 1), *card))
+        .into_iter()
+        .chain(
+            game_state
+                .tableau
+                .iter()
+                .enumerate()
+                .filter_map(|(col, column)| {
+                    column
+                        .last()
+                        .map(|card| (Position::Tableau(col, column.len() - 1), *card))
+                }),
+        );
+    for (from, card) in sources {
+        if let Some(foundation) = foundation_for(game_state, &card) {
+            to_foundation.push(GameAction::MoveCard {
+                from,
+                to: Position::Foundation(foundation),
+            });
+        }
+    }
+
+    let first_empty = game_state.tableau.iter().position(Vec::is_empty);
+    for (col, column) in game_state.tableau.iter().enumerate() {
+        let Some(start) = column.iter().position(|card| card.face_up) else {
+            continue;
+        };
+        for index in start..column.len() {
+            let run = &column[index..];
+            if !game_state.rules.is_movable_run(run) {
+                continue;
+            }
+            let reveals_card = index == start && index > 0;
+            for (dest, target) in targets(game_state, &run[0], first_empty) {
+                if dest == col || (index == 0 && target.is_none()) {
+                    continue;
+                }
+                let action = GameAction::MoveCard {
+                    from: Position::Tableau(col, index),
+                    to: Position::Tableau(dest, game_state.tableau[dest].len()),
+                };
+                if reveals_card {
+                    reveals.push(action);
+                } else {
+                    others.push(action);
+                }
+            }
+        }
+    }
+
+    if let Some(card) = game_state.waste.last() {
+        for (dest, _) in targets(game_state, card, first_empty) {
+            others.push(GameAction::MoveCard {
+                from: Position::Waste(game_state.waste.len() - 1),
+                to: Position::Tableau(dest, game_state.tableau[dest].len()),
+            });
+        }
+    }
+
+    let mut moves = to_foundation;
+    moves.extend(reveals);
+    moves.extend(others);
+    if !game_state.stock.is_empty() || !game_state.waste.is_empty() {
+        moves.push(GameAction::DealFromStock);
+    }
+    moves
+}
+
+/// Cards that can be played right now, leaving out dealing from the stock.
+/// None left is an early sign the game is running out of moves.
+pub fn count_plays(game_state: &GameState) -> usize {
+    legal_moves(game_state)
+        .iter()
+        .filter(|action| !matches!(action, GameAction::DealFromStock))
+        .count()
+}
+
+/// Foundation that accepts `card`, if any
+fn foundation_for(game_state: &GameState, card: &Card) -> Option<usize> {
+    game_state
+        .foundations
+        .iter()
+        .position(|pile| game_state.rules.accepts_on_foundation(card, pile.last()))
+}
+
+/// Columns `card` can be placed on, with the card it would cover (`None`
+/// for an empty column). Only the first empty column is offered, since
+/// empty columns are interchangeable.
+fn targets(
+    game_state: &GameState,
+    card: &Card,
+    first_empty: Option<usize>,
+) -> Vec<(usize, Option<Card>)> {
+    game_state
+        .tableau
+        .iter()
+        .enumerate()
+        .filter(|(dest, column)| !column.is_empty() || first_empty == Some(*dest))
+        .filter(|(_, column)| game_state.rules.accepts_on_tableau(card, column.last()))
+        .map(|(dest, column)| (dest, column.last().copied()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Rank, Suit};
+    use crate::golf::Golf;
+    use crate::thoughtful::Thoughtful;
+    use crate::variant::Variant;
+
+    fn full_suit(suit: Suit) -> Vec<Card> {
+        Rank::all()
+            .into_iter()
+            .map(|rank| Card::new(suit, rank, true))
+            .collect()
+    }
+
+    /// Clubs and Spades home; the red suits laid out by `arrange`
+    fn endgame(arrange: impl FnOnce(&mut GameState, Vec<Card>, Vec<Card>)) -> GameState {
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.draw_count = DrawCount::One;
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.stock.clear();
+        game_state.foundations = vec![
+            Vec::new(),
+            Vec::new(),
+            full_suit(Suit::Clubs),
+            full_suit(Suit::Spades),
+        ];
+        arrange(
+            &mut game_state,
+            full_suit(Suit::Hearts),
+            full_suit(Suit::Diamonds),
+        );
+        game_state
+    }
+
+    #[test]
+    fn test_finds_winning_line() {
+        let game_state = endgame(|game_state, hearts, diamonds| {
+            // Hearts buried face down in one column, Diamonds in the stock
+            game_state.tableau[0] = hearts.into_iter().rev().collect();
+            for card in &mut game_state.tableau[0][..12] {
+                card.face_up = false;
+            }
+            game_state.stock = diamonds
+                .into_iter()
+                .rev()
+                .map(|card| Card {
+                    face_up: false,
+                    ..card
+                })
+                .collect();
+        });
+
+        let Solution::Winnable(line) = Solver::default().solve(&game_state) else {
+            panic!("expected a winning line");
+        };
+        let mut replay = game_state.clone();
+        for action in line {
+            replay.handle_action(action).unwrap();
+        }
+        assert!(replay.game_won);
+    }
+
+    #[test]
+    fn test_proves_deadlock_unwinnable() {
+        let game_state = endgame(|game_state, hearts, diamonds| {
+            // The 2♥ sits face down under the 3♥, which has nowhere to go
+            game_state.foundations[0] = vec![hearts[0]];
+            game_state.tableau[0] = vec![
+                Card {
+                    face_up: false,
+                    ..hearts[1]
+                },
+                hearts[2],
+            ];
+            game_state.tableau[1] = diamonds;
+            game_state.stock = hearts[3..]
+                .iter()
+                .map(|card| Card {
+                    face_up: false,
+                    ..*card
+                })
+                .collect();
+        });
+        // The Diamonds are stacked Ace first, so the Ace never comes free
+        assert_eq!(Solver::default().solve(&game_state), Solution::Unwinnable);
+    }
+
+    #[test]
+    fn test_stock_cycles_end_the_search() {
+        let game_state = endgame(|game_state, hearts, diamonds| {
+            game_state.draw_count = DrawCount::Three;
+            game_state.foundations[0] = vec![hearts[0]];
+            game_state.tableau[0] = vec![
+                Card {
+                    face_up: false,
+                    ..hearts[1]
+                },
+                hearts[2],
+            ];
+            game_state.tableau[1] = diamonds;
+            game_state.tableau[2] = vec![hearts[12]];
+            // Nothing in the stock can ever be played
+            game_state.stock = hearts[3..12]
+                .iter()
+                .map(|card| Card {
+                    face_up: false,
+                    ..*card
+                })
+                .collect();
+        });
+        // Only a few positions exist: one per point in the stock's cycle
+        assert_eq!(Solver::new(20).solve(&game_state), Solution::Unwinnable);
+    }
+
+    #[test]
+    fn test_searches_thoughtful_games() {
+        let mut game_state = endgame(|game_state, hearts, diamonds| {
+            game_state.tableau[0] = hearts.into_iter().rev().collect();
+            game_state.stock = diamonds.into_iter().rev().collect();
+        });
+        game_state.rules = Variant::new(Thoughtful);
+        assert!(matches!(
+            Solver::default().solve(&game_state),
+            Solution::Winnable(_)
+        ));
+
+        game_state.rules = Variant::new(Golf);
+        assert_eq!(Solver::default().solve(&game_state), Solution::Unknown);
+    }
+
+    #[test]
+    fn test_gives_up_at_node_limit() {
+        let game_state = GameState::new_with_seed(2041);
+        assert_eq!(Solver::new(1).solve(&game_state), Solution::Unknown);
+    }
+
+    #[test]
+    fn test_position_reached_with_more_deals_left_is_searched_again() {
+        let game_state = GameState::new_with_seed(2050);
+        let mut search = Search {
+            node_limit: 0,
+            nodes: 0,
+            line: Vec::new(),
+            visited: HashMap::new(),
+        };
+        search
+            .visited
+            .insert(PackedState::from_game(&game_state), 2);
+        assert_eq!(search.explore(&game_state, 2), Some(false));
+        // Not skipped, so the search goes on until it runs out of nodes
+        assert_eq!(search.explore(&game_state, 0), None);
+    }
+
+    #[test]
+    fn test_legal_moves_are_legal() {
+        let mut game_state = GameState::new_with_seed(2041);
+        for _ in 0..30 {
+            let moves = legal_moves(&game_state);
+            assert!(!moves.is_empty());
+            for action in &moves {
+                assert!(game_state.clone().handle_action(action.clone()).is_ok());
+            }
+            // Walk a few steps into the game along the first move
+            game_state.handle_action(moves[0].clone()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_count_plays_leaves_out_the_stock() {
+        let mut game_state = GameState::new_with_seed(2041);
+        assert_eq!(count_plays(&game_state), legal_moves(&game_state).len() - 1);
+
+        // With the tableau gone only the stock is left to play
+        game_state.tableau.iter_mut().for_each(Vec::clear);
+        assert_eq!(count_plays(&game_state), 0);
+        assert_eq!(legal_moves(&game_state), [GameAction::DealFromStock]);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::deck::{Card, Rank, Suit};
+use crate::registry::{VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, StockDeal, deal_columns};
+use crate::variant::Variant;
+
+/// How many suits a Spider deck is made of: fewer suits make longer
+/// same-suit runs, and an easier game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpiderSuits {
+    One,
+    Two,
+    Four,
+}
+
+impl SpiderSuits {
+    pub fn all() -> [SpiderSuits; 3] {
+        [SpiderSuits::One, SpiderSuits::Two, SpiderSuits::Four]
+    }
+
+    /// Suits in play, each repeated to make up the 104 cards
+    fn suits(&self) -> &'static [Suit] {
+        match self {
+            SpiderSuits::One => &[Suit::Spades],
+            SpiderSuits::Two => &[Suit::Spades, Suit::Hearts],
+            SpiderSuits::Four => &[Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds],
+        }
+    }
+}
+
+/// Spider: 104 cards in ten columns, building down regardless of suit, but
+/// only runs of one suit move together. A run from King to Ace leaves the
+/// table by itself; clearing all eight wins. The stock deals a card onto
+/// every column at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spider {
+    pub suits: SpiderSuits,
+}
+
+impl Spider {
+    pub fn new(suits: SpiderSuits) -> Self {
+        Spider { suits }
+    }
+}
+
+impl GameRules for Spider {
+    fn name(&self) -> &'static str {
+        match self.suits {
+            SpiderSuits::One => "Spider (1 suit)",
+            SpiderSuits::Two => "Spider (2 suits)",
+            SpiderSuits::Four => "Spider (4 suits)",
+        }
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        let suits = self.suits.suits();
+        (0..8)
+            .flat_map(|set| {
+                let suit = suits[set % suits.len()];
+                Rank::all().map(|rank| Card::new(suit, rank, false))
+            })
+            .collect()
+    }
+
+    fn foundation_count(&self) -> usize {
+        8
+    }
+
+    /// Six cards in each of the first four columns and five in the rest,
+    /// leaving fifty cards for five deals of ten
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        deal_columns(deck, 10, |col| if col < 4 { 6 } else { 5 })
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        !cards.is_empty()
+            && cards.iter().all(|card| card.face_up)
+            && cards
+                .windows(2)
+                .all(|pair| pair[1].suit == pair[0].suit && builds_on(&pair[1], &pair[0]))
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        top.is_none_or(|top| builds_on(card, top))
+    }
+
+    /// Cards only reach the foundations as complete runs
+    fn accepts_on_foundation(&self, _card: &Card, _top: Option<&Card>) -> bool {
+        false
+    }
+
+    fn stock_deal(&self) -> StockDeal {
+        StockDeal::RowToTableau
+    }
+
+    fn clears_complete_runs(&self) -> bool {
+        true
+    }
+}
+
+/// One rank below a face-up card, in any suit
+fn builds_on(card: &Card, top: &Card) -> bool {
+    top.face_up && card.rank as u8 + 1 == top.rank as u8
+}
+
+/// Offer Spider to players, once for each number of suits
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    SpiderSuits::all().into_iter().try_for_each(|suits| {
+        registry.register(VariantEntry::new(Variant::new(Spider::new(suits))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::state::{GameState, Position};
+
+    fn suit_run(suit: Suit) -> Vec<Card> {
+        Rank::all()
+            .into_iter()
+            .rev()
+            .map(|rank| Card::new(suit, rank, true))
+            .collect()
+    }
+
+    #[test]
+    fn test_spider_decks_and_deal() {
+        for suits in SpiderSuits::all() {
+            let game_state = GameState::with_rules(Spider::new(suits), 2053);
+            assert_eq!(game_state.tableau.len(), 10);
+            assert_eq!(game_state.foundations.len(), 8);
+            assert_eq!(game_state.stock.len(), 50);
+
+            let mut cards: Vec<Card> = game_state
+                .tableau
+                .iter()
+                .flatten()
+                .chain(&game_state.stock)
+                .copied()
+                .collect();
+            assert_eq!(cards.len(), 104);
+            cards.sort_by_key(Card::index);
+            cards.dedup_by_key(|card| card.suit);
+            assert_eq!(cards.len(), suits.suits().len());
+        }
+    }
+
+    #[test]
+    fn test_spider_runs_move_in_suit() {
+        let spider = Spider::new(SpiderSuits::Two);
+        let nine = Card::new(Suit::Spades, Rank::Nine, true);
+        let eight = Card::new(Suit::Spades, Rank::Eight, true);
+        let red_eight = Card::new(Suit::Hearts, Rank::Eight, true);
+
+        assert!(spider.accepts_on_tableau(&red_eight, Some(&nine)));
+        assert!(spider.accepts_on_tableau(&nine, None));
+        assert!(!spider.accepts_on_tableau(&nine, Some(&eight)));
+        assert!(spider.is_movable_run(&[nine, eight]));
+        assert!(!spider.is_movable_run(&[nine, red_eight]));
+    }
+
+    #[test]
+    fn test_dealing_a_row() {
+        let mut game_state = GameState::with_rules(Spider::new(SpiderSuits::One), 1);
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        assert_eq!(game_state.stock.len(), 40);
+        assert!(game_state.waste.is_empty());
+        for column in &game_state.tableau {
+            assert!(column.last().unwrap().face_up);
+        }
+
+        // No dealing onto an empty column
+        game_state.tableau[3].clear();
+        assert!(game_state.handle_action(GameAction::DealFromStock).is_err());
+    }
+
+    #[test]
+    fn test_complete_runs_leave_the_table() {
+        let mut game_state = GameState::with_rules(Spider::new(SpiderSuits::One), 0);
+        game_state.stock.clear();
+        game_state.tableau = vec![Vec::new(); 10];
+        for pile in &mut game_state.foundations[1..] {
+            *pile = suit_run(Suit::Spades);
+        }
+        // The last run, with its Ace on another column
+        let mut run = suit_run(Suit::Spades);
+        let ace = run.pop().unwrap();
+        game_state.tableau[0] = vec![Card::new(Suit::Spades, Rank::Five, false)];
+        game_state.tableau[0].extend(run);
+        game_state.tableau[1] = vec![ace];
+
+        game_state
+            .handle_action(GameAction::MoveCard {
+                from: Position::Tableau(1, 0),
+                to: Position::Tableau(0, 13),
+            })
+            .unwrap();
+        assert_eq!(game_state.foundations[0].len(), 13);
+        assert_eq!(game_state.foundations[0].last().unwrap().rank, Rank::King);
+        // The card under the run is turned over
+        assert_eq!(game_state.tableau[0].len(), 1);
+        assert!(game_state.tableau[0][0].face_up);
+        assert!(game_state.game_won);
+    }
+}
@@ -0,0 +1,1766 @@
+use crate::actions::{DrawCount, GameAction, Scoring};
+use crate::deck::{Card, Rank, shuffle_with_seed};
+use crate::locale::{tr, trf};
+use crate::rules::{GameRules, StockDeal};
+use crate::settings::Accommodations;
+use crate::variant::Variant;
+use rand::{Rng, thread_rng};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+// Standard (Windows-style) scoring
+const SCORE_WASTE_TO_TABLEAU: i32 = 5;
+const SCORE_TO_FOUNDATION: i32 = 10;
+const SCORE_TURN_OVER_CARD: i32 = 5;
+const SCORE_RECYCLE_DRAW_ONE: i32 = -100;
+const SCORE_RECYCLE_DRAW_THREE: i32 = -20;
+const SCORE_COMPLETE_RUN: i32 = 100;
+
+// Vegas scoring, in dollars
+const VEGAS_STAKE_PER_CARD: i32 = 1;
+const VEGAS_PAYOUT_PER_CARD: i32 = 5;
+
+/// Prefix and version of position codes from `GameState::encode`
+const POSITION_CODE_VERSION: &str = "K1";
+/// One letter per card, indexed by `Card::index`
+const CARD_LETTERS: &[u8; 52] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+// TODO simplify this. Only the index of the tableau and foundation is needed, stock is not needed and waste is just unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Tableau(usize, usize), // column, index in column
+    Foundation(usize),     // foundation pile index (0-3)
+    Stock,
+    Waste(usize), // index in waste pile
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Tableau(col, idx) => write!(f, "Tableau({}, {})", col, idx),
+            Position::Foundation(idx) => write!(f, "Foundation({})", idx),
+            Position::Stock => write!(f, "Stock"),
+            Position::Waste(idx) => write!(f, "Waste({})", idx),
+        }
+    }
+}
+
+/// A pile on the board, where its cards are drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pile {
+    Tableau(usize),
+    Foundation(usize),
+    Stock,
+    Waste,
+}
+
+impl Pile {
+    pub fn of(position: Position) -> Self {
+        match position {
+            Position::Tableau(col, _) => Pile::Tableau(col),
+            Position::Foundation(foundation) => Pile::Foundation(foundation),
+            Position::Stock => Pile::Stock,
+            Position::Waste(_) => Pile::Waste,
+        }
+    }
+}
+
+/// A pile whose cards differ between two states of a game, so only it
+/// needs drawing again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PileChange {
+    pub pile: Pile,
+    /// How many cards at the bottom of the pile are as they were, the same
+    /// card the same way up; the cards above them changed
+    pub unchanged: usize,
+}
+
+/// A game in progress under the rules `R`; by default any `Variant`,
+/// chosen when the game is dealt
+#[derive(Debug, Clone)]
+pub struct GameState<R: GameRules = Variant> {
+    /// The variant's rules, consulted for every move
+    pub rules: R,
+    /// Tableau columns, left to right, each containing a stack of cards
+    pub tableau: Vec<Vec<Card>>,
+    /// Foundation piles, built up by the rules' foundation order
+    pub foundations: Vec<Vec<Card>>,
+    /// Stock pile (face-down cards to deal from)
+    pub stock: Vec<Card>,
+    /// Waste pile (face-up cards dealt from stock)
+    pub waste: Vec<Card>,
+    /// Number of moves made in current game
+    pub move_count: u32,
+    /// Current score: never negative when scored the standard way, while a
+    /// Vegas score starts out owing the stake
+    pub score: i32,
+    pub scoring: Scoring,
+    /// When the current game started
+    pub start_time: SystemTime,
+    /// When the game was won, stopping the clock
+    pub end_time: Option<SystemTime>,
+    /// Whether the game has been won
+    pub game_won: bool,
+    /// How many cards to draw from stock at once
+    pub draw_count: DrawCount,
+    /// Accessibility accommodations used at any point during this game
+    pub accommodations: Accommodations,
+    /// Practice games are never ranked: no streaks, bests or high scores.
+    /// Once set (at the start, or by using hints) it stays set.
+    pub practice: bool,
+    /// Seed the deck was shuffled with; the same seed always deals the same layout
+    pub seed: u64,
+    /// Dealt from an imported layout rather than a seed, so `seed` is meaningless
+    pub imported: bool,
+    /// Times the waste has been turned back over into the stock
+    pub recycles: u32,
+}
+
+impl GameState {
+    /// Create a new game with properly shuffled and dealt cards, using a fresh random seed
+    pub fn new() -> Self {
+        Self::new_with_rng(&mut thread_rng())
+    }
+
+    /// Create a new game whose seed is drawn from `rng`, so tests and tools
+    /// can reproduce exact shuffles from a seeded generator. The game still
+    /// records the drawn seed as its game number.
+    pub fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new_with_seed(rng.r#gen())
+    }
+
+    /// Create a new Klondike game whose shuffle is fully determined by `seed`
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self::with_rules(Variant::default(), seed)
+    }
+
+    /// Whether this game can count toward streaks, bests and high scores:
+    /// strict Klondike, outside practice
+    pub fn is_ranked(&self) -> bool {
+        self.rules.is_klondike() && !self.practice && self.accommodations.is_strict()
+    }
+
+    /// Create a new game with specific draw count
+    pub fn new_with_draw_count(draw_count: DrawCount) -> Self {
+        let mut game_state = Self::new();
+        game_state.draw_count = draw_count;
+        game_state
+    }
+
+    /// Compact shareable code for the full position: draw mode, score, moves
+    /// and every pile, e.g. `K1.3.25.14.Q.1bA...`. Fields are separated by
+    /// dots: tableau columns (face-down count, then cards bottom to top),
+    /// foundations, stock, then waste, one letter per card.
+    ///
+    /// Only Klondike's codes can be decoded. Other variants' boards are
+    /// written the same way, every pile they have included, but neither the
+    /// variant nor its rules are, so `position_key` is what tells positions
+    /// apart across variants.
+    pub fn encode(&self) -> String {
+        let letters = |cards: &[Card]| -> String {
+            cards
+                .iter()
+                .map(|card| CARD_LETTERS[card.index() as usize] as char)
+                .collect()
+        };
+
+        let mut fields = vec![
+            POSITION_CODE_VERSION.to_string(),
+            match self.draw_count {
+                DrawCount::One => "1".to_string(),
+                DrawCount::Three => "3".to_string(),
+            },
+            self.score.to_string(),
+            self.move_count.to_string(),
+        ];
+        for column in &self.tableau {
+            let hidden = column.iter().take_while(|card| !card.face_up).count();
+            fields.push(format!("{}{}", hidden, letters(column)));
+        }
+        fields.extend(self.foundations.iter().map(|pile| letters(pile)));
+        fields.push(letters(&self.stock));
+        fields.push(letters(&self.waste));
+        fields.join(".")
+    }
+
+    /// Key telling this position apart from any other, in any variant: what
+    /// analysis and hints worked out is kept against it
+    pub fn position_key(&self) -> String {
+        format!("{}.{}.{}", self.rules.name(), self.recycles, self.encode())
+    }
+
+    /// Rebuild a Klondike position from `encode`'s code, validating that it
+    /// holds each of the 52 cards once and that the foundations are built
+    /// legally
+    pub fn decode(code: &str) -> Result<GameState, String> {
+        let fields: Vec<&str> = code.trim().split('.').collect();
+        if fields.len() != 17 || fields[0] != POSITION_CODE_VERSION {
+            return Err("not a position code".to_string());
+        }
+
+        let parse_cards = |text: &str, face_up: bool| -> Result<Vec<Card>, String> {
+            text.bytes()
+                .map(|letter| {
+                    let index = CARD_LETTERS
+                        .iter()
+                        .position(|l| *l == letter)
+                        .ok_or_else(|| format!("invalid card letter `{}`", letter as char))?;
+                    let mut card = Card::from_index(index as u8).unwrap_or_else(|| unreachable!());
+                    card.face_up = face_up;
+                    Ok(card)
+                })
+                .collect()
+        };
+
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.imported = true;
+        game_state.draw_count = match fields[1] {
+            "1" => DrawCount::One,
+            "3" => DrawCount::Three,
+            other => return Err(format!("invalid draw mode `{}`", other)),
+        };
+        // Vegas scores go below zero
+        game_state.score = fields[2]
+            .parse()
+            .map_err(|_| format!("invalid score `{}`", fields[2]))?;
+        game_state.move_count = fields[3]
+            .parse()
+            .map_err(|_| format!("invalid move count `{}`", fields[3]))?;
+
+        for (col, field) in fields[4..11].iter().enumerate() {
+            let split = field
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(field.len());
+            let hidden: usize = field[..split]
+                .parse()
+                .map_err(|_| format!("column {} is missing its face-down count", col + 1))?;
+            let mut column = parse_cards(&field[split..], true)?;
+            if hidden > column.len() {
+                return Err(format!("column {} has too few cards", col + 1));
+            }
+            for card in &mut column[..hidden] {
+                card.face_up = false;
+            }
+            game_state.tableau[col] = column;
+        }
+
+        for (i, field) in fields[11..15].iter().enumerate() {
+            let pile = parse_cards(field, true)?;
+            for (n, card) in pile.iter().enumerate() {
+                let below = n.checked_sub(1).map(|below| &pile[below]);
+                if !card.can_place_on_foundation(below) {
+                    return Err(format!("foundation {} is not built up by suit", i + 1));
+                }
+            }
+            game_state.foundations[i] = pile;
+        }
+        game_state.stock = parse_cards(fields[15], false)?;
+        game_state.waste = parse_cards(fields[16], true)?;
+
+        let mut seen = [false; 52];
+        let all_cards = game_state
+            .tableau
+            .iter()
+            .chain(&game_state.foundations)
+            .chain([&game_state.stock, &game_state.waste])
+            .flatten();
+        for card in all_cards {
+            if std::mem::replace(&mut seen[card.index() as usize], true) {
+                return Err(format!("{} appears more than once", card.code()));
+            }
+        }
+        if let Some(missing) = seen.iter().position(|seen| !seen) {
+            let card = Card::from_index(missing as u8).unwrap_or_else(|| unreachable!());
+            return Err(format!("{} is missing", card.code()));
+        }
+
+        game_state.check_win();
+        Ok(game_state)
+    }
+}
+
+impl<R: GameRules + Clone> GameState<R> {
+    /// Create a new game of the variant `rules` whose shuffle is fully
+    /// determined by `seed`
+    pub fn with_rules(rules: R, seed: u64) -> Self {
+        let mut deck = rules.deck();
+        shuffle_with_seed(&mut deck, seed);
+        Self::from_deck(rules, deck, seed)
+    }
+
+    /// Deal an already shuffled deck into the variant's layout
+    fn from_deck(rules: R, deck: Vec<Card>, seed: u64) -> Self {
+        let (tableau, stock) = rules.deal(deck);
+        GameState {
+            foundations: vec![Vec::new(); rules.foundation_count()],
+            rules,
+            tableau,
+            stock,
+            waste: Vec::new(),
+            move_count: 0,
+            score: 0,
+            scoring: Scoring::Standard,
+            start_time: SystemTime::now(),
+            end_time: None,
+            game_won: false,
+            draw_count: DrawCount::Three, // Default to harder mode
+            accommodations: Accommodations::default(),
+            practice: false,
+            seed,
+            imported: false,
+            recycles: 0,
+        }
+    }
+
+    /// Time spent on this game so far (frozen once the game is won)
+    pub fn elapsed(&self) -> Duration {
+        let end = self.end_time.unwrap_or_else(SystemTime::now);
+        end.duration_since(self.start_time).unwrap_or_default()
+    }
+
+    /// Number of cards moved up to the foundations
+    pub fn foundation_card_count(&self) -> usize {
+        self.foundations.iter().map(Vec::len).sum()
+    }
+
+    /// Every pile on the board: the tableau columns, the foundations, then
+    /// the stock and waste
+    pub fn piles(&self) -> impl Iterator<Item = Pile> + use<R> {
+        (0..self.tableau.len())
+            .map(Pile::Tableau)
+            .chain((0..self.foundations.len()).map(Pile::Foundation))
+            .chain([Pile::Stock, Pile::Waste])
+    }
+
+    /// Cards of `pile`, bottom first; none for a pile this board doesn't have
+    pub fn cards(&self, pile: Pile) -> &[Card] {
+        let cards = match pile {
+            Pile::Tableau(col) => self.tableau.get(col),
+            Pile::Foundation(foundation) => self.foundations.get(foundation),
+            Pile::Stock => Some(&self.stock),
+            Pile::Waste => Some(&self.waste),
+        };
+        cards.map_or(&[], Vec::as_slice)
+    }
+
+    /// Piles whose cards differ from `old`'s, an earlier state of the same
+    /// deal, in board order. Cards turned over in place count as a change.
+    pub fn diff(&self, old: &GameState<R>) -> Vec<PileChange> {
+        self.piles()
+            .filter_map(|pile| {
+                let (was, is) = (old.cards(pile), self.cards(pile));
+                (was != is).then(|| PileChange {
+                    pile,
+                    unchanged: was.iter().zip(is).take_while(|(was, is)| was == is).count(),
+                })
+            })
+            .collect()
+    }
+
+    /// Finishing time projected from the current rate of building the
+    /// foundations, or `None` before the first card reaches them
+    pub fn projected_time(&self) -> Option<Duration> {
+        let placed = self.foundation_card_count() as u32;
+        if placed == 0 {
+            return None;
+        }
+        Some(self.elapsed() * self.rules.deck().len() as u32 / placed)
+    }
+
+    /// Get a summary of the current game state for display
+    pub fn summary(&self) -> String {
+        format!(
+            "Score: {} | Moves: {} | Stock: {} | Waste: {} | Draw: {:?} | {} | {}",
+            self.score,
+            self.move_count,
+            self.stock.len(),
+            self.waste.len(),
+            self.draw_count,
+            self.rules.name(),
+            self.deal_label()
+        )
+    }
+
+    /// Which deal this is, e.g. `Game #42`
+    pub fn deal_label(&self) -> String {
+        if self.imported {
+            tr("Imported deal").to_string()
+        } else {
+            trf("Game #{}", &[&self.seed])
+        }
+    }
+
+    /// Times the waste can still be turned back over, when the rules
+    /// limit it
+    pub fn recycles_left(&self) -> Option<u32> {
+        self.rules
+            .recycle_limit()
+            .map(|limit| limit.saturating_sub(self.recycles))
+    }
+
+    /// Waste cards on show, top last: the last three dealt when dealing
+    /// three at a time, otherwise just the top card
+    pub fn waste_fan(&self) -> &[Card] {
+        let shown = match (self.rules.stock_deal(), self.draw_count) {
+            (StockDeal::ToWaste, DrawCount::Three) => 3,
+            _ => 1,
+        };
+        &self.waste[self.waste.len().saturating_sub(shown)..]
+    }
+
+    /// Cards the next deal from the stock turns over onto the waste
+    fn cards_per_deal(&self) -> usize {
+        match (self.rules.stock_deal(), self.draw_count) {
+            (StockDeal::OneToWaste, _) | (_, DrawCount::One) => 1,
+            (_, DrawCount::Three) => 3.min(self.stock.len()),
+        }
+    }
+
+    /// What clicking the stock does now, e.g. `Deal 3 cards`
+    pub fn stock_label(&self) -> String {
+        if self.rules.stock_deal() == StockDeal::RowToTableau {
+            return if self.stock.is_empty() {
+                tr("The stock is empty").to_string()
+            } else {
+                tr("Deal a card onto every column").to_string()
+            };
+        }
+        if !self.stock.is_empty() {
+            return match self.cards_per_deal() {
+                1 => tr("Deal 1 card").to_string(),
+                cards => trf("Deal {} cards", &[&cards]),
+            };
+        }
+        match self.recycles_left() {
+            _ if self.waste.is_empty() => tr("The stock is empty").to_string(),
+            Some(0) => tr("The stock can't be turned over again").to_string(),
+            Some(left) => trf("Turn the waste over ({} left)", &[&left]),
+            None => tr("Turn the waste over").to_string(),
+        }
+    }
+
+    /// What the foundations take, for a reminder over them
+    pub fn foundation_label(&self) -> String {
+        if let Some(total) = self.rules.pair_total() {
+            trf("Cards adding up to {} are removed here", &[&total])
+        } else if self.rules.clears_complete_runs() {
+            tr("Runs from King down to Ace collect here").to_string()
+        } else {
+            tr("Build each suit up from Ace to King").to_string()
+        }
+    }
+
+    /// Which pass through the stock this is, counting from 1
+    pub fn stock_pass(&self) -> u32 {
+        self.recycles + 1
+    }
+
+    /// Passes through the stock allowed in all, when the rules limit them
+    pub fn stock_passes(&self) -> Option<u32> {
+        self.rules.recycle_limit().map(|limit| limit + 1)
+    }
+
+    /// Whether the stock can't be turned over again once it runs out
+    pub fn is_last_pass(&self) -> bool {
+        self.recycles_left() == Some(0)
+    }
+
+    /// Handle a game action and update the state accordingly
+    pub fn handle_action(&mut self, action: GameAction) -> Result<(), String> {
+        match action {
+            GameAction::DealFromStock => self.deal_from_stock(),
+            GameAction::MoveCard { from, to } => self.move_card(from, to),
+            GameAction::CollectAll => self.collect_all(),
+            GameAction::NewGame => {
+                let draw_count = self.draw_count;
+                let scoring = self.scoring;
+                *self = Self::with_rules(self.rules.clone(), thread_rng().r#gen());
+                self.draw_count = draw_count;
+                self.set_scoring(scoring);
+                Ok(())
+            }
+            GameAction::ReplaySeed if self.imported => {
+                Err("An imported deal has no seed to replay".to_string())
+            }
+            GameAction::ReplaySeed => {
+                let draw_count = self.draw_count;
+                let scoring = self.scoring;
+                let practice = self.practice;
+                *self = Self::with_rules(self.rules.clone(), self.seed);
+                self.draw_count = draw_count;
+                self.set_scoring(scoring);
+                // Replaying a deal already seen is still practice
+                self.practice = practice;
+                Ok(())
+            }
+            // Take-backs need the previous states, which live in `history::History`
+            GameAction::Undo | GameAction::Redo => {
+                Err("Undo and redo are handled by the game history".to_string())
+            }
+        }
+    }
+
+    /// Deal cards from stock to waste pile
+    pub fn deal_from_stock(&mut self) -> Result<(), String> {
+        if self.rules.stock_deal() == StockDeal::RowToTableau {
+            return self.deal_row();
+        }
+        if self.stock.is_empty() {
+            // If stock is empty, move all waste cards back to stock (face-down)
+            if self.waste.is_empty() {
+                return Err("Both stock and waste are empty".to_string());
+            }
+            if self
+                .rules
+                .recycle_limit()
+                .is_some_and(|limit| self.recycles >= limit)
+            {
+                return Err("The stock cannot be turned over again".to_string());
+            }
+
+            // Move waste back to stock, face-down unless the stock is open,
+            // in reverse order
+            while let Some(mut card) = self.waste.pop() {
+                card.face_up = self.rules.open_stock();
+                self.stock.push(card);
+            }
+            self.add_score(match self.draw_count {
+                DrawCount::One => SCORE_RECYCLE_DRAW_ONE,
+                DrawCount::Three => SCORE_RECYCLE_DRAW_THREE,
+            });
+            self.recycles += 1;
+            self.move_count += 1;
+            return Ok(());
+        }
+
+        // Deal cards from stock to waste
+        for _ in 0..self.cards_per_deal() {
+            if let Some(mut card) = self.stock.pop() {
+                card.face_up = true;
+                self.waste.push(card);
+            }
+        }
+
+        self.move_count += 1;
+        Ok(())
+    }
+
+    /// Deal one card from the stock face up onto every column
+    fn deal_row(&mut self) -> Result<(), String> {
+        if self.stock.is_empty() {
+            return Err("The stock is empty".to_string());
+        }
+        if self.tableau.iter().any(Vec::is_empty) {
+            return Err("Every column needs a card before dealing".to_string());
+        }
+
+        for column in &mut self.tableau {
+            if let Some(mut card) = self.stock.pop() {
+                card.face_up = true;
+                column.push(card);
+            }
+        }
+        self.move_count += 1;
+        self.clear_complete_runs();
+        self.check_win();
+        Ok(())
+    }
+
+    /// Flip a face-down card to face-up
+    pub fn flip_card(&mut self, position: Position) -> Result<(), String> {
+        match position {
+            Position::Tableau(col, idx) => {
+                if col >= self.tableau.len() {
+                    return Err("Invalid tableau column".to_string());
+                }
+
+                let pile = &mut self.tableau[col];
+                if idx >= pile.len() {
+                    return Err("Invalid card index in tableau".to_string());
+                }
+
+                // Can only flip the top card (last in the pile) and only if it's face-down
+                if idx != pile.len() - 1 {
+                    return Err("Can only flip the top card".to_string());
+                }
+
+                let card = &mut pile[idx];
+                if card.face_up {
+                    return Err("Card is already face-up".to_string());
+                }
+
+                card.face_up = true;
+                self.add_score(SCORE_TURN_OVER_CARD);
+                self.move_count += 1;
+                Ok(())
+            }
+            _ => Err("Can only flip cards in tableau".to_string()),
+        }
+    }
+
+    /// Move a card from one position to another
+    pub fn move_card(&mut self, from: Position, to: Position) -> Result<(), String> {
+        if let Some(total) = self.rules.pair_total()
+            && !matches!(to, Position::Foundation(_))
+        {
+            return self.remove_pair(from, to, total);
+        }
+
+        // Get the card(s) to move
+        let cards_to_move = self.get_cards_at_position(from)?;
+        if cards_to_move.is_empty() {
+            return Err("No cards to move".to_string());
+        }
+
+        // Validate the move
+        self.check_move(&cards_to_move, to)?;
+
+        // Remove cards from source
+        let moved = cards_to_move.len();
+        self.remove_cards_from_position(from, moved)?;
+
+        // Add cards to destination
+        self.add_cards_to_position(to, cards_to_move)?;
+
+        // Auto-flip newly exposed cards in tableau
+        if let Position::Tableau(col, _) = from
+            && let Some(top_card) = self.tableau[col].last_mut()
+            && !top_card.face_up
+        {
+            top_card.face_up = true;
+            self.add_score(SCORE_TURN_OVER_CARD);
+        }
+
+        match (from, to) {
+            (_, Position::Foundation(_) | Position::Waste(_)) => {
+                self.add_score(SCORE_TO_FOUNDATION)
+            }
+            (Position::Waste(_), Position::Tableau(..)) => self.add_score(SCORE_WASTE_TO_TABLEAU),
+            _ => {}
+        }
+        match (from, to) {
+            (_, Position::Foundation(_)) => self.add_vegas_payout(moved as i32),
+            (Position::Foundation(_), _) => self.add_vegas_payout(-(moved as i32)),
+            _ => {}
+        }
+
+        self.move_count += 1;
+        self.clear_complete_runs();
+        self.check_win();
+        Ok(())
+    }
+
+    /// Remove the card at `from` with the top card at `to` when their ranks
+    /// add up to `total`, for rules that pair cards off rather than build
+    fn remove_pair(&mut self, from: Position, to: Position, total: u8) -> Result<(), String> {
+        let from = self.top_of(from);
+        let to = self.top_of(to);
+        if from == to {
+            return Err("A card cannot pair with itself".to_string());
+        }
+        let card = self.get_cards_at_position(from)?[0];
+        let other = self.get_cards_at_position(to)?[0];
+        if card.rank as u8 + other.rank as u8 != total {
+            return Err(trf("Those cards do not add up to {}", &[&total]));
+        }
+        if self.foundations.is_empty() {
+            return Err("There is no pile to discard to".to_string());
+        }
+
+        self.remove_cards_from_position(from, 1)?;
+        self.remove_cards_from_position(to, 1)?;
+        self.foundations[0].extend([card, other]);
+        self.add_score(2 * SCORE_TO_FOUNDATION);
+        self.add_vegas_payout(2);
+        self.move_count += 1;
+        self.check_win();
+        Ok(())
+    }
+
+    /// The top card's position of the pile at `position`
+    fn top_of(&self, position: Position) -> Position {
+        match position {
+            Position::Tableau(col, _) => Position::Tableau(
+                col,
+                self.tableau
+                    .get(col)
+                    .map_or(0, |column| column.len().saturating_sub(1)),
+            ),
+            Position::Waste(_) => Position::Waste(self.waste.len().saturating_sub(1)),
+            other => other,
+        }
+    }
+
+    /// Whether no move is left: the stock cannot be dealt and no card can be
+    /// played anywhere
+    pub fn is_stuck(&self) -> bool {
+        if self.game_won || self.clone().deal_from_stock().is_ok() {
+            return false;
+        }
+        self.card_moves().next().is_none()
+    }
+
+    /// Every move that can be made now, dealing from the stock first, for
+    /// playing from the keyboard alone. A card that could go to any of
+    /// several foundations is only offered the first.
+    pub fn available_moves(&self) -> Vec<GameAction> {
+        if self.game_won {
+            return Vec::new();
+        }
+        let deal = self.clone().deal_from_stock().is_ok();
+        let mut moves: Vec<GameAction> = deal
+            .then_some(GameAction::DealFromStock)
+            .into_iter()
+            .chain(self.card_moves())
+            .collect();
+        moves.dedup_by(|later, earlier| match (later, earlier) {
+            (
+                GameAction::MoveCard {
+                    from,
+                    to: Position::Foundation(_),
+                },
+                GameAction::MoveCard {
+                    from: earlier_from,
+                    to: Position::Foundation(_),
+                },
+            ) => from == earlier_from,
+            _ => false,
+        });
+        moves
+    }
+
+    /// Cards that can be moved with where they can go, found by trying each
+    /// pile's cards on every other pile in a copy of the game
+    fn card_moves(&self) -> impl Iterator<Item = GameAction> + '_ {
+        let mut sources = Vec::new();
+        let mut destinations = Vec::new();
+        for (col, column) in self.tableau.iter().enumerate() {
+            sources.extend((0..column.len()).map(|idx| Position::Tableau(col, idx)));
+            destinations.push(Position::Tableau(col, column.len()));
+        }
+        if let Some(top) = self.waste.len().checked_sub(1) {
+            sources.push(Position::Waste(top));
+            destinations.push(Position::Waste(top));
+        }
+        destinations.extend((0..self.foundations.len()).map(Position::Foundation));
+
+        sources
+            .into_iter()
+            .filter(|from| self.get_cards_at_position(*from).is_ok())
+            .flat_map(move |from| {
+                destinations
+                    .clone()
+                    .into_iter()
+                    .filter(move |to| self.clone().move_card(from, *to).is_ok())
+                    .map(move |to| GameAction::MoveCard { from, to })
+            })
+    }
+
+    /// Next card that can go up to a foundation, from the waste first and
+    /// then the columns left to right, for auto-completing a game
+    pub fn next_foundation_move(&self) -> Option<GameAction> {
+        self.foundation_move_where(|_| true)
+    }
+
+    /// Move every card that is safe to collect up to the foundations. A card
+    /// is safe once no card could still need to be built on it: Aces and
+    /// Twos always, anything else once every card of the other color one
+    /// rank lower is on the foundations.
+    pub fn collect_all(&mut self) -> Result<(), String> {
+        let mut collected = 0;
+        while let Some(GameAction::MoveCard { from, to }) =
+            self.foundation_move_where(|card| self.is_safe_to_collect(card))
+        {
+            self.move_card(from, to)?;
+            collected += 1;
+        }
+        if collected == 0 {
+            return Err("No cards are safe to collect".to_string());
+        }
+        Ok(())
+    }
+
+    fn is_safe_to_collect(&self, card: &Card) -> bool {
+        if card.rank as u8 <= Rank::Two as u8 {
+            return true;
+        }
+        // Each deck has two cards of the other color one rank lower
+        let decks = self.foundations.len() / 4;
+        let collected = self
+            .foundations
+            .iter()
+            .flatten()
+            .filter(|other| {
+                other.is_red() != card.is_red() && other.rank as u8 + 1 == card.rank as u8
+            })
+            .count();
+        collected >= 2 * decks
+    }
+
+    /// First move of a card accepted by `keep` up to a foundation
+    fn foundation_move_where(&self, keep: impl Fn(&Card) -> bool) -> Option<GameAction> {
+        let waste = self.waste.len().checked_sub(1).map(Position::Waste);
+        let column_tops = (0..self.tableau.len()).filter_map(|col| {
+            let top = self.tableau[col].len().checked_sub(1)?;
+            Some(Position::Tableau(col, top))
+        });
+        waste.into_iter().chain(column_tops).find_map(|from| {
+            let card = match from {
+                Position::Waste(_) => self.waste.last()?,
+                Position::Tableau(col, _) => self.tableau[col].last()?,
+                _ => return None,
+            };
+            if !keep(card) {
+                return None;
+            }
+            (0..self.foundations.len())
+                .map(Position::Foundation)
+                .find(|to| self.clone().move_card(from, *to).is_ok())
+                .map(|to| GameAction::MoveCard { from, to })
+        })
+    }
+
+    /// Send every King-to-Ace run on top of a column to an empty foundation,
+    /// Ace first, when the rules clear complete runs
+    fn clear_complete_runs(&mut self) {
+        if !self.rules.clears_complete_runs() {
+            return;
+        }
+        for col in 0..self.tableau.len() {
+            let Some(start) = self.tableau[col].len().checked_sub(13) else {
+                continue;
+            };
+            let run = &self.tableau[col][start..];
+            if run[0].rank != Rank::King || !self.rules.is_movable_run(run) {
+                continue;
+            }
+            let Some(foundation) = self.foundations.iter().position(Vec::is_empty) else {
+                return;
+            };
+
+            let run = self.tableau[col].split_off(start);
+            self.foundations[foundation] = run.into_iter().rev().collect();
+            self.add_score(SCORE_COMPLETE_RUN);
+            self.add_vegas_payout(self.foundations[foundation].len() as i32);
+            if let Some(top_card) = self.tableau[col].last_mut()
+                && !top_card.face_up
+            {
+                top_card.face_up = true;
+                self.add_score(SCORE_TURN_OVER_CARD);
+            }
+        }
+    }
+
+    /// Score a game just dealt by `scoring`, from its opening score
+    pub fn set_scoring(&mut self, scoring: Scoring) {
+        self.scoring = scoring;
+        self.score = match scoring {
+            Scoring::Vegas => -VEGAS_STAKE_PER_CARD * self.rules.deck().len() as i32,
+            Scoring::Standard | Scoring::None => 0,
+        };
+    }
+
+    /// Adjust a standard score, never letting it drop below zero
+    fn add_score(&mut self, points: i32) {
+        if self.scoring == Scoring::Standard {
+            self.score = (self.score + points).max(0);
+        }
+    }
+
+    /// Pay out on `cards` reaching the foundations in a Vegas game, or take
+    /// it back for cards leaving them
+    fn add_vegas_payout(&mut self, cards: i32) {
+        if self.scoring == Scoring::Vegas {
+            self.score += VEGAS_PAYOUT_PER_CARD * cards;
+        }
+    }
+
+    /// Mark the game as won once the rules say so
+    fn check_win(&mut self) {
+        if !self.game_won && self.rules.is_won(&self.tableau, &self.foundations) {
+            self.game_won = true;
+            self.end_time = Some(SystemTime::now());
+        }
+    }
+
+    pub fn get_cards_at_position(&self, position: Position) -> Result<Vec<Card>, String> {
+        match position {
+            Position::Tableau(col, idx) => {
+                if col >= self.tableau.len() {
+                    return Err("Invalid tableau column".to_string());
+                }
+                let pile = &self.tableau[col];
+                if idx >= pile.len() {
+                    return Err("Invalid card index".to_string());
+                }
+
+                if self.rules.is_covered(&self.tableau, col) {
+                    return Err("That card is covered".to_string());
+                }
+
+                // Get all cards from the specified index to the end (card sequences)
+                let cards = pile[idx..].to_vec();
+
+                // Validate that this forms a valid sequence
+                if self.rules.is_movable_run(&cards) {
+                    Ok(cards)
+                } else {
+                    Err("Invalid card sequence".to_string())
+                }
+            }
+            Position::Waste(idx) => {
+                if idx >= self.waste.len() {
+                    return Err("Invalid waste index".to_string());
+                }
+                if idx == self.waste.len() - 1 {
+                    Ok(vec![self.waste[idx]])
+                } else {
+                    Err("Can only move top card from waste".to_string())
+                }
+            }
+            _ => Err("Cannot move cards from this position".to_string()),
+        }
+    }
+
+    /// Check `cards` may be placed at `to`, explaining why not in words
+    /// the player can act on
+    fn check_move(&self, cards: &[Card], to: Position) -> Result<(), String> {
+        let Some(&first_card) = cards.first() else {
+            return Err("No cards to move".to_string());
+        };
+
+        let (accepted, top) = match to {
+            Position::Tableau(col, _) => {
+                let column = self.tableau.get(col).ok_or("Invalid tableau column")?;
+                (
+                    self.rules.accepts_on_tableau(&first_card, column.last()),
+                    column.last(),
+                )
+            }
+            Position::Foundation(foundation) => {
+                let pile = self
+                    .foundations
+                    .get(foundation)
+                    .ok_or("Invalid foundation")?;
+                if cards.len() != 1 {
+                    return Err("Only one card at a time can go to a foundation".to_string());
+                }
+                (
+                    self.rules.accepts_on_foundation(&first_card, pile.last()),
+                    pile.last(),
+                )
+            }
+            Position::Waste(_) => {
+                if cards.len() != 1 {
+                    return Err("Only one card at a time can go to the waste".to_string());
+                }
+                (
+                    self.rules.accepts_on_waste(&first_card, self.waste.last()),
+                    self.waste.last(),
+                )
+            }
+            Position::Stock => return Err("Cards cannot be moved to the stock".to_string()),
+        };
+        if accepted {
+            return Ok(());
+        }
+
+        // Name the card the empty pile would take, when the rules want one rank
+        let starts_with = |rank: Rank| {
+            let card = Card::new(first_card.suit, rank, true);
+            match to {
+                Position::Tableau(..) => self.rules.accepts_on_tableau(&card, None),
+                _ => self.rules.accepts_on_foundation(&card, None),
+            }
+        };
+        Err(match (to, top) {
+            (_, Some(top)) => trf("{} can't go on {}", &[&first_card, top]),
+            (Position::Tableau(..), None) if starts_with(Rank::King) => {
+                tr("Only a King can go on an empty column").to_string()
+            }
+            (Position::Foundation(_), None) if starts_with(Rank::Ace) => {
+                tr("Foundations start with an Ace").to_string()
+            }
+            _ => trf("{} can't go there", &[&first_card]),
+        })
+    }
+
+    fn remove_cards_from_position(
+        &mut self,
+        position: Position,
+        count: usize,
+    ) -> Result<(), String> {
+        match position {
+            Position::Tableau(col, _) => {
+                if col >= self.tableau.len() {
+                    return Err("Invalid tableau column".to_string());
+                }
+                let pile = &mut self.tableau[col];
+                if pile.len() < count {
+                    return Err("Not enough cards to remove".to_string());
+                }
+                for _ in 0..count {
+                    pile.pop();
+                }
+                Ok(())
+            }
+            Position::Waste(_) => {
+                if self.waste.len() < count {
+                    return Err("Not enough cards in waste".to_string());
+                }
+                for _ in 0..count {
+                    self.waste.pop();
+                }
+                Ok(())
+            }
+            _ => Err("Cannot remove cards from this position".to_string()),
+        }
+    }
+
+    fn add_cards_to_position(
+        &mut self,
+        position: Position,
+        cards: Vec<Card>,
+    ) -> Result<(), String> {
+        match position {
+            Position::Tableau(col, _) => {
+                if col >= self.tableau.len() {
+                    return Err("Invalid tableau column".to_string());
+                }
+                for card in cards {
+                    self.tableau[col].push(card);
+                }
+                Ok(())
+            }
+            Position::Foundation(foundation) => {
+                if foundation >= self.foundations.len() {
+                    return Err("Invalid foundation".to_string());
+                }
+                for card in cards {
+                    self.foundations[foundation].push(card);
+                }
+                Ok(())
+            }
+            Position::Waste(_) => {
+                self.waste.extend(cards);
+                Ok(())
+            }
+            _ => Err("Cannot add cards to this position".to_string()),
+        }
+    }
+
+    /// Check if a position can be clicked (for UI interaction)
+    pub fn can_click_position(&self, position: Position) -> bool {
+        match position {
+            Position::Stock => true, // Can always click stock to deal
+            Position::Tableau(col, idx) => {
+                if col >= self.tableau.len() {
+                    return false;
+                }
+                let pile = &self.tableau[col];
+                if idx >= pile.len() {
+                    return false;
+                }
+                // Can click top card if it's face-down (to flip) or face-up (to move)
+                idx == pile.len() - 1
+            }
+            Position::Waste(_) => false, // Can't click waste pile directly yet
+            Position::Foundation(_) => false, // Can't click foundation directly yet
+        }
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a game number as a player would type or share it, e.g. `1234` or
+/// `#1234`. Game numbers are deal seeds, so any number is a valid deal.
+pub fn parse_game_number(text: &str) -> Result<u64, String> {
+    let digits = text.trim().trim_start_matches('#').trim();
+    digits
+        .parse()
+        .map_err(|_| format!("`{}` is not a game number", text.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Rank, Suit};
+    use crate::thoughtful::Thoughtful;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_game_state_creation() {
+        let game_state = GameState::new();
+
+        // Check that tableau has correct number of columns
+        assert_eq!(game_state.tableau.len(), 7);
+
+        // Check that foundations are empty initially
+        for foundation in &game_state.foundations {
+            assert!(foundation.is_empty());
+        }
+
+        // Check that waste is empty initially
+        assert!(game_state.waste.is_empty());
+
+        // Check initial game state
+        assert_eq!(game_state.move_count, 0);
+        assert!(!game_state.game_won);
+        assert_eq!(game_state.draw_count, DrawCount::Three);
+    }
+
+    #[test]
+    fn test_tableau_dealing() {
+        let game_state = GameState::new();
+
+        // Check that tableau columns have correct number of cards
+        // Column 0: 1 card, Column 1: 2 cards, ..., Column 6: 7 cards
+        for (col, pile) in game_state.tableau.iter().enumerate() {
+            assert_eq!(
+                pile.len(),
+                col + 1,
+                "Column {} should have {} cards",
+                col,
+                col + 1
+            );
+
+            // Check that only the top card is face-up
+            for (i, card) in pile.iter().enumerate() {
+                if i == pile.len() - 1 {
+                    // Top card should be face-up
+                    assert!(card.face_up, "Top card in column {} should be face-up", col);
+                } else {
+                    // Other cards should be face-down
+                    assert!(
+                        !card.face_up,
+                        "Non-top card in column {} should be face-down",
+                        col
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_total_cards_dealt() {
+        let game_state = GameState::new();
+
+        // Count cards in tableau
+        let tableau_cards: usize = game_state.tableau.iter().map(|pile| pile.len()).sum();
+
+        // Count cards in stock
+        let stock_cards = game_state.stock.len();
+
+        // Total should be 52 (standard deck)
+        assert_eq!(tableau_cards + stock_cards, 52);
+
+        // Tableau should have 28 cards (1+2+3+4+5+6+7)
+        assert_eq!(tableau_cards, 28);
+
+        // Stock should have remaining 24 cards
+        assert_eq!(stock_cards, 24);
+    }
+
+    #[test]
+    fn test_draw_count_setting() {
+        let game_state_one = GameState::new_with_draw_count(DrawCount::One);
+        let game_state_three = GameState::new_with_draw_count(DrawCount::Three);
+
+        assert_eq!(game_state_one.draw_count, DrawCount::One);
+        assert_eq!(game_state_three.draw_count, DrawCount::Three);
+    }
+
+    #[test]
+    fn test_projected_time() {
+        let mut game_state = GameState::new_with_seed(7);
+        assert_eq!(game_state.projected_time(), None);
+
+        game_state.start_time = SystemTime::now() - Duration::from_secs(60);
+        game_state.end_time = Some(game_state.start_time + Duration::from_secs(60));
+        let aces = [
+            Card::new(Suit::Hearts, Rank::Ace, true),
+            Card::new(Suit::Spades, Rank::Ace, true),
+            Card::new(Suit::Clubs, Rank::Ace, true),
+            Card::new(Suit::Diamonds, Rank::Ace, true),
+        ];
+        for (foundation, ace) in game_state.foundations.iter_mut().zip(aces) {
+            foundation.push(ace);
+        }
+        assert_eq!(game_state.foundation_card_count(), 4);
+        // 4 of 52 cards in a minute: 13 minutes at this rate
+        assert_eq!(game_state.projected_time(), Some(Duration::from_secs(780)));
+    }
+
+    #[test]
+    fn test_parse_game_number() {
+        assert_eq!(parse_game_number("1234"), Ok(1234));
+        assert_eq!(parse_game_number(" #42 "), Ok(42));
+        assert_eq!(parse_game_number("# 7"), Ok(7));
+        assert!(parse_game_number("").is_err());
+        assert!(parse_game_number("-3").is_err());
+        assert!(parse_game_number("12a").is_err());
+        assert!(parse_game_number("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_practice_survives_replay() {
+        let mut game_state = GameState::new_with_seed(9);
+        assert!(game_state.is_ranked());
+
+        game_state.practice = true;
+        assert!(!game_state.is_ranked());
+        game_state.handle_action(GameAction::ReplaySeed).unwrap();
+        assert!(game_state.practice);
+
+        game_state.handle_action(GameAction::NewGame).unwrap();
+        assert!(game_state.is_ranked());
+    }
+
+    #[test]
+    fn test_summary_format() {
+        let game_state = GameState::new();
+        let summary = game_state.summary();
+
+        // Check that summary contains expected information
+        assert!(summary.contains("Moves: 0"));
+        assert!(summary.contains("Stock: 24"));
+        assert!(summary.contains("Waste: 0"));
+        assert!(summary.contains("Draw: Three"));
+        assert!(summary.contains(&format!("Game #{}", game_state.seed)));
+    }
+
+    #[test]
+    fn test_same_seed_deals_same_layout() {
+        let game1 = GameState::new_with_seed(424242);
+        let game2 = GameState::new_with_seed(424242);
+        let other = GameState::new_with_seed(424243);
+
+        assert_eq!(game1.seed, 424242);
+        assert_eq!(game1.tableau, game2.tableau);
+        assert_eq!(game1.stock, game2.stock);
+        assert!(game1.tableau != other.tableau || game1.stock != other.stock);
+    }
+
+    #[test]
+    fn test_replay_seed_restarts_same_deal() {
+        let mut game_state = GameState::new_with_draw_count(DrawCount::One);
+        let original = game_state.clone();
+
+        game_state.deal_from_stock().unwrap();
+        game_state.handle_action(GameAction::ReplaySeed).unwrap();
+
+        assert_eq!(game_state.seed, original.seed);
+        assert_eq!(game_state.tableau, original.tableau);
+        assert_eq!(game_state.stock, original.stock);
+        assert!(game_state.waste.is_empty());
+        assert_eq!(game_state.move_count, 0);
+        assert_eq!(game_state.draw_count, DrawCount::One);
+    }
+
+    #[test]
+    fn test_new_game_uses_fresh_seed() {
+        let mut game_state = GameState::new_with_seed(5);
+        game_state.handle_action(GameAction::NewGame).unwrap();
+        // A fresh deal is always rebuildable from its recorded seed
+        let replayed = GameState::new_with_seed(game_state.seed);
+        assert_eq!(game_state.tableau, replayed.tableau);
+        assert_eq!(game_state.stock, replayed.stock);
+    }
+
+    #[test]
+    fn test_injected_rng_reproduces_shuffle() {
+        let first = GameState::new_with_rng(&mut StdRng::seed_from_u64(2039));
+        let second = GameState::new_with_rng(&mut StdRng::seed_from_u64(2039));
+        assert_eq!(first.seed, second.seed);
+        assert_eq!(first.tableau, second.tableau);
+        assert_eq!(first.stock, second.stock);
+
+        // The drawn seed replays the same deal by game number
+        assert_eq!(GameState::new_with_seed(first.seed).tableau, first.tableau);
+
+        // A generator keeps producing different deals
+        let mut rng = StdRng::seed_from_u64(2039);
+        let seeds: Vec<u64> = (0..3)
+            .map(|_| GameState::new_with_rng(&mut rng).seed)
+            .collect();
+        assert_eq!(seeds[0], first.seed);
+        assert_ne!(seeds[0], seeds[1]);
+    }
+
+    #[test]
+    fn test_cards_are_shuffled() {
+        // Create two game states and verify they have different card arrangements
+        let game1 = GameState::new();
+        let game2 = GameState::new();
+
+        // Get the first few cards from each tableau to compare
+        let mut cards1 = Vec::new();
+        let mut cards2 = Vec::new();
+
+        for pile in &game1.tableau {
+            if let Some(card) = pile.first() {
+                cards1.push(*card);
+            }
+        }
+
+        for pile in &game2.tableau {
+            if let Some(card) = pile.first() {
+                cards2.push(*card);
+            }
+        }
+
+        // It's extremely unlikely that two shuffled decks would have the same
+        // first 7 cards in the same positions (but not impossible)
+        // This test might occasionally fail due to randomness, but it's very unlikely
+        let same_arrangement = cards1.iter().zip(cards2.iter()).all(|(a, b)| a == b);
+
+        // If they're the same, let's at least verify we have valid cards
+        if same_arrangement {
+            assert!(!cards1.is_empty(), "Should have cards in tableau");
+            assert_eq!(cards1.len(), 7, "Should have 7 tableau columns");
+        }
+    }
+
+    #[test]
+    fn test_position_display() {
+        let tableau_pos = Position::Tableau(2, 5);
+        let foundation_pos = Position::Foundation(1);
+        let stock_pos = Position::Stock;
+        let waste_pos = Position::Waste(3);
+
+        assert_eq!(format!("{}", tableau_pos), "Tableau(2, 5)");
+        assert_eq!(format!("{}", foundation_pos), "Foundation(1)");
+        assert_eq!(format!("{}", stock_pos), "Stock");
+        assert_eq!(format!("{}", waste_pos), "Waste(3)");
+    }
+
+    #[test]
+    fn test_deal_from_stock() {
+        let mut game_state = GameState::new_with_draw_count(DrawCount::One);
+        let initial_stock_count = game_state.stock.len();
+        let initial_waste_count = game_state.waste.len();
+
+        // Deal one card
+        let result = game_state.deal_from_stock();
+        assert!(result.is_ok());
+        assert_eq!(game_state.stock.len(), initial_stock_count - 1);
+        assert_eq!(game_state.waste.len(), initial_waste_count + 1);
+        assert_eq!(game_state.move_count, 1);
+
+        // Check that the dealt card is face-up
+        if let Some(top_waste_card) = game_state.waste.last() {
+            assert!(top_waste_card.face_up);
+        }
+    }
+
+    #[test]
+    fn test_deal_from_stock_three_cards() {
+        let mut game_state = GameState::new_with_draw_count(DrawCount::Three);
+        let initial_stock_count = game_state.stock.len();
+
+        // Deal three cards
+        let result = game_state.deal_from_stock();
+        assert!(result.is_ok());
+        assert_eq!(game_state.stock.len(), initial_stock_count - 3);
+        assert_eq!(game_state.waste.len(), 3);
+        assert_eq!(game_state.move_count, 1);
+
+        // Check that all dealt cards are face-up
+        for card in &game_state.waste {
+            assert!(card.face_up);
+        }
+    }
+
+    #[test]
+    fn test_stock_label() {
+        let mut game_state = GameState::new_with_draw_count(DrawCount::Three);
+        assert_eq!(game_state.stock_label(), "Deal 3 cards");
+        game_state.draw_count = DrawCount::One;
+        assert_eq!(game_state.stock_label(), "Deal 1 card");
+
+        while !game_state.stock.is_empty() {
+            game_state.deal_from_stock().unwrap();
+        }
+        assert_eq!(game_state.stock_label(), "Turn the waste over");
+        game_state.waste.clear();
+        assert_eq!(game_state.stock_label(), "The stock is empty");
+    }
+
+    #[test]
+    fn test_waste_fan() {
+        let mut game_state = GameState::new_with_draw_count(DrawCount::Three);
+        assert!(game_state.waste_fan().is_empty());
+        game_state.deal_from_stock().unwrap();
+        game_state.deal_from_stock().unwrap();
+        assert_eq!(game_state.waste_fan(), &game_state.waste[3..]);
+
+        // Two left showing after the top one is played
+        game_state.waste.pop();
+        assert_eq!(game_state.waste_fan(), &game_state.waste[2..]);
+
+        game_state.draw_count = DrawCount::One;
+        assert_eq!(game_state.waste_fan(), &game_state.waste[4..]);
+    }
+
+    #[test]
+    fn test_deal_from_empty_stock_recycles_waste() {
+        let mut game_state = GameState::new();
+
+        // Empty the stock by dealing all cards
+        while !game_state.stock.is_empty() {
+            let _ = game_state.deal_from_stock();
+        }
+
+        let waste_count_before_recycle = game_state.waste.len();
+        assert!(waste_count_before_recycle > 0);
+        assert!(game_state.stock.is_empty());
+
+        // Deal from empty stock should recycle waste back to stock
+        let result = game_state.deal_from_stock();
+        assert!(result.is_ok());
+        assert_eq!(game_state.stock.len(), waste_count_before_recycle);
+        assert!(game_state.waste.is_empty());
+
+        // All recycled cards should be face-down
+        for card in &game_state.stock {
+            assert!(!card.face_up);
+        }
+    }
+
+    #[test]
+    fn test_flip_card_in_tableau() {
+        let mut game_state = GameState::new();
+
+        // Find a tableau column with face-down cards
+        let mut test_col = None;
+        for (col, pile) in game_state.tableau.iter().enumerate() {
+            if pile.len() > 1 {
+                // Check if there's a face-down card that's not the top card
+                for (idx, card) in pile.iter().enumerate() {
+                    if !card.face_up && idx < pile.len() - 1 {
+                        test_col = Some(col);
+                        break;
+                    }
+                }
+                if test_col.is_some() {
+                    break;
+                }
+            }
+        }
+
+        if let Some(col) = test_col {
+            // Remove the top card to expose a face-down card
+            let top_card = game_state.tableau[col].pop().unwrap();
+            let top_idx = game_state.tableau[col].len() - 1;
+
+            // The newly exposed card should be face-down
+            assert!(!game_state.tableau[col][top_idx].face_up);
+
+            // Flip the card
+            let result = game_state.flip_card(Position::Tableau(col, top_idx));
+            assert!(result.is_ok());
+            assert!(game_state.tableau[col][top_idx].face_up);
+            assert_eq!(game_state.move_count, 1);
+
+            // Put the top card back
+            game_state.tableau[col].push(top_card);
+        }
+    }
+
+    #[test]
+    fn test_flip_card_errors() {
+        let mut game_state = GameState::new();
+
+        // Try to flip card in invalid column
+        let result = game_state.flip_card(Position::Tableau(7, 0));
+        assert!(result.is_err());
+
+        // Try to flip card with invalid index
+        let result = game_state.flip_card(Position::Tableau(0, 10));
+        assert!(result.is_err());
+
+        // Try to flip already face-up card (top card in tableau is face-up)
+        let result = game_state.flip_card(Position::Tableau(0, 0));
+        assert!(result.is_err());
+
+        // Try to flip non-tableau position
+        let result = game_state.flip_card(Position::Stock);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_can_click_position() {
+        let game_state = GameState::new();
+
+        // Can always click stock
+        assert!(game_state.can_click_position(Position::Stock));
+
+        // Can click top card in tableau
+        assert!(game_state.can_click_position(Position::Tableau(0, 0))); // Column 0 has 1 card
+        assert!(game_state.can_click_position(Position::Tableau(6, 6))); // Column 6 has 7 cards
+
+        // Cannot click non-top cards in tableau
+        assert!(!game_state.can_click_position(Position::Tableau(6, 0))); // Not top card
+        assert!(!game_state.can_click_position(Position::Tableau(6, 5))); // Not top card
+
+        // Cannot click invalid positions
+        assert!(!game_state.can_click_position(Position::Tableau(7, 0))); // Invalid column
+        assert!(!game_state.can_click_position(Position::Tableau(0, 5))); // Invalid index
+
+        // Cannot click waste or foundation yet
+        assert!(!game_state.can_click_position(Position::Waste(0)));
+        assert!(!game_state.can_click_position(Position::Foundation(0)));
+    }
+
+    #[test]
+    fn test_position_code_round_trip() {
+        let mut game_state = GameState::new_with_seed(2037);
+        game_state.draw_count = DrawCount::Three;
+        for _ in 0..4 {
+            game_state.handle_action(GameAction::DealFromStock).unwrap();
+        }
+
+        let code = game_state.encode();
+        assert!(code.starts_with("K1.3.0.4."), "{}", code);
+        let decoded = GameState::decode(&code).unwrap();
+        assert!(decoded.imported);
+        assert_eq!(decoded.draw_count, DrawCount::Three);
+        assert_eq!(decoded.move_count, 4);
+        assert_eq!(decoded.tableau, game_state.tableau);
+        assert_eq!(decoded.foundations, game_state.foundations);
+        assert_eq!(decoded.stock, game_state.stock);
+        assert_eq!(decoded.waste, game_state.waste);
+        assert_eq!(decoded.encode(), code);
+    }
+
+    #[test]
+    fn test_position_key_tells_variants_apart() {
+        let klondike = GameState::new_with_seed(2037);
+        // The same board played by other rules
+        let mut thoughtful = klondike.clone();
+        thoughtful.rules = Variant::new(Thoughtful);
+        assert_eq!(klondike.encode(), thoughtful.encode());
+        assert_ne!(klondike.position_key(), thoughtful.position_key());
+        assert_eq!(klondike.position_key(), klondike.clone().position_key());
+    }
+
+    #[test]
+    fn test_position_code_keeps_foundations() {
+        let mut game_state = GameState::new_with_seed(1);
+        for suit in Suit::all() {
+            game_state
+                .tableau
+                .iter_mut()
+                .for_each(|column| column.retain(|card| card.suit != suit));
+            game_state.stock.retain(|card| card.suit != suit);
+        }
+        game_state.foundations = Suit::all()
+            .map(|suit| {
+                Rank::all()
+                    .into_iter()
+                    .map(|rank| Card::new(suit, rank, true))
+                    .collect()
+            })
+            .into();
+
+        let decoded = GameState::decode(&game_state.encode()).unwrap();
+        assert!(decoded.game_won);
+        assert_eq!(decoded.foundations, game_state.foundations);
+    }
+
+    #[test]
+    fn test_refused_moves_explain_why() {
+        let mut game_state = GameState::new_with_seed(1);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[0] = vec![Card::new(Suit::Hearts, Rank::Queen, true)];
+        game_state.tableau[1] = vec![Card::new(Suit::Diamonds, Rank::Jack, true)];
+        game_state.foundations[0] = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+
+        let mut refused = |from, to| game_state.move_card(from, to).unwrap_err();
+        assert_eq!(
+            refused(Position::Tableau(0, 0), Position::Tableau(2, 0)),
+            "Only a King can go on an empty column"
+        );
+        assert_eq!(
+            refused(Position::Tableau(1, 0), Position::Tableau(0, 1)),
+            "J♦ can't go on Q♥"
+        );
+        assert_eq!(
+            refused(Position::Tableau(1, 0), Position::Foundation(1)),
+            "Foundations start with an Ace"
+        );
+        assert_eq!(
+            refused(Position::Tableau(0, 0), Position::Foundation(0)),
+            "Q♥ can't go on A♥"
+        );
+    }
+
+    #[test]
+    fn test_collect_all_takes_only_safe_cards() {
+        let mut game_state = GameState::new_with_seed(1);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[0] = vec![
+            Card::new(Suit::Hearts, Rank::Three, true),
+            Card::new(Suit::Hearts, Rank::Two, true),
+        ];
+        game_state.tableau[1] = vec![Card::new(Suit::Spades, Rank::Ace, true)];
+        game_state.waste = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+
+        // The Three of Hearts could still take a black Two, so it stays
+        game_state.handle_action(GameAction::CollectAll).unwrap();
+        assert_eq!(game_state.foundation_card_count(), 3);
+        assert_eq!(
+            game_state.tableau[0],
+            [Card::new(Suit::Hearts, Rank::Three, true)]
+        );
+        assert!(game_state.handle_action(GameAction::CollectAll).is_err());
+    }
+
+    #[test]
+    fn test_scoring_modes() {
+        let mut game_state = GameState::new_with_seed(1);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[0] = vec![Card::new(Suit::Hearts, Rank::Two, true)];
+        game_state.waste = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+
+        let scored = |scoring| {
+            let mut game_state = game_state.clone();
+            game_state.set_scoring(scoring);
+            let opening = game_state.score;
+            game_state.handle_action(GameAction::CollectAll).unwrap();
+            game_state.handle_action(GameAction::DealFromStock).unwrap();
+            (opening, game_state.score)
+        };
+        assert_eq!(scored(Scoring::Standard), (0, 20));
+        // Vegas pays out only on cards reaching the foundations
+        assert_eq!(scored(Scoring::Vegas), (-52, -42));
+        assert_eq!(scored(Scoring::None), (0, 0));
+    }
+
+    #[test]
+    fn test_available_moves() {
+        let mut game_state = GameState::new_with_seed(7);
+        game_state.tableau[0] = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+        let moves = game_state.available_moves();
+        assert_eq!(moves[0], GameAction::DealFromStock);
+        for action in &moves {
+            assert!(game_state.clone().handle_action(action.clone()).is_ok());
+        }
+
+        // The Ace is offered the first foundation only
+        let ace_moves: Vec<&GameAction> = moves
+            .iter()
+            .filter(|action| {
+                matches!(action, GameAction::MoveCard { from, .. } if *from == Position::Tableau(0, 0))
+            })
+            .collect();
+        assert_eq!(
+            ace_moves,
+            [&GameAction::MoveCard {
+                from: Position::Tableau(0, 0),
+                to: Position::Foundation(0),
+            }]
+        );
+
+        game_state.game_won = true;
+        assert!(game_state.available_moves().is_empty());
+    }
+
+    #[test]
+    fn test_next_foundation_move() {
+        let mut game_state = GameState::new_with_seed(1);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.tableau[2] = vec![
+            Card::new(Suit::Spades, Rank::Two, true),
+            Card::new(Suit::Spades, Rank::Ace, true),
+        ];
+        game_state.waste = vec![Card::new(Suit::Hearts, Rank::Ace, true)];
+
+        // The waste goes first, then the column, which frees its Two
+        let mut moves = Vec::new();
+        while let Some(action) = game_state.next_foundation_move() {
+            game_state.handle_action(action.clone()).unwrap();
+            moves.push(action);
+        }
+        assert_eq!(moves.len(), 3);
+        assert!(matches!(
+            moves[0],
+            GameAction::MoveCard {
+                from: Position::Waste(0),
+                ..
+            }
+        ));
+        assert_eq!(game_state.foundation_card_count(), 3);
+        assert!(game_state.tableau.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_invalid_position_codes() {
+        let code = GameState::new_with_seed(9).encode();
+        let mut fields: Vec<String> = code.split('.').map(str::to_string).collect();
+
+        assert!(GameState::decode("").is_err());
+        assert!(GameState::decode(&code.replacen("K1", "K9", 1)).is_err());
+        let mut bad_draw = fields.clone();
+        bad_draw[1] = "2".to_string();
+        assert!(GameState::decode(&bad_draw.join(".")).is_err());
+
+        // A card other than an Ace starting a foundation
+        let position = fields[15].rfind(|c| !"ANan".contains(c)).unwrap();
+        let card = fields[15].remove(position);
+        fields[11].push(card);
+        let error = GameState::decode(&fields.join(".")).unwrap_err();
+        assert!(error.contains("foundation 1"), "{}", error);
+
+        // A card listed twice
+        fields[11].clear();
+        fields[15].insert(position, card);
+        fields[16].push(card);
+        let error = GameState::decode(&fields.join(".")).unwrap_err();
+        assert!(error.contains("more than once"), "{}", error);
+
+        // A column claiming more face-down cards than it holds
+        fields[16].clear();
+        fields[4] = format!("9{}", &fields[4][1..]);
+        assert!(GameState::decode(&fields.join(".")).is_err());
+    }
+
+    #[test]
+    fn test_diff_finds_the_changed_piles() {
+        let old = GameState::new_with_seed(31);
+        assert!(old.diff(&old).is_empty());
+
+        let mut dealt = old.clone();
+        dealt.draw_count = DrawCount::Three;
+        dealt.deal_from_stock().unwrap();
+        assert_eq!(
+            dealt.diff(&old),
+            [
+                PileChange {
+                    pile: Pile::Stock,
+                    unchanged: old.stock.len() - 3,
+                },
+                PileChange {
+                    pile: Pile::Waste,
+                    unchanged: 0,
+                },
+            ]
+        );
+
+        // A card turned over in place changes its column from that card up
+        let mut flipped = old.clone();
+        flipped.tableau[2][1].face_up = true;
+        assert_eq!(
+            flipped.diff(&old),
+            [PileChange {
+                pile: Pile::Tableau(2),
+                unchanged: 1,
+            }]
+        );
+    }
+}
@@ -0,0 +1,622 @@
+use crate::actions::{DrawCount, Scoring};
+use crate::daily::{DailyResult, Date};
+use crate::settings::Accommodations;
+use crate::state::GameState;
+use crate::storage::{self, Checked};
+use crate::tournament::TournamentResult;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Lifetime statistics, persisted to `stats.toml` in the data directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    pub games_played: u32,
+    pub games_won: u32,
+    /// Games played with at least one accessibility accommodation
+    pub accommodated_games: u32,
+    /// Practice games, kept out of every other total
+    pub practice_games: u32,
+    /// Fastest winning time among strict games
+    pub best_time: Option<Duration>,
+    /// Highest score reached in a single strict game
+    pub best_score: i32,
+    /// Completed tournaments, kept separate from single-game totals
+    pub tournaments: Vec<TournamentResult>,
+    /// Daily challenge results, one per date in date order, kept separate
+    /// from single-game totals
+    pub daily: Vec<DailyResult>,
+    /// Consecutive single games won, reset by a loss
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Single games played on the most recent day with any
+    pub latest_day: Option<DayTally>,
+}
+
+/// Single games played and won on one date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayTally {
+    pub date: Date,
+    #[serde(default)]
+    pub games_played: u32,
+    #[serde(default)]
+    pub games_won: u32,
+}
+
+impl Statistics {
+    /// Default location of the statistics file
+    pub fn default_path() -> PathBuf {
+        storage::data_dir().join("stats.toml")
+    }
+
+    /// Record a finished (won or abandoned) single game. Only Klondike is
+    /// kept; other variants leave the statistics alone. Games played with
+    /// accommodations count toward totals but never set best time or score;
+    /// practice games are only counted as practice. Returns whether the game
+    /// beat the best time set before it.
+    pub fn record_game(&mut self, game_state: &GameState) -> bool {
+        if !game_state.rules.is_klondike() {
+            return false;
+        }
+        if game_state.practice {
+            self.practice_games += 1;
+            return false;
+        }
+
+        self.games_played += 1;
+        if game_state.game_won {
+            self.games_won += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+        } else {
+            self.current_streak = 0;
+        }
+        self.tally_day(Date::today(), game_state.game_won);
+
+        if !game_state.accommodations.is_strict() {
+            self.accommodated_games += 1;
+            return false;
+        }
+
+        if game_state.scoring == Scoring::Standard {
+            self.best_score = self.best_score.max(game_state.score);
+        }
+        if !game_state.game_won {
+            return false;
+        }
+        let time = game_state.elapsed();
+        let previous = self.best_time;
+        if previous.is_none_or(|best| time < best) {
+            self.best_time = Some(time);
+        }
+        previous.is_some_and(|best| time < best)
+    }
+
+    fn tally_day(&mut self, date: Date, won: bool) {
+        let tally = match self.latest_day {
+            Some(tally) if tally.date == date => tally,
+            _ => DayTally {
+                date,
+                games_played: 0,
+                games_won: 0,
+            },
+        };
+        self.latest_day = Some(DayTally {
+            games_played: tally.games_played + 1,
+            games_won: tally.games_won + u32::from(won),
+            ..tally
+        });
+    }
+
+    /// Single games played and won on `date`
+    pub fn games_on(&self, date: Date) -> (u32, u32) {
+        match self.latest_day {
+            Some(tally) if tally.date == date => (tally.games_played, tally.games_won),
+            _ => (0, 0),
+        }
+    }
+
+    /// Record a completed tournament
+    pub fn record_tournament(&mut self, result: TournamentResult) {
+        self.tournaments.push(result);
+    }
+
+    /// Record a daily challenge attempt. Only the best attempt per date is
+    /// kept, so replaying a day can improve but never erase its result.
+    /// Practice attempts are not recorded.
+    pub fn record_daily(&mut self, result: DailyResult) {
+        if result.practice {
+            self.practice_games += 1;
+            return;
+        }
+
+        match self
+            .daily
+            .binary_search_by_key(&result.date, |existing| existing.date)
+        {
+            Ok(index) => {
+                if result.is_better_than(&self.daily[index]) {
+                    self.daily[index] = result;
+                }
+            }
+            Err(index) => self.daily.insert(index, result),
+        }
+    }
+
+    pub fn daily_result(&self, date: Date) -> Option<&DailyResult> {
+        self.daily
+            .binary_search_by_key(&date, |result| result.date)
+            .ok()
+            .map(|index| &self.daily[index])
+    }
+
+    /// Days of the given month on which the daily challenge was won
+    pub fn completed_days(&self, year: i32, month: u32) -> Vec<u32> {
+        self.daily
+            .iter()
+            .filter(|result| result.won && result.date.year == year && result.date.month == month)
+            .map(|result| result.date.day)
+            .collect()
+    }
+
+    /// Percentage of played games that were won
+    pub fn win_percentage(&self) -> f32 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            self.games_won as f32 * 100.0 / self.games_played as f32
+        }
+    }
+
+    /// Best tournament by total score
+    pub fn best_tournament(&self) -> Option<&TournamentResult> {
+        self.tournaments
+            .iter()
+            .max_by_key(|result| result.total_score)
+    }
+
+    /// Load statistics from disk; a missing file yields empty statistics
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        storage::load::<StatisticsFile>(path)?.try_into()
+    }
+
+    /// Load at startup. A damaged file is quarantined and replaced by an empty
+    /// record, with a notice for the player.
+    pub fn load_checked(path: &Path) -> Checked<Self> {
+        storage::load_checked(path, Self::parse)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        storage::save(path, &StatisticsFile::from(self))
+    }
+
+    /// Read statistics, checking the totals agree with each other
+    pub fn parse(text: &str) -> Result<Self, String> {
+        storage::parse::<StatisticsFile>(text)?.try_into()
+    }
+}
+
+/// Layout of `stats.toml`: the single-game totals, the latest day's tally
+/// and a `[[tournament]]` or `[[daily]]` table per result
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StatisticsFile {
+    totals: Totals,
+    latest_day: Option<DayTally>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tournament: Vec<TournamentEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    daily: Vec<DailyEntry>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Totals {
+    games_played: u32,
+    games_won: u32,
+    accommodated_games: u32,
+    practice_games: u32,
+    best_score: i32,
+    current_streak: u32,
+    best_streak: u32,
+    best_time_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TournamentEntry {
+    #[serde(with = "storage::seeds")]
+    seeds: Vec<u64>,
+    #[serde(default)]
+    deals_won: u32,
+    #[serde(default)]
+    total_score: i32,
+    #[serde(default)]
+    total_moves: u32,
+    #[serde(default)]
+    total_time_ms: u64,
+    #[serde(default)]
+    draw_count: DrawCount,
+    #[serde(default)]
+    finished_at: u64,
+    #[serde(default)]
+    accommodations: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DailyEntry {
+    date: Date,
+    #[serde(default)]
+    won: bool,
+    #[serde(default)]
+    score: i32,
+    #[serde(default)]
+    moves: u32,
+    #[serde(default)]
+    time_ms: u64,
+    #[serde(default)]
+    accommodations: u8,
+}
+
+impl From<&Statistics> for StatisticsFile {
+    fn from(statistics: &Statistics) -> Self {
+        StatisticsFile {
+            totals: Totals {
+                games_played: statistics.games_played,
+                games_won: statistics.games_won,
+                accommodated_games: statistics.accommodated_games,
+                practice_games: statistics.practice_games,
+                best_score: statistics.best_score,
+                current_streak: statistics.current_streak,
+                best_streak: statistics.best_streak,
+                best_time_ms: statistics
+                    .best_time
+                    .map(|best_time| best_time.as_millis() as u64),
+            },
+            latest_day: statistics.latest_day,
+            tournament: statistics
+                .tournaments
+                .iter()
+                .map(|result| TournamentEntry {
+                    seeds: result.seeds.clone(),
+                    deals_won: result.deals_won,
+                    total_score: result.total_score,
+                    total_moves: result.total_moves,
+                    total_time_ms: result.total_time.as_millis() as u64,
+                    draw_count: result.draw_count,
+                    finished_at: result.finished_at,
+                    accommodations: result.accommodations.to_flags(),
+                })
+                .collect(),
+            daily: statistics
+                .daily
+                .iter()
+                .map(|result| DailyEntry {
+                    date: result.date,
+                    won: result.won,
+                    score: result.score,
+                    moves: result.moves,
+                    time_ms: result.time.as_millis() as u64,
+                    accommodations: result.accommodations.to_flags(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<StatisticsFile> for Statistics {
+    type Error = String;
+
+    fn try_from(file: StatisticsFile) -> Result<Self, String> {
+        let totals = file.totals;
+        if totals.games_won > totals.games_played {
+            return Err("games_won exceeds games_played".to_string());
+        }
+        if totals.current_streak > totals.best_streak || totals.best_streak > totals.games_won {
+            return Err("win streaks are inconsistent with games won".to_string());
+        }
+        if let Some(tally) = file.latest_day
+            && tally.games_won > tally.games_played
+        {
+            return Err("latest_day games_won exceeds games_played".to_string());
+        }
+
+        let mut statistics = Statistics {
+            games_played: totals.games_played,
+            games_won: totals.games_won,
+            accommodated_games: totals.accommodated_games,
+            practice_games: totals.practice_games,
+            best_time: totals.best_time_ms.map(Duration::from_millis),
+            best_score: totals.best_score,
+            tournaments: file
+                .tournament
+                .into_iter()
+                .map(|entry| TournamentResult {
+                    seeds: entry.seeds,
+                    deals_won: entry.deals_won,
+                    total_score: entry.total_score,
+                    total_moves: entry.total_moves,
+                    total_time: Duration::from_millis(entry.total_time_ms),
+                    draw_count: entry.draw_count,
+                    finished_at: entry.finished_at,
+                    accommodations: Accommodations::from_flags(entry.accommodations),
+                })
+                .collect(),
+            daily: Vec::new(),
+            current_streak: totals.current_streak,
+            best_streak: totals.best_streak,
+            latest_day: file.latest_day,
+        };
+
+        for entry in file.daily {
+            if statistics.daily_result(entry.date).is_some() {
+                return Err(format!("duplicate daily result for {}", entry.date));
+            }
+            statistics.record_daily(DailyResult {
+                date: entry.date,
+                won: entry.won,
+                score: entry.score,
+                moves: entry.moves,
+                time: Duration::from_millis(entry.time_ms),
+                accommodations: Accommodations::from_flags(entry.accommodations),
+                practice: false,
+            });
+        }
+
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golf::Golf;
+    use crate::variant::Variant;
+
+    #[test]
+    fn test_record_game() {
+        let mut statistics = Statistics::default();
+
+        let mut lost = GameState::new_with_seed(1);
+        lost.score = 40;
+        statistics.record_game(&lost);
+        assert_eq!(statistics.games_played, 1);
+        assert_eq!(statistics.games_won, 0);
+        assert_eq!(statistics.best_time, None);
+        assert_eq!(statistics.best_score, 40);
+
+        let mut slow = GameState::new_with_seed(2);
+        slow.game_won = true;
+        slow.start_time -= Duration::from_secs(600);
+        // The first win has no record to beat
+        assert!(!statistics.record_game(&slow));
+        assert_eq!(statistics.games_won, 1);
+        assert!(statistics.best_time.is_some());
+        assert_eq!(statistics.win_percentage(), 50.0);
+
+        let mut quick = GameState::new_with_seed(3);
+        quick.game_won = true;
+        assert!(statistics.record_game(&quick));
+        assert!(!statistics.record_game(&slow));
+    }
+
+    #[test]
+    fn test_accommodated_games_are_not_ranked() {
+        let mut statistics = Statistics::default();
+
+        let mut game = GameState::new_with_seed(3);
+        game.score = 500;
+        game.game_won = true;
+        game.accommodations.untimed = true;
+        statistics.record_game(&game);
+
+        assert_eq!(statistics.games_played, 1);
+        assert_eq!(statistics.games_won, 1);
+        assert_eq!(statistics.accommodated_games, 1);
+        assert_eq!(statistics.best_score, 0);
+        assert_eq!(statistics.best_time, None);
+    }
+
+    #[test]
+    fn test_other_variants_are_not_recorded() {
+        let mut statistics = Statistics::default();
+        statistics.record_game(&GameState::new_with_seed(4));
+
+        let mut golf = GameState::new_with_seed(5);
+        golf.rules = Variant::new(Golf);
+        golf.score = 500;
+        golf.game_won = true;
+        assert!(!statistics.record_game(&golf));
+
+        assert_eq!(statistics.games_played, 1);
+        assert_eq!(statistics.games_won, 0);
+        assert_eq!(statistics.current_streak, 0);
+        assert_eq!(statistics.best_score, 0);
+        assert_eq!(statistics.best_time, None);
+    }
+
+    #[test]
+    fn test_statistics_round_trip() {
+        let statistics = Statistics {
+            games_played: 10,
+            games_won: 3,
+            accommodated_games: 2,
+            practice_games: 4,
+            best_time: Some(Duration::from_millis(182_500)),
+            best_score: 610,
+            tournaments: vec![TournamentResult {
+                seeds: vec![u64::MAX, 7],
+                deals_won: 1,
+                total_score: 700,
+                total_moves: 230,
+                total_time: Duration::from_millis(900_000),
+                draw_count: DrawCount::One,
+                finished_at: 1_700_000_000,
+                accommodations: Accommodations {
+                    large_drop_targets: true,
+                    untimed: false,
+                },
+            }],
+            daily: vec![DailyResult {
+                date: Date::new(2025, 1, 31).unwrap(),
+                won: true,
+                score: 540,
+                moves: 118,
+                time: Duration::from_millis(301_250),
+                accommodations: Accommodations::default(),
+                practice: false,
+            }],
+            current_streak: 2,
+            best_streak: 3,
+            latest_day: Some(DayTally {
+                date: Date::new(2025, 2, 1).unwrap(),
+                games_played: 4,
+                games_won: 2,
+            }),
+        };
+
+        let text = storage::to_text(&StatisticsFile::from(&statistics)).unwrap();
+        let loaded = Statistics::parse(&text).unwrap();
+        assert_eq!(loaded, statistics);
+        assert_eq!(loaded.best_tournament().unwrap().total_score, 700);
+    }
+
+    #[test]
+    fn test_practice_games_are_kept_apart() {
+        let mut statistics = Statistics::default();
+        let mut won = GameState::new_with_seed(1);
+        won.game_won = true;
+        won.score = 700;
+        statistics.record_game(&won);
+
+        let mut practice = won.clone();
+        practice.practice = true;
+        practice.score = 900;
+        statistics.record_game(&practice);
+        let mut practice_loss = practice.clone();
+        practice_loss.game_won = false;
+        statistics.record_game(&practice_loss);
+        let date = Date::new(2025, 7, 4).unwrap();
+        statistics.record_daily(DailyResult::from_game(date, &practice));
+
+        assert_eq!(statistics.practice_games, 3);
+        assert_eq!(statistics.games_played, 1);
+        assert_eq!(statistics.current_streak, 1);
+        assert_eq!(statistics.best_score, 700);
+        assert_eq!(statistics.games_on(Date::today()), (1, 1));
+        assert!(statistics.daily_result(date).is_none());
+    }
+
+    #[test]
+    fn test_win_streaks_and_todays_games() {
+        let mut statistics = Statistics::default();
+        let mut won = GameState::new_with_seed(1);
+        won.game_won = true;
+        let lost = GameState::new_with_seed(2);
+
+        for game in [&won, &won, &lost, &won] {
+            statistics.record_game(game);
+        }
+        assert_eq!(statistics.current_streak, 1);
+        assert_eq!(statistics.best_streak, 2);
+        assert_eq!(statistics.games_on(Date::today()), (4, 3));
+        assert_eq!(statistics.games_on(Date::new(2000, 1, 1).unwrap()), (0, 0));
+
+        // A new day starts a fresh tally
+        statistics.latest_day = Some(DayTally {
+            date: Date::new(2000, 1, 1).unwrap(),
+            games_played: 9,
+            games_won: 9,
+        });
+        statistics.record_game(&lost);
+        assert_eq!(statistics.games_on(Date::today()), (1, 0));
+        assert_eq!(statistics.current_streak, 0);
+    }
+
+    #[test]
+    fn test_daily_results_keep_best_per_date() {
+        let mut statistics = Statistics::default();
+        let date = Date::new(2025, 5, 20).unwrap();
+        let mut game = GameState::new_with_seed(crate::daily::daily_seed(date));
+
+        game.score = 80;
+        statistics.record_daily(DailyResult::from_game(date, &game));
+        game.game_won = true;
+        game.score = 60;
+        statistics.record_daily(DailyResult::from_game(date, &game));
+        game.game_won = false;
+        game.score = 500;
+        statistics.record_daily(DailyResult::from_game(date, &game));
+
+        let result = statistics.daily_result(date).unwrap();
+        assert!(result.won);
+        assert_eq!(result.score, 60);
+
+        // Daily games stay out of the single-game totals
+        assert_eq!(statistics.games_played, 0);
+
+        let earlier = Date::new(2025, 5, 3).unwrap();
+        statistics.record_daily(DailyResult::from_game(earlier, &game));
+        statistics.record_daily(DailyResult::from_game(Date::new(2025, 4, 30).unwrap(), &{
+            let mut won = game.clone();
+            won.game_won = true;
+            won
+        }));
+        assert_eq!(statistics.daily.first().unwrap().date.month, 4);
+        assert_eq!(statistics.completed_days(2025, 5), vec![20]);
+        assert_eq!(statistics.completed_days(2025, 4), vec![30]);
+    }
+
+    #[test]
+    fn test_statistics_rejects_invalid_values() {
+        for bad in [
+            "[totals]\ngames_played = -1",
+            "[totals]\ngames_played = 1\ngames_won = 2",
+            "[[tournament]]\nseeds = [1]",
+            "[[tournament]]\ndeals_won = 1",
+            "[totals]\ngames_won = 1\nbest_streak = 2",
+            "[[daily]]\ndate = \"2025-02-30\"",
+            "[[daily]]\ndate = \"2025-02-03\"\n[[daily]]\ndate = \"2025-02-03\"",
+        ] {
+            assert!(Statistics::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_load_checked_quarantines_corrupt_fixtures() {
+        let fixtures = [
+            "[totals\ngames_played = 3",
+            "[totals]\ngames_played = \"three\"",
+            "[totals]\nbest_time_ms = -5",
+            "[totals]\nbest_score = true",
+            "[[tournament]]\nseeds = [\"1\"]\ndraw_count = \"Two\"",
+        ];
+
+        let dir =
+            std::env::temp_dir().join(format!("solitaire_corrupt_stats_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stats.toml");
+
+        for fixture in fixtures {
+            std::fs::write(&path, fixture).unwrap();
+            let checked = Statistics::load_checked(&path);
+            assert_eq!(checked.value, Statistics::default(), "{}", fixture);
+            assert!(checked.notice.is_some(), "{}", fixture);
+            assert!(!path.exists());
+            assert_eq!(
+                std::fs::read_to_string(dir.join("stats.toml.bad")).unwrap(),
+                fixture
+            );
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let path = std::env::temp_dir().join("solitaire_missing_stats_file.toml");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Statistics::load(&path).unwrap(), Statistics::default());
+    }
+}
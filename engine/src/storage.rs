@@ -0,0 +1,260 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the directory (under the platform data dir) holding persisted files
+const APP_DIR_NAME: &str = "vibe-solitaire";
+
+/// Directory where statistics and other persisted data live.
+///
+/// Follows the platform conventions without pulling in an extra crate:
+/// `$XDG_DATA_HOME` / `~/.local/share` on Linux, `~/Library/Application Support`
+/// on macOS and `%APPDATA%` on Windows. Falls back to the working directory.
+pub fn data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+    };
+
+    base.unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR_NAME)
+}
+
+/// Directory where the settings file lives: `$XDG_CONFIG_HOME` /
+/// `~/.config` on Linux, and the same place as `data_dir` on macOS and
+/// Windows, which keep preferences alongside application data.
+pub fn config_dir() -> PathBuf {
+    if cfg!(target_os = "windows") || cfg!(target_os = "macos") {
+        return data_dir();
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR_NAME)
+}
+
+/// Suffix appended to a persisted file that failed validation
+pub const QUARANTINE_SUFFIX: &str = "bad";
+
+/// A value loaded from a persisted file at startup, with a message for the
+/// player if the file had to be set aside
+#[derive(Debug)]
+pub struct Checked<T> {
+    pub value: T,
+    pub notice: Option<String>,
+}
+
+/// Load and validate a persisted file with `parse`.
+///
+/// A missing file yields the default silently. A file that fails validation is
+/// quarantined (renamed with a `.bad` suffix) so it is neither lost nor
+/// overwritten by the next save, and the default is used with a notice.
+pub fn load_checked<T: Default>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Checked<T> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Checked {
+                value: T::default(),
+                notice: None,
+            };
+        }
+        Err(e) => {
+            return Checked {
+                value: T::default(),
+                notice: Some(format!("Could not read {}: {}", path.display(), e)),
+            };
+        }
+    };
+
+    match parse(&text) {
+        Ok(value) => Checked {
+            value,
+            notice: None,
+        },
+        Err(error) => {
+            let notice = match quarantine(path) {
+                Ok(bad_path) => format!(
+                    "{} was damaged ({}). It was moved to {} and a fresh one was started.",
+                    path.display(),
+                    error,
+                    bad_path.display()
+                ),
+                Err(quarantine_error) => format!(
+                    "{} was damaged ({}) and could not be moved aside: {}",
+                    path.display(),
+                    error,
+                    quarantine_error
+                ),
+            };
+            Checked {
+                value: T::default(),
+                notice: Some(notice),
+            }
+        }
+    }
+}
+
+/// Move a damaged file aside to `<name>.bad`, replacing any older quarantined
+/// copy. Returns the new path.
+pub fn quarantine(path: &Path) -> Result<PathBuf, String> {
+    let mut bad_name = path.file_name().unwrap_or_default().to_os_string();
+    bad_name.push(".");
+    bad_name.push(QUARANTINE_SUFFIX);
+    let bad_path = path.with_file_name(bad_name);
+
+    fs::rename(path, &bad_path)
+        .map_err(|e| format!("Failed to rename {}: {}", path.display(), e))?;
+    Ok(bad_path)
+}
+
+/// Parse TOML text into `T`, reporting what was wrong with it
+pub fn parse<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Render `value` as TOML text
+pub fn to_text<T: Serialize>(value: &T) -> Result<String, String> {
+    toml::to_string(value).map_err(|e| e.to_string())
+}
+
+/// Read and parse a TOML file from disk
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Write `value` to disk as TOML, creating parent directories as needed.
+/// The file is written to a temporary sibling first so a crash mid-write
+/// never leaves a truncated file behind.
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = to_text(value).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, text)
+        .map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+/// Seeds use the full u64 range, past what TOML integers hold, so they are
+/// stored as strings
+pub(crate) mod seed {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&seed.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse()
+            .map_err(|_| de::Error::custom(format!("invalid seed `{}`", text)))
+    }
+}
+
+/// A list of seeds, each stored as a string
+pub(crate) mod seeds {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+    pub fn serialize<S: Serializer>(seeds: &[u64], serializer: S) -> Result<S::Ok, S::Error> {
+        let texts: Vec<String> = seeds.iter().map(u64::to_string).collect();
+        texts.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|text| {
+                text.parse()
+                    .map_err(|_| de::Error::custom(format!("invalid seed `{}`", text)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    /// A fresh, empty directory under the system temp dir
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "vibe-solitaire-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[derive(Deserialize)]
+    struct Answer {
+        answer: i64,
+    }
+
+    fn parse_answer(text: &str) -> Result<i64, String> {
+        parse::<Answer>(text).map(|parsed| parsed.answer)
+    }
+
+    #[test]
+    fn test_load_checked_missing_file_is_silent() {
+        let dir = test_dir("missing");
+        let checked = load_checked(&dir.join("absent.toml"), parse_answer);
+        assert_eq!(checked.value, 0);
+        assert!(checked.notice.is_none());
+    }
+
+    #[test]
+    fn test_load_checked_valid_file() {
+        let dir = test_dir("valid");
+        let path = dir.join("data.toml");
+        fs::write(&path, "answer = 42\n").unwrap();
+
+        let checked = load_checked(&path, parse_answer);
+        assert_eq!(checked.value, 42);
+        assert!(checked.notice.is_none());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_load_checked_quarantines_corrupt_file() {
+        let dir = test_dir("corrupt");
+        let path = dir.join("data.toml");
+        fs::write(&path, "answer = \"unterminated\n").unwrap();
+
+        let checked = load_checked(&path, parse_answer);
+        assert_eq!(checked.value, 0);
+        assert!(checked.notice.unwrap().contains("data.toml.bad"));
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("data.toml.bad")).unwrap(),
+            "answer = \"unterminated\n"
+        );
+
+        // A second corrupt file replaces the older quarantined copy
+        fs::write(&path, "[[[").unwrap();
+        assert!(load_checked(&path, parse_answer).notice.is_some());
+        assert_eq!(
+            fs::read_to_string(dir.join("data.toml.bad")).unwrap(),
+            "[[["
+        );
+    }
+}
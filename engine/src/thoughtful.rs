@@ -0,0 +1,78 @@
+use crate::deck::Card;
+use crate::registry::{VariantEntry, VariantRegistry};
+use crate::rules::{GameRules, Klondike};
+use crate::variant::Variant;
+
+/// Thoughtful: Klondike played open, every card of the tableau and stock
+/// dealt face up so the whole deal can be planned from the first move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Thoughtful;
+
+impl GameRules for Thoughtful {
+    fn name(&self) -> &'static str {
+        "Thoughtful"
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        let (mut tableau, mut stock) = Klondike.deal(deck);
+        for card in tableau.iter_mut().flatten().chain(&mut stock) {
+            card.face_up = true;
+        }
+        (tableau, stock)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        Klondike.is_movable_run(cards)
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        Klondike.accepts_on_tableau(card, top)
+    }
+
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool {
+        Klondike.accepts_on_foundation(card, top)
+    }
+
+    fn open_stock(&self) -> bool {
+        true
+    }
+    fn is_solvable(&self) -> bool {
+        true
+    }
+}
+
+/// Offer Thoughtful to players
+pub fn register(registry: &mut VariantRegistry) -> Result<(), String> {
+    registry.register(VariantEntry::new(Variant::new(Thoughtful)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::state::GameState;
+
+    #[test]
+    fn test_thoughtful_deals_like_klondike_face_up() {
+        let game_state = GameState::with_rules(Thoughtful, 2061);
+        let klondike = GameState::with_rules(Klondike, 2061);
+        assert_eq!(game_state.tableau.len(), 7);
+        for (column, klondike_column) in game_state.tableau.iter().zip(&klondike.tableau) {
+            assert_eq!(column.len(), klondike_column.len());
+            assert!(column.iter().all(|card| card.face_up));
+        }
+        assert_eq!(game_state.stock.len(), 24);
+        assert!(game_state.stock.iter().all(|card| card.face_up));
+    }
+
+    #[test]
+    fn test_stock_stays_face_up_when_turned_over() {
+        let mut game_state = GameState::with_rules(Thoughtful, 1);
+        while !game_state.stock.is_empty() {
+            game_state.handle_action(GameAction::DealFromStock).unwrap();
+        }
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        assert_eq!(game_state.stock.len(), 24);
+        assert!(game_state.stock.iter().all(|card| card.face_up));
+    }
+}
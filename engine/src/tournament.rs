@@ -0,0 +1,224 @@
+use crate::actions::{DrawCount, Scoring};
+use crate::locale::trf;
+use crate::settings::Accommodations;
+use crate::state::GameState;
+use rand::{Rng, thread_rng};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default number of deals in a tournament
+pub const DEFAULT_TOURNAMENT_DEALS: usize = 5;
+
+/// Outcome of a single deal played as part of a tournament
+#[derive(Debug, Clone, PartialEq)]
+pub struct DealResult {
+    pub seed: u64,
+    pub won: bool,
+    pub score: i32,
+    pub moves: u32,
+    pub time: Duration,
+    pub accommodations: Accommodations,
+}
+
+/// Aggregated outcome of a finished tournament, as recorded in statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    /// Seeds of every deal, in the order they were played
+    pub seeds: Vec<u64>,
+    pub deals_won: u32,
+    pub total_score: i32,
+    pub total_moves: u32,
+    pub total_time: Duration,
+    pub draw_count: DrawCount,
+    /// Seconds since the Unix epoch when the tournament finished
+    pub finished_at: u64,
+    /// Accommodations used in any of the deals
+    pub accommodations: Accommodations,
+}
+
+impl TournamentResult {
+    pub fn deals(&self) -> usize {
+        self.seeds.len()
+    }
+}
+
+/// A fixed set of seeds played back-to-back with score and time aggregated
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    seeds: Vec<u64>,
+    results: Vec<DealResult>,
+    draw_count: DrawCount,
+}
+
+impl Tournament {
+    /// Create a tournament over the given seeds
+    pub fn new(seeds: Vec<u64>, draw_count: DrawCount) -> Self {
+        Tournament {
+            seeds,
+            results: Vec::new(),
+            draw_count,
+        }
+    }
+
+    /// Create a tournament of `deals` freshly rolled seeds
+    pub fn with_random_seeds(deals: usize, draw_count: DrawCount) -> Self {
+        let mut rng = thread_rng();
+        let seeds = (0..deals).map(|_| rng.r#gen::<u64>()).collect();
+        Self::new(seeds, draw_count)
+    }
+
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    pub fn results(&self) -> &[DealResult] {
+        &self.results
+    }
+
+    pub fn draw_count(&self) -> DrawCount {
+        self.draw_count
+    }
+
+    /// Index of the deal currently being played (or next to be played)
+    pub fn current_deal(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.results.len() >= self.seeds.len()
+    }
+
+    /// Build the game for the current deal, or `None` once all deals are
+    /// played. Every deal is scored the standard way, whatever the player's
+    /// setting, so tournament totals compare with each other.
+    pub fn current_game(&self) -> Option<GameState> {
+        let seed = *self.seeds.get(self.current_deal())?;
+        let mut game_state = GameState::new_with_seed(seed);
+        game_state.draw_count = self.draw_count;
+        game_state.set_scoring(Scoring::Standard);
+        Some(game_state)
+    }
+
+    /// Record the outcome of the current deal (won or abandoned) and advance
+    pub fn record_deal(&mut self, game_state: &GameState) -> Result<(), String> {
+        let seed = *self
+            .seeds
+            .get(self.current_deal())
+            .ok_or_else(|| "Tournament is already finished".to_string())?;
+
+        self.results.push(DealResult {
+            seed,
+            won: game_state.game_won,
+            score: game_state.score,
+            moves: game_state.move_count,
+            time: game_state.elapsed(),
+            accommodations: game_state.accommodations,
+        });
+        Ok(())
+    }
+
+    pub fn deals_won(&self) -> u32 {
+        self.results.iter().filter(|result| result.won).count() as u32
+    }
+
+    pub fn total_score(&self) -> i32 {
+        self.results.iter().map(|result| result.score).sum()
+    }
+
+    pub fn total_moves(&self) -> u32 {
+        self.results.iter().map(|result| result.moves).sum()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.results.iter().map(|result| result.time).sum()
+    }
+
+    /// Summarise a finished tournament for the statistics record
+    pub fn result(&self) -> Option<TournamentResult> {
+        if !self.is_finished() {
+            return None;
+        }
+
+        Some(TournamentResult {
+            seeds: self.seeds.clone(),
+            deals_won: self.deals_won(),
+            total_score: self.total_score(),
+            total_moves: self.total_moves(),
+            total_time: self.total_time(),
+            draw_count: self.draw_count,
+            finished_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            accommodations: self
+                .results
+                .iter()
+                .fold(Accommodations::default(), |flags, result| {
+                    flags.union(result.accommodations)
+                }),
+        })
+    }
+
+    /// Short progress line for the status bar, e.g. "Tournament deal 2/5"
+    pub fn progress_label(&self) -> String {
+        let deal = (self.current_deal() + 1).min(self.seeds.len());
+        trf("Tournament deal {}/{}", &[&deal, &self.seeds.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tournament_plays_seeds_in_order() {
+        let mut tournament = Tournament::new(vec![11, 22, 33], DrawCount::One);
+        assert_eq!(tournament.current_deal(), 0);
+        assert!(!tournament.is_finished());
+
+        let first = tournament.current_game().unwrap();
+        assert_eq!(first.draw_count, DrawCount::One);
+        assert_eq!(first.scoring, Scoring::Standard);
+        assert_eq!(first.tableau, GameState::new_with_seed(11).tableau);
+
+        tournament.record_deal(&first).unwrap();
+        let second = tournament.current_game().unwrap();
+        assert_eq!(second.tableau, GameState::new_with_seed(22).tableau);
+        assert_eq!(tournament.progress_label(), "Tournament deal 2/3");
+    }
+
+    #[test]
+    fn test_tournament_aggregates_results() {
+        let mut tournament = Tournament::new(vec![1, 2], DrawCount::Three);
+
+        let mut game = tournament.current_game().unwrap();
+        game.score = 120;
+        game.move_count = 40;
+        game.game_won = true;
+        tournament.record_deal(&game).unwrap();
+        assert!(tournament.result().is_none());
+
+        let mut game = tournament.current_game().unwrap();
+        game.score = 30;
+        game.move_count = 12;
+        game.accommodations.large_drop_targets = true;
+        tournament.record_deal(&game).unwrap();
+
+        assert!(tournament.is_finished());
+        assert!(tournament.current_game().is_none());
+        assert!(tournament.record_deal(&game).is_err());
+
+        let result = tournament.result().unwrap();
+        assert_eq!(result.deals(), 2);
+        assert_eq!(result.deals_won, 1);
+        assert_eq!(result.total_score, 150);
+        assert_eq!(result.total_moves, 52);
+        assert_eq!(result.seeds, vec![1, 2]);
+        assert!(result.accommodations.large_drop_targets);
+    }
+
+    #[test]
+    fn test_random_tournament_has_requested_deals() {
+        let tournament = Tournament::with_random_seeds(DEFAULT_TOURNAMENT_DEALS, DrawCount::Three);
+        assert_eq!(tournament.seeds().len(), DEFAULT_TOURNAMENT_DEALS);
+    }
+}
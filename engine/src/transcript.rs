@@ -0,0 +1,394 @@
+use crate::actions::{DrawCount, GameAction};
+use crate::state::{GameState, Position};
+use std::fmt;
+
+/// A deal (seed + draw mode) and the sequence of actions played on it.
+///
+/// The text form is one item per line, `#` starting a comment:
+///
+/// ```text
+/// seed = 1234
+/// draw = Three
+/// deal
+/// move W0 T3.4
+/// move T3.4 F0
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub seed: u64,
+    pub draw_count: DrawCount,
+    pub actions: Vec<GameAction>,
+}
+
+impl Transcript {
+    pub fn new(seed: u64, draw_count: DrawCount) -> Self {
+        Transcript {
+            seed,
+            draw_count,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Build the starting position of the deal
+    pub fn initial_state(&self) -> GameState {
+        let mut game_state = GameState::new_with_seed(self.seed);
+        game_state.draw_count = self.draw_count;
+        game_state
+    }
+
+    pub fn parse(text: &str) -> Result<Transcript, String> {
+        let mut seed = None;
+        let mut draw_count = None;
+        let mut actions = Vec::new();
+
+        for (line_number, raw_line) in text.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line_error = |message: String| format!("line {}: {}", line_number + 1, message);
+
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim();
+                match key.trim() {
+                    "seed" => {
+                        seed = Some(
+                            value
+                                .parse::<u64>()
+                                .map_err(|_| line_error(format!("invalid seed `{}`", value)))?,
+                        )
+                    }
+                    "draw" => draw_count = Some(parse_draw_count(value).map_err(line_error)?),
+                    other => return Err(line_error(format!("unknown header `{}`", other))),
+                }
+            } else {
+                actions.push(parse_action(line).map_err(line_error)?);
+            }
+        }
+
+        Ok(Transcript {
+            seed: seed.ok_or_else(|| "transcript is missing `seed = ...`".to_string())?,
+            draw_count: draw_count.unwrap_or(DrawCount::Three),
+            actions,
+        })
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed = {}", self.seed)?;
+        writeln!(f, "draw = {:?}", self.draw_count)?;
+        for action in &self.actions {
+            writeln!(f, "{}", action_notation(action))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_draw_count(text: &str) -> Result<DrawCount, String> {
+    match text {
+        "One" => Ok(DrawCount::One),
+        "Three" => Ok(DrawCount::Three),
+        _ => Err(format!("invalid draw count `{}`", text)),
+    }
+}
+
+/// Compact notation for a position: `T<col>.<idx>`, `F<n>`, `W<idx>` or `S`
+pub fn position_notation(position: Position) -> String {
+    match position {
+        Position::Tableau(col, idx) => format!("T{}.{}", col, idx),
+        Position::Foundation(foundation) => format!("F{}", foundation),
+        Position::Waste(idx) => format!("W{}", idx),
+        Position::Stock => "S".to_string(),
+    }
+}
+
+pub fn parse_position(text: &str) -> Result<Position, String> {
+    let invalid = || format!("invalid position `{}`", text);
+    let number = |digits: &str| digits.parse::<usize>().map_err(|_| invalid());
+
+    match text.split_at_checked(1) {
+        Some(("T", rest)) => {
+            let (col, idx) = rest.split_once('.').ok_or_else(invalid)?;
+            Ok(Position::Tableau(number(col)?, number(idx)?))
+        }
+        Some(("F", rest)) => Ok(Position::Foundation(number(rest)?)),
+        Some(("W", rest)) => Ok(Position::Waste(number(rest)?)),
+        Some(("S", "")) => Ok(Position::Stock),
+        _ => Err(invalid()),
+    }
+}
+
+/// Notation for a single action, e.g. `deal` or `move T2.5 F1`
+pub fn action_notation(action: &GameAction) -> String {
+    match action {
+        GameAction::MoveCard { from, to } => format!(
+            "move {} {}",
+            position_notation(*from),
+            position_notation(*to)
+        ),
+        other => other.name().to_string(),
+    }
+}
+
+pub fn parse_action(text: &str) -> Result<GameAction, String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["move", from, to] => Ok(GameAction::MoveCard {
+            from: parse_position(from)?,
+            to: parse_position(to)?,
+        }),
+        ["deal"] => Ok(GameAction::DealFromStock),
+        ["new"] => Ok(GameAction::NewGame),
+        ["replay"] => Ok(GameAction::ReplaySeed),
+        ["undo"] => Ok(GameAction::Undo),
+        ["redo"] => Ok(GameAction::Redo),
+        ["collect"] => Ok(GameAction::CollectAll),
+        _ => Err(format!("unrecognised action `{}`", text)),
+    }
+}
+
+/// The first step of a transcript that could not be applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct IllegalStep {
+    /// 1-based step number
+    pub step: usize,
+    pub action: GameAction,
+    pub reason: String,
+}
+
+/// Outcome of replaying a transcript against the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationReport {
+    pub seed: u64,
+    pub draw_count: DrawCount,
+    /// Number of actions applied successfully
+    pub steps_applied: usize,
+    pub total_steps: usize,
+    pub illegal_step: Option<IllegalStep>,
+    /// Cards on the foundations when replay stopped
+    pub foundation_cards: usize,
+    pub won: bool,
+}
+
+impl VerificationReport {
+    /// A transcript is a legal win when every step applied and the game ended won
+    pub fn is_legal_win(&self) -> bool {
+        self.illegal_step.is_none() && self.won
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Seed {} (draw {:?}): {} of {} steps applied",
+            self.seed, self.draw_count, self.steps_applied, self.total_steps
+        )?;
+        if let Some(illegal) = &self.illegal_step {
+            writeln!(
+                f,
+                "Illegal step {}: `{}`: {}",
+                illegal.step,
+                action_notation(&illegal.action),
+                illegal.reason
+            )?;
+        }
+        if self.is_legal_win() {
+            write!(f, "Result: legal win")
+        } else if self.won {
+            write!(
+                f,
+                "Result: NOT a legal win (game was won but the transcript continues illegally)"
+            )
+        } else {
+            write!(
+                f,
+                "Result: NOT a legal win ({}/52 cards on foundations)",
+                self.foundation_cards
+            )
+        }
+    }
+}
+
+/// Replay a transcript from its seed and check that it is a legal win
+pub fn verify(transcript: &Transcript) -> VerificationReport {
+    let mut report = verify_from(transcript.initial_state(), &transcript.actions);
+    report.seed = transcript.seed;
+    report
+}
+
+/// Replay `actions` from an arbitrary starting position
+pub fn verify_from(mut game_state: GameState, actions: &[GameAction]) -> VerificationReport {
+    let mut illegal_step = None;
+    let mut steps_applied = 0;
+
+    for (i, action) in actions.iter().enumerate() {
+        let result = match action {
+            _ if game_state.game_won => Err("the game was already won".to_string()),
+            // A claimed win must be played out on a single deal without take-backs
+            GameAction::NewGame | GameAction::ReplaySeed | GameAction::Undo | GameAction::Redo => {
+                Err("not allowed in a win transcript".to_string())
+            }
+            _ => game_state.handle_action(action.clone()),
+        };
+
+        if let Err(reason) = result {
+            illegal_step = Some(IllegalStep {
+                step: i + 1,
+                action: action.clone(),
+                reason,
+            });
+            break;
+        }
+        steps_applied += 1;
+    }
+
+    VerificationReport {
+        seed: 0,
+        draw_count: game_state.draw_count,
+        steps_applied,
+        total_steps: actions.len(),
+        illegal_step,
+        foundation_cards: game_state.foundation_card_count(),
+        won: game_state.game_won,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Card, Rank, Suit};
+
+    /// A position one move per suit away from winning: every foundation holds
+    /// Ace..Queen and the four Kings sit face-up in the first tableau column.
+    fn nearly_won_state() -> GameState {
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.tableau = vec![Vec::new(); 7];
+        game_state.stock.clear();
+        game_state.waste.clear();
+
+        for (i, suit) in Suit::all().into_iter().enumerate() {
+            game_state.foundations[i] = Rank::all()[..12]
+                .iter()
+                .map(|&rank| Card::new(suit, rank, true))
+                .collect();
+            game_state.tableau[0].push(Card::new(suit, Rank::King, true));
+        }
+        game_state
+    }
+
+    fn finishing_moves() -> Vec<GameAction> {
+        // Kings are stacked Hearts, Diamonds, Clubs, Spades; take them from the top
+        (0..4)
+            .rev()
+            .map(|i| GameAction::MoveCard {
+                from: Position::Tableau(0, i),
+                to: Position::Foundation(i),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let mut transcript = Transcript::new(u64::MAX, DrawCount::One);
+        transcript.actions = vec![
+            GameAction::DealFromStock,
+            GameAction::MoveCard {
+                from: Position::Waste(0),
+                to: Position::Tableau(3, 4),
+            },
+            GameAction::MoveCard {
+                from: Position::Tableau(3, 4),
+                to: Position::Foundation(2),
+            },
+            GameAction::CollectAll,
+        ];
+
+        let parsed = Transcript::parse(&transcript.to_string()).unwrap();
+        assert_eq!(parsed, transcript);
+    }
+
+    #[test]
+    fn test_transcript_parse_errors() {
+        assert!(Transcript::parse("deal").is_err()); // Missing seed
+        assert!(Transcript::parse("seed = abc").is_err());
+        assert!(Transcript::parse("seed = 1\ndraw = Two").is_err());
+        assert!(Transcript::parse("seed = 1\nmove T1 F0").is_err());
+        assert!(Transcript::parse("seed = 1\nfly away").is_err());
+
+        let parsed =
+            Transcript::parse("# shared by a friend\nseed = 7 # the deal\ndeal\n").unwrap();
+        assert_eq!(parsed.seed, 7);
+        assert_eq!(parsed.draw_count, DrawCount::Three);
+        assert_eq!(parsed.actions, vec![GameAction::DealFromStock]);
+    }
+
+    #[test]
+    fn test_position_notation() {
+        for position in [
+            Position::Tableau(6, 12),
+            Position::Foundation(3),
+            Position::Waste(23),
+            Position::Stock,
+        ] {
+            assert_eq!(parse_position(&position_notation(position)), Ok(position));
+        }
+        assert!(parse_position("X1").is_err());
+        assert!(parse_position("").is_err());
+    }
+
+    #[test]
+    fn test_verify_legal_win() {
+        let report = verify_from(nearly_won_state(), &finishing_moves());
+        assert!(report.is_legal_win(), "{}", report);
+        assert_eq!(report.steps_applied, 4);
+        assert_eq!(report.foundation_cards, 52);
+    }
+
+    #[test]
+    fn test_verify_reports_illegal_step() {
+        let mut actions = finishing_moves();
+        // Try to put the Spades King on the Hearts foundation
+        actions[0] = GameAction::MoveCard {
+            from: Position::Tableau(0, 3),
+            to: Position::Foundation(0),
+        };
+
+        let report = verify_from(nearly_won_state(), &actions);
+        assert!(!report.is_legal_win());
+        let illegal = report.illegal_step.unwrap();
+        assert_eq!(illegal.step, 1);
+        assert_eq!(illegal.reason, "K♠ can't go on Q♥");
+    }
+
+    #[test]
+    fn test_verify_rejects_incomplete_and_padded_transcripts() {
+        let moves = finishing_moves();
+        let report = verify_from(nearly_won_state(), &moves[..3]);
+        assert!(report.illegal_step.is_none());
+        assert!(!report.is_legal_win());
+        assert_eq!(report.foundation_cards, 51);
+
+        let mut padded = moves.clone();
+        padded.push(GameAction::DealFromStock);
+        let report = verify_from(nearly_won_state(), &padded);
+        assert!(!report.is_legal_win());
+        assert_eq!(report.illegal_step.unwrap().step, 5);
+
+        let mut with_undo = moves;
+        with_undo.insert(1, GameAction::Undo);
+        let report = verify_from(nearly_won_state(), &with_undo);
+        assert_eq!(report.illegal_step.unwrap().step, 2);
+    }
+
+    #[test]
+    fn test_verify_replays_from_seed() {
+        let mut transcript = Transcript::new(99, DrawCount::One);
+        transcript.actions = vec![GameAction::DealFromStock; 3];
+        let report = verify(&transcript);
+        assert_eq!(report.seed, 99);
+        assert_eq!(report.steps_applied, 3);
+        assert!(!report.won);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::deck::Card;
+use crate::registry;
+use crate::rules::{GameRules, Klondike, StockDeal};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Any of the variants the game can be played as, chosen at runtime. This is
+/// the rule set of a plain `GameState`; it hands every question to the rules
+/// it was made from, so a variant only has to be registered to be played.
+/// Saved games and replays store it by name.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Variant(&'static (dyn GameRules + Sync));
+
+impl Variant {
+    /// Play by `rules`, which are kept for the rest of the app's life
+    pub fn new(rules: impl GameRules + Sync + 'static) -> Self {
+        Variant(Box::leak(Box::new(rules)))
+    }
+
+    /// Every variant offered on the new game screen, as registered
+    pub fn all() -> Vec<Variant> {
+        registry::variants()
+            .entries()
+            .iter()
+            .map(|entry| entry.variant)
+            .collect()
+    }
+
+    /// Whether this is classic Klondike, the only variant position codes
+    /// understand
+    pub fn is_klondike(&self) -> bool {
+        self.name() == Klondike.name()
+    }
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Variant::new(Klondike)
+    }
+}
+
+/// Variants are told apart by name, which the registry keeps unique
+impl PartialEq for Variant {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Variant {}
+
+impl TryFrom<String> for Variant {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        Variant::all()
+            .into_iter()
+            .find(|variant| variant.name() == name)
+            .ok_or_else(|| format!("unknown variant `{}`", name))
+    }
+}
+
+impl From<Variant> for String {
+    fn from(variant: Variant) -> String {
+        variant.name().to_string()
+    }
+}
+
+impl fmt::Debug for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Variant").field(&self.0).finish()
+    }
+}
+
+impl GameRules for Variant {
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn deck(&self) -> Vec<Card> {
+        self.0.deck()
+    }
+
+    fn foundation_count(&self) -> usize {
+        self.0.foundation_count()
+    }
+
+    fn deal(&self, deck: Vec<Card>) -> (Vec<Vec<Card>>, Vec<Card>) {
+        self.0.deal(deck)
+    }
+
+    fn is_movable_run(&self, cards: &[Card]) -> bool {
+        self.0.is_movable_run(cards)
+    }
+
+    fn accepts_on_tableau(&self, card: &Card, top: Option<&Card>) -> bool {
+        self.0.accepts_on_tableau(card, top)
+    }
+
+    fn accepts_on_foundation(&self, card: &Card, top: Option<&Card>) -> bool {
+        self.0.accepts_on_foundation(card, top)
+    }
+
+    fn accepts_on_waste(&self, card: &Card, top: Option<&Card>) -> bool {
+        self.0.accepts_on_waste(card, top)
+    }
+
+    fn stock_deal(&self) -> StockDeal {
+        self.0.stock_deal()
+    }
+
+    fn recycle_limit(&self) -> Option<u32> {
+        self.0.recycle_limit()
+    }
+
+    fn open_stock(&self) -> bool {
+        self.0.open_stock()
+    }
+
+    fn is_covered(&self, tableau: &[Vec<Card>], col: usize) -> bool {
+        self.0.is_covered(tableau, col)
+    }
+
+    fn pair_total(&self) -> Option<u8> {
+        self.0.pair_total()
+    }
+
+    fn clears_complete_runs(&self) -> bool {
+        self.0.clears_complete_runs()
+    }
+
+    fn is_won(&self, tableau: &[Vec<Card>], foundations: &[Vec<Card>]) -> bool {
+        self.0.is_won(tableau, foundations)
+    }
+
+    fn is_solvable(&self) -> bool {
+        self.0.is_solvable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spider::{Spider, SpiderSuits};
+    use crate::state::GameState;
+
+    #[test]
+    fn test_variants_play_by_their_rules() {
+        let spider = Spider::new(SpiderSuits::Two);
+        let variant = Variant::new(spider);
+        assert_eq!(variant.name(), spider.name());
+        assert_eq!(
+            GameState::with_rules(variant, 2053).tableau,
+            GameState::with_rules(spider, 2053).tableau
+        );
+        assert_eq!(variant, Variant::new(Spider::new(SpiderSuits::Two)));
+        assert_ne!(variant, Variant::new(Spider::new(SpiderSuits::Four)));
+        assert_eq!(GameState::new_with_seed(1).rules, Variant::default());
+        assert!(Variant::default().is_klondike());
+    }
+}
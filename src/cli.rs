@@ -0,0 +1,89 @@
+use crate::game::actions::DrawCount;
+use crate::game::simulation::{self, Policy};
+use crate::game::transcript::{self, Transcript};
+use rand::thread_rng;
+use std::fs;
+
+const USAGE: &str = "\
+Usage: solitaire [COMMAND]
+
+Without a command the game window opens.
+
+Commands:
+  --verify <FILE>   Check that a transcript (seed + moves) is a legal win
+  --simulate <N> [--draw 1|3] [--policy heuristic|random]
+                    Play N games without a window and report the win rate,
+                    average moves and timing
+  --help            Show this message";
+
+/// Run a command-line command if one was given.
+///
+/// Returns the process exit code when a command ran, or `None` when the
+/// game window should be opened instead.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+
+    let code = match command.as_str() {
+        "--verify" => match args.get(1) {
+            Some(path) => verify(path),
+            None => usage_error("--verify needs a transcript file"),
+        },
+        "--simulate" => simulate(&args[1..]),
+        "--help" | "-h" => {
+            println!("{}", USAGE);
+            0
+        }
+        other => usage_error(&format!("unknown argument `{}`", other)),
+    };
+    Some(code)
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    2
+}
+
+/// Verify a claimed win: exit code 0 for a legal win, 1 otherwise
+fn verify(path: &str) -> i32 {
+    let transcript = match fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+        .and_then(|text| Transcript::parse(&text))
+    {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 2;
+        }
+    };
+
+    let report = transcript::verify(&transcript);
+    println!("{}", report);
+    if report.is_legal_win() { 0 } else { 1 }
+}
+
+/// Play a batch of games headlessly and print the totals
+fn simulate(args: &[String]) -> i32 {
+    let Some(games) = args.first().and_then(|n| n.parse::<u32>().ok()) else {
+        return usage_error("--simulate needs a number of games");
+    };
+
+    let mut draw_count = DrawCount::Three;
+    let mut policy = Policy::Heuristic;
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().map(String::as_str);
+        match (option.as_str(), value) {
+            ("--draw", Some("1")) => draw_count = DrawCount::One,
+            ("--draw", Some("3")) => draw_count = DrawCount::Three,
+            ("--policy", Some(name)) => match Policy::parse(name) {
+                Ok(parsed) => policy = parsed,
+                Err(error) => return usage_error(&error),
+            },
+            _ => return usage_error(&format!("invalid --simulate option `{}`", option)),
+        }
+    }
+
+    let report = simulation::simulate(games, draw_count, policy, &mut thread_rng());
+    println!("{}", report);
+    0
+}
@@ -1,3 +0,0 @@
-pub mod actions;
-pub mod deck;
-pub mod state;
@@ -1,11 +1,20 @@
-use gpui::{AppContext, Application, WindowOptions};
+use gpui::{AppContext, Application, Focusable, WindowOptions};
 
-mod game;
+mod cli;
 mod ui;
 
+// The engine crate keeps its old place in the module tree, so the app and
+// CLI reach it as `crate::game`
+use solitaire_engine as game;
+
 use crate::ui::app::SolitaireApp;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(exit_code) = cli::run(&args) {
+        std::process::exit(exit_code);
+    }
+
     Application::new().run(|cx| {
         // Configure the application to quit when all windows are closed
         cx.activate(true);
@@ -19,9 +28,18 @@ fn main() {
 
         // Open the main window
         let _window = cx
-            .open_window(WindowOptions::default(), |_, cx| {
-                cx.new(|_| SolitaireApp::new())
-            })
+            .open_window(
+                WindowOptions {
+                    window_min_size: Some(ui::layout::min_window_size()),
+                    ..WindowOptions::default()
+                },
+                |window, cx| {
+                    let app = cx.new(|cx| SolitaireApp::new(window, cx));
+                    // Focus the app so keyboard shortcuts work straight away
+                    window.focus(&app.focus_handle(cx));
+                    app
+                },
+            )
             .unwrap();
     });
 }
@@ -0,0 +1,659 @@
+use crate::game::actions::GameAction;
+use crate::game::clock::Clock;
+use crate::game::deck::Card;
+use crate::game::settings::Easing;
+use crate::game::state::{GameState, Pile, Position};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Time a card takes to travel between piles
+pub const MOVE_DURATION: Duration = Duration::from_millis(150);
+/// How many times longer turning a whole pile over takes than a move
+const TURN_OVER_SLOWDOWN: u32 = 3;
+
+/// Share of the way covered at `t`, the share of the time gone by, for a
+/// card moving with `easing`. Both ends are exact: 0 at the start and 1 at
+/// the end.
+pub fn ease(easing: Easing, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match easing {
+        Easing::Linear => t,
+        Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+        Easing::Spring => {
+            1.0 - (-5.0 * t).exp() * (2.5 * std::f32::consts::PI * t).cos() * (1.0 - t)
+        }
+    }
+}
+
+/// One card travelling from where it was to where it landed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flight {
+    /// The card as it lands, face up or down
+    pub card: Card,
+    pub from: Position,
+    pub to: Position,
+    /// Whether the card turns over on the way
+    pub flips: bool,
+    /// Share of the animation gone by when the card sets off and lands
+    start: f32,
+    end: f32,
+}
+
+impl Flight {
+    /// How far the card has travelled at the animation's `progress`
+    pub fn travelled(&self, progress: f32) -> f32 {
+        ((progress - self.start) / (self.end - self.start)).clamp(0.0, 1.0)
+    }
+
+    /// The card as drawn part way, showing the face it started with until
+    /// it turns over half way along
+    pub fn face(&self, travelled: f32) -> Card {
+        let mut card = self.card;
+        if self.flips && travelled < 0.5 {
+            card.face_up = !card.face_up;
+        }
+        card
+    }
+
+    /// Width of the card as a share of its full width, narrowing to nothing
+    /// as it turns over edge on
+    pub fn width(&self, travelled: f32) -> f32 {
+        if self.flips {
+            (1.0 - 2.0 * travelled).abs()
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Cards moving between piles after a change to the board, so moves, auto
+/// plays and undos slide into place instead of teleporting.
+///
+/// Progress is read from a `Clock`, so the same animation can follow the
+/// wall clock in play or a frame clock in playback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub flights: Vec<Flight>,
+    /// Clock time the animation started at
+    started: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Animation {
+    /// Animate every card whose place differs between `before` and `after`.
+    /// Returns `None` when nothing moved, such as after a card flip.
+    ///
+    /// A whole pile turned over between the stock and the waste, when the
+    /// waste is recycled or that is undone, takes longer: the cards leave
+    /// one after another and flip over on the way, so the player sees the
+    /// pile go round.
+    pub fn between(
+        before: &GameState,
+        after: &GameState,
+        clock: &impl Clock,
+        duration: Duration,
+        easing: Easing,
+    ) -> Option<Self> {
+        // Only cards on piles that changed can have moved
+        let changed: Vec<Pile> = after
+            .diff(before)
+            .into_iter()
+            .map(|change| change.pile)
+            .collect();
+        let mut was: HashMap<u8, Vec<(Position, bool)>> = HashMap::new();
+        for (card, position) in placed_cards(before, &changed) {
+            was.entry(card.index())
+                .or_default()
+                .push((position, card.face_up));
+        }
+
+        let mut landed: Vec<(Card, Position)> = Vec::new();
+        for (card, position) in placed_cards(after, &changed) {
+            let places = was.entry(card.index()).or_default();
+            // A card that kept its place didn't move; two-deck games have
+            // two of each card, so match the one that stayed first
+            match places.iter().position(|&(place, _)| place == position) {
+                Some(i) => {
+                    places.swap_remove(i);
+                }
+                None => landed.push((card, position)),
+            }
+        }
+
+        let mut flights: Vec<Flight> = landed
+            .into_iter()
+            .filter_map(|(card, to)| {
+                let places = was.get_mut(&card.index())?;
+                (!places.is_empty()).then(|| {
+                    let (from, face_up) = places.remove(0);
+                    Flight {
+                        card,
+                        from,
+                        to,
+                        flips: face_up != card.face_up,
+                        start: 0.0,
+                        end: 1.0,
+                    }
+                })
+            })
+            .collect();
+        if flights.is_empty() {
+            return None;
+        }
+
+        // More cards than any deal moves, all between the stock and waste
+        let turned_over = flights.len() > 3
+            && flights.iter().all(|flight| {
+                matches!(
+                    (flight.from, flight.to),
+                    (Position::Waste(_), Position::Stock) | (Position::Stock, Position::Waste(_))
+                )
+            });
+        let duration = if turned_over {
+            // Each card takes half the time, setting off in the order it lands
+            let count = flights.len() as f32;
+            for (i, flight) in flights.iter_mut().enumerate() {
+                flight.start = 0.5 * i as f32 / (count - 1.0);
+                flight.end = flight.start + 0.5;
+            }
+            duration * TURN_OVER_SLOWDOWN
+        } else {
+            duration
+        };
+
+        Some(Animation {
+            flights,
+            started: clock.now(),
+            duration,
+            easing,
+        })
+    }
+
+    /// How far the cards have travelled, from 0 to 1
+    pub fn progress(&self, clock: &impl Clock) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = clock.now().saturating_sub(self.started);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Share of the way from its pile to where it lands that `flight` has
+    /// covered, eased
+    pub fn position(&self, flight: &Flight, clock: &impl Clock) -> f32 {
+        ease(self.easing, flight.travelled(self.progress(clock)))
+    }
+
+    pub fn is_finished(&self, clock: &impl Clock) -> bool {
+        self.progress(clock) >= 1.0
+    }
+
+    /// The board with the travelling cards lifted off the piles they are
+    /// landing on, for drawing beneath them
+    pub fn landing_board(&self, after: &GameState) -> GameState {
+        let mut board = after.clone();
+        for flight in &self.flights {
+            match flight.to {
+                Position::Tableau(col, index) => board.tableau[col].truncate(index),
+                Position::Waste(index) => board.waste.truncate(index),
+                Position::Foundation(foundation) => {
+                    board.foundations[foundation].pop();
+                }
+                Position::Stock => {
+                    board.stock.pop();
+                }
+            }
+        }
+        board
+    }
+}
+
+/// How long a hint stays highlighted on the board
+pub const HINT_DURATION: Duration = Duration::from_millis(2500);
+/// Time for one pulse of a hint's glow
+const HINT_PULSE: Duration = Duration::from_millis(800);
+
+/// A suggested move shown on the board: a glow pulsing on the card to move
+/// and a marker travelling to where it goes, for a couple of seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HintHighlight {
+    pub from: Position,
+    pub to: Position,
+    /// Clock time the highlight started at
+    started: Duration,
+}
+
+impl HintHighlight {
+    /// Highlight `action` on `game_state`; only moves and deals can be shown
+    pub fn new(game_state: &GameState, action: &GameAction, clock: &impl Clock) -> Option<Self> {
+        let (from, to) = match *action {
+            GameAction::MoveCard { from, to } => (from, to),
+            GameAction::DealFromStock if game_state.stock.is_empty() => (
+                Position::Waste(game_state.waste.len().saturating_sub(1)),
+                Position::Stock,
+            ),
+            GameAction::DealFromStock => (Position::Stock, Position::Waste(game_state.waste.len())),
+            _ => return None,
+        };
+        Some(HintHighlight {
+            from,
+            to,
+            started: clock.now(),
+        })
+    }
+
+    fn elapsed(&self, clock: &impl Clock) -> Duration {
+        clock.now().saturating_sub(self.started)
+    }
+
+    /// Strength of the glow, rising and falling between 0 and 1 each pulse
+    pub fn glow(&self, clock: &impl Clock) -> f32 {
+        let phase = self.elapsed(clock).as_secs_f32() / HINT_PULSE.as_secs_f32();
+        (1.0 - (phase * std::f32::consts::TAU).cos()) / 2.0
+    }
+
+    /// How far the marker has travelled towards the destination, starting
+    /// over each pulse
+    pub fn travel(&self, clock: &impl Clock) -> f32 {
+        let pulse = HINT_PULSE.as_secs_f32();
+        (self.elapsed(clock).as_secs_f32() % pulse) / pulse
+    }
+
+    pub fn is_finished(&self, clock: &impl Clock) -> bool {
+        self.elapsed(clock) >= HINT_DURATION
+    }
+}
+
+/// Time between one card leaving its pile and the next in a win cascade
+const CASCADE_LAUNCH_INTERVAL: Duration = Duration::from_millis(80);
+/// Time each cascading card stays in the air before it is gone
+const CASCADE_FLIGHT: Duration = Duration::from_millis(3000);
+/// Most cards launched, so two-deck wins don't cascade twice as long
+const CASCADE_MAX_CARDS: usize = 52;
+/// Downward acceleration, in pixels per second squared
+const GRAVITY: f32 = 1800.0;
+/// Share of its speed a card keeps when it bounces
+const BOUNCE: f32 = 0.7;
+/// Simulation step for the bounces
+const STEP: f32 = 1.0 / 120.0;
+
+/// A card bouncing away from the board in a win cascade
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Launch {
+    card: Card,
+    pile: Pile,
+    /// Delay after the cascade starts
+    delay: Duration,
+    /// Starting speed in pixels per second
+    velocity: (f32, f32),
+}
+
+/// One card of a cascade to draw, in pixels from where its pile is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub card: Card,
+    pub pile: Pile,
+    pub offset: (f32, f32),
+}
+
+/// The classic win celebration: the finished piles' cards leave one at a
+/// time from the top, each bouncing off the bottom of the window and
+/// leaving a trail of copies behind it.
+///
+/// Every card's path is a pure function of the clock, so frames can be
+/// drawn at any rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cascade {
+    launches: Vec<Launch>,
+    started: Duration,
+}
+
+impl Cascade {
+    /// Launch the cards from the won game's foundations, taking a card off
+    /// each in turn, or from the waste in games without foundations
+    pub fn new(game_state: &GameState, clock: &impl Clock) -> Self {
+        let mut piles: Vec<(Pile, Vec<Card>)> = game_state
+            .foundations
+            .iter()
+            .enumerate()
+            .map(|(foundation, cards)| (Pile::Foundation(foundation), cards.clone()))
+            .filter(|(_, cards)| !cards.is_empty())
+            .collect();
+        if piles.is_empty() {
+            piles.push((Pile::Waste, game_state.waste.clone()));
+        }
+
+        let mut launches = Vec::new();
+        while launches.len() < CASCADE_MAX_CARDS {
+            let before = launches.len();
+            for (pile, cards) in &mut piles {
+                let Some(mut card) = cards.pop() else {
+                    continue;
+                };
+                card.face_up = true;
+                let n = launches.len() as u32;
+                // Alternate left and right at a spread of speeds
+                let speed = 150.0 + (card.index() as u32 * 37 % 250) as f32;
+                let direction = if n.is_multiple_of(2) { -1.0 } else { 1.0 };
+                launches.push(Launch {
+                    card,
+                    pile: *pile,
+                    delay: CASCADE_LAUNCH_INTERVAL * n,
+                    velocity: (direction * speed, -((n * 53 % 300) as f32)),
+                });
+            }
+            if launches.len() == before {
+                break;
+            }
+        }
+        launches.truncate(CASCADE_MAX_CARDS);
+
+        Cascade {
+            launches,
+            started: clock.now(),
+        }
+    }
+
+    /// Cards that have left their piles so far
+    pub fn launched(&self, clock: &impl Clock) -> usize {
+        let elapsed = clock.now().saturating_sub(self.started);
+        self.launches
+            .iter()
+            .filter(|launch| launch.delay <= elapsed)
+            .count()
+    }
+
+    /// Cards in the air with `trail` copies behind each, `spacing` apart in
+    /// time, oldest first. `floor` is how far below its pile each card
+    /// bounces.
+    pub fn sprites(
+        &self,
+        clock: &impl Clock,
+        floor: impl Fn(Pile) -> f32,
+        trail: usize,
+        spacing: Duration,
+    ) -> Vec<Sprite> {
+        let elapsed = clock.now().saturating_sub(self.started);
+        let mut sprites = Vec::new();
+        for launch in &self.launches {
+            let Some(flying) = elapsed.checked_sub(launch.delay) else {
+                continue;
+            };
+            if flying >= CASCADE_FLIGHT {
+                continue;
+            }
+            for copy in (0..=trail).rev() {
+                let Some(at) = flying.checked_sub(spacing * copy as u32) else {
+                    continue;
+                };
+                sprites.push(Sprite {
+                    card: launch.card,
+                    pile: launch.pile,
+                    offset: bounce(launch.velocity, floor(launch.pile), at),
+                });
+            }
+        }
+        sprites
+    }
+
+    pub fn is_finished(&self, clock: &impl Clock) -> bool {
+        let last = self
+            .launches
+            .last()
+            .map_or(Duration::ZERO, |launch| launch.delay);
+        clock.now().saturating_sub(self.started) >= last + CASCADE_FLIGHT
+    }
+
+    /// The won board with the launched cards gone from their piles
+    pub fn board(&self, after: &GameState, clock: &impl Clock) -> GameState {
+        let mut board = after.clone();
+        for launch in &self.launches[..self.launched(clock)] {
+            match launch.pile {
+                Pile::Foundation(foundation) => {
+                    board.foundations[foundation].pop();
+                }
+                Pile::Waste => {
+                    board.waste.pop();
+                }
+                Pile::Tableau(_) | Pile::Stock => {}
+            }
+        }
+        board
+    }
+}
+
+/// Offset of a card thrown at `velocity` after `time`, bouncing on `floor`
+fn bounce(velocity: (f32, f32), floor: f32, time: Duration) -> (f32, f32) {
+    let (vx, mut vy) = velocity;
+    let mut y = 0.0;
+    let mut remaining = time.as_secs_f32();
+    while remaining > 0.0 {
+        let dt = remaining.min(STEP);
+        vy += GRAVITY * dt;
+        y += vy * dt;
+        if y > floor {
+            y = floor;
+            vy = -vy * BOUNCE;
+        }
+        remaining -= dt;
+    }
+    (vx * time.as_secs_f32(), y)
+}
+
+/// Every card on `piles` of the board with its position
+fn placed_cards(game_state: &GameState, piles: &[Pile]) -> Vec<(Card, Position)> {
+    piles
+        .iter()
+        .flat_map(|pile| {
+            game_state
+                .cards(*pile)
+                .iter()
+                .enumerate()
+                .map(move |(i, card)| {
+                    let position = match *pile {
+                        Pile::Tableau(col) => Position::Tableau(col, i),
+                        Pile::Foundation(foundation) => Position::Foundation(foundation),
+                        Pile::Stock => Position::Stock,
+                        Pile::Waste => Position::Waste(i),
+                    };
+                    (*card, position)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::FrameClock;
+    use crate::game::deck::{Rank, Suit};
+
+    #[test]
+    fn test_dealt_card_flies_to_the_waste() {
+        let before = GameState::new_with_seed(2073);
+        let mut after = before.clone();
+        after.handle_action(GameAction::DealFromStock).unwrap();
+        let dealt = after.waste.len();
+
+        let animation = Animation::between(
+            &before,
+            &after,
+            &FrameClock::new(60),
+            MOVE_DURATION,
+            Easing::Linear,
+        )
+        .unwrap();
+        assert_eq!(animation.flights.len(), dealt);
+        assert!(animation.flights.iter().all(|flight| {
+            flight.from == Position::Stock && matches!(flight.to, Position::Waste(_))
+        }));
+        assert!(animation.landing_board(&after).waste.is_empty());
+
+        // Undoing flies them back, face down again
+        let undo = Animation::between(
+            &after,
+            &before,
+            &FrameClock::new(60),
+            MOVE_DURATION,
+            Easing::Linear,
+        )
+        .unwrap();
+        assert_eq!(undo.flights.len(), dealt);
+        let flight = undo.flights[0];
+        assert!(flight.flips);
+        assert!(flight.face(0.25).face_up);
+        assert!(!flight.face(0.75).face_up);
+        assert_eq!(flight.width(0.5), 0.0);
+        assert_eq!(flight.travelled(1.0), 1.0);
+        assert!(
+            Animation::between(
+                &before,
+                &before,
+                &FrameClock::new(60),
+                MOVE_DURATION,
+                Easing::Linear
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_recycled_waste_turns_over_card_by_card() {
+        let mut before = GameState::new_with_seed(2079);
+        while !before.stock.is_empty() {
+            before.handle_action(GameAction::DealFromStock).unwrap();
+        }
+        let mut after = before.clone();
+        after.handle_action(GameAction::DealFromStock).unwrap();
+
+        let mut clock = FrameClock::new(100);
+        let animation =
+            Animation::between(&before, &after, &clock, MOVE_DURATION, Easing::Linear).unwrap();
+        assert_eq!(animation.flights.len(), before.waste.len());
+        assert!(animation.flights.iter().all(|flight| flight.flips));
+
+        // The first card is on its way before the last one has set off
+        for _ in 0..15 {
+            clock.advance();
+        }
+        let progress = animation.progress(&clock);
+        let first = animation.flights.first().unwrap();
+        let last = animation.flights.last().unwrap();
+        assert!(first.travelled(progress) > 0.0);
+        assert_eq!(last.travelled(progress), 0.0);
+        assert!(!animation.is_finished(&clock));
+        for _ in 0..30 {
+            clock.advance();
+        }
+        assert!(animation.is_finished(&clock));
+        assert_eq!(last.travelled(animation.progress(&clock)), 1.0);
+    }
+
+    #[test]
+    fn test_animation_progress() {
+        let before = GameState::new_with_seed(2073);
+        let mut after = before.clone();
+        after.handle_action(GameAction::DealFromStock).unwrap();
+
+        let mut clock = FrameClock::new(20);
+        let animation =
+            Animation::between(&before, &after, &clock, MOVE_DURATION, Easing::EaseOut).unwrap();
+        assert_eq!(animation.progress(&clock), 0.0);
+        clock.advance();
+        assert!((animation.progress(&clock) - 1.0 / 3.0).abs() < 1e-6);
+        let flight = &animation.flights[0];
+        assert!(animation.position(flight, &clock) > 1.0 / 3.0);
+        clock.advance();
+        clock.advance();
+        clock.advance();
+        assert_eq!(animation.progress(&clock), 1.0);
+        assert!(animation.is_finished(&clock));
+    }
+
+    #[test]
+    fn test_hint_highlight() {
+        let mut game_state = GameState::new_with_seed(2078);
+        let mut clock = FrameClock::new(10);
+        let deal = HintHighlight::new(&game_state, &GameAction::DealFromStock, &clock).unwrap();
+        assert_eq!((deal.from, deal.to), (Position::Stock, Position::Waste(0)));
+        assert_eq!(deal.glow(&clock), 0.0);
+        assert!(HintHighlight::new(&game_state, &GameAction::Undo, &clock).is_none());
+
+        // The glow peaks half way through a pulse, as the marker gets half way
+        for _ in 0..4 {
+            clock.advance();
+        }
+        assert!((deal.glow(&clock) - 1.0).abs() < 1e-6);
+        assert!((deal.travel(&clock) - 0.5).abs() < 1e-6);
+        while !deal.is_finished(&clock) {
+            clock.advance();
+        }
+        assert_eq!(clock.now(), HINT_DURATION);
+
+        // With the stock used up the hint points at recycling the waste
+        game_state.waste = std::mem::take(&mut game_state.stock);
+        let recycle = HintHighlight::new(&game_state, &GameAction::DealFromStock, &clock).unwrap();
+        assert_eq!(recycle.to, Position::Stock);
+    }
+
+    #[test]
+    fn test_easing_curves() {
+        for easing in Easing::all() {
+            assert_eq!(ease(easing, 0.0), 0.0, "{:?}", easing);
+            assert!((ease(easing, 1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+        }
+        // Ease-out is ahead of constant speed, and a spring overshoots
+        assert!(ease(Easing::EaseOut, 0.5) > 0.5);
+        assert!((0..100).any(|i| ease(Easing::Spring, i as f32 / 100.0) > 1.0));
+    }
+
+    #[test]
+    fn test_win_cascade() {
+        let mut won = GameState::new_with_seed(2075);
+        won.tableau.iter_mut().for_each(Vec::clear);
+        won.stock.clear();
+        won.waste.clear();
+        won.foundations = Suit::all()
+            .into_iter()
+            .map(|suit| {
+                Rank::all()
+                    .into_iter()
+                    .map(|rank| Card::new(suit, rank, true))
+                    .collect()
+            })
+            .collect();
+
+        let mut clock = FrameClock::new(10);
+        let cascade = Cascade::new(&won, &clock);
+        assert_eq!(cascade.launched(&clock), 1);
+        let sprites = cascade.sprites(&clock, |_| 500.0, 3, Duration::from_millis(50));
+        assert_eq!(sprites.len(), 1);
+        assert_eq!(sprites[0].card.rank, Rank::King);
+        assert_eq!(sprites[0].offset, (0.0, 0.0));
+
+        // A second later the first card has come down and bounced, never
+        // going through the floor, and left a trail behind it
+        for _ in 0..10 {
+            clock.advance();
+        }
+        let sprites = cascade.sprites(&clock, |_| 500.0, 3, Duration::from_millis(50));
+        assert!(sprites.iter().all(|sprite| sprite.offset.1 <= 500.0));
+        assert!(sprites.len() > cascade.launched(&clock));
+        let board = cascade.board(&won, &clock);
+        assert_eq!(
+            board.foundations.iter().map(Vec::len).sum::<usize>(),
+            52 - cascade.launched(&clock)
+        );
+
+        while !cascade.is_finished(&clock) {
+            clock.advance();
+        }
+        assert_eq!(cascade.launched(&clock), 52);
+        assert!(
+            cascade
+                .sprites(&clock, |_| 500.0, 3, Duration::from_millis(50))
+                .is_empty()
+        );
+    }
+}
@@ -1,11 +1,69 @@
-use crate::game::actions::GameAction;
-use crate::game::deck::Card;
-use crate::game::state::{GameState, Position};
-use crate::{game, ui};
+use crate::game::actions::{DrawCount, GameAction, Scoring};
+use crate::game::autosave::SavedGame;
+use crate::game::clock::SystemClock;
+use crate::game::command::GameCommand;
+use crate::game::daily::{self, DailyResult, Date};
+use crate::game::deck::{Card, Suit};
+use crate::game::evaluator::{self, Evaluator, MoveGrade};
+use crate::game::events::GameEvent;
+use crate::game::highscores::{HighScores, Placement};
+use crate::game::history::History;
+use crate::game::journal::Journal;
+use crate::game::locale::{self, tr, trf};
+use crate::game::mirror;
+use crate::game::playback::Playback;
+use crate::game::pyramid;
+use crate::game::registry::{self, BoardLayout, VariantRegistry};
+use crate::game::replay::Replay;
+use crate::game::report::{self, Report, Reporter};
+use crate::game::reroll::SeedRoll;
+use crate::game::rules::StockDeal;
+use crate::game::scripted::{self, ScriptedRules};
+use crate::game::settings::{InputMode, Settings, ThemeChoice};
+use crate::game::solver::{self, Solution, Solver};
+use crate::game::state::{GameState, Pile, Position};
+use crate::game::stats::Statistics;
+use crate::game::tournament::Tournament;
+use crate::game::variant::Variant;
+use crate::ui;
+use crate::ui::animation::{self, Animation, Cascade, HintHighlight, MOVE_DURATION};
+use crate::ui::audio::Audio;
+use crate::ui::card_art;
+use crate::ui::cursor::{BoardCursor, Direction};
+use crate::ui::deal_layout::DealLayout;
+use crate::ui::drag::DragState;
+use crate::ui::dwell::Dwell;
+use crate::ui::keymap::{Command, Keymap};
+use crate::ui::latency::LatencyMonitor;
+use crate::ui::layout;
+use crate::ui::motion;
+use crate::ui::music;
+use crate::ui::pile_view::{PileLook, PileView};
+use crate::ui::shortcuts;
+use crate::ui::sound::{Cue, SoundPack};
+use crate::ui::theme::{self, Theme};
+use crate::ui::theme_file::ThemeFile;
+use crate::ui::toast::{ToastKind, Toasts};
+use crate::ui::tooltip;
+use crate::ui::watch::FileWatcher;
 use gpui::{
-    div, prelude::*, px, rgb, white, Context, ElementId, FontWeight, IntoElement, MouseButton,
-    Render, Window,
+    AnyElement, AnyView, App, Bounds, ClipboardItem, Context, Display, Div, ElementId, Entity,
+    EventEmitter, FocusHandle, Focusable, FontWeight, Hsla, IntoElement, KeyDownEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathBuilder, Pixels, Point, Render,
+    ScrollWheelEvent, Stateful, StyleRefinement, Subscription, Task, Window, canvas, div,
+    prelude::*, px, rgb, rgba, size, white,
 };
+use rand::thread_rng;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+mod dialogs;
+mod replay;
+mod settings;
+mod tournament;
 
 #[derive(Debug, Clone)]
 pub struct DragInfo {
@@ -16,222 +74,2492 @@ pub struct DragInfo {
 
 impl Render for DragInfo {
     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
-        // Render the dragged cards in a stack
-        let mut drag_element = div().flex().flex_col().opacity(0.8); // Make it semi-transparent to show it's being dragged
+        // Fan the dragged cards exactly as the tableau does, so the preview
+        // matches what lands
+        let mut drag_element = div()
+            .flex()
+            .flex_col()
+            .relative()
+            .w(px(layout::card_width()))
+            .opacity(0.8); // Make it semi-transparent to show it's being dragged
 
         for (i, card) in self.dragged_cards.iter().enumerate() {
-            let card_element = div()
-                .child(ui::render_card(*card))
-                .border_2()
-                .border_color(rgb(0x3B82F6)); // Blue border to indicate dragging
-
-            if i == 0 {
-                drag_element = drag_element.child(card_element);
+            let card_element = div().child(ui::render_card(*card));
+            drag_element = drag_element.child(if i == 0 {
+                card_element
             } else {
-                // Stack subsequent cards with small offset to show sequence
-                drag_element = drag_element.child(
-                    div()
-                        .mt(px(-ui::CARD_HEIGHT + 12.0)) // Smaller offset for dragged cards
-                        .child(card_element),
-                );
-            }
+                card_element.mt(px(-layout::card_height() + layout::tableau_offset()))
+            });
         }
 
-        drag_element
+        // Blue outline to indicate dragging, drawn over the fan so it
+        // doesn't change its size
+        drag_element.child(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .border_2()
+                .border_color(rgb(0x3B82F6))
+                .rounded_md(),
+        )
+    }
+}
+
+/// A window point as the engine takes it
+fn window_point(point: Point<Pixels>) -> (f32, f32) {
+    (f32::from(point.x), f32::from(point.y))
+}
+
+/// Paint an arrow in `color` from `from` to its head at `to`, in window
+/// coordinates
+fn paint_arrow(window: &mut Window, from: Point<Pixels>, to: Point<Pixels>, color: Hsla) {
+    let (x0, y0) = (f32::from(from.x), f32::from(from.y));
+    let (x1, y1) = (f32::from(to.x), f32::from(to.y));
+    let length = (x1 - x0).hypot(y1 - y0);
+    if length < ARROW_HEAD {
+        return;
     }
+    // One pixel along the arrow, and where the head meets the shaft
+    let (dx, dy) = ((x1 - x0) / length, (y1 - y0) / length);
+    let (bx, by) = (x1 - dx * ARROW_HEAD, y1 - dy * ARROW_HEAD);
+    let point = |x: f32, y: f32| Point::new(px(x), px(y));
+
+    let mut shaft = PathBuilder::stroke(px(ARROW_WIDTH));
+    shaft.move_to(point(x0, y0));
+    shaft.line_to(point(bx, by));
+    if let Ok(path) = shaft.build() {
+        window.paint_path(path, color);
+    }
+
+    let half = ARROW_HEAD / 2.0;
+    let mut head = PathBuilder::fill();
+    head.move_to(point(x1, y1));
+    head.line_to(point(bx - dy * half, by + dx * half));
+    head.line_to(point(bx + dy * half, by - dx * half));
+    head.close();
+    if let Ok(path) = head.build() {
+        window.paint_path(path, color);
+    }
+}
+
+/// Small count pinned to the corner of a pile
+fn render_count_badge(count: usize) -> impl IntoElement {
+    div()
+        .absolute()
+        .top_1()
+        .right_1()
+        .px_1()
+        .rounded_md()
+        .bg(rgba(0x000000AA))
+        .text_color(white())
+        .text_xs()
+        .font_weight(FontWeight::BOLD)
+        .child(count.to_string())
+}
+
+/// Green wash over a pile that would take the cards being dragged, drawn
+/// on top so the pile keeps its size
+fn render_drop_highlight() -> impl IntoElement {
+    div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .bg(rgba(0x22C55E55)) // Green highlight for valid drop
+        .border_4()
+        .border_color(rgb(0x16A34A)) // Darker green border
+        .rounded_lg()
+}
+
+/// Share of a card's height uncovered of a buried card hovered in a
+/// tableau column
+const PEEK_REVEAL: f32 = 0.5;
+
+/// Pixels of wheel scrolling over the stock that deal one batch of cards
+const WHEEL_NOTCH: f32 = 20.0;
+
+/// Thickness of the hint arrow's shaft, and length of its head, in pixels
+const ARROW_WIDTH: f32 = 4.0;
+const ARROW_HEAD: f32 = 18.0;
+
+/// Share of a card's width left showing of each waste card fanned under
+/// the top one
+const WASTE_FAN: f32 = 0.3;
+
+/// Root text size at 100%, gpui's default, in pixels
+const BASE_REM_SIZE: f32 = 16.0;
+
+/// A replay being watched on the board, with the game it set aside
+struct ReplayViewer {
+    replay: Replay,
+    playback: Playback,
+    /// Index into `REPLAY_SPEEDS`
+    speed: usize,
+    playing: bool,
+    /// Timer advancing the playback while it plays
+    task: Option<Task<()>>,
+    /// Game in play when the viewer opened, restored when it closes
+    set_aside: SavedGame,
+}
+
+/// What a game in progress is given up for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Abandon {
+    NewGame,
+    Tournament,
+}
+
+/// Modal screens shown on top of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overlay {
+    /// Messages for the player under a heading, e.g. damaged files found at startup
+    Notices {
+        title: &'static str,
+    },
+    /// Hidden latency percentiles for development (F12)
+    Diagnostics,
+    /// Calendar of the current month's daily challenges
+    DailyCalendar,
+    /// Dialog for dealing a specific game number
+    PlayGameNumber,
+    /// Seed picker with a preview of each deal, shown before a new game
+    NewGame,
+    /// Standings shown between tournament deals
+    TournamentProgress,
+    /// Offer to carry on with the game saved at the end of the last session
+    ResumeGame,
+    /// Check before a game in progress is given up for what comes `next`
+    ConfirmAbandon {
+        next: Abandon,
+    },
+    /// Exported replays to pick one to watch
+    Replays,
+    /// Offer to open the report written when something went wrong
+    ErrorReport,
+    Settings,
+    /// High-score tables for a draw mode, with the latest win's placement if any
+    HighScores {
+        draw_count: DrawCount,
+        placement: Option<Placement>,
+    },
 }
 
 pub struct SolitaireApp {
+    focus_handle: FocusHandle,
     game_state: GameState,
-    current_drag: Option<DragInfo>,
+    /// Undo/redo history for the current game
+    history: History,
+    /// Cards picked up with the pointer, from the press on them to their
+    /// drop
+    drag: DragState<DragInfo>,
+    /// Wheel scrolling over the stock and waste not yet dealt for, in pixels
+    stock_scroll: f32,
+    /// Keyboard focus on the board, shown once an arrow key is pressed
+    board_cursor: Option<BoardCursor>,
+    /// Cards picked up with Enter, from where they lie, until put down
+    held: Option<Position>,
+    /// Move picked with the next and previous move keys for Enter to make:
+    /// the position it was picked on and its place among the moves available
+    /// there
+    move_choice: Option<(String, usize)>,
+    /// Face-up tableau card under the mouse, with the cards over it moved
+    /// down so it can be read
+    peek: Option<Position>,
+    /// Shortcut waiting in settings for the key to bind it to
+    rebinding: Option<Command>,
+    /// Pile the pointer rests on in dwell mode, and the timer that clicks it
+    dwell: Dwell<Position>,
+    dwell_task: Option<Task<()>>,
+    /// Steps the board cursor from pile to pile in scanning mode
+    scan_task: Option<Task<()>>,
+    /// The settings and theme files, watched for edits made while the app
+    /// runs
+    watcher: FileWatcher,
+    /// Polls the watched files
+    watch_task: Option<Task<()>>,
+    /// The settings shared by every view. Changes go through it, so each
+    /// view observing it sees them at once.
+    settings_model: Entity<Settings>,
+    /// The shared settings as this view last saw them
+    settings: Settings,
+    /// Keyboard shortcuts from the settings, with the defaults filled in
+    keymap: Keymap,
+    /// Theme file chosen in settings, loaded from the themes folder
+    theme_file: Option<ThemeFile>,
+    /// The operating system's reduce motion preference, read at startup
+    system_reduce_motion: bool,
+    /// Sound output, if the machine has one
+    audio: Option<Audio>,
+    /// Whether a move has been undone this game, which rules out the
+    /// no-undo fanfare
+    undone: bool,
+    /// Theme files found when settings were last opened, by id
+    theme_files: Vec<(String, Result<ThemeFile, String>)>,
+    /// Sound packs found when settings were last opened
+    sound_packs: Vec<String>,
+    statistics: Statistics,
+    high_scores: HighScores,
+    /// Tournament in progress, if any
+    tournament: Option<Tournament>,
+    /// Date of the daily challenge being played, if the current game is one
+    daily: Option<Date>,
+    overlay: Option<Overlay>,
+    /// Digits typed into the "Play game #" dialog
+    game_number_input: String,
+    game_number_error: Option<String>,
+    /// Seeds offered on the new-game screen, with recently skipped ones
+    seed_roll: SeedRoll,
+    /// Variant the new-game screen deals
+    variant: Variant,
+    /// Whether the quick stats heads-up display is showing (toggled with Tab)
+    show_stats_hud: bool,
+    /// Messages for the player, such as damaged files set aside at startup
+    notices: Vec<String>,
+    /// Brief messages over the board, such as why a move was refused
+    toasts: Toasts,
+    /// Time taken by each kind of action and by rendering
+    latency: LatencyMonitor,
+    /// Solver verdict for `analysed_position`, or `None` while it runs
+    winnability: Option<Solution>,
+    /// Position code the winnability verdict is for
+    analysed_position: String,
+    /// Position the player chose to look over after being told no moves
+    /// are left, so the notice stays closed until the board changes
+    stuck_dismissed: Option<String>,
+    /// Background solver run; dropping it discards a stale result
+    winnability_task: Option<Task<()>>,
+    /// Latest hint text with the position code it was asked for
+    hint: Option<(String, String)>,
+    hint_task: Option<Task<()>>,
+    /// The latest hint's move, glowing on the board for a couple of seconds
+    hint_highlight: Option<HintHighlight>,
+    /// Trainer mode: every move is graded against the evaluator's best
+    trainer: bool,
+    /// Feedback on the last graded move
+    last_grade: Option<String>,
+    /// Moves graded best, ok and blunder since the trainer was turned on
+    grade_counts: [u32; 3],
+    /// Solver's winning line being played on the board, if any
+    line_playback: Option<Playback>,
+    /// Timer driving `line_playback` one frame at a time
+    line_task: Option<Task<()>>,
+    /// Replay being watched; the board shows its playback until it closes
+    replay_viewer: Option<ReplayViewer>,
+    /// Cards sliding into place after the last change to the board
+    animation: Option<Animation>,
+    /// Win cascade playing, with the screen to show once it is over
+    celebration: Option<(Cascade, Option<Overlay>)>,
+    /// Wall clock the animations run on
+    clock: SystemClock,
+    /// Window position of each pile as last drawn, where flights start and land
+    pile_origins: Rc<RefCell<HashMap<Pile, Point<Pixels>>>>,
+    /// Each pile of the board as its own view, made when first drawn
+    pile_views: HashMap<Pile, Entity<PileView>>,
+    /// Game left unfinished last session, until the player resumes or declines it
+    saved_game: Option<SavedGame>,
+    /// Keeps the recent play for a report when something goes wrong
+    reporter: Reporter,
+    /// Report written about the last crash or a broken move, until the
+    /// player has seen the offer to open it
+    error_report: Option<PathBuf>,
+    /// Crash-recovery record of the actions played in the current game
+    journal: Journal,
+    /// Redraws the status bar once a second so its clock runs
+    clock_ticker: Task<()>,
+    /// Saves the game in progress when the app quits
+    autosave_on_quit: Subscription,
+    /// Pauses the music while the window is in the background
+    music_on_activation: Subscription,
+    /// Plays the sound for each move's events
+    sound_on_move: Subscription,
+    /// Records the game and celebrates when a move's events include the win
+    stats_on_win: Subscription,
+    /// Applies changes to the shared settings, whichever view made them
+    settings_changed: Subscription,
 }
 
 impl SolitaireApp {
-    pub(crate) fn new() -> Self {
-        Self {
-            game_state: GameState::new(),
-            current_drag: None,
+    pub(crate) fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        // Scripted variants are registered first so saved games of them load
+        let mut variants = VariantRegistry::builtin();
+        let script_notices = scripted::register(&mut variants, &ScriptedRules::default_dir());
+        if let Err(error) = registry::install(variants) {
+            println!("Rules scripts were not loaded: {}", error);
+        }
+        let settings = Settings::load_checked(&Settings::default_path());
+        // Startup notices are shown in the player's language
+        locale::set_language(settings.value.language);
+        let theme_file = settings
+            .value
+            .theme_file
+            .as_deref()
+            .map(|id| ThemeFile::load_id(&ThemeFile::default_dir(), id));
+        let sound_pack = settings
+            .value
+            .sound_pack
+            .as_deref()
+            .map(|name| SoundPack::load(&SoundPack::default_dir(), name));
+        let statistics = Statistics::load_checked(&Statistics::default_path());
+        let high_scores = HighScores::load_checked(&HighScores::default_path());
+        let autosaved =
+            SavedGame::load_checked(&SavedGame::default_path(), settings.value.history_limits);
+        // A journal left behind means the last session crashed, and it is at
+        // least as recent as the autosave
+        let recovered = Journal::recover(&Journal::default_path(), settings.value.history_limits);
+        let theme_notice = match &theme_file {
+            Some(Err(error)) => Some(trf("Theme file could not be loaded: {}", &[error])),
+            _ => None,
+        };
+        let sound_pack_notice = match &sound_pack {
+            Some(Err(error)) => Some(trf("Sound pack could not be loaded: {}", &[error])),
+            _ => None,
+        };
+        let keymap = Keymap::try_from(settings.value.key_bindings.clone());
+        let keymap_notice = match &keymap {
+            Err(error) => Some(trf("Keyboard shortcuts could not be loaded: {}", &[error])),
+            Ok(_) => None,
+        };
+        let notices: Vec<String> = [
+            settings.notice,
+            theme_notice,
+            sound_pack_notice,
+            keymap_notice,
+            statistics.notice,
+            high_scores.notice,
+            autosaved.notice,
+            recovered.notice,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(script_notices)
+        .collect();
+        let saved_game = recovered.value.or(autosaved.value);
+
+        let settings = settings.value;
+        let keymap = keymap.unwrap_or_default();
+        shortcuts::bind_keys(&keymap, cx);
+        let settings_model = cx.new(|_| settings.clone());
+        let settings_changed = cx.observe(&settings_model, |app, model, cx| {
+            let settings = model.read(cx).clone();
+            app.apply_settings(settings, cx);
+        });
+        let reporter = Reporter::new(env!("CARGO_PKG_VERSION"), Report::default_dir());
+        reporter.set_enabled(settings.error_reports);
+        reporter.install_panic_hook();
+        let error_report = reporter.take_crash_report();
+        let mut audio = Audio::new();
+        if let Some(audio) = &mut audio {
+            audio.use_pack(sound_pack.as_ref().and_then(|pack| pack.as_ref().ok()));
+        }
+        let mut game_state = GameState::new_with_draw_count(settings.draw_count);
+        game_state.set_scoring(settings.scoring);
+        game_state.accommodations = settings.accessibility;
+
+        let mut app = Self {
+            focus_handle: cx.focus_handle(),
+            history: History::new(game_state.clone(), settings.history_limits),
+            game_state,
+            drag: DragState::Idle,
+            stock_scroll: 0.0,
+            board_cursor: None,
+            held: None,
+            move_choice: None,
+            peek: None,
+            rebinding: None,
+            dwell: Dwell::default(),
+            dwell_task: None,
+            scan_task: None,
+            watcher: FileWatcher::default(),
+            watch_task: None,
+            settings_model,
+            settings,
+            keymap,
+            theme_file: theme_file.and_then(Result::ok),
+            system_reduce_motion: motion::system_prefers_reduced_motion(),
+            audio,
+            undone: false,
+            theme_files: Vec::new(),
+            sound_packs: Vec::new(),
+            statistics: statistics.value,
+            high_scores: high_scores.value,
+            tournament: None,
+            daily: None,
+            overlay: if saved_game.is_some() {
+                Some(Overlay::ResumeGame)
+            } else if error_report.is_some() {
+                Some(Overlay::ErrorReport)
+            } else {
+                (!notices.is_empty()).then_some(Overlay::Notices {
+                    title: "Some saved data was damaged",
+                })
+            },
+            game_number_input: String::new(),
+            game_number_error: None,
+            seed_roll: SeedRoll::with_random_seed(),
+            variant: Variant::default(),
+            show_stats_hud: false,
+            notices,
+            toasts: Toasts::default(),
+            latency: LatencyMonitor::default(),
+            winnability: None,
+            analysed_position: String::new(),
+            stuck_dismissed: None,
+            winnability_task: None,
+            hint: None,
+            hint_task: None,
+            hint_highlight: None,
+            trainer: false,
+            last_grade: None,
+            grade_counts: [0; 3],
+            line_playback: None,
+            line_task: None,
+            replay_viewer: None,
+            animation: None,
+            celebration: None,
+            clock: SystemClock::new(),
+            pile_origins: Rc::default(),
+            pile_views: HashMap::new(),
+            saved_game,
+            reporter,
+            error_report,
+            journal: Journal::new(Journal::default_path()),
+            // Tick the status bar's clock while a game is being played
+            clock_ticker: cx.spawn(async move |app, cx| {
+                loop {
+                    cx.background_executor().timer(Duration::from_secs(1)).await;
+                    let ticked = app.update(cx, |app, cx| {
+                        if app.game_state.end_time.is_none()
+                            && !app.game_state.accommodations.untimed
+                        {
+                            cx.notify();
+                        }
+                    });
+                    if ticked.is_err() {
+                        break;
+                    }
+                }
+            }),
+            autosave_on_quit: cx.on_app_quit(|app, _cx| {
+                app.save_on_quit();
+                async {}
+            }),
+            music_on_activation: cx.observe_window_activation(window, |app, window, _cx| {
+                app.pause_music_in_background(window.is_window_active());
+            }),
+            sound_on_move: cx.subscribe_self(|app, events: &Vec<GameEvent>, _cx| {
+                app.play_events_cue(events);
+            }),
+            stats_on_win: cx.subscribe_self(|app, events: &Vec<GameEvent>, cx| {
+                if events.contains(&GameEvent::GameWon) {
+                    app.handle_game_won(cx);
+                }
+            }),
+            settings_changed,
+        };
+        app.restart_journal();
+        app.restart_scan(cx);
+        app.start_watching(cx);
+        app.restart_music();
+        app
+    }
+
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let key = event.keystroke.key.as_str();
+        if let Some(command) = self.rebinding {
+            if key == "escape" {
+                self.rebinding = None;
+            } else if let Some(keystroke) = shortcuts::record(&event.keystroke) {
+                self.rebinding = None;
+                self.rebind(command, &keystroke, cx);
+            }
+            cx.notify();
+            return;
+        }
+        if self.celebration.is_some() {
+            return self.finish_celebration(cx);
+        }
+        if self.replay_viewer.is_some() && self.overlay.is_none() {
+            match key {
+                "left" => return self.step_replay(false, cx),
+                "right" => return self.step_replay(true, cx),
+                "escape" => return self.close_replay(cx),
+                _ => {}
+            }
+        }
+        if self.overlay == Some(Overlay::PlayGameNumber) {
+            match key {
+                "enter" => self.play_game_number(cx),
+                "escape" => self.overlay = None,
+                "backspace" => {
+                    self.game_number_input.pop();
+                }
+                _ if key.len() == 1 && key.chars().all(|c| c.is_ascii_digit()) => {
+                    self.game_number_input.push_str(key);
+                }
+                _ => return,
+            }
+            cx.notify();
+            return;
+        }
+        if self.overlay.is_none() && self.replay_viewer.is_none() {
+            let direction = match key {
+                "left" => Some(Direction::Left),
+                "right" => Some(Direction::Right),
+                "up" => Some(Direction::Up),
+                "down" => Some(Direction::Down),
+                _ => None,
+            };
+            if let Some(direction) = direction {
+                // The first press only shows the cursor
+                self.board_cursor = Some(match self.board_cursor {
+                    Some(cursor) => cursor.step(&self.game_state, direction),
+                    None => BoardCursor::default(),
+                });
+                cx.notify();
+                return;
+            }
+            match key {
+                "enter" => {
+                    if let Some(action) = self.chosen_move() {
+                        self.move_choice = None;
+                        self.hint_highlight = None;
+                        return self.handle_action(action, cx);
+                    }
+                    return self.press_board_cursor(cx);
+                }
+                "escape" if self.held.is_some() || self.move_choice.is_some() => {
+                    self.held = None;
+                    self.move_choice = None;
+                    self.hint_highlight = None;
+                    cx.notify();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        if key == "f12" {
+            self.overlay = match self.overlay {
+                Some(Overlay::Diagnostics) => None,
+                _ => Some(Overlay::Diagnostics),
+            };
+            cx.notify();
+        }
+    }
+
+    /// Enter on the board: deal from the stock, pick up the cards from the
+    /// cursor down, or put held cards down at the cursor
+    fn press_board_cursor(&mut self, cx: &mut Context<Self>) {
+        let Some(cursor) = self.board_cursor else {
+            return;
+        };
+        // Give the player a full interval on the next pile before scanning
+        // moves on
+        self.restart_scan(cx);
+        let at = cursor.position;
+        match self.held.take() {
+            // Enter on the pile the cards came from puts them back
+            Some(from) if Pile::of(from) == Pile::of(at) => {}
+            Some(from) => {
+                if let Some(to) = cursor.drop_target(&self.game_state) {
+                    self.handle_action(GameAction::MoveCard { from, to }, cx);
+                }
+            }
+            None if at == Position::Stock => self.handle_action(GameAction::DealFromStock, cx),
+            None => {
+                if !self.get_draggable_cards(at).is_empty() {
+                    self.held = Some(at);
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Step the board cursor on to the next pile every `scan_interval`
+    /// while scanning, starting a full interval from now
+    fn restart_scan(&mut self, cx: &mut Context<Self>) {
+        if self.settings.input_mode != InputMode::Scan {
+            self.scan_task = None;
+            return;
+        }
+        let interval = self.settings.scan_interval;
+        self.scan_task = Some(cx.spawn(async move |app, cx| {
+            loop {
+                cx.background_executor().timer(interval).await;
+                if app.update(cx, |app, cx| app.advance_scan(cx)).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Move the scanning cursor on, unless a dialog or replay is showing
+    fn advance_scan(&mut self, cx: &mut Context<Self>) {
+        if self.overlay.is_some() || self.celebration.is_some() || self.replay_viewer.is_some() {
+            return;
+        }
+        self.board_cursor = Some(match self.board_cursor {
+            Some(cursor) => cursor.scan_next(&self.game_state),
+            None => BoardCursor::default(),
+        });
+        cx.notify();
+    }
+
+    /// Follow the pointer in dwell mode, timing a click on the pile it
+    /// comes to rest on
+    fn dwell_over(&mut self, point: Point<Pixels>, cx: &mut Context<Self>) {
+        let board_showing =
+            self.overlay.is_none() && self.celebration.is_none() && self.replay_viewer.is_none();
+        let target = board_showing.then(|| self.position_at(point)).flatten();
+        let before = self.dwell;
+        if self.dwell.hover(target, &self.clock) {
+            let duration = self.settings.dwell_time;
+            self.dwell_task = Some(cx.spawn(async move |app, cx| {
+                cx.background_executor().timer(duration).await;
+                app.update(cx, |app, cx| app.dwell_click(cx)).ok();
+            }));
+        }
+        if self.dwell != before {
+            cx.notify();
+        }
+    }
+
+    /// Click the pile the pointer has rested on, as Enter on the board
+    /// cursor would
+    fn dwell_click(&mut self, cx: &mut Context<Self>) {
+        if let Some(position) = self.dwell.take_click(self.settings.dwell_time, &self.clock) {
+            self.board_cursor = Some(BoardCursor { position });
+            self.press_board_cursor(cx);
+        }
+    }
+
+    /// The face-up card or pile under `point` as the board was last drawn,
+    /// the card on top where they overlap
+    fn position_at(&self, point: Point<Pixels>) -> Option<Position> {
+        let game_state = &self.game_state;
+        let layout = layout::current();
+        let mut positions = vec![
+            Position::Stock,
+            Position::Waste(game_state.waste.len().saturating_sub(1)),
+        ];
+        positions.extend((0..game_state.foundations.len()).map(Position::Foundation));
+        for (col, column) in game_state.tableau.iter().enumerate() {
+            let first = column
+                .iter()
+                .position(|card| card.face_up)
+                .unwrap_or(column.len().saturating_sub(1));
+            positions
+                .extend((first..column.len().max(1)).map(|index| Position::Tableau(col, index)));
+        }
+        positions.into_iter().rev().find(|position| {
+            let scale = match position {
+                Position::Tableau(..) => layout.card_scale,
+                _ => layout.top_row().card_scale,
+            };
+            let card = size(px(ui::CARD_WIDTH * scale), px(ui::CARD_HEIGHT * scale));
+            self.card_origin(*position)
+                .is_some_and(|origin| Bounds::new(origin, card).contains(&point))
+        })
+    }
+
+    /// Step through the moves that can be made, forwards or back, showing
+    /// each on the board and naming it in the status bar for Enter to make
+    fn cycle_moves(&mut self, backwards: bool, cx: &mut Context<Self>) {
+        let moves = self.game_state.available_moves();
+        if moves.is_empty() {
+            return self.show_info("No moves left", cx);
+        }
+        let position = self.game_state.position_key();
+        let count = moves.len();
+        let index = match &self.move_choice {
+            Some((at, index)) if *at == position && backwards => (index + count - 1) % count,
+            Some((at, index)) if *at == position => (index + 1) % count,
+            _ if backwards => count - 1,
+            _ => 0,
+        };
+        self.hint_highlight = HintHighlight::new(&self.game_state, &moves[index], &self.clock);
+        self.move_choice = Some((position, index));
+        cx.notify();
+    }
+
+    /// The move picked to make, if it was picked on the board as it is now
+    fn chosen_move(&self) -> Option<GameAction> {
+        let (position, index) = self.move_choice.as_ref()?;
+        if *position != self.game_state.position_key() {
+            return None;
+        }
+        self.game_state.available_moves().get(*index).cloned()
+    }
+
+    /// `text` for a tooltip, followed by the first shortcut for `command`
+    fn with_shortcut(&self, text: &str, command: Command) -> String {
+        match self.keymap.keys(command).first() {
+            Some(keystroke) => format!("{} ({})", tr(text), shortcuts::describe(keystroke)),
+            None => tr(text).to_string(),
+        }
+    }
+
+    /// Run a game command dispatched by a shortcut, menu or button, unless a
+    /// dialog or the win cascade has the keyboard, in which case the
+    /// command goes on to them
+    fn shortcut(
+        &mut self,
+        cx: &mut Context<Self>,
+        run: impl FnOnce(&mut Self, &mut Context<Self>),
+    ) {
+        if self.overlay.is_some() || self.celebration.is_some() {
+            cx.propagate();
+            return;
+        }
+        run(self, cx);
+    }
+
+    /// Space deals, or plays and pauses a replay being watched. When
+    /// scanning it is the switch, picking the pile the cursor is on.
+    fn deal_or_toggle_replay(&mut self, cx: &mut Context<Self>) {
+        if self.replay_viewer.is_some() {
+            self.toggle_replay_playing(cx);
+        } else if self.settings.input_mode == InputMode::Scan {
+            self.press_board_cursor(cx);
+        } else {
+            self.handle_action(GameAction::DealFromStock, cx);
+        }
+    }
+
+    /// Deal once per wheel notch scrolled over the stock and waste, either
+    /// way. Trackpads scroll in small steps, which add up to a notch.
+    fn scroll_stock(&mut self, event: &ScrollWheelEvent, cx: &mut Context<Self>) {
+        let delta = event.delta.pixel_delta(px(WHEEL_NOTCH));
+        self.stock_scroll += f32::from(delta.y).abs();
+        if self.stock_scroll >= WHEEL_NOTCH {
+            // A fast fling still deals only once per event
+            self.stock_scroll = 0.0;
+            self.handle_action(GameAction::DealFromStock, cx);
+        }
+    }
+
+    /// Play every card that can go up to a foundation, one move at a time
+    fn auto_complete(&mut self, cx: &mut Context<Self>) {
+        if self.game_state.next_foundation_move().is_none() {
+            return self.show_info("No cards can go up to the foundations", cx);
+        }
+        while let Some(action) = self.game_state.next_foundation_move() {
+            let placed = self.game_state.foundation_card_count();
+            self.handle_action(action, cx);
+            // Moves are refused while a replay or winning line is showing
+            if self.game_state.foundation_card_count() == placed {
+                break;
+            }
+        }
+    }
+
+    /// Start a game from a deal layout, position code or replay pasted on
+    /// the clipboard
+    fn import_deal_from_clipboard(&mut self, cx: &mut Context<Self>) {
+        let result = if self.tournament.is_some() {
+            Err(tr("Finish the tournament before importing a deal").to_string())
+        } else {
+            cx.read_from_clipboard()
+                .and_then(|item| item.text())
+                .ok_or_else(|| tr("The clipboard has no text to import").to_string())
+                .and_then(|text| {
+                    if text.trim_start().starts_with("K1.") {
+                        // Shared mid-game positions never count towards stats
+                        GameState::decode(&text).map(|mut game_state| {
+                            game_state.practice = true;
+                            (game_state, Vec::new())
+                        })
+                    } else if text.contains('=') {
+                        // Replays are shared games too
+                        Replay::parse(&text).map(|replay| {
+                            let mut game_state = replay.initial_state();
+                            game_state.practice = true;
+                            (game_state, replay.actions)
+                        })
+                    } else {
+                        DealLayout::parse(&text)
+                            .map(|layout| (layout.into_game(self.settings.draw_count), Vec::new()))
+                    }
+                })
+        };
+
+        match result {
+            Ok((game_state, actions)) => {
+                self.record_unfinished_game();
+                self.game_state = game_state;
+                self.daily = None;
+                self.reset_game_setup();
+                // A replay carries on from its last move, with its moves in
+                // the undo history
+                if !actions.is_empty() {
+                    for action in actions {
+                        if let Ok(command) = GameCommand::apply(&mut self.game_state, action) {
+                            self.history.record(command, &self.game_state);
+                        }
+                    }
+                    self.restart_journal();
+                    self.autosave();
+                }
+                self.overlay = None;
+            }
+            Err(error) => {
+                self.notices = vec![error];
+                self.overlay = Some(Overlay::Notices {
+                    title: "Could not import the deal",
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    /// Copy the current position's code to the clipboard for sharing
+    fn share_position(&mut self, cx: &mut Context<Self>) {
+        if !self.game_state.rules.is_klondike() {
+            self.show_error("Only Klondike positions can be shared", cx);
+            return;
+        }
+        cx.write_to_clipboard(ClipboardItem::new_string(self.game_state.encode()));
+        self.show_info("Position code copied to the clipboard", cx);
+    }
+
+    /// Ask before a game in progress is given up for `next`, unless the
+    /// player turned the question off. Confirming comes back here with the
+    /// question still showing, and goes ahead.
+    fn confirm_abandon(&mut self, next: Abandon, cx: &mut Context<Self>) -> bool {
+        let in_progress = self.game_state.move_count > 0 && !self.game_state.game_won;
+        let asking = Some(Overlay::ConfirmAbandon { next });
+        if in_progress && self.settings.confirm_abandon && self.overlay != asking {
+            self.overlay = asking;
+            cx.notify();
+            return false;
+        }
+        true
+    }
+
+    /// Leave the current game for a fresh deal of `seed` in the chosen variant
+    fn deal_seed(&mut self, seed: u64, cx: &mut Context<Self>) {
+        self.record_unfinished_game();
+        self.game_state = GameState::with_rules(self.variant, seed);
+        self.game_state.draw_count = self.settings.draw_count;
+        self.game_state.set_scoring(self.settings.scoring);
+        self.daily = None;
+        self.reset_game_setup();
+        self.overlay = None;
+        cx.notify();
+    }
+
+    /// Tell the player an action was refused, and why
+    fn show_error(&mut self, error: impl Into<String>, cx: &mut Context<Self>) {
+        self.toasts
+            .push(ToastKind::Error, tr(&error.into()), &self.clock);
+        cx.notify();
+    }
+
+    fn show_info(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
+        self.toasts
+            .push(ToastKind::Info, tr(&message.into()), &self.clock);
+        cx.notify();
+    }
+
+    fn handle_action(&mut self, action: GameAction, cx: &mut Context<Self>) {
+        if self.line_playback.is_some() {
+            self.show_error("Take over from the winning line first", cx);
+            return;
+        }
+        if self.replay_viewer.is_some() {
+            self.show_error("Close the replay first", cx);
+            return;
+        }
+        let label = action.name();
+        let started = Instant::now();
+        self.apply_action(action, cx);
+        self.record_latency(label, started.elapsed());
+    }
+
+    /// Start solving the current position in the background unless it has
+    /// already been analysed
+    fn refresh_winnability(&mut self, cx: &mut Context<Self>) {
+        let position = self.game_state.position_key();
+        if position == self.analysed_position
+            || self.line_playback.is_some()
+            || self.replay_viewer.is_some()
+        {
+            return;
+        }
+        self.analysed_position = position;
+        self.winnability = None;
+
+        let game_state = self.game_state.clone();
+        let solve = cx
+            .background_executor()
+            .spawn(async move { Solver::default().solve(&game_state) });
+        self.winnability_task = Some(cx.spawn(async move |app, cx| {
+            let solution = solve.await;
+            app.update(cx, |app, cx| {
+                app.winnability = Some(solution);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Whether hints can be given, and moves graded, in the game being
+    /// played: never in a tournament, and only for variants whose moves the
+    /// solver knows
+    fn offers_hints(&self) -> bool {
+        self.tournament.is_none() && self.game_state.rules.is_solvable()
+    }
+
+    /// Suggest a move, worked out in the background. Taking a hint makes the
+    /// game a practice game.
+    fn request_hint(&mut self, cx: &mut Context<Self>) {
+        self.game_state.practice = true;
+        let position = self.game_state.position_key();
+        self.hint = Some((position.clone(), tr("Hint: thinking…").to_string()));
+
+        let game_state = self.game_state.clone();
+        let find = cx.background_executor().spawn(async move {
+            let hint = evaluator::hint(&game_state, &mut thread_rng());
+            (game_state, hint)
+        });
+        self.hint_task = Some(cx.spawn(async move |app, cx| {
+            let (game_state, hint) = find.await;
+            app.update(cx, |app, cx| {
+                // Put in words here rather than in the background, where the
+                // language isn't set
+                let text = match &hint {
+                    Some(hint) => trf(
+                        "Hint: {} ({}% win chance)",
+                        &[
+                            &mirror::describe_action(&game_state, &hint.action),
+                            &format!("{:.0}", hint.win_rate * 100.0),
+                        ],
+                    ),
+                    None => tr("Hint: no moves left").to_string(),
+                };
+                // Only show the move on the board it was worked out for
+                if app.game_state.position_key() == position {
+                    app.hint_highlight = hint.and_then(|hint| {
+                        HintHighlight::new(&app.game_state, &hint.action, &app.clock)
+                    });
+                }
+                app.hint = Some((position, text));
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Turn trainer mode on or off; trained games are practice games
+    fn toggle_trainer(&mut self, cx: &mut Context<Self>) {
+        self.trainer = !self.trainer;
+        if self.trainer {
+            self.game_state.practice = true;
+            self.grade_counts = [0; 3];
+        }
+        self.last_grade = None;
+        cx.notify();
+    }
+
+    /// Grade a move just played from `before` in the background
+    fn grade_move(&mut self, before: GameState, action: GameAction, cx: &mut Context<Self>) {
+        self.game_state.practice = true;
+        let grade = cx.background_executor().spawn(async move {
+            Evaluator::default()
+                .grade(&before, &action, &mut thread_rng())
+                .map(|graded| (before, graded))
+        });
+        cx.spawn(async move |app, cx| {
+            let Some((before, graded)) = grade.await else {
+                return;
+            };
+            app.update(cx, |app, cx| {
+                if !app.trainer {
+                    return;
+                }
+                let advice = match graded.grade {
+                    MoveGrade::Best => String::new(),
+                    _ => trf(
+                        " (better: {})",
+                        &[&mirror::describe_action(&before, &graded.best.action)],
+                    ),
+                };
+                app.grade_counts[graded.grade as usize] += 1;
+                app.last_grade = Some(format!("{}{}", tr(graded.grade.label()), advice));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Status bar text for the winnability verdict
+    fn winnability_label(&self) -> &'static str {
+        tr(match self.winnability {
+            None => "Checking…",
+            Some(Solution::Winnable(_)) => "Winnable",
+            Some(Solution::Unknown) => "Unknown",
+            Some(Solution::Unwinnable) => "Lost",
+        })
+    }
+
+    /// Add a latency sample, warning in debug builds when it is over budget
+    fn record_latency(&mut self, label: &'static str, elapsed: Duration) {
+        if let Some(warning) = self.latency.record(label, elapsed)
+            && cfg!(debug_assertions)
+        {
+            println!("Latency budget exceeded: {}", warning);
+        }
+    }
+
+    fn apply_action(&mut self, action: GameAction, cx: &mut Context<Self>) {
+        if matches!(action, GameAction::NewGame | GameAction::ReplaySeed) {
+            self.start_new_game(action, cx);
+            return;
+        }
+        if matches!(action, GameAction::Undo | GameAction::Redo) {
+            self.step_history(action, cx);
+            return;
+        }
+
+        let before = self.game_state.clone();
+        match GameCommand::apply(&mut self.game_state, action.clone()) {
+            Ok(command) => {
+                self.animate_from(&before);
+                if self.trainer && self.offers_hints() {
+                    self.grade_move(before, action.clone(), cx);
+                }
+                cx.emit(command.events());
+                self.history.record(command, &self.game_state);
+                self.journal_action(&action);
+                self.autosave();
+                if let Some(problem) = report::invariant_violation(&before, &self.game_state) {
+                    self.report_error(&problem, cx);
+                }
+                // Action succeeded, trigger a re-render
+                cx.notify();
+            }
+            Err(error) => {
+                self.play_cue(Cue::Invalid);
+                self.show_error(error, cx);
+            }
+        }
+    }
+
+    /// Play the sound for a move that did `events`. A win plays its fanfare
+    /// once the result is recorded.
+    fn play_events_cue(&self, events: &[GameEvent]) {
+        let cue = Cue::for_events(events);
+        if cue != Cue::Win {
+            self.play_cue(cue);
+        }
+    }
+
+    /// A move broke the board: write a report of `reason` if the player
+    /// opted in, and offer to open it
+    fn report_error(&mut self, reason: &str, cx: &mut Context<Self>) {
+        println!("Invariant violated: {}", reason);
+        match self.reporter.write(reason) {
+            Some(Ok(path)) => {
+                self.error_report = Some(path);
+                self.overlay = Some(Overlay::ErrorReport);
+                cx.notify();
+            }
+            Some(Err(error)) => self.show_error(trf("Could not write a report: {}", &[&error]), cx),
+            None => self.show_error("Something went wrong with that move", cx),
+        }
+    }
+
+    /// Play `cue` unless sound cues are turned off or muted
+    fn play_cue(&self, cue: Cue) {
+        if self.settings.sound_cues
+            && !self.settings.muted
+            && let Some(audio) = &self.audio
+        {
+            audio.play(cue, self.settings.effects_level());
+        }
+    }
+
+    /// Silence all sound at once, or bring it back at the volumes set
+    fn toggle_mute(&mut self, cx: &mut Context<Self>) {
+        self.change_settings(cx, |settings| settings.muted = !settings.muted);
+    }
+
+    /// Loop the tracks in the music folder if music is on, or stop it
+    fn restart_music(&mut self) {
+        let volume = self.settings.music_level();
+        let Some(audio) = &mut self.audio else {
+            return;
+        };
+        if self.settings.music {
+            audio.play_music(&music::tracks(&music::default_dir()), volume);
+        } else {
+            audio.stop_music();
+        }
+    }
+
+    /// Pause the music while the window is in the background, if the player
+    /// asked for that, and carry on when it comes back
+    fn pause_music_in_background(&self, window_active: bool) {
+        if let Some(audio) = &self.audio {
+            audio.pause_music(!window_active && self.settings.pause_music_in_background);
+        }
+    }
+
+    /// Start a new game (fresh or replaying the current seed), counting the
+    /// current one as played if it was started. During a tournament a new game
+    /// forfeits the current deal and replays are not allowed.
+    fn start_new_game(&mut self, action: GameAction, cx: &mut Context<Self>) {
+        if self.tournament.is_some() {
+            if action == GameAction::ReplaySeed {
+                self.show_error("Deals cannot be replayed during a tournament", cx);
+            } else {
+                self.finish_tournament_deal(cx);
+            }
+            return;
+        }
+
+        self.record_unfinished_game();
+        if let Err(error) = self.game_state.handle_action(action.clone()) {
+            self.show_error(error, cx);
+        }
+        // Replaying keeps playing the same daily challenge; a new game leaves
+        // it, and picks up the draw and scoring settings
+        if action == GameAction::NewGame {
+            self.game_state.draw_count = self.settings.draw_count;
+            self.game_state.set_scoring(self.settings.scoring);
+            self.daily = None;
+        }
+        self.reset_game_setup();
+        cx.notify();
+    }
+
+    /// Count the current game as played if it was started but not won
+    fn record_unfinished_game(&mut self) {
+        if self.game_state.move_count == 0 || self.game_state.game_won {
+            return;
+        }
+        match self.daily {
+            Some(date) => self
+                .statistics
+                .record_daily(DailyResult::from_game(date, &self.game_state)),
+            None => {
+                self.statistics.record_game(&self.game_state);
+            }
+        }
+        self.save_statistics();
+    }
+
+    /// Deal today's daily challenge: the same cards for every player
+    fn start_daily_challenge(&mut self, cx: &mut Context<Self>) {
+        if self.tournament.is_some() {
+            self.show_error(
+                "Finish the tournament before playing the daily challenge",
+                cx,
+            );
+            return;
+        }
+
+        self.record_unfinished_game();
+        let today = Date::today();
+        self.game_state = GameState::new_with_seed(daily::daily_seed(today));
+        self.game_state.draw_count = self.settings.draw_count;
+        self.game_state.set_scoring(self.settings.scoring);
+        self.daily = Some(today);
+        self.reset_game_setup();
+        self.overlay = None;
+        cx.notify();
+    }
+
+    /// Undo or redo one action. A won game is final, and accommodations in
+    /// use stay on the restored state since the game was played with them.
+    fn step_history(&mut self, action: GameAction, cx: &mut Context<Self>) {
+        if self.game_state.game_won {
+            self.show_error("The game is already won", cx);
+            return;
+        }
+
+        let restored = if action == GameAction::Undo {
+            self.history.undo()
+        } else {
+            self.history.redo()
+        };
+        match restored {
+            Some(mut game_state) => {
+                game_state.accommodations = game_state
+                    .accommodations
+                    .union(self.game_state.accommodations);
+                game_state.practice |= self.game_state.practice;
+                let before = std::mem::replace(&mut self.game_state, game_state);
+                self.undone |= action == GameAction::Undo;
+                self.animate_from(&before);
+                self.journal_action(&action);
+                self.autosave();
+                cx.notify();
+            }
+            None if action == GameAction::Undo => self.show_error("Nothing to undo", cx),
+            None => self.show_error("Nothing to redo", cx),
+        }
+    }
+
+    /// Whether motion is cut down, as asked for in settings, by the theme
+    /// or by the operating system
+    fn reduce_motion(&self) -> bool {
+        self.settings.reduce_motion
+            || self.system_reduce_motion
+            || self
+                .theme_file
+                .as_ref()
+                .is_some_and(|file| file.reduce_motion)
+    }
+
+    /// Whether cards glide between piles and cascade off a won board
+    fn animates(&self) -> bool {
+        self.settings.animations && !self.reduce_motion()
+    }
+
+    /// Slide the cards that moved since `before` into their new places. A
+    /// hint shown on the old board no longer applies.
+    fn animate_from(&mut self, before: &GameState) {
+        self.animation = if self.animates() {
+            Animation::between(
+                before,
+                &self.game_state,
+                &self.clock,
+                MOVE_DURATION,
+                self.settings.easing,
+            )
+        } else {
+            None
+        };
+        self.hint_highlight = None;
+    }
+
+    /// Apply the settings to a freshly dealt game and start its history
+    fn reset_game_setup(&mut self) {
+        self.line_playback = None;
+        self.line_task = None;
+        self.animation = None;
+        self.celebration = None;
+        self.game_state.accommodations = self.settings.accessibility;
+        self.history = History::new(self.game_state.clone(), self.settings.history_limits);
+        self.undone = false;
+        self.restart_journal();
+        self.autosave();
+    }
+
+    /// Journal the current game from here on. Tournament deals are not
+    /// recovered, so the journal is cleared during a tournament.
+    fn restart_journal(&mut self) {
+        self.reporter.start(&self.game_state);
+        // Keep last session's journal until the player decides on its game
+        if self.saved_game.is_some() {
+            return;
+        }
+        let result = if self.tournament.is_some() {
+            self.journal.clear()
+        } else {
+            self.journal.start(&SavedGame {
+                game_state: self.game_state.clone(),
+                history: self.history.clone(),
+                daily: self.daily,
+            })
+        };
+        if let Err(error) = result {
+            println!("Failed to start the journal: {}", error);
+        }
+    }
+
+    fn journal_action(&mut self, action: &GameAction) {
+        self.reporter.record(action, &self.game_state);
+        if let Err(error) = self.journal.append(action, &self.game_state) {
+            println!("Failed to write the journal: {}", error);
+        }
+    }
+
+    /// Keep the game in progress on disk to resume at the next launch. Only
+    /// single games and daily challenges with moves played are kept; a won
+    /// game or a tournament deal clears the save instead.
+    fn autosave(&self) {
+        // Leave last session's game alone until the player decides on it
+        if self.saved_game.is_some() {
+            return;
+        }
+        let path = SavedGame::default_path();
+        let result =
+            if self.tournament.is_some() || self.game_state.game_won || self.history.is_empty() {
+                SavedGame::clear(&path)
+            } else {
+                SavedGame {
+                    game_state: self.game_state.clone(),
+                    history: self.history.clone(),
+                    daily: self.daily,
+                }
+                .save(&path)
+            };
+        if let Err(error) = result {
+            println!("Failed to autosave: {}", error);
+        }
+    }
+
+    /// Save the game in progress on a clean exit. The autosave is then all
+    /// that is needed to resume, so the crash journal goes.
+    fn save_on_quit(&mut self) {
+        if self.saved_game.is_some() {
+            return;
+        }
+        self.restore_set_aside_game();
+        self.autosave();
+        if let Err(error) = self.journal.clear() {
+            println!("Failed to clear the journal: {}", error);
+        }
+    }
+
+    fn handle_game_won(&mut self, cx: &mut Context<Self>) {
+        if self.tournament.is_some() {
+            // Tournament deals are recorded with the tournament, not as single games
+            self.play_cue(Cue::fanfare(false, self.undone));
+            self.finish_tournament_deal(cx);
+        } else if let Some(date) = self.daily {
+            // Daily challenges are tracked apart from single games
+            self.play_cue(Cue::fanfare(false, self.undone));
+            self.statistics
+                .record_daily(DailyResult::from_game(date, &self.game_state));
+            self.save_statistics();
+            self.celebrate(Some(Overlay::DailyCalendar), cx);
+        } else if !self.game_state.rules.is_klondike() {
+            // Statistics and high scores are kept for Klondike only
+            self.play_cue(Cue::fanfare(false, self.undone));
+            self.celebrate(None, cx);
+        } else {
+            let record_time = self.statistics.record_game(&self.game_state);
+            self.play_cue(Cue::fanfare(record_time, self.undone));
+            self.save_statistics();
+
+            let placement = self.high_scores.record(&self.game_state);
+            if placement.is_new_entry()
+                && let Err(error) = self.high_scores.save(&HighScores::default_path())
+            {
+                println!("Failed to save high scores: {}", error);
+            }
+            self.celebrate(
+                Some(Overlay::HighScores {
+                    draw_count: self.game_state.draw_count,
+                    placement: Some(placement),
+                }),
+                cx,
+            );
+        }
+    }
+
+    /// Play the win cascade, then show `overlay` if any
+    fn celebrate(&mut self, overlay: Option<Overlay>, cx: &mut Context<Self>) {
+        self.animation = None;
+        if !self.animates() {
+            self.overlay = overlay;
+            cx.notify();
+            return;
+        }
+        self.celebration = Some((Cascade::new(&self.game_state, &self.clock), overlay));
+        cx.notify();
+    }
+
+    /// End the win cascade, early if the player clicks or presses a key
+    fn finish_celebration(&mut self, cx: &mut Context<Self>) {
+        if let Some((_, overlay)) = self.celebration.take() {
+            self.overlay = overlay;
+        }
+        cx.notify();
+    }
+
+    fn save_statistics(&self) {
+        if let Err(error) = self.statistics.save(&Statistics::default_path()) {
+            println!("Failed to save statistics: {}", error);
+        }
+    }
+
+    fn handle_drop(
+        &mut self,
+        drag_info: &DragInfo,
+        drop_position: Position,
+        cx: &mut Context<Self>,
+    ) {
+        // Back on their own pile the cards don't move, and letting go of
+        // them may yet make a click
+        if Pile::of(drop_position) == Pile::of(drag_info.source_position) {
+            return;
+        }
+        self.drag.drop();
+        // A pile that wasn't redrawn since the board changed hands out the
+        // targets of the board it was drawn for, so they are found again
+        let valid_drop_targets =
+            self.get_valid_drop_targets(&drag_info.dragged_cards, drag_info.source_position);
+        if valid_drop_targets.contains(&drop_position) {
+            // Perform the move
+            let move_action = GameAction::MoveCard {
+                from: drag_info.source_position,
+                to: drop_position,
+            };
+            self.handle_action(move_action, cx);
+        } else if let Err(error) = self
+            .game_state
+            .clone()
+            .move_card(drag_info.source_position, drop_position)
+        {
+            // Say why the pile refused the cards
+            self.show_error(error, cx);
+        }
+
+        self.drag.finish();
+        cx.notify();
+    }
+
+    /// The left button was let go at `point`: a click on the card it went
+    /// down on if it barely moved, and the end of any drag not dropped on a
+    /// pile
+    fn release_pointer(&mut self, point: Point<Pixels>, cx: &mut Context<Self>) {
+        if let Some(position) = self.drag.release(window_point(point)) {
+            self.click_card(position, cx);
+        }
+        if self.drag.finish() {
+            cx.notify();
+        }
+    }
+
+    /// Drag preview for cards picked up from the board. Starting the drag
+    /// also records it, so the piles that would take the cards light up.
+    fn drag_preview(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> impl Fn(&DragInfo, Point<Pixels>, &mut Window, &mut App) -> Entity<DragInfo> + 'static
+    {
+        let app = cx.entity().downgrade();
+        move |drag_info: &DragInfo, _cursor_position, _window, cx| {
+            println!(
+                "Drag started: from {:?}, {} valid targets: {:?}",
+                drag_info.source_position,
+                drag_info.valid_drop_targets.len(),
+                drag_info.valid_drop_targets
+            );
+            app.update(cx, |app, cx| {
+                let mut drag_info = drag_info.clone();
+                drag_info.valid_drop_targets =
+                    app.get_valid_drop_targets(&drag_info.dragged_cards, drag_info.source_position);
+                app.drag.start(drag_info);
+                cx.notify();
+            })
+            .ok();
+            cx.new(|_| drag_info.clone())
+        }
+    }
+
+    /// Whether the cards being dragged may be dropped at `position`
+    fn is_drop_target(&self, position: Position) -> bool {
+        self.drag
+            .dragging()
+            .is_some_and(|drag| drag.valid_drop_targets.contains(&position))
+    }
+
+    /// Let a draggable card be clicked too: a press released within
+    /// `drag::CLICK_THRESHOLD` of where it went down sends the card where it
+    /// can go. The drag a wobbly press starts goes nowhere, since the card's
+    /// own pile never takes it back.
+    fn click_to_move(
+        &self,
+        card: Stateful<Div>,
+        position: Position,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        card.on_mouse_down(
+            MouseButton::Left,
+            cx.listener(move |app, event: &MouseDownEvent, _window, _cx| {
+                app.drag.press(position, window_point(event.position));
+            }),
+        )
+        .on_mouse_up(
+            MouseButton::Left,
+            cx.listener(|app, event: &MouseUpEvent, _window, cx| {
+                app.release_pointer(event.position, cx);
+            }),
+        )
+    }
+
+    /// Move the clicked cards to a foundation if one takes them, otherwise
+    /// to the first other pile that does. Cards that pair off only move when
+    /// a single card pairs with them, so a click never picks a partner.
+    fn click_card(&mut self, position: Position, cx: &mut Context<Self>) {
+        let cards = self.get_draggable_cards(position);
+        let targets = self.get_valid_drop_targets(&cards, position);
+        let foundation = targets
+            .iter()
+            .find(|target| matches!(target, Position::Foundation(_)));
+        let ambiguous = self.game_state.rules.pair_total().is_some() && targets.len() > 1;
+        match foundation.or_else(|| targets.first().filter(|_| !ambiguous)) {
+            Some(&to) => self.handle_action(GameAction::MoveCard { from: position, to }, cx),
+            None if ambiguous => self.show_info("Drag the card onto the one to pair it with", cx),
+            None => self.show_error("That card has nowhere to go", cx),
+        }
+    }
+
+    fn get_draggable_cards(&self, position: Position) -> Vec<Card> {
+        // Use the game state's logic to get draggable cards
+        self.game_state
+            .get_cards_at_position(position)
+            .unwrap_or_else(|_| Vec::new())
+    }
+
+    fn get_valid_drop_targets(&self, cards: &[Card], source: Position) -> Vec<Position> {
+        if cards.is_empty() {
+            return Vec::new();
+        }
+
+        // Cards that pair off can go wherever the game accepts the move
+        if self.game_state.rules.pair_total().is_some() {
+            let waste = self
+                .game_state
+                .waste
+                .len()
+                .checked_sub(1)
+                .map(Position::Waste);
+            return (0..self.game_state.tableau.len())
+                .map(|col| Position::Tableau(col, self.game_state.tableau[col].len()))
+                .chain(waste)
+                .chain((0..self.game_state.foundations.len()).map(Position::Foundation))
+                .filter(|to| self.game_state.clone().move_card(source, *to).is_ok())
+                .collect();
+        }
+
+        let first_card = cards[0]; // The card that will be placed on the destination
+        let mut targets = Vec::new();
+
+        // Check tableau columns
+        for col in 0..self.game_state.tableau.len() {
+            let tableau_pos = Position::Tableau(col, self.game_state.tableau[col].len());
+            if self.can_drop_on_tableau(first_card, col)
+                && !self.is_same_position(source, Position::Tableau(col, 0))
+            {
+                targets.push(tableau_pos);
+            }
+        }
+
+        // Check foundation piles (only for single cards)
+        if cards.len() == 1 {
+            for foundation in 0..self.game_state.foundations.len() {
+                let foundation_pos = Position::Foundation(foundation);
+                if self.can_drop_on_foundation(first_card, foundation) {
+                    targets.push(foundation_pos);
+                }
+            }
+            // Golf plays cards onto the waste
+            if self
+                .game_state
+                .rules
+                .accepts_on_waste(&first_card, self.game_state.waste.last())
+            {
+                targets.push(Position::Waste(self.game_state.waste.len() - 1));
+            }
+        }
+
+        targets
+    }
+
+    fn can_drop_on_tableau(&self, card: Card, col: usize) -> bool {
+        self.game_state
+            .tableau
+            .get(col)
+            .is_some_and(|pile| self.game_state.rules.accepts_on_tableau(&card, pile.last()))
+    }
+
+    fn can_drop_on_foundation(&self, card: Card, foundation: usize) -> bool {
+        self.game_state
+            .foundations
+            .get(foundation)
+            .is_some_and(|pile| {
+                self.game_state
+                    .rules
+                    .accepts_on_foundation(&card, pile.last())
+            })
+    }
+
+    fn is_same_position(&self, pos1: Position, pos2: Position) -> bool {
+        match (pos1, pos2) {
+            (Position::Tableau(col1, _), Position::Tableau(col2, _)) => col1 == col2,
+            _ => false,
+        }
+    }
+
+    fn render_menu_bar(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let new_game_label = if self.tournament.is_some() {
+            tr("Forfeit Deal")
+        } else {
+            tr("New Game")
+        };
+        let offers_winning_line = self.tournament.is_none()
+            && self.line_playback.is_none()
+            && matches!(&self.winnability, Some(Solution::Winnable(line)) if !line.is_empty());
+
+        ui::row()
+            .justify_center()
+            .gap_2()
+            .child(
+                ui::render_button("menu_new_game", new_game_label)
+                    .tooltip(tooltip::text(
+                        self.with_shortcut("Deal a new game", Command::NewGame),
+                    ))
+                    .on_click(shortcuts::dispatch(shortcuts::NewGame)),
+            )
+            .child(
+                ui::render_button("menu_undo", tr("Undo"))
+                    .tooltip(tooltip::text(
+                        self.with_shortcut("Take back the last move", Command::Undo),
+                    ))
+                    .on_click(shortcuts::dispatch(shortcuts::Undo)),
+            )
+            .child(
+                ui::render_button("menu_redo", tr("Redo"))
+                    .tooltip(tooltip::text(
+                        self.with_shortcut("Make the move taken back again", Command::Redo),
+                    ))
+                    .on_click(shortcuts::dispatch(shortcuts::Redo)),
+            )
+            .child(
+                ui::render_button("menu_auto_complete", tr("Auto-complete"))
+                    .tooltip(tooltip::text(self.with_shortcut(
+                        "Play every card that can go to the foundations",
+                        Command::AutoComplete,
+                    )))
+                    .on_click(shortcuts::dispatch(shortcuts::AutoComplete)),
+            )
+            .child(
+                ui::render_button("menu_collect_all", tr("Collect All"))
+                    .tooltip(tooltip::text(self.with_shortcut(
+                        "Move the cards no longer needed on the tableau to the foundations",
+                        Command::CollectAll,
+                    )))
+                    .on_click(shortcuts::dispatch(shortcuts::CollectAll)),
+            )
+            .when(self.line_playback.is_some(), |menu| {
+                menu.child(
+                    ui::render_button("menu_take_over", tr("Take Over")).on_click(cx.listener(
+                        |app, _event, _window, cx| {
+                            app.line_task = None;
+                            app.take_over(cx);
+                        },
+                    )),
+                )
+            })
+            .when(offers_winning_line, |menu| {
+                menu.child(
+                    ui::render_button("menu_winning_line", tr("Show Winning Line")).on_click(
+                        cx.listener(|app, _event, _window, cx| {
+                            app.show_winning_line(cx);
+                        }),
+                    ),
+                )
+            })
+            .child(
+                ui::render_button(
+                    "menu_mute",
+                    if self.settings.muted {
+                        tr("Unmute")
+                    } else {
+                        tr("Mute")
+                    },
+                )
+                .tooltip(tooltip::text(
+                    self.with_shortcut("Turn all sound off or back on", Command::Mute),
+                ))
+                .on_click(shortcuts::dispatch(shortcuts::ToggleMute)),
+            )
+            .when(self.offers_hints(), |menu| {
+                menu.child(
+                    ui::render_button("menu_hint", tr("Hint"))
+                        .tooltip(tooltip::text(
+                            self.with_shortcut("Show a good move", Command::Hint),
+                        ))
+                        .on_click(shortcuts::dispatch(shortcuts::Hint)),
+                )
+                .child(
+                    ui::render_button(
+                        "menu_trainer",
+                        if self.trainer {
+                            tr("Trainer: On")
+                        } else {
+                            tr("Trainer: Off")
+                        },
+                    )
+                    .tooltip(tooltip::text(tr("Grade every move against the best one")))
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.toggle_trainer(cx);
+                    })),
+                )
+            })
+            .when(
+                self.tournament.is_none()
+                    && !self.game_state.practice
+                    && self.game_state.move_count == 0,
+                |menu| {
+                    // Only offered before the first move, so a game going badly
+                    // can't be turned into practice to protect a streak
+                    menu.child(
+                        ui::render_button("menu_practice", tr("Practice Game")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.game_state.practice = true;
+                                cx.notify();
+                            }),
+                        ),
+                    )
+                },
+            )
+            .when(
+                self.tournament.is_none() && !self.game_state.imported,
+                |menu| {
+                    menu.child(
+                        ui::render_button("menu_replay_seed", tr("Replay Deal")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.handle_action(GameAction::ReplaySeed, cx);
+                            }),
+                        ),
+                    )
+                },
+            )
+            .child(
+                ui::render_button("menu_tournament", tr("Tournament")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.start_tournament(cx);
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_game_number", tr("Play Game #…")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.open_game_number_dialog(cx);
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_import_deal", tr("Import Deal")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.import_deal_from_clipboard(cx);
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_share_position", tr("Share Position")).on_click(
+                    cx.listener(|app, _event, _window, cx| {
+                        app.share_position(cx);
+                    }),
+                ),
+            )
+            .child(
+                ui::render_button("menu_export_replay", tr("Export Replay")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.export_replay(cx);
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_watch_replay", tr("Watch Replay")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.overlay = Some(Overlay::Replays);
+                        cx.notify();
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_daily", tr("Daily Challenge")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.overlay = Some(Overlay::DailyCalendar);
+                        cx.notify();
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_high_scores", tr("High Scores")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.overlay = Some(Overlay::HighScores {
+                            draw_count: app.game_state.draw_count,
+                            placement: None,
+                        });
+                        cx.notify();
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("menu_settings", tr("Settings")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.open_settings(cx);
+                    },
+                )),
+            )
+    }
+
+    /// Compact live stats in the corner of the felt: streak, today's games and
+    /// the current pace against the best time for this draw mode
+    fn render_stats_hud(&self) -> impl IntoElement {
+        let (played_today, won_today) = self.statistics.games_on(Date::today());
+        let best_time = self
+            .high_scores
+            .mode(self.game_state.draw_count)
+            .fastest_wins
+            .first()
+            .map(|entry| entry.time);
+        let pace = match (self.game_state.projected_time(), best_time) {
+            (Some(pace), Some(best)) => {
+                let marker = tr(if pace <= best { "ahead" } else { "behind" });
+                trf(
+                    "Pace {} vs best {} ({})",
+                    &[
+                        &ui::format_duration(pace),
+                        &ui::format_duration(best),
+                        &marker,
+                    ],
+                )
+            }
+            (Some(pace), None) => trf("Pace {} (no best yet)", &[&ui::format_duration(pace)]),
+            (None, _) => tr("Pace: no foundation cards yet").to_string(),
+        };
+
+        div()
+            .absolute()
+            .top_4()
+            .right_4()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_2()
+            .bg(rgba(theme::current().backdrop))
+            .rounded_md()
+            .text_xs()
+            .text_color(rgb(theme::current().text))
+            .child(trf(
+                "Streak {} (best {})",
+                &[
+                    &self.statistics.current_streak,
+                    &self.statistics.best_streak,
+                ],
+            ))
+            .child(trf("Today {} played, {} won", &[&played_today, &won_today]))
+            .child(pace)
+    }
+
+    /// Text mirror of the board, rebuilt from the game state on every render
+    fn render_text_board(&self) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_2()
+            .bg(rgb(theme::current().panel))
+            .rounded_md()
+            .text_sm()
+            .text_color(rgb(theme::current().text))
+            .children(
+                mirror::board_lines(&self.game_state)
+                    .into_iter()
+                    .map(|line| div().child(line)),
+            )
+    }
+
+    fn render_game_board_with_drag_drop(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settled = self.show_board();
+        let board = self.render_board_piles(cx);
+        if let Some(settled) = settled {
+            self.game_state = settled;
         }
+        board
     }
 
-    fn handle_action(&mut self, action: GameAction, cx: &mut Context<Self>) {
-        match self.game_state.handle_action(action) {
-            Ok(()) => {
-                // Action succeeded, trigger a re-render
-                cx.notify();
-            }
-            Err(error) => {
-                // For now, just print the error. In a real app, we might show a message to the user
-                println!("Action failed: {}", error);
-            }
+    /// Swap the board as it is shown in for the game state, returning the
+    /// settled state to put back once drawn. Cards in flight are drawn by
+    /// the animation layers instead, so the piles are drawn without them
+    /// until they land.
+    fn show_board(&mut self) -> Option<GameState> {
+        let shown = match (&self.celebration, &self.animation) {
+            (Some((cascade, _)), _) => Some(cascade.board(&self.game_state, &self.clock)),
+            (None, Some(animation)) => Some(animation.landing_board(&self.game_state)),
+            (None, None) => None,
+        };
+        shown.map(|shown| std::mem::replace(&mut self.game_state, shown))
+    }
+
+    /// Record where `pile` is drawn each frame, for cards flying to or from it
+    fn track_pile(&self, pile: Pile) -> impl IntoElement {
+        let pile_origins = self.pile_origins.clone();
+        canvas(
+            move |bounds, _window, _cx| {
+                pile_origins.borrow_mut().insert(pile, bounds.origin);
+            },
+            |_bounds, _state, _window, _cx| {},
+        )
+        .absolute()
+        .size_full()
+    }
+
+    /// Cards in flight, drawn over the board between the piles they left
+    /// and the ones they are landing on
+    fn render_flights(&self) -> Option<impl IntoElement> {
+        let animation = self.animation.as_ref()?;
+        let progress = animation.progress(&self.clock);
+        // Cards still waiting to set off stay stacked as their pile was, so
+        // they are drawn top first beneath the ones on their way
+        let (waiting, moving): (Vec<_>, Vec<_>) = animation
+            .flights
+            .iter()
+            .partition(|flight| flight.travelled(progress) == 0.0);
+
+        let mut layer = div().absolute().top_0().left_0().size_full();
+        for flight in waiting.into_iter().rev().chain(moving) {
+            let (Some(from), Some(to)) =
+                (self.card_origin(flight.from), self.card_origin(flight.to))
+            else {
+                continue;
+            };
+            let travelled = flight.travelled(progress);
+            let along = animation.position(flight, &self.clock);
+            // A card turning over narrows to its edge and widens again
+            let inset = layout::card_width() * (1.0 - flight.width(travelled)) / 2.0;
+            layer = layer.child(
+                div()
+                    .absolute()
+                    .left(from.x + (to.x - from.x) * along + px(inset))
+                    .top(from.y + (to.y - from.y) * along)
+                    .w(px(layout::card_width() - 2.0 * inset))
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .ml(px(-inset))
+                            .child(ui::render_card(flight.face(travelled))),
+                    ),
+            );
         }
+        Some(layer)
     }
 
-    fn handle_drop(
-        &mut self,
-        drag_info: &DragInfo,
-        drop_position: Position,
+    /// The win cascade over the whole window; a click skips it
+    fn render_cascade(
+        &self,
+        window_height: Pixels,
         cx: &mut Context<Self>,
-    ) {
-        if drag_info.valid_drop_targets.contains(&drop_position) {
-            // Perform the move
-            let move_action = GameAction::MoveCard {
-                from: drag_info.source_position,
-                to: drop_position,
+    ) -> Option<impl IntoElement> {
+        let (cascade, _) = self.celebration.as_ref()?;
+        let pile_origins = self.pile_origins.borrow();
+        let floor = |pile: Pile| {
+            pile_origins.get(&pile).map_or(0.0, |origin| {
+                f32::from(window_height - origin.y - px(layout::card_height()))
+            })
+        };
+        let sprites = cascade.sprites(&self.clock, floor, 4, Duration::from_millis(40));
+
+        let mut layer = div()
+            .id("win_cascade")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .on_click(cx.listener(|app, _event, _window, cx| {
+                app.finish_celebration(cx);
+            }));
+        for sprite in sprites {
+            let Some(origin) = pile_origins.get(&sprite.pile) else {
+                continue;
             };
-            self.handle_action(move_action, cx);
+            layer = layer.child(
+                div()
+                    .absolute()
+                    .left(origin.x + px(sprite.offset.0))
+                    .top(origin.y + px(sprite.offset.1))
+                    .child(ui::render_card(sprite.card)),
+            );
         }
+        Some(layer)
+    }
 
-        // Clear drag state
-        self.current_drag = None;
-        cx.notify();
+    /// The hint's move: a pulsing glow on the card to move, a highlight on
+    /// where it goes, and an arrow between them with a marker running along
+    fn render_hint_highlight(&self) -> Option<impl IntoElement> {
+        let hint = self.hint_highlight.as_ref()?;
+        let from = self.card_origin(hint.from)?;
+        let to = self.card_origin(hint.to)?;
+        // With motion reduced the highlight holds still, at full strength
+        let still = self.reduce_motion();
+        let glow = if still { 1.0 } else { hint.glow(&self.clock) };
+        let travel = animation::ease(self.settings.easing, hint.travel(&self.clock));
+        let alpha = |strength: f32| (strength * 255.0) as u32;
+
+        let outline = |origin: Point<Pixels>, color: u32, strength: f32| {
+            div()
+                .absolute()
+                .left(origin.x - px(4.0))
+                .top(origin.y - px(4.0))
+                .w(px(layout::card_width() + 8.0))
+                .h(px(layout::card_height() + 8.0))
+                .rounded_lg()
+                .border_4()
+                .border_color(rgba(color << 8 | alpha(strength)))
+                .bg(rgba(color << 8 | alpha(strength * 0.25)))
+        };
+        let centre = |origin: Point<Pixels>| {
+            Point::new(
+                origin.x + px(layout::card_width() / 2.0),
+                origin.y + px(layout::card_height() / 2.0),
+            )
+        };
+        let (start, end) = (centre(from), centre(to));
+        let arrow_color: Hsla = rgba(0xFACC15 << 8 | alpha(0.6 + 0.4 * glow)).into();
+        let arrow = canvas(
+            |_bounds, _window, _cx| {},
+            move |_bounds, _state, window, _cx| {
+                paint_arrow(window, start, end, arrow_color);
+            },
+        )
+        .absolute()
+        .size_full();
+        let marker_size = 12.0;
+        let marker = div()
+            .absolute()
+            .left(start.x + (end.x - start.x) * travel - px(marker_size / 2.0))
+            .top(start.y + (end.y - start.y) * travel - px(marker_size / 2.0))
+            .w(px(marker_size))
+            .h(px(marker_size))
+            .rounded_full()
+            .bg(rgb(0xFACC15));
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .child(outline(from, 0xFACC15, 0.4 + 0.6 * glow))
+                .child(outline(to, 0x22C55E, 0.4 + 0.6 * (1.0 - glow)))
+                .child(arrow)
+                .when(!still, |overlay| overlay.child(marker)),
+        )
     }
 
-    fn get_draggable_cards(&self, position: Position) -> Vec<Card> {
-        // Use the game state's logic to get draggable cards
-        self.game_state
-            .get_cards_at_position(position)
-            .unwrap_or_else(|_| Vec::new())
+    /// Outline around the card under the board cursor, and around the cards
+    /// picked up with Enter
+    fn render_board_cursor(&self) -> Option<impl IntoElement> {
+        let cursor = self.board_cursor?;
+        let chrome = theme::current();
+        let outline = |position: Position, color: u32| {
+            self.card_origin(position).map(|origin| {
+                div()
+                    .absolute()
+                    .left(origin.x - px(3.0))
+                    .top(origin.y - px(3.0))
+                    .w(px(layout::card_width() + 6.0))
+                    .h(px(layout::card_height() + 6.0))
+                    .rounded_lg()
+                    .border_2()
+                    .border_color(rgb(color))
+            })
+        };
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .children(self.held.and_then(|held| outline(held, chrome.highlight)))
+                .children(outline(cursor.position, chrome.accent)),
+        )
     }
 
-    fn get_valid_drop_targets(&self, cards: &[Card], source: Position) -> Vec<Position> {
-        if cards.is_empty() {
-            return Vec::new();
+    /// Bar filling over the pile the pointer rests on in dwell mode,
+    /// full when it clicks
+    fn render_dwell_progress(&self) -> Option<impl IntoElement> {
+        if self.settings.input_mode != InputMode::Dwell {
+            return None;
         }
+        let (position, progress) = self.dwell.progress(self.settings.dwell_time, &self.clock)?;
+        let origin = self.card_origin(position)?;
+        Some(
+            div()
+                .absolute()
+                .left(origin.x)
+                .top(origin.y - px(8.0))
+                .w(px(layout::card_width() * progress))
+                .h(px(4.0))
+                .rounded_full()
+                .bg(rgb(theme::current().highlight)),
+        )
+    }
 
-        let first_card = cards[0]; // The card that will be placed on the destination
-        let mut targets = Vec::new();
+    /// Score, time, moves and the stock in labelled slots, with `details`
+    /// (the deal, the solver's verdict, hints and so on) after them
+    fn render_status_bar(&self, details: String) -> impl IntoElement {
+        let chrome = theme::current();
+        let game_state = &self.game_state;
+        let item = |label: &'static str, value: String, tip: &'static str| {
+            div()
+                .id(label)
+                .tooltip(tooltip::text(tr(tip)))
+                .flex()
+                .gap_1()
+                .child(div().text_color(rgb(chrome.muted_text)).child(tr(label)))
+                .child(div().font_weight(FontWeight::BOLD).child(value))
+        };
+        let last_pass = game_state.is_last_pass();
 
-        // Check tableau columns
-        for col in 0..7 {
-            let tableau_pos = Position::Tableau(col, self.game_state.tableau[col].len());
-            if self.can_drop_on_tableau(first_card, col)
-                && !self.is_same_position(source, Position::Tableau(col, 0))
-            {
-                targets.push(tableau_pos);
-            }
+        let mut items = Vec::new();
+        match game_state.scoring {
+            Scoring::Standard => items.push(item(
+                "Score",
+                game_state.score.to_string(),
+                "Points for cards played, less the penalties",
+            )),
+            Scoring::Vegas => items.push(item(
+                "Vegas",
+                format!("${}", game_state.score),
+                "Dollars won back on cards played, less the stake",
+            )),
+            Scoring::None => {}
         }
-
-        // Check foundation piles (only for single cards)
-        if cards.len() == 1 {
-            for foundation in 0..4 {
-                let foundation_pos = Position::Foundation(foundation);
-                if self.can_drop_on_foundation(first_card, foundation) {
-                    targets.push(foundation_pos);
-                }
-            }
+        if !game_state.accommodations.untimed {
+            items.push(item(
+                "Time",
+                ui::format_duration(game_state.elapsed()),
+                "Time since the cards were dealt",
+            ));
+        }
+        items.extend([
+            item(
+                "Moves",
+                game_state.move_count.to_string(),
+                "Moves made this game, deals included",
+            ),
+            item(
+                "Stock",
+                game_state.stock.len().to_string(),
+                "Cards left to deal",
+            ),
+            item(
+                "Waste",
+                game_state.waste.len().to_string(),
+                "Cards dealt and not yet played",
+            ),
+        ]);
+        // Games that deal the stock in rows never turn it over
+        if game_state.rules.stock_deal() != StockDeal::RowToTableau {
+            let pass = match game_state.stock_passes() {
+                Some(passes) => trf("{} of {}", &[&game_state.stock_pass(), &passes]),
+                None => game_state.stock_pass().to_string(),
+            };
+            items.push(
+                item(
+                    if last_pass { "Last pass" } else { "Pass" },
+                    pass,
+                    "Times through the stock, counting this one",
+                )
+                .when(last_pass, |cell| cell.text_color(rgb(chrome.highlight))),
+            );
+        }
+        if self.settings.muted {
+            items.push(
+                div()
+                    .id("status_muted")
+                    .tooltip(tooltip::text(
+                        self.with_shortcut("All sound is off", Command::Mute),
+                    ))
+                    .text_color(rgb(chrome.highlight))
+                    .child(format!("🔇 {}", tr("Muted"))),
+            );
+        }
+        // Only the variants the solver plays have their moves listed
+        if self.settings.move_counter && game_state.rules.is_solvable() {
+            let plays = solver::count_plays(game_state);
+            items.push(
+                item(
+                    "Plays",
+                    plays.to_string(),
+                    "Cards that can be played now, not counting the stock",
+                )
+                .when(plays == 0, |cell| cell.text_color(rgb(chrome.highlight))),
+            );
         }
 
-        targets
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .bg(rgb(chrome.panel))
+            .rounded_md()
+            .text_sm()
+            .text_color(rgb(chrome.text))
+            // A replay's board is not the player's game
+            .when(self.replay_viewer.is_none(), |bar| {
+                bar.child(
+                    ui::row()
+                        .flex_wrap()
+                        .justify_center()
+                        .gap_4()
+                        .children(items),
+                )
+            })
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(chrome.muted_text))
+                    .text_center()
+                    .child(locale::directed(&details)),
+            )
     }
 
-    fn can_drop_on_tableau(&self, card: Card, col: usize) -> bool {
-        if col >= 7 {
-            return false;
-        }
-
-        let pile = &self.game_state.tableau[col];
-        if pile.is_empty() {
-            // Can only place King on empty tableau
-            return card.rank == game::deck::Rank::King;
-        }
+    /// Toasts stacked at the bottom centre of the window, newest last
+    fn render_toasts(&self) -> impl IntoElement {
+        let chrome = theme::current();
+        div()
+            .absolute()
+            .bottom(px(48.0))
+            .left_0()
+            .right_0()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_2()
+            .children(self.toasts.iter().map(|toast| {
+                let border = match toast.kind {
+                    ToastKind::Info => chrome.accent,
+                    ToastKind::Error => chrome.error,
+                };
+                div()
+                    .px_4()
+                    .py_2()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(rgb(border))
+                    .bg(rgb(chrome.panel))
+                    .text_sm()
+                    .text_color(rgb(chrome.text))
+                    .opacity(toast.opacity(&self.clock))
+                    .child(toast.message.clone())
+            }))
+    }
 
-        let top_card = pile.last().unwrap();
-        card.can_place_on_tableau(top_card)
+    /// Window position of the card at `position`, from where its pile was
+    /// last drawn
+    fn card_origin(&self, position: Position) -> Option<Point<Pixels>> {
+        let origin = *self.pile_origins.borrow().get(&Pile::of(position))?;
+        let offset = match position {
+            Position::Tableau(_, index) => layout::tableau_offset() * index as f32,
+            _ => 0.0,
+        };
+        Some(Point::new(origin.x, origin.y + px(offset)))
     }
 
-    fn can_drop_on_foundation(&self, card: Card, foundation: usize) -> bool {
-        if foundation >= 4 {
-            return false;
+    /// What `pile` shows on the board being drawn
+    fn pile_look(&self, pile: Pile) -> PileLook {
+        let layout = layout::current();
+        let (drop_target, layout) = match pile {
+            Pile::Tableau(col) => {
+                let len = self.game_state.cards(pile).len();
+                (self.is_drop_target(Position::Tableau(col, len)), layout)
+            }
+            Pile::Foundation(foundation) => (
+                self.is_drop_target(Position::Foundation(foundation)),
+                layout.top_row(),
+            ),
+            Pile::Waste => {
+                let len = self.game_state.waste.len();
+                let top = Position::Waste(len.saturating_sub(1));
+                (len > 0 && self.is_drop_target(top), layout.top_row())
+            }
+            Pile::Stock => (false, layout.top_row()),
+        };
+        let label = match pile {
+            Pile::Stock => self.game_state.stock_label(),
+            Pile::Foundation(_) => self.game_state.foundation_label(),
+            _ => String::new(),
+        };
+        PileLook {
+            cards: self.game_state.cards(pile).to_vec(),
+            drop_target,
+            peek: self.peek.filter(|peek| Pile::of(*peek) == pile),
+            label,
+            last_pass: pile == Pile::Stock && self.game_state.is_last_pass(),
+            fan: match pile {
+                Pile::Waste => self.game_state.waste_fan().len(),
+                _ => 0,
+            },
+            rules: self.game_state.rules,
+            accommodations: self.game_state.accommodations,
+            theme_file: self.theme_file.clone(),
+            theme: theme::current(),
+            layout,
         }
+    }
 
-        let pile = &self.game_state.foundations[foundation];
-        let top_card = pile.last();
-        card.can_place_on_foundation(top_card)
+    /// `pile` as its own view, drawn again only when what it shows has
+    /// changed since it was last drawn
+    fn pile_element(&mut self, pile: Pile, cx: &mut Context<Self>) -> AnyElement {
+        let look = self.pile_look(pile);
+        let app = cx.weak_entity();
+        let settings = self.settings_model.clone();
+        let view = self
+            .pile_views
+            .entry(pile)
+            .or_insert_with(|| cx.new(|cx| PileView::new(pile, app, &settings, cx)))
+            .clone();
+        let changed = view.update(cx, |view, _cx| view.show(look));
+        let view = AnyView::from(view);
+        if changed {
+            return view.into_any_element();
+        }
+        // Laid out as a flex box like the pile inside it, so columns still
+        // stretch to the tallest one
+        let mut style = StyleRefinement::default();
+        style.display = Some(Display::Flex);
+        view.cached(style).into_any_element()
     }
 
-    fn is_same_position(&self, pos1: Position, pos2: Position) -> bool {
-        match (pos1, pos2) {
-            (Position::Tableau(col1, _), Position::Tableau(col2, _)) => col1 == col2,
-            _ => false,
+    /// Draw `pile` for its view, from the board as it is shown
+    pub(crate) fn render_pile(&mut self, pile: Pile, cx: &mut Context<Self>) -> AnyElement {
+        let settled = self.show_board();
+        let layout = layout::current();
+        // The stock, waste and foundations are drawn at the top row's size
+        if !matches!(pile, Pile::Tableau(_)) {
+            layout::set_current(layout.top_row());
         }
+        let element = match pile {
+            Pile::Tableau(col) => self.render_tableau_with_drag(col, cx).into_any_element(),
+            Pile::Foundation(foundation) => self
+                .render_foundation_with_drop(foundation, cx)
+                .into_any_element(),
+            Pile::Stock => div()
+                .id("stock")
+                .tooltip(tooltip::text(self.game_state.stock_label()))
+                .child(self.render_clickable_stock_pile(cx))
+                .into_any_element(),
+            Pile::Waste => self.render_waste_pile_with_drag(cx),
+        };
+        layout::set_current(layout);
+        if let Some(settled) = settled {
+            self.game_state = settled;
+        }
+        element
     }
 
-    fn render_game_board_with_drag_drop(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
-        let drag_info_text = "Drag and drop cards to move them! Foundation piles and tableau columns are drop targets.".to_string();
+    fn render_board_piles(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let drag_info_text = tr(
+            "Drag and drop cards to move them! Foundation piles and tableau columns are drop targets.",
+        );
+        let layout = layout::current();
+        let foundations: Vec<AnyElement> = (0..self.game_state.foundations.len())
+            .map(|foundation| self.pile_element(Pile::Foundation(foundation), cx))
+            .collect();
+        let stock_and_waste = div()
+            .flex()
+            .gap(px(layout.pile_gap))
+            .on_scroll_wheel(cx.listener(|app, event, _window, cx| {
+                app.scroll_stock(event, cx);
+            }))
+            .child(self.pile_element(Pile::Stock, cx))
+            .child(self.pile_element(Pile::Waste, cx));
+        let board_layout = registry::variants().layout(self.game_state.rules);
+        let columns: Vec<AnyElement> = if let BoardLayout::Pyramid { rows } = board_layout {
+            vec![self.render_pyramid(rows, cx).into_any_element()]
+        } else {
+            (0..self.game_state.tableau.len())
+                .map(|col| self.pile_element(Pile::Tableau(col), cx))
+                .collect()
+        };
+        // Columns fanned longer than the window scroll on their own, leaving
+        // the top row in view
+        let tableau_row = div()
+            .id("tableau_row")
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .child(
+                div()
+                    .flex()
+                    .justify_center()
+                    .gap(px(layout.pile_gap))
+                    .children(columns),
+            );
 
-        div()
+        let board = div()
             .flex()
             .flex_col()
             .size_full()
-            .gap_4()
-            .child(
-                // Drag state info
-                div()
-                    .text_sm()
-                    .text_color(white())
-                    .text_center()
-                    .child(drag_info_text),
-            )
+            .gap(px(layout.row_gap))
+            .when(!layout.compact, |board| {
+                board.child(
+                    // Drag state info
+                    div()
+                        .text_sm()
+                        .text_color(white())
+                        .text_center()
+                        .child(drag_info_text),
+                )
+            });
+
+        // Without foundations to show, the compact board is the columns with
+        // the stock and waste centred below them
+        if board_layout == BoardLayout::StockBelow {
+            return board
+                .child(tableau_row)
+                .child(div().flex().justify_center().child(stock_and_waste))
+                .into_any_element();
+        }
+
+        board
             .child(
                 // Top row: Stock, Waste, and Foundations
                 div()
                     .flex()
                     .justify_between()
                     .items_start()
+                    // Left side: Stock and Waste
+                    .child(stock_and_waste)
                     .child(
-                        // Left side: Stock and Waste
-                        div()
-                            .flex()
-                            .gap_2()
-                            .child(self.render_clickable_stock_pile(cx))
-                            .child(self.render_waste_pile_with_drag(cx)),
-                    )
-                    .child(
-                        // Right side: Four foundation piles with drop zones
-                        div()
-                            .flex()
-                            .gap_2()
-                            .child(self.render_foundation_with_drop(0, cx))
-                            .child(self.render_foundation_with_drop(1, cx))
-                            .child(self.render_foundation_with_drop(2, cx))
-                            .child(self.render_foundation_with_drop(3, cx)),
+                        // Right side: Foundation piles with drop zones
+                        div().flex().gap(px(layout.pile_gap)).children(foundations),
                     ),
             )
-            .child(
-                // Bottom row: Seven tableau columns with simple drag functionality
-                div()
-                    .flex()
-                    .justify_center()
-                    .gap_2()
-                    .child(self.render_tableau_with_drag(0, cx))
-                    .child(self.render_tableau_with_drag(1, cx))
-                    .child(self.render_tableau_with_drag(2, cx))
-                    .child(self.render_tableau_with_drag(3, cx))
-                    .child(self.render_tableau_with_drag(4, cx))
-                    .child(self.render_tableau_with_drag(5, cx))
-                    .child(self.render_tableau_with_drag(6, cx)),
-            )
+            // Bottom row: Tableau columns with simple drag functionality
+            .child(tableau_row)
+            .when(self.game_state.rules.open_stock(), |board| {
+                board.child(self.render_open_stock())
+            })
+            .into_any_element()
     }
 
     fn render_tableau_with_drag(&mut self, col: usize, cx: &mut Context<Self>) -> impl IntoElement {
         let cards = &self.game_state.tableau[col];
-        // Don't highlight as we'll let the drop handler do validation
-        let is_valid_drop_target = false;
+        let is_valid_drop_target = self.is_drop_target(Position::Tableau(col, cards.len()));
+
+        let large_drop_targets = self.game_state.accommodations.large_drop_targets;
 
         let mut column = div()
             .flex()
             .flex_col()
-            .w(px(ui::CARD_WIDTH))
-            .min_h(px(ui::CARD_HEIGHT));
+            .relative()
+            .w(px(layout::card_width()))
+            .min_h(px(layout::card_height()))
+            .child(self.track_pile(Pile::Tableau(col)));
 
-        // Add drop zone styling if this is a valid drop target
-        if is_valid_drop_target {
-            column = column
-                .bg(rgb(0x22C55E)) // Green highlight for valid drop
-                .border_4()
-                .border_color(rgb(0x16A34A)) // Darker green border
-                .rounded_lg(); // More prominent rounded corners
+        // With large drop targets the whole column (stretched to the tallest one)
+        // accepts drops, replacing the per-card drop handlers below
+        if large_drop_targets {
+            let drop_position = Position::Tableau(col, cards.len());
+            column = column.on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
+                app.handle_drop(drag_info, drop_position, cx);
+            }));
         }
 
         if cards.is_empty() {
@@ -240,10 +2568,14 @@ impl SolitaireApp {
             let empty_placeholder = div()
                 .id(ElementId::Name(format!("tableau_{}", col).into()))
                 .child(ui::render_empty_pile(""))
-                .on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
-                    println!("ON_DROP HANDLER CALLED: empty tableau column {}", col);
-                    app.handle_drop(drag_info, drop_position, cx);
-                }));
+                .when(!large_drop_targets, |placeholder| {
+                    placeholder.on_drop(cx.listener(
+                        move |app, drag_info: &DragInfo, _window, cx| {
+                            println!("ON_DROP HANDLER CALLED: empty tableau column {}", col);
+                            app.handle_drop(drag_info, drop_position, cx);
+                        },
+                    ))
+                });
             column = column.child(empty_placeholder);
         } else {
             // Render stacked cards with drag functionality
@@ -255,8 +2587,10 @@ impl SolitaireApp {
                 let mut card_element = if is_draggable {
                     // Face-up card that can be dragged (either single or as part of sequence)
                     let card_id = card.id();
-                    div()
-                        .id(ElementId::Name(format!("card_{}", card_id).into())) // TODO: ugh another format ?
+                    let card_element = div().id(ElementId::Name(
+                        format!("card_{}_{}_{}", col, i, card_id).into(),
+                    )); // TODO: ugh another format ?
+                    self.click_to_move(card_element, position, cx)
                         .relative() // Ensure proper positioning
                         .child(ui::render_card(*card))
                         .cursor_pointer()
@@ -272,25 +2606,33 @@ impl SolitaireApp {
                                     valid_drop_targets,
                                 }
                             },
-                            move |drag_info: &DragInfo, _cursor_position, _window, cx| {
-                                println!(
-                                    "Drag started: from {:?}, {} valid targets: {:?}",
-                                    drag_info.source_position,
-                                    drag_info.valid_drop_targets.len(),
-                                    drag_info.valid_drop_targets
-                                );
-                                cx.new(|_| drag_info.clone())
-                            },
+                            self.drag_preview(cx),
                         )
                 } else {
                     // Other cards - just render normally wrapped in div for type compatibility
                     div()
-                        .id(ElementId::Name(format!("static_card_{}", card.id()).into())) // TODO: ugh another format ?
+                        .id(ElementId::Name(
+                            format!("static_card_{}_{}_{}", col, i, card.id()).into(),
+                        )) // TODO: ugh another format ?
                         .child(ui::render_card(*card))
                 };
 
+                // Hovering a buried face-up card opens the fan under it
+                if card.face_up && !is_top_card {
+                    card_element = card_element.on_hover(cx.listener(
+                        move |app, hovered: &bool, _window, cx| {
+                            if *hovered {
+                                app.peek = Some(position);
+                            } else if app.peek == Some(position) {
+                                app.peek = None;
+                            }
+                            cx.notify();
+                        },
+                    ));
+                }
+
                 // Add drop functionality to the top card area
-                if is_top_card {
+                if is_top_card && !large_drop_targets {
                     let drop_position = Position::Tableau(col, cards.len());
                     card_element = card_element.on_drop(cx.listener(
                         move |app, drag_info: &DragInfo, _window, cx| {
@@ -309,14 +2651,19 @@ impl SolitaireApp {
                 } else {
                     // Subsequent cards - add negative margin to create stacking effect
                     // For the top card, ensure it's positioned to receive mouse events
+                    let offset = if self.peek == Some(Position::Tableau(col, i - 1)) {
+                        layout::tableau_offset().max(layout::card_height() * PEEK_REVEAL)
+                    } else {
+                        layout::tableau_offset()
+                    };
                     let card_container = if is_top_card {
                         div()
-                            .mt(px(-ui::CARD_HEIGHT + ui::TABLEAU_CARD_OFFSET))
+                            .mt(px(-layout::card_height() + offset))
                             .relative() // Ensure proper positioning for mouse events
                             .child(card_element)
                     } else {
                         div()
-                            .mt(px(-ui::CARD_HEIGHT + ui::TABLEAU_CARD_OFFSET))
+                            .mt(px(-layout::card_height() + offset))
                             .child(card_element)
                     };
                     column = column.child(card_container);
@@ -324,15 +2671,92 @@ impl SolitaireApp {
             }
         }
 
+        // Counted over the column's base, where the face-down cards lie
+        let face_down = cards.iter().filter(|card| !card.face_up).count();
         column
+            .when(self.settings.pile_counts && face_down > 0, |column| {
+                column.child(render_count_badge(face_down))
+            })
+            .when(is_valid_drop_target, |column| {
+                column.child(render_drop_highlight())
+            })
+    }
+
+    /// The Pyramid board: one row per pyramid row, each half covering the
+    /// one above. Uncovered cards are dragged onto their partner to pair
+    /// them off, or onto the foundation for a King.
+    fn render_pyramid(&mut self, rows: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut pyramid = div().flex().flex_col().items_center();
+        for row in 0..rows {
+            let mut cards = div().flex().gap_2();
+            for place in 0..=row {
+                let col = pyramid::slot(row, place);
+                let Some(card) = self.game_state.tableau[col].last().copied() else {
+                    // Keep the gap so the rows stay lined up
+                    cards = cards.child(
+                        div()
+                            .relative()
+                            .w(px(layout::card_width()))
+                            .h(px(layout::card_height()))
+                            .child(self.track_pile(Pile::Tableau(col))),
+                    );
+                    continue;
+                };
+                let position = Position::Tableau(col, 0);
+                let is_valid_drop_target =
+                    self.is_drop_target(Position::Tableau(col, self.game_state.tableau[col].len()));
+                let card_element = div()
+                    .id(ElementId::Name(format!("pyramid_{}", col).into()))
+                    .relative()
+                    .child(self.track_pile(Pile::Tableau(col)))
+                    .child(ui::render_card(card))
+                    .when(is_valid_drop_target, |element| {
+                        element.child(render_drop_highlight())
+                    });
+                if self
+                    .game_state
+                    .rules
+                    .is_covered(&self.game_state.tableau, col)
+                {
+                    cards = cards.child(card_element);
+                    continue;
+                }
+
+                let dragged_cards = self.get_draggable_cards(position);
+                let valid_drop_targets = self.get_valid_drop_targets(&dragged_cards, position);
+                let drop_position = Position::Tableau(col, 1);
+                cards = cards.child(
+                    self.click_to_move(card_element, position, cx)
+                        .cursor_pointer()
+                        .hover(|style| style.shadow_xl().border_color(rgb(0x3B82F6)))
+                        .on_drag(
+                            DragInfo {
+                                source_position: position,
+                                dragged_cards,
+                                valid_drop_targets,
+                            },
+                            self.drag_preview(cx),
+                        )
+                        .on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
+                            app.handle_drop(drag_info, drop_position, cx);
+                        })),
+                );
+            }
+            pyramid = pyramid.child(if row == 0 {
+                cards
+            } else {
+                cards.mt(px(-layout::card_height() / 2.0))
+            });
+        }
+        pyramid
     }
 
     fn render_clickable_stock_pile(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
         if self.game_state.stock.is_empty() {
             // Empty stock pile - clickable to recycle waste
             div()
-                .w(px(ui::CARD_WIDTH))
-                .h(px(ui::CARD_HEIGHT))
+                .w(px(layout::card_width()))
+                .h(px(layout::card_height()))
                 .bg(rgb(0x1F2937))
                 .border_2()
                 .border_color(rgb(0x4B5563))
@@ -341,6 +2765,8 @@ impl SolitaireApp {
                 .flex()
                 .items_center()
                 .justify_center()
+                .relative()
+                .child(self.track_pile(Pile::Stock))
                 .cursor_pointer()
                 .hover(|style| style.border_color(rgb(0x3B82F6)))
                 .on_mouse_down(
@@ -353,20 +2779,34 @@ impl SolitaireApp {
                 .child(
                     div()
                         .text_color(rgb(0x9CA3AF))
-                        .text_size(px(12.0))
+                        .text_size(layout::text_size(12.0))
                         .font_weight(FontWeight::MEDIUM)
-                        .child("Stock"),
+                        .child(tr("Stock")),
                 )
         } else {
-            // Stock pile with cards - show face-down card
+            // Stock pile with cards - show face-down card, or the next card
+            // when the stock is open
+            let top = match self.game_state.stock.last() {
+                Some(card) if self.game_state.rules.open_stock() => {
+                    ui::render_card(*card).into_any_element()
+                }
+                _ => ui::render_card_back(),
+            };
             div()
-                .w(px(ui::CARD_WIDTH))
-                .h(px(ui::CARD_HEIGHT))
+                .w(px(layout::card_width()))
+                .h(px(layout::card_height()))
                 .bg(white())
                 .border_2()
+                .when(card_art::style().high_contrast, |stock| stock.border_4())
                 .border_color(rgb(0x000000))
+                // The stock won't come round again once these are dealt
+                .when(self.game_state.is_last_pass(), |stock| {
+                    stock.border_color(rgb(theme::current().highlight))
+                })
                 .rounded_md()
                 .shadow_lg()
+                .relative()
+                .child(self.track_pile(Pile::Stock))
                 .cursor_pointer()
                 .hover(|style| style.shadow_xl().border_color(rgb(0x3B82F6)))
                 .on_mouse_down(
@@ -376,32 +2816,68 @@ impl SolitaireApp {
                         app.handle_action(GameAction::DealFromStock, cx);
                     }),
                 )
-                .child(
-                    div()
-                        .size_full()
-                        .bg(rgb(0x1E3A8A))
-                        .flex()
-                        .items_center()
-                        .justify_center()
-                        .child(div().text_color(white()).text_size(px(24.0)).child("🂠")),
-                )
+                .child(top)
+                .when(self.settings.pile_counts, |stock| {
+                    stock.child(render_count_badge(self.game_state.stock.len()))
+                })
         }
     }
 
-    fn render_waste_pile_with_drag(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+    /// The cards still in an open stock, next to be dealt first
+    fn render_open_stock(&self) -> impl IntoElement {
+        let cards = self.game_state.stock.iter().rev().map(|card| {
+            div()
+                .w(px(32.0))
+                .py_1()
+                .bg(white())
+                .rounded_md()
+                .text_center()
+                .text_size(layout::text_size(12.0))
+                .text_color(rgb(card_art::suit_color(card.suit)))
+                .child(card.to_string())
+        });
+        div()
+            .flex()
+            .justify_center()
+            .items_center()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(white())
+                    .mr_2()
+                    .child(tr("Stock, next first:")),
+            )
+            .children(cards)
+    }
+
+    fn render_waste_pile_with_drag(&mut self, cx: &mut Context<Self>) -> AnyElement {
         if self.game_state.waste.is_empty() {
             div()
                 .id(ElementId::Name("empty_waste".into()))
-                .child(ui::render_empty_pile("Waste"))
+                .relative()
+                .child(self.track_pile(Pile::Waste))
+                .child(ui::render_empty_pile(tr("Waste")))
+                .into_any_element()
         } else {
             let top_card = *self.game_state.waste.last().unwrap();
             let position = Position::Waste(self.game_state.waste.len() - 1);
             let card_id = top_card.id();
 
-            // Make the waste pile card draggable
-            div()
-                .id(ElementId::Name(format!("waste_card_{}", card_id).into()))
+            // Make the waste pile card draggable, and a drop target for
+            // variants that pair cards off
+            let waste = div().id(ElementId::Name(format!("waste_card_{}", card_id).into()));
+            let top = self
+                .click_to_move(waste, position, cx)
+                .on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
+                    app.handle_drop(drag_info, position, cx);
+                }))
+                .relative()
+                .child(self.track_pile(Pile::Waste))
                 .child(ui::render_card(top_card))
+                .when(self.is_drop_target(position), |waste| {
+                    waste.child(render_drop_highlight())
+                })
                 .cursor_pointer()
                 .hover(|style| style.shadow_xl().border_color(rgb(0x3B82F6)))
                 .on_drag(
@@ -415,16 +2891,22 @@ impl SolitaireApp {
                             valid_drop_targets,
                         }
                     },
-                    move |drag_info: &DragInfo, _cursor_position, _window, cx| {
-                        println!(
-                            "Drag started: from {:?}, {} valid targets: {:?}",
-                            drag_info.source_position,
-                            drag_info.valid_drop_targets.len(),
-                            drag_info.valid_drop_targets
-                        );
-                        cx.new(|_| drag_info.clone())
-                    },
+                    self.drag_preview(cx),
+                );
+
+            // Dealing three at a time, the two cards under the top one show
+            // beside it, to be read but not played
+            let (under, _) = self.game_state.waste_fan().split_last().unwrap();
+            let overlap = -layout::card_width() * (1.0 - WASTE_FAN);
+            div()
+                .flex()
+                .children(
+                    under
+                        .iter()
+                        .map(|card| div().mr(px(overlap)).child(ui::render_card(*card))),
                 )
+                .child(top)
+                .into_any_element()
         }
     }
 
@@ -434,24 +2916,17 @@ impl SolitaireApp {
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
         let cards = &self.game_state.foundations[foundation];
-        // Don't highlight as we'll let the drop handler do validation
-        let is_valid_drop_target = false;
-
         let position = Position::Foundation(foundation);
+        let is_valid_drop_target = self.is_drop_target(position);
 
         if cards.is_empty() {
-            // Empty foundation - show drop zone
-            let suit_labels = ["♥", "♦", "♣", "♠"];
-            let suit_colors = [
-                rgb(0xDC2626), // Hearts - red
-                rgb(0xDC2626), // Diamonds - red
-                rgb(0x000000), // Clubs - black
-                rgb(0x000000), // Spades - black
-            ];
-
-            let mut empty_foundation = div()
-                .w(px(ui::CARD_WIDTH))
-                .h(px(ui::CARD_HEIGHT))
+            // Empty foundation - show drop zone. Two-deck games have eight,
+            // so the suits repeat.
+            let suit = Suit::all()[foundation % 4];
+
+            let empty_foundation = div()
+                .w(px(layout::card_width()))
+                .h(px(layout::card_height()))
                 .bg(rgb(0x1F2937)) // Dark gray background
                 .border_2()
                 .border_color(rgb(0x4B5563)) // Lighter gray border
@@ -462,21 +2937,20 @@ impl SolitaireApp {
                 .justify_center()
                 .child(
                     div()
-                        .text_color(suit_colors[foundation])
-                        .text_size(px(32.0))
-                        .child(suit_labels[foundation]),
+                        .text_color(rgb(card_art::suit_color(suit)))
+                        .text_size(layout::card_text_size(32.0))
+                        .child(card_art::suit_symbol(suit)),
                 );
 
-            if is_valid_drop_target {
-                empty_foundation = empty_foundation
-                    .bg(rgb(0x22C55E)) // Green highlight for valid drop zones
-                    .border_4()
-                    .border_color(rgb(0x16A34A)); // Darker green border
-            }
-
             // Make it a drop target
             empty_foundation
+                .relative()
+                .child(self.track_pile(Pile::Foundation(foundation)))
+                .when(is_valid_drop_target, |pile| {
+                    pile.child(render_drop_highlight())
+                })
                 .id(ElementId::Name(format!("foundation_{}", foundation).into()))
+                .tooltip(tooltip::text(self.game_state.foundation_label()))
                 .on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
                     println!("ON_DROP HANDLER CALLED: foundation {}", foundation);
                     app.handle_drop(drag_info, position, cx);
@@ -490,7 +2964,13 @@ impl SolitaireApp {
                 .id(ElementId::Name(
                     format!("foundation_{}_top", foundation).into(),
                 ))
+                .tooltip(tooltip::text(self.game_state.foundation_label()))
+                .relative()
+                .child(self.track_pile(Pile::Foundation(foundation)))
                 .child(card_element)
+                .when(is_valid_drop_target, |pile| {
+                    pile.child(render_drop_highlight())
+                })
                 .on_drop(cx.listener(move |app, drag_info: &DragInfo, _window, cx| {
                     println!(
                         "ON_DROP HANDLER CALLED: foundation {} (on top card)",
@@ -503,40 +2983,372 @@ impl SolitaireApp {
 }
 
 impl Render for SolitaireApp {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        div()
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let started = Instant::now();
+        // A drag the window lost track of ends without a drop
+        if !cx.has_active_drag() {
+            self.drag.cancel();
+        }
+        self.drag.finish();
+        // Keep the keyboard cursor on the board as it changes, and let go of
+        // held cards that have moved
+        if let Some(cursor) = self.board_cursor {
+            self.board_cursor = Some(cursor.settle(&self.game_state));
+        }
+        if self
+            .held
+            .is_some_and(|held| self.get_draggable_cards(held).is_empty())
+        {
+            self.held = None;
+        }
+        // Follow the OS light or dark preference unless one is chosen
+        let chrome = match &self.theme_file {
+            Some(file) => Theme::from_file(file, window.appearance()),
+            None => Theme::resolve(self.settings.theme, window.appearance()),
+        };
+        let base = self
+            .theme_file
+            .as_ref()
+            .map_or(self.settings.theme, |file| file.base);
+        theme::set_current(chrome);
+        locale::set_language(self.settings.language);
+        // Cards fill the window, re-fitted whenever it is resized
+        let columns = match registry::variants().layout(self.game_state.rules) {
+            BoardLayout::Pyramid { rows } => rows,
+            _ => self
+                .game_state
+                .tableau
+                .len()
+                .max(self.game_state.foundations.len() + 2),
+        };
+        // Text sizes given in rems follow the root size; the rest read the
+        // scale from the layout
+        let text_scale = self.settings.text_scale as f32 / 100.0;
+        window.set_rem_size(px(BASE_REM_SIZE * text_scale));
+        layout::set_current(layout::BoardLayout::fit(
+            window.viewport_size(),
+            columns,
+            self.settings.compact_layout,
+            text_scale,
+        ));
+        card_art::set_style(card_art::CardStyle {
+            four_color: self.settings.four_color_deck,
+            high_contrast: base == ThemeChoice::HighContrast,
+            large_index: self.settings.large_index,
+        });
+        card_art::set_theme_art(
+            self.theme_file
+                .as_ref()
+                .and_then(|file| file.card_art.as_deref()),
+        );
+        // Redraw every frame while cards are in flight
+        if let Some(animation) = &self.animation {
+            if animation.is_finished(&self.clock) {
+                self.animation = None;
+            } else {
+                window.request_animation_frame();
+            }
+        }
+        if let Some(hint) = &self.hint_highlight {
+            if hint.is_finished(&self.clock) {
+                self.hint_highlight = None;
+            } else {
+                window.request_animation_frame();
+            }
+        }
+        // Fill the dwell bar while the pointer rests
+        if self.settings.input_mode == InputMode::Dwell
+            && self
+                .dwell
+                .progress(self.settings.dwell_time, &self.clock)
+                .is_some()
+        {
+            window.request_animation_frame();
+        }
+        self.toasts.expire(&self.clock);
+        if !self.toasts.is_empty() {
+            // Keep fading until the last toast is gone
+            window.request_animation_frame();
+        }
+        if let Some((cascade, overlay)) = &self.celebration {
+            if cascade.is_finished(&self.clock) {
+                self.overlay = *overlay;
+                self.celebration = None;
+            } else {
+                window.request_animation_frame();
+            }
+        }
+        // Score, time and the piles have their own slots in the status bar;
+        // the rest is said in words after them
+        let mut status = format!(
+            "{} | {} | {}",
+            self.game_state.rules.name(),
+            tr(match self.game_state.draw_count {
+                DrawCount::One => "Draw: One",
+                DrawCount::Three => "Draw: Three",
+            }),
+            self.game_state.deal_label()
+        );
+        if let Some(tournament) = &self.tournament {
+            status = format!("{} | {}", tournament.progress_label(), status);
+        }
+        if let Some(date) = self.daily {
+            status = format!("{} | {}", trf("Daily Challenge {}", &[&date]), status);
+        }
+        if self.game_state.practice {
+            status = format!("{} | {}", tr("Practice"), status);
+        }
+        self.refresh_winnability(cx);
+        let stuck = self.game_state.is_stuck();
+        if self.game_state.rules.is_solvable() {
+            status = format!("{} | {}", status, self.winnability_label());
+        } else if stuck {
+            status = format!("{} | {}", status, tr("No moves left"));
+        }
+        if let Some((position, hint)) = &self.hint
+            && *position == self.analysed_position
+        {
+            status = format!("{} | {}", status, hint);
+        }
+        if let Some((position, index)) = &self.move_choice
+            && *position == self.game_state.position_key()
+        {
+            let moves = self.game_state.available_moves();
+            if let Some(action) = moves.get(*index) {
+                status = format!(
+                    "{} | {}",
+                    status,
+                    trf(
+                        "Move {} of {}: {} (Enter to make it)",
+                        &[
+                            &(index + 1),
+                            &moves.len(),
+                            &mirror::describe_action(&self.game_state, action),
+                        ],
+                    )
+                );
+            }
+        }
+        if let Some(playback) = &self.line_playback {
+            status = format!(
+                "{} | {}",
+                status,
+                trf(
+                    "Winning line: move {} of {}",
+                    &[
+                        &(playback.current_frame().step + 1).min(playback.actions().len()),
+                        &playback.actions().len(),
+                    ],
+                )
+            );
+        }
+        // Variants the solver can't search aren't graded
+        if self.trainer && self.offers_hints() {
+            let [best, ok, blunders] = self.grade_counts;
+            status = format!(
+                "{} | {}",
+                status,
+                trf(
+                    "Trainer: {} | Best {}, Ok {}, Blunders {}",
+                    &[
+                        &self.last_grade.as_deref().unwrap_or(tr("make a move")),
+                        &best,
+                        &ok,
+                        &blunders,
+                    ],
+                )
+            );
+        }
+
+        // A replay's board is not the player's game
+        if let Some(viewer) = &self.replay_viewer {
+            let frame = viewer.playback.current_frame();
+            status = trf(
+                "Replay of {} #{} | Move {} of {}",
+                &[
+                    &viewer.replay.variant.name(),
+                    &viewer.replay.seed,
+                    &frame.step,
+                    &viewer.playback.actions().len(),
+                ],
+            );
+        }
+
+        let overlay = match self.overlay {
+            Some(Overlay::TournamentProgress) => self.tournament.clone().map(|tournament| {
+                self.render_tournament_progress(&tournament, cx)
+                    .into_any_element()
+            }),
+            Some(Overlay::Notices { title }) => {
+                Some(self.render_notices(title, cx).into_any_element())
+            }
+            Some(Overlay::ResumeGame) => Some(self.render_resume_offer(cx).into_any_element()),
+            Some(Overlay::ConfirmAbandon { next }) => {
+                Some(self.render_confirm_abandon(next, cx).into_any_element())
+            }
+            Some(Overlay::Replays) => Some(self.render_replay_list(cx).into_any_element()),
+            Some(Overlay::ErrorReport) => Some(self.render_error_report(cx).into_any_element()),
+            Some(Overlay::Diagnostics) => Some(self.render_diagnostics(cx).into_any_element()),
+            Some(Overlay::PlayGameNumber) => {
+                Some(self.render_game_number_dialog(cx).into_any_element())
+            }
+            Some(Overlay::NewGame) => Some(self.render_new_game_screen(cx).into_any_element()),
+            Some(Overlay::DailyCalendar) => Some(self.render_daily_calendar(cx).into_any_element()),
+            Some(Overlay::Settings) => Some(self.render_settings(cx).into_any_element()),
+            Some(Overlay::HighScores {
+                draw_count,
+                placement,
+            }) => Some(
+                self.render_high_scores(draw_count, placement, cx)
+                    .into_any_element(),
+            ),
+            None if stuck
+                && self.replay_viewer.is_none()
+                && self.line_playback.is_none()
+                && self.stuck_dismissed.as_ref() != Some(&self.analysed_position) =>
+            {
+                Some(self.render_stuck(cx).into_any_element())
+            }
+            None => None,
+        };
+
+        let root = div()
             .flex()
             .flex_col()
             .size_full()
-            .bg(rgb(0x0F5132)) // Green felt background
+            .bg(rgb(chrome.felt)) // Felt background
             .p_4()
             .relative() // Enable absolute positioning for overlay
+            .track_focus(&self.focus_handle)
+            .key_context(shortcuts::CONTEXT)
+            .on_action(cx.listener(|app, _: &shortcuts::NewGame, _window, cx| {
+                app.shortcut(cx, Self::open_new_game_screen)
+            }))
+            .on_action(cx.listener(|app, _: &shortcuts::Undo, _window, cx| {
+                app.shortcut(cx, |app, cx| app.handle_action(GameAction::Undo, cx))
+            }))
+            .on_action(cx.listener(|app, _: &shortcuts::Redo, _window, cx| {
+                app.shortcut(cx, |app, cx| app.handle_action(GameAction::Redo, cx))
+            }))
+            .on_action(cx.listener(|app, _: &shortcuts::Hint, _window, cx| {
+                app.shortcut(cx, |app, cx| {
+                    if app.offers_hints() {
+                        app.request_hint(cx);
+                    }
+                })
+            }))
+            .on_action(cx.listener(|app, _: &shortcuts::Deal, _window, cx| {
+                app.shortcut(cx, Self::deal_or_toggle_replay)
+            }))
+            .on_action(
+                cx.listener(|app, _: &shortcuts::AutoComplete, _window, cx| {
+                    app.shortcut(cx, Self::auto_complete)
+                }),
+            )
+            .on_action(cx.listener(|app, _: &shortcuts::CollectAll, _window, cx| {
+                app.shortcut(cx, |app, cx| app.handle_action(GameAction::CollectAll, cx))
+            }))
+            .on_action(cx.listener(|app, _: &shortcuts::NextMove, _window, cx| {
+                app.shortcut(cx, |app, cx| {
+                    if app.replay_viewer.is_none() {
+                        app.cycle_moves(false, cx);
+                    }
+                })
+            }))
+            .on_action(
+                cx.listener(|app, _: &shortcuts::PreviousMove, _window, cx| {
+                    app.shortcut(cx, |app, cx| {
+                        if app.replay_viewer.is_none() {
+                            app.cycle_moves(true, cx);
+                        }
+                    })
+                }),
+            )
+            .on_action(
+                cx.listener(|app, _: &shortcuts::ToggleStatsHud, _window, cx| {
+                    app.show_stats_hud = !app.show_stats_hud;
+                    cx.notify();
+                }),
+            )
+            .on_action(cx.listener(|app, _: &shortcuts::ToggleMute, _window, cx| {
+                // Unlike other shortcuts this works during the win cascade, to
+                // cut its fanfare short
+                if app.overlay.is_some() {
+                    cx.propagate();
+                } else {
+                    app.toggle_mute(cx);
+                }
+            }))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            // Cards let go away from every pile are put back
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|app, event: &MouseUpEvent, _window, cx| {
+                    app.release_pointer(event.position, cx);
+                }),
+            )
+            .when(self.settings.input_mode == InputMode::Dwell, |root| {
+                root.on_mouse_move(cx.listener(|app, event: &MouseMoveEvent, _window, cx| {
+                    app.dwell_over(event.position, cx);
+                }))
+            })
             .child(
                 div()
                     .flex()
                     .flex_col()
+                    .flex_1()
+                    .min_h_0()
                     .gap_4()
+                    .when(!self.settings.compact_layout, |column| {
+                        column.child(
+                            // Header with game title
+                            div()
+                                .text_xl()
+                                .font_weight(FontWeight::BOLD)
+                                .text_color(white())
+                                .text_center()
+                                .child(tr("Klondike Solitaire")),
+                        )
+                    })
+                    .child(if self.replay_viewer.is_some() {
+                        self.render_replay_controls(cx).into_any_element()
+                    } else {
+                        self.render_menu_bar(cx).into_any_element()
+                    })
+                    .child(self.render_status_bar(status))
                     .child(
-                        // Header with game title
-                        div()
-                            .text_xl()
-                            .font_weight(FontWeight::BOLD)
-                            .text_color(white())
-                            .text_center()
-                            .child("Klondike Solitaire"),
-                    )
-                    .child(
-                        // Game status bar
+                        // Main game board with drag and drop functionality,
+                        // taking the height left under the bars
                         div()
-                            .text_sm()
-                            .text_color(white())
-                            .text_center()
-                            .child(self.game_state.summary()),
+                            .flex_1()
+                            .min_h_0()
+                            .child(self.render_game_board_with_drag_drop(cx)),
                     )
-                    .child(
-                        // Main game board with drag and drop functionality
-                        self.render_game_board_with_drag_drop(cx),
-                    ),
+                    .when(self.settings.text_board, |column| {
+                        column.child(self.render_text_board())
+                    }),
             )
+            .children(self.render_hint_highlight())
+            .children(self.render_board_cursor())
+            .children(self.render_dwell_progress())
+            .child(self.render_toasts())
+            .children(self.render_flights())
+            .children(self.render_cascade(window.viewport_size().height, cx))
+            .when(self.show_stats_hud, |root| {
+                root.child(self.render_stats_hud())
+            })
+            .children(overlay);
+        self.record_latency("render", started.elapsed());
+        root
+    }
+}
+
+/// Each move played emits what it did, for the parts of the app that react
+/// to play
+impl EventEmitter<Vec<GameEvent>> for SolitaireApp {}
+
+impl Focusable for SolitaireApp {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
     }
 }
@@ -0,0 +1,761 @@
+use super::{Abandon, Overlay, SolitaireApp};
+use crate::game::actions::{DrawCount, GameAction};
+use crate::game::daily::{self, Date};
+use crate::game::highscores::{HighScoreEntry, Placement};
+use crate::game::locale::{tr, trf};
+use crate::game::registry;
+use crate::game::state;
+use crate::ui;
+use crate::ui::card_art;
+use crate::ui::theme;
+use gpui::{Context, ElementId, FontWeight, IntoElement, div, prelude::*, px, rgb, white};
+use std::time::Duration;
+
+impl SolitaireApp {
+    pub(super) fn open_game_number_dialog(&mut self, cx: &mut Context<Self>) {
+        self.game_number_input.clear();
+        self.game_number_error = None;
+        self.overlay = Some(Overlay::PlayGameNumber);
+        cx.notify();
+    }
+
+    /// Deal the game number typed into the dialog
+    pub(super) fn play_game_number(&mut self, cx: &mut Context<Self>) {
+        if self.tournament.is_some() {
+            self.game_number_error =
+                Some(tr("Finish the tournament before choosing a game").to_string());
+            return;
+        }
+        let game_number = match state::parse_game_number(&self.game_number_input) {
+            Ok(game_number) => game_number,
+            Err(error) => {
+                self.game_number_error = Some(error);
+                return;
+            }
+        };
+
+        self.deal_seed(game_number, cx);
+    }
+
+    /// Show the new-game screen with a fresh seed, or forfeit the current
+    /// tournament deal
+    pub(super) fn open_new_game_screen(&mut self, cx: &mut Context<Self>) {
+        if !self.confirm_abandon(Abandon::NewGame, cx) {
+            return;
+        }
+        self.overlay = None;
+        if self.tournament.is_some() {
+            self.start_new_game(GameAction::NewGame, cx);
+            return;
+        }
+        self.seed_roll.reroll();
+        self.overlay = Some(Overlay::NewGame);
+        cx.notify();
+    }
+
+    /// Carry on with the game saved at the end of the last session
+    fn resume_saved_game(&mut self, cx: &mut Context<Self>) {
+        if let Some(saved) = self.saved_game.take() {
+            self.game_state = saved.game_state;
+            self.history = saved.history;
+            self.daily = saved.daily;
+        }
+        self.close_resume_offer(cx);
+    }
+
+    /// Keep the fresh deal instead, counting the saved game as played like
+    /// any other game left unfinished
+    fn decline_saved_game(&mut self, cx: &mut Context<Self>) {
+        if let Some(saved) = self.saved_game.take() {
+            let fresh = std::mem::replace(&mut self.game_state, saved.game_state);
+            self.daily = saved.daily;
+            self.record_unfinished_game();
+            self.game_state = fresh;
+            self.daily = None;
+        }
+        self.close_resume_offer(cx);
+    }
+
+    /// Move on from the resume offer to any crash report and startup
+    /// notices
+    fn close_resume_offer(&mut self, cx: &mut Context<Self>) {
+        self.overlay = if self.error_report.is_some() {
+            Some(Overlay::ErrorReport)
+        } else {
+            (!self.notices.is_empty()).then_some(Overlay::Notices {
+                title: "Some saved data was damaged",
+            })
+        };
+        self.restart_journal();
+        self.autosave();
+        cx.notify();
+    }
+
+    pub(super) fn render_game_number_dialog(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let input = if self.game_number_input.is_empty() {
+            tr("Type a game number").to_string()
+        } else {
+            format!("#{}", self.game_number_input)
+        };
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Play Game #…")),
+                )
+                .child(
+                    div()
+                        .w(px(240.0))
+                        .px_2()
+                        .py_1()
+                        .bg(rgb(theme::current().control))
+                        .border_1()
+                        .border_color(rgb(theme::current().accent))
+                        .rounded_md()
+                        .child(input),
+                )
+                .children(
+                    self.game_number_error
+                        .clone()
+                        .map(|error| div().text_sm().text_color(rgb(theme::current().error)).child(error)),
+                )
+                .child(div().text_xs().text_color(rgb(theme::current().muted_text)).child(
+                    tr("The same number always deals the same cards. Enter to deal, Esc to cancel."),
+                ))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(
+                            ui::render_button("game_number_deal", tr("Deal")).on_click(cx.listener(
+                                |app, _event, _window, cx| {
+                                    app.play_game_number(cx);
+                                    cx.notify();
+                                },
+                            )),
+                        )
+                        .child(ui::render_button("game_number_cancel", tr("Cancel")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.overlay = None;
+                                cx.notify();
+                            }),
+                        )),
+                ),
+        )
+    }
+
+    /// The seed on offer with its first row of cards, a re-roll button and
+    /// the breadcrumb of recently seen seeds
+    pub(super) fn render_new_game_screen(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let seed = self.seed_roll.current();
+        let registry = registry::variants();
+        let variants = registry.entries().iter().enumerate().map(|(index, entry)| {
+            let variant = entry.variant;
+            ui::render_button(
+                ElementId::Name(format!("new_game_variant_{}", index).into()),
+                entry.name(),
+            )
+            .when(variant == self.variant, |button| {
+                button.border_color(rgb(theme::current().accent))
+            })
+            .on_click(cx.listener(move |app, _event, _window, cx| {
+                app.variant = variant;
+                cx.notify();
+            }))
+        });
+        let preview = registry
+            .preview(self.variant, seed)
+            .into_iter()
+            .map(|card| {
+                div()
+                    .w(px(40.0))
+                    .py_1()
+                    .bg(white())
+                    .rounded_md()
+                    .text_center()
+                    .text_color(rgb(card_art::suit_color(card.suit)))
+                    .child(card.to_string())
+            });
+        let breadcrumb = self
+            .seed_roll
+            .seeds()
+            .iter()
+            .enumerate()
+            .map(|(index, seen)| {
+                ui::render_button(
+                    ElementId::Name(format!("new_game_seen_{}", index).into()),
+                    format!("#{}", seen),
+                )
+                .when(index == self.seed_roll.current_index(), |button| {
+                    button.border_color(rgb(theme::current().accent))
+                })
+                .on_click(cx.listener(move |app, _event, _window, cx| {
+                    app.seed_roll.select(index);
+                    cx.notify();
+                }))
+            });
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(trf("New Game #{}", &[&seed])),
+                )
+                .child(div().flex().flex_wrap().gap_1().children(variants))
+                .child(div().flex().gap_1().children(preview))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(ui::render_button("new_game_deal", tr("Deal")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                let seed = app.seed_roll.current();
+                                app.deal_seed(seed, cx);
+                            }),
+                        ))
+                        .child(
+                            ui::render_button("new_game_reroll", tr("Re-roll")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.seed_roll.reroll();
+                                    cx.notify();
+                                }),
+                            ),
+                        )
+                        .child(ui::render_button("new_game_cancel", tr("Cancel")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.overlay = None;
+                                cx.notify();
+                            }),
+                        )),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(theme::current().muted_text))
+                        .child(tr("Recently seen — click one to go back to it")),
+                )
+                .child(div().flex().flex_wrap().gap_1().children(breadcrumb)),
+        )
+    }
+
+    /// This month's calendar with won daily challenges ticked off
+    pub(super) fn render_daily_calendar(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let today = Date::today();
+        let first = today.first_of_month();
+        let completed = self.statistics.completed_days(today.year, today.month);
+
+        let mut header = div().flex().gap_1();
+        for weekday in ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] {
+            header = header.child(div().w(px(36.0)).text_center().text_xs().child(tr(weekday)));
+        }
+
+        // Leading blanks so the 1st lands under its weekday
+        let mut cells: Vec<Option<u32>> = vec![None; first.weekday() as usize];
+        cells.extend((1..=daily::days_in_month(today.year, today.month)).map(Some));
+
+        let mut weeks = div().flex().flex_col().gap_1().child(header);
+        for week in cells.chunks(7) {
+            let mut row = div().flex().gap_1();
+            for day in week {
+                let cell = div()
+                    .w(px(36.0))
+                    .h(px(28.0))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_sm()
+                    .rounded_sm();
+                row = row.child(match day {
+                    Some(day) => cell
+                        .when(completed.contains(day), |cell| cell.bg(rgb(0x15803D)))
+                        .when(*day == today.day, |cell| {
+                            cell.border_1().border_color(rgb(0xFACC15))
+                        })
+                        .child(if completed.contains(day) {
+                            format!("{} ✓", day)
+                        } else {
+                            day.to_string()
+                        }),
+                    None => cell,
+                });
+            }
+            weeks = weeks.child(row);
+        }
+
+        let today_status = match self.statistics.daily_result(today) {
+            Some(result) if result.won => trf(
+                "Today's deal won: Score {} | Moves {} | {}",
+                &[
+                    &result.score,
+                    &result.moves,
+                    &ui::format_duration(result.time),
+                ],
+            ),
+            Some(_) => tr("Today's deal attempted, not yet won").to_string(),
+            None => tr("Today's deal not played yet").to_string(),
+        };
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(div().text_xl().font_weight(FontWeight::BOLD).child(trf(
+                    "Daily Challenge: {}",
+                    &[&format!("{:04}-{:02}", today.year, today.month)],
+                )))
+                .child(weeks)
+                .child(div().text_sm().child(today_status))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .when(self.tournament.is_none(), |buttons| {
+                            buttons.child(
+                                ui::render_button("daily_play", tr("Play Today's Deal")).on_click(
+                                    cx.listener(|app, _event, _window, cx| {
+                                        app.start_daily_challenge(cx);
+                                    }),
+                                ),
+                            )
+                        })
+                        .child(ui::render_button("daily_close", tr("Close")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.overlay = None;
+                                cx.notify();
+                            }),
+                        )),
+                ),
+        )
+    }
+
+    pub(super) fn render_high_scores(
+        &mut self,
+        draw_count: DrawCount,
+        placement: Option<Placement>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mode = self.high_scores.mode(draw_count);
+        let placement = placement.unwrap_or_default();
+        let title = tr(match draw_count {
+            DrawCount::One => "High Scores: Draw One",
+            DrawCount::Three => "High Scores: Draw Three",
+        });
+        let other_mode = match draw_count {
+            DrawCount::One => DrawCount::Three,
+            DrawCount::Three => DrawCount::One,
+        };
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(div().text_xl().font_weight(FontWeight::BOLD).child(title))
+                .when(placement.is_new_entry(), |panel| {
+                    panel.child(
+                        div()
+                            .text_color(rgb(theme::current().highlight))
+                            .child(tr("New high score!")),
+                    )
+                })
+                .child(
+                    ui::row()
+                        .gap_6()
+                        .child(Self::render_high_score_list(
+                            "Best Scores",
+                            &mode.best_scores,
+                            placement.score_rank,
+                        ))
+                        .child(Self::render_high_score_list(
+                            "Fastest Wins",
+                            &mode.fastest_wins,
+                            placement.time_rank,
+                        )),
+                )
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(
+                            ui::render_button(
+                                "high_scores_other_mode",
+                                tr(match other_mode {
+                                    DrawCount::One => "Show Draw One",
+                                    DrawCount::Three => "Show Draw Three",
+                                }),
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.overlay = Some(Overlay::HighScores {
+                                        draw_count: other_mode,
+                                        placement: None,
+                                    });
+                                    cx.notify();
+                                },
+                            )),
+                        )
+                        .child(
+                            ui::render_button("high_scores_close", tr("Close")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.overlay = None;
+                                    cx.notify();
+                                }),
+                            ),
+                        ),
+                ),
+        )
+    }
+
+    /// One ranked table; the row at `highlight` (the latest win) is picked out
+    fn render_high_score_list(
+        title: &'static str,
+        entries: &[HighScoreEntry],
+        highlight: Option<usize>,
+    ) -> impl IntoElement {
+        let mut list = div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(div().font_weight(FontWeight::BOLD).child(tr(title)));
+
+        if entries.is_empty() {
+            list = list.child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(theme::current().muted_text))
+                    .child(tr("No wins yet")),
+            );
+        }
+        for (rank, entry) in entries.iter().enumerate() {
+            list = list.child(
+                div()
+                    .text_sm()
+                    .when(highlight == Some(rank), |row| {
+                        row.text_color(rgb(theme::current().highlight))
+                    })
+                    .child(trf(
+                        "{}. {} pts | {} | {} moves",
+                        &[
+                            &format!("{:>2}", rank + 1),
+                            &entry.score,
+                            &ui::format_duration(entry.time),
+                            &entry.moves,
+                        ],
+                    )),
+            );
+        }
+        list
+    }
+
+    /// Latency percentiles per label, opened with F12 during development
+    pub(super) fn render_diagnostics(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ms = |duration: Duration| format!("{:.2}", duration.as_secs_f64() * 1000.0);
+        let header = ["", "count", "over", "p50", "p95", "p99", "max"];
+        let rows = self.latency.summaries().into_iter().map(|summary| {
+            [
+                summary.label.to_string(),
+                summary.count.to_string(),
+                summary.over_budget.to_string(),
+                ms(summary.p50),
+                ms(summary.p95),
+                ms(summary.p99),
+                ms(summary.max),
+            ]
+        });
+        let row = |cells: Vec<String>| {
+            ui::row()
+                .gap_2()
+                .children(cells.into_iter().map(|cell| div().w(px(64.0)).child(cell)))
+        };
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Diagnostics")),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(theme::current().muted_text))
+                        .child(trf(
+                            "Latency in ms over the most recent samples; budget {} ms",
+                            &[&ms(self.latency.budget())],
+                        )),
+                )
+                .child(
+                    row(header.map(str::to_string).to_vec())
+                        .font_weight(FontWeight::BOLD)
+                        .text_sm(),
+                )
+                .children(rows.map(|cells| row(cells.to_vec()).text_sm()))
+                .child(
+                    ui::render_button("diagnostics_close", tr("Close")).on_click(cx.listener(
+                        |app, _event, _window, cx| {
+                            app.overlay = None;
+                            cx.notify();
+                        },
+                    )),
+                ),
+        )
+    }
+
+    pub(super) fn render_resume_offer(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let details = self.saved_game.as_ref().map(|saved| {
+            let game = match saved.daily {
+                Some(date) => trf("Daily Challenge {}", &[&date]),
+                None => saved.game_state.rules.name().to_string(),
+            };
+            trf(
+                "{} | Score {} | Moves {} | {}",
+                &[
+                    &game,
+                    &saved.game_state.score,
+                    &saved.game_state.move_count,
+                    &ui::format_duration(saved.game_state.elapsed()),
+                ],
+            )
+        });
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Resume your game?")),
+                )
+                .children(details.map(|details| div().text_sm().child(details)))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(ui::render_button("resume_game", tr("Resume")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.resume_saved_game(cx);
+                            }),
+                        ))
+                        .child(
+                            ui::render_button("resume_decline", tr("New Game")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.decline_saved_game(cx);
+                                }),
+                            ),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_error_report(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let path = self.error_report.clone().unwrap_or_default();
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Something went wrong")),
+                )
+                .child(div().text_sm().child(tr(
+                    "A report was saved with the game's version, the deal, the position and the last moves played. Attach it to a bug report so the problem can be found.",
+                )))
+                .child(div().text_sm().child(path.display().to_string()))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(ui::render_button("error_report_open", tr("Open Report")).on_click(
+                            cx.listener(move |app, _event, _window, cx| {
+                                cx.open_with_system(&path);
+                                app.close_error_report(cx);
+                            }),
+                        ))
+                        .child(ui::render_button("error_report_close", tr("Close")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.close_error_report(cx);
+                            }),
+                        )),
+                ),
+        )
+    }
+
+    /// Move on from the offer to open a report to any startup notices
+    fn close_error_report(&mut self, cx: &mut Context<Self>) {
+        self.error_report = None;
+        self.overlay = (!self.notices.is_empty()).then_some(Overlay::Notices {
+            title: "Some saved data was damaged",
+        });
+        cx.notify();
+    }
+
+    pub(super) fn render_confirm_abandon(
+        &mut self,
+        next: Abandon,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let consequence = if self.tournament.is_some() {
+            tr("This forfeits the current tournament deal.")
+        } else if self.game_state.practice {
+            tr("Practice games are not counted in your statistics.")
+        } else {
+            tr("It will count as a loss in your statistics.")
+        };
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Abandon current game?")),
+                )
+                .child(div().text_sm().child(consequence))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(
+                            ui::render_button("abandon_confirm", tr("Abandon")).on_click(
+                                cx.listener(move |app, _event, _window, cx| match next {
+                                    Abandon::NewGame => app.open_new_game_screen(cx),
+                                    Abandon::Tournament => app.start_tournament(cx),
+                                }),
+                            ),
+                        )
+                        .child(
+                            ui::render_button("abandon_cancel", tr("Keep Playing")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.overlay = None;
+                                    cx.notify();
+                                }),
+                            ),
+                        ),
+                )
+                .child(
+                    ui::render_toggle(
+                        "abandon_dont_ask",
+                        tr("Don't ask again (turn back on in Settings)"),
+                        !self.settings.confirm_abandon,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.confirm_abandon = !settings.confirm_abandon
+                        });
+                    })),
+                ),
+        )
+    }
+
+    /// Shown over the dimmed board once no move is left, with the ways out
+    pub(super) fn render_stuck(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let reason = if self.game_state.stock.is_empty() && self.game_state.waste.is_empty() {
+            tr("No card can be played and the stock is used up.")
+        } else {
+            tr("No card can be played and the stock can't be dealt again.")
+        };
+        let can_undo = self.history.cursor() > 0;
+        let can_restart = self.tournament.is_none() && !self.game_state.imported;
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("No legal moves remain")),
+                )
+                .child(div().text_sm().child(reason))
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .when(can_undo, |buttons| {
+                            buttons.child(ui::render_button("stuck_undo", tr("Undo")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.handle_action(GameAction::Undo, cx);
+                                }),
+                            ))
+                        })
+                        .when(can_restart, |buttons| {
+                            buttons.child(
+                                ui::render_button("stuck_restart", tr("Restart Deal")).on_click(
+                                    cx.listener(|app, _event, _window, cx| {
+                                        app.handle_action(GameAction::ReplaySeed, cx);
+                                    }),
+                                ),
+                            )
+                        })
+                        .child(
+                            ui::render_button("stuck_new_game", tr("New Game")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.open_new_game_screen(cx);
+                                }),
+                            ),
+                        )
+                        .child(
+                            ui::render_button("stuck_look", tr("Look at the Board")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    app.stuck_dismissed = Some(app.analysed_position.clone());
+                                    cx.notify();
+                                }),
+                            ),
+                        ),
+                ),
+        )
+    }
+
+    pub(super) fn render_notices(
+        &mut self,
+        title: &'static str,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut messages = div().flex().flex_col().gap_1();
+        for notice in &self.notices {
+            messages = messages.child(div().text_sm().child(notice.clone()));
+        }
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr(title)),
+                )
+                .child(messages)
+                .child(
+                    ui::render_button("notices_ok", tr("OK")).on_click(cx.listener(
+                        |app, _event, _window, cx| {
+                            app.notices.clear();
+                            app.overlay = None;
+                            cx.notify();
+                        },
+                    )),
+                ),
+        )
+    }
+}
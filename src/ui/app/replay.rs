@@ -0,0 +1,389 @@
+use super::{Overlay, ReplayViewer, SolitaireApp};
+use crate::game::autosave::SavedGame;
+use crate::game::command::GameCommand;
+use crate::game::locale::{tr, trf};
+use crate::game::playback::{Playback, PlaybackTiming};
+use crate::game::replay::Replay;
+use crate::game::solver::Solution;
+use crate::ui;
+use crate::ui::shortcuts;
+use crate::ui::theme;
+use gpui::{
+    ClipboardItem, Context, ElementId, FontWeight, IntoElement, div, prelude::*, px, rgb, white,
+};
+use std::time::Duration;
+
+/// Playback speeds offered by the replay viewer's slider
+const REPLAY_SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+impl SolitaireApp {
+    /// Save the moves played so far as a replay file, and put it on the
+    /// clipboard for sharing
+    pub(super) fn export_replay(&mut self, cx: &mut Context<Self>) {
+        let exported = Replay::from_game(&self.history).and_then(|replay| {
+            cx.write_to_clipboard(ClipboardItem::new_string(replay.to_string()));
+            replay.save_in(&Replay::default_dir())
+        });
+        self.notices = vec![match exported {
+            Ok(path) => trf(
+                "Saved to {} and copied to the clipboard. Paste it into Import Deal to watch it again.",
+                &[&path.display()],
+            ),
+            Err(error) => error,
+        }];
+        self.overlay = Some(Overlay::Notices {
+            title: "Export Replay",
+        });
+        cx.notify();
+    }
+
+    /// Play the solver's winning line on the board, one action at a time.
+    /// Watching the solution makes the game a practice game.
+    pub(super) fn show_winning_line(&mut self, cx: &mut Context<Self>) {
+        let Some(Solution::Winnable(line)) = self.winnability.clone() else {
+            return;
+        };
+        self.game_state.practice = true;
+        let timing = PlaybackTiming::default();
+        match Playback::from_state(self.game_state.clone(), line, timing) {
+            Ok(playback) => self.line_playback = Some(playback),
+            Err(error) => {
+                self.show_error(error, cx);
+                return;
+            }
+        }
+
+        let frame_time = Duration::from_secs(1) / timing.fps;
+        self.line_task = Some(cx.spawn(async move |app, cx| {
+            loop {
+                cx.background_executor().timer(frame_time).await;
+                let playing = app
+                    .update(cx, |app, cx| app.advance_winning_line(cx))
+                    .unwrap_or(false);
+                if !playing {
+                    break;
+                }
+            }
+        }));
+        cx.notify();
+    }
+
+    /// Show the next frame of the winning line; returns false once it is over
+    fn advance_winning_line(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(playback) = &mut self.line_playback else {
+            return false;
+        };
+        let playing = playback.advance();
+        let before =
+            std::mem::replace(&mut self.game_state, playback.current_frame().state.clone());
+        self.animate_from(&before);
+        if !playing {
+            self.take_over(cx);
+        }
+        cx.notify();
+        playing
+    }
+
+    /// Stop the winning line and play on from the board shown, with the
+    /// actions played so far in the undo history. The actions are replayed
+    /// on the board in one go, without a cue or journal entry for each; the
+    /// journal and autosave are written once at the end.
+    pub(super) fn take_over(&mut self, cx: &mut Context<Self>) {
+        let Some(playback) = self.line_playback.take() else {
+            return;
+        };
+        let step = playback.current_frame().step;
+        self.game_state = playback.state_after(0).clone();
+        for action in &playback.actions()[..step] {
+            match GameCommand::apply(&mut self.game_state, action.clone()) {
+                Ok(command) => self.history.record(command, &self.game_state),
+                Err(error) => {
+                    self.show_error(error, cx);
+                    break;
+                }
+            }
+        }
+        self.restart_journal();
+        self.autosave();
+        if self.game_state.game_won {
+            self.handle_game_won(cx);
+        }
+        cx.notify();
+    }
+
+    /// Watch a replay on the board, setting the current game aside until
+    /// the viewer closes
+    fn watch_replay(&mut self, replay: Replay, cx: &mut Context<Self>) {
+        self.line_task = None;
+        self.take_over(cx);
+        self.close_replay(cx);
+
+        let playback = match Playback::from_state(
+            replay.initial_state(),
+            replay.actions.clone(),
+            PlaybackTiming::default(),
+        ) {
+            Ok(playback) => playback,
+            Err(error) => {
+                self.show_error(error, cx);
+                return;
+            }
+        };
+        let set_aside = SavedGame {
+            game_state: std::mem::replace(
+                &mut self.game_state,
+                playback.current_frame().state.clone(),
+            ),
+            history: self.history.clone(),
+            daily: self.daily,
+        };
+        self.replay_viewer = Some(ReplayViewer {
+            replay,
+            playback,
+            speed: REPLAY_SPEEDS
+                .iter()
+                .position(|&speed| speed == 1.0)
+                .unwrap_or(0),
+            playing: false,
+            task: None,
+            set_aside,
+        });
+        self.overlay = None;
+        self.toggle_replay_playing(cx);
+    }
+
+    /// Play or pause the replay; playing it again from the end starts over
+    pub(super) fn toggle_replay_playing(&mut self, cx: &mut Context<Self>) {
+        let Some(viewer) = &mut self.replay_viewer else {
+            return;
+        };
+        if viewer.playing {
+            viewer.playing = false;
+            viewer.task = None;
+            cx.notify();
+            return;
+        }
+        if viewer.playback.is_finished() {
+            viewer.playback.seek(0);
+        }
+        viewer.playing = true;
+
+        let timing = PlaybackTiming::default();
+        let frame_time =
+            Duration::from_secs(1).div_f32(timing.fps as f32 * REPLAY_SPEEDS[viewer.speed]);
+        viewer.task = Some(cx.spawn(async move |app, cx| {
+            loop {
+                cx.background_executor().timer(frame_time).await;
+                let playing = app
+                    .update(cx, |app, cx| app.advance_replay(cx))
+                    .unwrap_or(false);
+                if !playing {
+                    break;
+                }
+            }
+        }));
+        self.show_replay_frame(cx);
+    }
+
+    /// Show the next frame of the replay; returns false once it is paused
+    /// or over
+    fn advance_replay(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(viewer) = &mut self.replay_viewer else {
+            return false;
+        };
+        if !viewer.playing {
+            return false;
+        }
+        viewer.playing = viewer.playback.advance();
+        let playing = viewer.playing;
+        self.show_replay_frame(cx);
+        playing
+    }
+
+    /// Pause the replay and move one action forward or back
+    pub(super) fn step_replay(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let Some(viewer) = &mut self.replay_viewer else {
+            return;
+        };
+        viewer.playing = false;
+        viewer.task = None;
+        if forward {
+            viewer.playback.step_forward();
+        } else {
+            viewer.playback.step_back();
+        }
+        self.show_replay_frame(cx);
+    }
+
+    /// Change playback speed, carrying on at the new speed if playing
+    fn set_replay_speed(&mut self, speed: usize, cx: &mut Context<Self>) {
+        let Some(viewer) = &mut self.replay_viewer else {
+            return;
+        };
+        viewer.speed = speed.min(REPLAY_SPEEDS.len() - 1);
+        if viewer.playing {
+            viewer.playing = false;
+            self.toggle_replay_playing(cx);
+        }
+        cx.notify();
+    }
+
+    fn show_replay_frame(&mut self, cx: &mut Context<Self>) {
+        if let Some(viewer) = &self.replay_viewer {
+            let before = std::mem::replace(
+                &mut self.game_state,
+                viewer.playback.current_frame().state.clone(),
+            );
+            self.animate_from(&before);
+        }
+        cx.notify();
+    }
+
+    /// Stop watching and go back to the game set aside
+    pub(super) fn close_replay(&mut self, cx: &mut Context<Self>) {
+        self.restore_set_aside_game();
+        cx.notify();
+    }
+
+    pub(super) fn restore_set_aside_game(&mut self) {
+        if let Some(viewer) = self.replay_viewer.take() {
+            self.animation = None;
+            self.game_state = viewer.set_aside.game_state;
+            self.history = viewer.set_aside.history;
+            self.daily = viewer.set_aside.daily;
+        }
+    }
+
+    /// Transport controls shown in place of the menu while a replay plays
+    pub(super) fn render_replay_controls(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (playing, speed) = self
+            .replay_viewer
+            .as_ref()
+            .map_or((false, 0), |viewer| (viewer.playing, viewer.speed));
+
+        // Speed slider: a track of notches, filled up to the chosen speed
+        let notches = REPLAY_SPEEDS.iter().enumerate().map(|(i, _)| {
+            div()
+                .id(ElementId::Name(format!("replay_speed_{}", i).into()))
+                .w(px(20.0))
+                .h(px(12.0))
+                .rounded_sm()
+                .cursor_pointer()
+                .bg(if i <= speed {
+                    rgb(theme::current().accent)
+                } else {
+                    rgb(theme::current().control_border)
+                })
+                .on_click(cx.listener(move |app, _event, _window, cx| {
+                    app.set_replay_speed(i, cx);
+                }))
+        });
+
+        div()
+            .flex()
+            .justify_center()
+            .items_center()
+            .gap_2()
+            .child(
+                ui::render_button("replay_step_back", tr("◀ Step")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.step_replay(false, cx);
+                    },
+                )),
+            )
+            .child(
+                ui::render_button("replay_play", tr(if playing { "Pause" } else { "Play" }))
+                    .on_click(shortcuts::dispatch(shortcuts::Deal)),
+            )
+            .child(
+                ui::render_button("replay_step_forward", tr("Step ▶")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.step_replay(true, cx);
+                    },
+                )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .text_sm()
+                    .text_color(white())
+                    .child(tr("Speed"))
+                    .children(notches)
+                    .child(format!("{}×", REPLAY_SPEEDS[speed])),
+            )
+            .child(
+                ui::render_button("replay_close", tr("Close Replay")).on_click(cx.listener(
+                    |app, _event, _window, cx| {
+                        app.close_replay(cx);
+                    },
+                )),
+            )
+    }
+
+    /// Exported replays to watch, or one pasted on the clipboard
+    pub(super) fn render_replay_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let files = Replay::saved_in(&Replay::default_dir());
+        let mut list = div().flex().flex_col().gap_1();
+        if files.is_empty() {
+            list = list.child(div().text_sm().child(tr("No replays exported yet")));
+        }
+        for (i, path) in files.into_iter().take(10).enumerate() {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            list = list.child(
+                ui::render_button(ElementId::Name(format!("replay_file_{}", i).into()), name)
+                    .on_click(cx.listener(move |app, _event, _window, cx| {
+                        match Replay::load(&path) {
+                            Ok(replay) => app.watch_replay(replay, cx),
+                            Err(error) => app.show_error(error, cx),
+                        }
+                    })),
+            );
+        }
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Watch Replay")),
+                )
+                .child(list)
+                .child(
+                    ui::row()
+                        .gap_2()
+                        .child(
+                            ui::render_button("replays_clipboard", tr("From Clipboard")).on_click(
+                                cx.listener(|app, _event, _window, cx| {
+                                    let replay = cx
+                                        .read_from_clipboard()
+                                        .and_then(|item| item.text())
+                                        .ok_or_else(|| {
+                                            tr("The clipboard has no replay to watch").to_string()
+                                        })
+                                        .and_then(|text| Replay::parse(&text));
+                                    match replay {
+                                        Ok(replay) => app.watch_replay(replay, cx),
+                                        Err(error) => app.show_error(error, cx),
+                                    }
+                                }),
+                            ),
+                        )
+                        .child(ui::render_button("replays_close", tr("Close")).on_click(
+                            cx.listener(|app, _event, _window, cx| {
+                                app.overlay = None;
+                                cx.notify();
+                            }),
+                        )),
+                ),
+        )
+    }
+}
@@ -0,0 +1,858 @@
+use super::{Overlay, SolitaireApp};
+use crate::game::actions::{DrawCount, Scoring};
+use crate::game::locale::{Language, tr, trf};
+use crate::game::settings::{
+    Accommodations, DWELL_TIMES, Easing, InputMode, SCAN_INTERVALS, Settings, TEXT_SCALES,
+    ThemeChoice,
+};
+use crate::ui;
+use crate::ui::dwell::Dwell;
+use crate::ui::keymap::{Command, Keymap};
+use crate::ui::music;
+use crate::ui::shortcuts;
+use crate::ui::sound::SoundPack;
+use crate::ui::theme;
+use crate::ui::theme_file::ThemeFile;
+use crate::ui::watch;
+use gpui::{Context, ElementId, FontWeight, IntoElement, div, prelude::*, px, rgb};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+impl SolitaireApp {
+    /// Bind `command` to `keystroke` alone, live and in the settings file
+    pub(super) fn rebind(&mut self, command: Command, keystroke: &str, cx: &mut Context<Self>) {
+        let mut keymap = self.keymap.clone();
+        if let Err(error) = keymap.rebind(command, keystroke) {
+            return self.show_error(error, cx);
+        }
+        self.change_settings(cx, |settings| settings.key_bindings = keymap.into());
+    }
+
+    /// Show the settings dialog, looking for theme files and sound packs
+    /// dropped in since it was last open
+    pub(super) fn open_settings(&mut self, cx: &mut Context<Self>) {
+        self.theme_files = ThemeFile::load_all(&ThemeFile::default_dir());
+        self.sound_packs = SoundPack::names(&SoundPack::default_dir());
+        self.overlay = Some(Overlay::Settings);
+        cx.notify();
+    }
+
+    /// Switch to theme file `id`, reading it again so edits show at once,
+    /// or back to the built-in theme when `None`
+    fn select_theme_file(&mut self, id: Option<String>, cx: &mut Context<Self>) {
+        self.theme_file = None;
+        if let Some(id) = &id {
+            match ThemeFile::load_id(&ThemeFile::default_dir(), id) {
+                Ok(file) => self.theme_file = Some(file),
+                Err(error) => {
+                    self.notices = vec![error];
+                    self.overlay = Some(Overlay::Notices {
+                        title: "Could not load the theme",
+                    });
+                    cx.notify();
+                    return;
+                }
+            }
+        }
+        self.change_settings(cx, |settings| settings.theme_file = id);
+    }
+
+    /// Switch to sound pack `name`, reading it again so new files are
+    /// heard at once, or back to the built-in tones when `None`
+    fn select_sound_pack(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        let pack = match &name {
+            Some(name) => match SoundPack::load(&SoundPack::default_dir(), name) {
+                Ok(pack) => Some(pack),
+                Err(error) => {
+                    self.notices = vec![error];
+                    self.overlay = Some(Overlay::Notices {
+                        title: "Could not load the sound pack",
+                    });
+                    cx.notify();
+                    return;
+                }
+            },
+            None => None,
+        };
+        if let Some(audio) = &mut self.audio {
+            audio.use_pack(pack.as_ref());
+        }
+        self.change_settings(cx, |settings| settings.sound_pack = name);
+    }
+
+    /// Change the settings every view shares, saving them. This view sees
+    /// the change, like the others, once its observer applies it.
+    pub(super) fn change_settings(
+        &self,
+        cx: &mut Context<Self>,
+        change: impl FnOnce(&mut Settings),
+    ) {
+        self.settings_model.update(cx, |settings, cx| {
+            change(settings);
+            if let Err(error) = settings.save(&Settings::default_path()) {
+                println!("Failed to save settings: {}", error);
+            }
+            cx.notify();
+        });
+    }
+
+    /// Take up `settings` as changed in the shared model, carrying the
+    /// change on to the key bindings, sound and input that depend on it
+    pub(super) fn apply_settings(&mut self, settings: Settings, cx: &mut Context<Self>) {
+        let old = std::mem::replace(&mut self.settings, settings);
+        if old.key_bindings != self.settings.key_bindings {
+            match Keymap::try_from(self.settings.key_bindings.clone()) {
+                Ok(keymap) => {
+                    shortcuts::bind_keys(&keymap, cx);
+                    self.keymap = keymap;
+                }
+                Err(error) => self.show_error(
+                    trf("Keyboard shortcuts could not be loaded: {}", &[&error]),
+                    cx,
+                ),
+            }
+        }
+        self.reporter.set_enabled(self.settings.error_reports);
+        if old.music != self.settings.music {
+            self.restart_music();
+        } else if let Some(audio) = &self.audio {
+            audio.set_music_volume(self.settings.music_level());
+        }
+        if old.input_mode != self.settings.input_mode
+            || old.scan_interval != self.settings.scan_interval
+        {
+            self.restart_scan(cx);
+        }
+        if old.theme_file != self.settings.theme_file {
+            let dir = ThemeFile::default_dir();
+            if let Some(id) = &old.theme_file {
+                self.watcher.unwatch(&ThemeFile::path(&dir, id));
+            }
+            if let Some(id) = &self.settings.theme_file {
+                self.watcher.watch(ThemeFile::path(&dir, id));
+            }
+            // Chosen by editing the settings file rather than in the app
+            if self.theme_file.as_ref().map(|file| &file.id) != self.settings.theme_file.as_ref() {
+                self.reload_theme_file(cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Watch the settings file and the chosen theme file, so edits made to
+    /// them while the app runs show without a restart
+    pub(super) fn start_watching(&mut self, cx: &mut Context<Self>) {
+        self.watcher.watch(Settings::default_path());
+        if let Some(id) = &self.settings.theme_file {
+            self.watcher
+                .watch(ThemeFile::path(&ThemeFile::default_dir(), id));
+        }
+        self.watch_task = Some(cx.spawn(async move |app, cx| {
+            loop {
+                cx.background_executor().timer(watch::POLL_INTERVAL).await;
+                if app
+                    .update(cx, |app, cx| app.reload_watched_files(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Read the watched files that were edited, once the edits have settled
+    fn reload_watched_files(&mut self, cx: &mut Context<Self>) {
+        let settings_path = Settings::default_path();
+        for path in self.watcher.poll(Instant::now()) {
+            if path == settings_path {
+                self.reload_settings(&path, cx);
+            } else {
+                self.reload_theme_file(cx);
+            }
+        }
+    }
+
+    /// Take up the settings file as it was edited, keeping the settings as
+    /// they are if it no longer reads. Every view sees the change.
+    fn reload_settings(&mut self, path: &Path, cx: &mut Context<Self>) {
+        // Removed, or being replaced; it is written again on the next change
+        if !path.exists() {
+            return;
+        }
+        match Settings::reload(path) {
+            Ok(settings) => self.settings_model.update(cx, |shared, cx| {
+                // The app's own saves read back unchanged
+                if *shared != settings {
+                    *shared = settings;
+                    cx.notify();
+                }
+            }),
+            Err(error) => {
+                let error = trf("Settings could not be reloaded: {}", &[&error]);
+                self.show_error(error, cx);
+            }
+        }
+    }
+
+    /// Read the chosen theme file again, keeping the theme as it is if the
+    /// file no longer loads
+    fn reload_theme_file(&mut self, cx: &mut Context<Self>) {
+        let Some(id) = &self.settings.theme_file else {
+            self.theme_file = None;
+            cx.notify();
+            return;
+        };
+        match ThemeFile::load_id(&ThemeFile::default_dir(), id) {
+            Ok(file) => self.theme_file = Some(file),
+            Err(error) => {
+                let error = trf("Theme file could not be loaded: {}", &[&error]);
+                return self.show_error(error, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Change the accessibility accommodations. Turning one on mid-game marks
+    /// the current game as well, since it was played (partly) with it.
+    fn update_accessibility(
+        &mut self,
+        update: impl FnOnce(&mut Accommodations),
+        cx: &mut Context<Self>,
+    ) {
+        let mut accessibility = self.settings.accessibility;
+        update(&mut accessibility);
+        self.game_state.accommodations = self.game_state.accommodations.union(accessibility);
+        self.change_settings(cx, |settings| settings.accessibility = accessibility);
+    }
+
+    /// Theme files from the themes folder, each a button to switch to it,
+    /// with the error for any that cannot be loaded
+    fn render_theme_files(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self.theme_file.as_ref().map(|file| file.id.as_str());
+        let files = self.theme_files.iter().map(|(id, file)| match file {
+            Ok(file) => {
+                let label = if selected == Some(id.as_str()) {
+                    format!("● {}", file.name)
+                } else {
+                    file.name.clone()
+                };
+                let id = id.clone();
+                ui::render_button(
+                    ElementId::Name(format!("settings_theme_file_{}", id).into()),
+                    label,
+                )
+                .on_click(cx.listener(move |app, _event, _window, cx| {
+                    app.select_theme_file(Some(id.clone()), cx);
+                }))
+                .into_any_element()
+            }
+            Err(error) => div()
+                .text_xs()
+                .text_color(rgb(theme::current().error))
+                .child(format!("{}: {}", id, error))
+                .into_any_element(),
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_sm()
+            .child(
+                ui::row()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_2()
+                    .child(tr("Theme files:"))
+                    .children(files),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(theme::current().muted_text))
+                    .child(trf(
+                        "Drop .toml theme files into {} and reopen settings",
+                        &[&ThemeFile::default_dir().display()],
+                    )),
+            )
+    }
+
+    /// The built-in tones and each sound pack in the sounds folder, as
+    /// buttons to switch to them
+    fn render_sound_packs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self.settings.sound_pack.as_deref();
+        let choices = std::iter::once(None).chain(self.sound_packs.iter().map(Some));
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                ui::row()
+                    .items_center()
+                    .gap_2()
+                    .text_sm()
+                    .child(tr("Sounds:"))
+                    .children(choices.map(|name| {
+                        let label = name.map_or(tr("Built-in tones"), |name| name.as_str());
+                        let label = if selected == name.map(String::as_str) {
+                            format!("● {}", label)
+                        } else {
+                            label.to_string()
+                        };
+                        let name = name.cloned();
+                        ui::render_button(
+                            ElementId::Name(
+                                format!(
+                                    "settings_sound_pack_{}",
+                                    name.as_deref().unwrap_or_default()
+                                )
+                                .into(),
+                            ),
+                            label,
+                        )
+                        .on_click(cx.listener(
+                            move |app, _event, _window, cx| {
+                                app.select_sound_pack(name.clone(), cx);
+                            },
+                        ))
+                    })),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(theme::current().muted_text))
+                    .child(trf(
+                        "Drop a folder of .wav or .ogg files named after the cues (move, invalid, flip, deal, win, record_win, no_undo_win) into {} and reopen settings",
+                        &[&SoundPack::default_dir().display()],
+                    )),
+            )
+    }
+
+    /// A labelled volume slider, applied as soon as it is moved; `set`
+    /// stores the percentage picked
+    fn render_volume(
+        &self,
+        id: &str,
+        label: &'static str,
+        percent: u32,
+        set: fn(&mut Settings, u32),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        ui::row()
+            .items_center()
+            .gap_2()
+            .text_sm()
+            .child(tr(label))
+            .child(ui::render_slider(
+                id,
+                percent,
+                cx.listener(move |app, percent: &u32, _window, cx| {
+                    let percent = *percent;
+                    app.change_settings(cx, |settings| set(settings, percent));
+                }),
+            ))
+    }
+
+    pub(super) fn render_settings(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let accessibility = self.settings.accessibility;
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(
+                    div()
+                        .text_xl()
+                        .font_weight(FontWeight::BOLD)
+                        .child(tr("Settings")),
+                )
+                .child(div().font_weight(FontWeight::BOLD).child(tr("Game")))
+                .child(
+                    ui::render_toggle(
+                        "settings_draw_three",
+                        tr("Draw three cards at a time (from the next game)"),
+                        self.settings.draw_count == DrawCount::Three,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.draw_count = match settings.draw_count {
+                                DrawCount::One => DrawCount::Three,
+                                DrawCount::Three => DrawCount::One,
+                            }
+                        });
+                    })),
+                )
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Scoring (from the next game):"))
+                        .children(Scoring::all().into_iter().map(|scoring| {
+                            let label = if scoring == self.settings.scoring {
+                                format!("● {}", tr(scoring.label()))
+                            } else {
+                                tr(scoring.label()).to_string()
+                            };
+                            ui::render_button(
+                                ElementId::Name(
+                                    format!("settings_scoring_{}", scoring.name()).into(),
+                                ),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.change_settings(cx, |settings| settings.scoring = scoring);
+                                },
+                            ))
+                        })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_confirm_abandon",
+                        tr("Ask before abandoning a game in progress"),
+                        self.settings.confirm_abandon,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.confirm_abandon = !settings.confirm_abandon;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_move_counter",
+                        tr("Count the cards that can be played"),
+                        self.settings.move_counter,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.move_counter = !settings.move_counter;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_error_reports",
+                        tr("Save a report for bug reports when something goes wrong"),
+                        self.settings.error_reports,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.error_reports = !settings.error_reports
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_animations",
+                        tr("Animate cards"),
+                        self.settings.animations,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.animation = None;
+                        app.change_settings(cx, |settings| {
+                            settings.animations = !settings.animations;
+                        });
+                    })),
+                )
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Card motion:"))
+                        .children(Easing::all().into_iter().map(|easing| {
+                            let label = if easing == self.settings.easing {
+                                format!("● {}", easing.name())
+                            } else {
+                                easing.name().to_string()
+                            };
+                            ui::render_button(
+                                ElementId::Name(
+                                    format!("settings_easing_{}", easing.name()).into(),
+                                ),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.change_settings(cx, |settings| settings.easing = easing);
+                                },
+                            ))
+                        })),
+                )
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Theme:"))
+                        .children(ThemeChoice::all().into_iter().map(|choice| {
+                            let label = if self.theme_file.is_none() && choice == self.settings.theme {
+                                format!("● {}", choice.name())
+                            } else {
+                                choice.name().to_string()
+                            };
+                            ui::render_button(
+                                ElementId::Name(format!("settings_theme_{}", choice.name()).into()),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.change_settings(cx, |settings| settings.theme = choice);
+                                    app.select_theme_file(None, cx);
+                                },
+                            ))
+                        })),
+                )
+                .child(self.render_theme_files(cx))
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Text size:"))
+                        .children(TEXT_SCALES.into_iter().map(|scale| {
+                            let label = if scale == self.settings.text_scale {
+                                format!("● {}%", scale)
+                            } else {
+                                format!("{}%", scale)
+                            };
+                            ui::render_button(
+                                ElementId::Name(format!("settings_text_scale_{}", scale).into()),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.change_settings(cx, |settings| settings.text_scale = scale);
+                                },
+                            ))
+                        })),
+                )
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Language:"))
+                        .children(Language::all().into_iter().map(|language| {
+                            let label = if language == self.settings.language {
+                                format!("● {}", language.native_name())
+                            } else {
+                                language.native_name().to_string()
+                            };
+                            ui::render_button(
+                                ElementId::Name(
+                                    format!("settings_language_{}", language.code()).into(),
+                                ),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.change_settings(cx, |settings| {
+                                        settings.language = language;
+                                    });
+                                },
+                            ))
+                        })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_four_color_deck",
+                        tr("Four-color deck (blue diamonds, green clubs)"),
+                        self.settings.four_color_deck,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.four_color_deck = !settings.four_color_deck;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_large_index",
+                        tr("Large index cards (big corner rank and suit, plain center)"),
+                        self.settings.large_index,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.large_index = !settings.large_index;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_pile_counts",
+                        tr("Count the cards in the stock and face down in each column"),
+                        self.settings.pile_counts,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.pile_counts = !settings.pile_counts;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_compact_layout",
+                        tr("Compact layout (for small windows)"),
+                        self.settings.compact_layout,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.compact_layout = !settings.compact_layout;
+                        });
+                    })),
+                )
+                .child(div().font_weight(FontWeight::BOLD).child(tr("Accessibility")))
+                .child(
+                    ui::render_toggle(
+                        "settings_large_drop_targets",
+                        tr("Large drop targets (drop anywhere over a column)"),
+                        accessibility.large_drop_targets,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.update_accessibility(
+                            |flags| flags.large_drop_targets = !flags.large_drop_targets,
+                            cx,
+                        );
+                    })),
+                )
+                .child(
+                    ui::render_toggle("settings_untimed", tr("Untimed play"), accessibility.untimed)
+                        .on_click(cx.listener(|app, _event, _window, cx| {
+                            app.update_accessibility(|flags| flags.untimed = !flags.untimed, cx);
+                        })),
+                )
+                .child(div().text_xs().text_color(rgb(theme::current().muted_text)).child(
+                    tr("Games played with accommodations count in your totals but are not ranked."),
+                ))
+                .child(
+                    ui::render_toggle(
+                        "settings_reduce_motion",
+                        tr("Reduce motion (cards jump into place, hints hold still)"),
+                        self.settings.reduce_motion,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.reduce_motion = !settings.reduce_motion;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_sound_cues",
+                        tr("Sound cues (moves, flips, deals, wins and refused moves)"),
+                        self.settings.sound_cues,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.sound_cues = !settings.sound_cues;
+                        });
+                    })),
+                )
+                .child(
+                    ui::render_toggle(
+                        "settings_music",
+                        tr("Background music"),
+                        self.settings.music,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| settings.music = !settings.music);
+                    })),
+                )
+                .when(self.settings.sound_cues, |settings| {
+                    settings.child(self.render_sound_packs(cx))
+                })
+                .child(self.render_volume(
+                    "settings_master_volume",
+                    "Volume:",
+                    self.settings.master_volume,
+                    |settings, percent| settings.master_volume = percent,
+                    cx,
+                ))
+                .child(self.render_volume(
+                    "settings_effects_volume",
+                    "Effects:",
+                    self.settings.effects_volume,
+                    |settings, percent| settings.effects_volume = percent,
+                    cx,
+                ))
+                .child(self.render_volume(
+                    "settings_music_volume",
+                    "Music:",
+                    self.settings.music_volume,
+                    |settings, percent| settings.music_volume = percent,
+                    cx,
+                ))
+                .when(self.settings.music, |settings| {
+                    settings
+                        .child(
+                            ui::render_toggle(
+                                "settings_pause_music",
+                                tr("Pause music while the window is in the background"),
+                                self.settings.pause_music_in_background,
+                            )
+                            .on_click(cx.listener(|app, _event, _window, cx| {
+                                app.change_settings(cx, |settings| {
+                                    settings.pause_music_in_background = !settings.pause_music_in_background;
+                                });
+                            })),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(theme::current().muted_text))
+                                .child(trf(
+                                    "Plays .ogg and .wav tracks from {} in name order",
+                                    &[&music::default_dir().display()],
+                                )),
+                        )
+                })
+                .child(
+                    ui::render_toggle(
+                        "settings_text_board",
+                        tr("Text board (one line per pile, for screen readers)"),
+                        self.settings.text_board,
+                    )
+                    .on_click(cx.listener(|app, _event, _window, cx| {
+                        app.change_settings(cx, |settings| {
+                            settings.text_board = !settings.text_board;
+                        });
+                    })),
+                )
+                .child(
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(tr("Input:"))
+                        .children(InputMode::all().into_iter().map(|mode| {
+                            let label = if mode == self.settings.input_mode {
+                                format!("● {}", tr(mode.label()))
+                            } else {
+                                tr(mode.label()).to_string()
+                            };
+                            ui::render_button(
+                                ElementId::Name(format!("settings_input_{}", mode.name()).into()),
+                                label,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.dwell = Dwell::default();
+                                    app.dwell_task = None;
+                                    app.change_settings(cx, |settings| settings.input_mode = mode);
+                                },
+                            ))
+                        })),
+                )
+                .when(self.settings.input_mode == InputMode::Dwell, |dialog| {
+                    dialog.child(
+                        ui::row()
+                            .items_center()
+                            .gap_2()
+                            .text_sm()
+                            .child(tr("Rest on a pile for:"))
+                            .children(DWELL_TIMES.into_iter().map(|millis| {
+                                let time = Duration::from_millis(millis);
+                                let label = if time == self.settings.dwell_time {
+                                    format!("● {:.1} s", time.as_secs_f32())
+                                } else {
+                                    format!("{:.1} s", time.as_secs_f32())
+                                };
+                                ui::render_button(
+                                    ElementId::Name(format!("settings_dwell_{}", millis).into()),
+                                    label,
+                                )
+                                .on_click(cx.listener(
+                                    move |app, _event, _window, cx| {
+                                        app.change_settings(cx, |settings| {
+                                            settings.dwell_time = time;
+                                        });
+                                    },
+                                ))
+                            })),
+                    )
+                })
+                .when(self.settings.input_mode == InputMode::Scan, |dialog| {
+                    dialog.child(
+                        ui::row()
+                            .items_center()
+                            .gap_2()
+                            .text_sm()
+                            .child(tr("Stay on each pile for:"))
+                            .children(SCAN_INTERVALS.into_iter().map(|millis| {
+                                let interval = Duration::from_millis(millis);
+                                let label = if interval == self.settings.scan_interval {
+                                    format!("● {:.1} s", interval.as_secs_f32())
+                                } else {
+                                    format!("{:.1} s", interval.as_secs_f32())
+                                };
+                                ui::render_button(
+                                    ElementId::Name(format!("settings_scan_{}", millis).into()),
+                                    label,
+                                )
+                                .on_click(cx.listener(
+                                    move |app, _event, _window, cx| {
+                                        app.change_settings(cx, |settings| {
+                                            settings.scan_interval = interval
+                                        });
+                                    },
+                                ))
+                            })),
+                    )
+                })
+                .child(div().font_weight(FontWeight::BOLD).child(tr("Keyboard shortcuts")))
+                .children(Command::all().into_iter().map(|command| {
+                    let keys = self.keymap.keys(command);
+                    let bound = if keys.is_empty() {
+                        tr("None").to_string()
+                    } else {
+                        keys.iter()
+                            .map(|keystroke| shortcuts::describe(keystroke))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let change = if self.rebinding == Some(command) {
+                        tr("Press a key…")
+                    } else {
+                        tr("Change")
+                    };
+                    ui::row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(div().w(px(120.0)).child(tr(command.label())))
+                        .child(div().w(px(160.0)).font_weight(FontWeight::BOLD).child(bound))
+                        .child(
+                            ui::render_button(
+                                ElementId::Name(format!("settings_keys_{}", command.name()).into()),
+                                change,
+                            )
+                            .on_click(cx.listener(
+                                move |app, _event, _window, cx| {
+                                    app.rebinding = Some(command);
+                                    cx.notify();
+                                },
+                            )),
+                        )
+                }))
+                .child(
+                    ui::render_button("settings_keys_reset", tr("Restore Default Shortcuts"))
+                        .on_click(cx.listener(|app, _event, _window, cx| {
+                            app.rebinding = None;
+                            app.change_settings(cx, |settings| settings.key_bindings.clear());
+                        })),
+                )
+                .child(
+                    ui::render_button("settings_close", tr("Close")).on_click(cx.listener(
+                        |app, _event, _window, cx| {
+                            app.overlay = None;
+                            cx.notify();
+                        },
+                    )),
+                ),
+        )
+    }
+}
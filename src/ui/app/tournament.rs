@@ -0,0 +1,124 @@
+use super::{Abandon, Overlay, SolitaireApp};
+use crate::game::locale::{tr, trf};
+use crate::game::state::GameState;
+use crate::game::tournament::{DEFAULT_TOURNAMENT_DEALS, Tournament};
+use crate::ui;
+use gpui::{Context, FontWeight, IntoElement, div, prelude::*};
+
+impl SolitaireApp {
+    pub(super) fn start_tournament(&mut self, cx: &mut Context<Self>) {
+        if self.tournament.is_some() {
+            self.show_error("Finish the tournament before starting another", cx);
+            return;
+        }
+        if !self.confirm_abandon(Abandon::Tournament, cx) {
+            return;
+        }
+
+        self.record_unfinished_game();
+        let tournament =
+            Tournament::with_random_seeds(DEFAULT_TOURNAMENT_DEALS, self.game_state.draw_count);
+        if let Some(game_state) = tournament.current_game() {
+            self.game_state = game_state;
+            self.reset_game_setup();
+        }
+        self.tournament = Some(tournament);
+        self.daily = None;
+        self.overlay = None;
+        cx.notify();
+    }
+
+    /// Record the current deal (won or forfeited) and show the progress screen
+    pub(super) fn finish_tournament_deal(&mut self, cx: &mut Context<Self>) {
+        if let Some(tournament) = self.tournament.as_mut() {
+            if let Err(error) = tournament.record_deal(&self.game_state) {
+                self.show_error(error, cx);
+            }
+            self.overlay = Some(Overlay::TournamentProgress);
+        }
+        cx.notify();
+    }
+
+    /// Leave the progress screen: deal the next seed or wrap up the tournament
+    fn continue_tournament(&mut self, cx: &mut Context<Self>) {
+        self.overlay = None;
+
+        let next_game = self
+            .tournament
+            .as_ref()
+            .and_then(|tournament| tournament.current_game());
+        match next_game {
+            Some(game_state) => self.game_state = game_state,
+            None => {
+                if let Some(result) = self.tournament.take().and_then(|t| t.result()) {
+                    self.statistics.record_tournament(result);
+                    self.save_statistics();
+                }
+                self.game_state = GameState::new_with_draw_count(self.game_state.draw_count);
+            }
+        }
+        self.reset_game_setup();
+        cx.notify();
+    }
+
+    pub(super) fn render_tournament_progress(
+        &mut self,
+        tournament: &Tournament,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut rows = div().flex().flex_col().gap_1();
+        for (i, seed) in tournament.seeds().iter().enumerate() {
+            let outcome = match tournament.results().get(i) {
+                Some(result) => trf(
+                    "{} | Score {} | Moves {} | {}",
+                    &[
+                        &tr(if result.won { "Won" } else { "Lost" }),
+                        &result.score,
+                        &result.moves,
+                        &ui::format_duration(result.time),
+                    ],
+                ),
+                None => tr("Not played").to_string(),
+            };
+            rows = rows.child(
+                div()
+                    .text_sm()
+                    .child(trf("Deal {} (game #{}): {}", &[&(i + 1), seed, &outcome])),
+            );
+        }
+
+        let button_label = tr(if tournament.is_finished() {
+            "Finish Tournament"
+        } else {
+            "Next Deal"
+        });
+
+        ui::render_modal(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(div().text_xl().font_weight(FontWeight::BOLD).child(trf(
+                    "Tournament: {} of {} deals played",
+                    &[&tournament.results().len(), &tournament.seeds().len()],
+                )))
+                .child(rows)
+                .child(div().font_weight(FontWeight::BOLD).child(trf(
+                    "Total: {} won | Score {} | Moves {} | {}",
+                    &[
+                        &tournament.deals_won(),
+                        &tournament.total_score(),
+                        &tournament.total_moves(),
+                        &ui::format_duration(tournament.total_time()),
+                    ],
+                )))
+                .child(
+                    ui::render_button("tournament_continue", button_label).on_click(cx.listener(
+                        |app, _event, _window, cx| {
+                            app.continue_tournament(cx);
+                        },
+                    )),
+                ),
+        )
+    }
+}
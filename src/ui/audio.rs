@@ -0,0 +1,126 @@
+use crate::ui::sound::{Cue, SoundPack};
+use rodio::source::{self, Buffered, SineWave, Source};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// A sound file decoded once and played as often as needed
+type Recording = Buffered<Decoder<BufReader<File>>>;
+
+/// Loudness of the built-in tones, which are pure sine waves and carry
+/// further than recorded sounds
+const CUE_VOLUME: f32 = 0.2;
+
+/// The default sound output, kept open for the life of the app
+pub struct Audio {
+    /// Dropping the stream closes the device, so it is held alongside its
+    /// handle
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    /// Background music while it plays; dropping the sink stops it
+    music: Option<Sink>,
+    /// Sound pack recordings played in place of the tones for their cues
+    pack: Vec<(Cue, Recording)>,
+}
+
+impl Audio {
+    /// Open the default output device, or `None` on machines without one
+    pub fn new() -> Option<Self> {
+        match OutputStream::try_default() {
+            Ok((stream, handle)) => Some(Audio {
+                _stream: stream,
+                handle,
+                music: None,
+                pack: Vec::new(),
+            }),
+            Err(error) => {
+                println!("Sound is off, no output device: {}", error);
+                None
+            }
+        }
+    }
+
+    /// Start `cue` playing at `volume`, from 0 to 1; it plays out on its
+    /// own alongside other sounds
+    pub fn play(&self, cue: Cue, volume: f32) {
+        let Ok(sink) = Sink::try_new(&self.handle) else {
+            return;
+        };
+        match self.pack.iter().find(|(recorded, _)| *recorded == cue) {
+            Some((_, recording)) => sink.append(recording.clone().amplify(volume)),
+            None => {
+                for note in cue.notes() {
+                    sink.append(
+                        SineWave::new(note.frequency)
+                            .take_duration(note.duration)
+                            .amplify(CUE_VOLUME * volume),
+                    );
+                }
+            }
+        }
+        sink.detach();
+    }
+
+    /// Play `pack`'s recordings for the cues it has, or only the built-in
+    /// tones when `None`. Recordings that can't be decoded keep their tone.
+    pub fn use_pack(&mut self, pack: Option<&SoundPack>) {
+        self.pack = pack
+            .into_iter()
+            .flat_map(|pack| pack.sounds())
+            .filter_map(|(cue, path)| Some((cue, decode(path)?)))
+            .collect();
+    }
+
+    /// Loop `tracks` one after another at `volume`, from 0 to 1, in place of
+    /// any music playing. Tracks that can't be decoded are skipped.
+    pub fn play_music(&mut self, tracks: &[PathBuf], volume: f32) {
+        self.stop_music();
+        let decoded: Vec<Recording> = tracks.iter().filter_map(|path| decode(path)).collect();
+        if decoded.is_empty() {
+            return;
+        }
+        let Ok(sink) = Sink::try_new(&self.handle) else {
+            return;
+        };
+        sink.set_volume(volume);
+        // Each pass through the playlist plays fresh copies of the tracks
+        sink.append(source::from_iter(decoded.into_iter().cycle()));
+        self.music = Some(sink);
+    }
+
+    pub fn stop_music(&mut self) {
+        self.music = None;
+    }
+
+    pub fn set_music_volume(&self, volume: f32) {
+        if let Some(music) = &self.music {
+            music.set_volume(volume);
+        }
+    }
+
+    /// Hold the music where it is, or carry on from there
+    pub fn pause_music(&self, paused: bool) {
+        if let Some(music) = &self.music {
+            if paused {
+                music.pause();
+            } else {
+                music.play();
+            }
+        }
+    }
+}
+
+/// Read the sound file at `path`, or `None` with the reason printed
+fn decode(path: &Path) -> Option<Recording> {
+    let decoder = File::open(path)
+        .map_err(|error| error.to_string())
+        .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|error| error.to_string()));
+    match decoder {
+        Ok(decoder) => Some(decoder.buffered()),
+        Err(error) => {
+            println!("Skipping sound {}: {}", path.display(), error);
+            None
+        }
+    }
+}
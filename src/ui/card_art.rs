@@ -0,0 +1,174 @@
+use crate::game::deck::{Card, Suit};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Folders of recolored faces beside the standard artwork
+const FOUR_COLOR_DIR: &str = "four-color";
+const HIGH_CONTRAST_DIR: &str = "high-contrast";
+const HIGH_CONTRAST_FOUR_COLOR_DIR: &str = "high-contrast-four-color";
+
+/// Card artwork shipped in `assets/cards`: one SVG per face named by its
+/// card code, e.g. `10H.svg`, and `back.svg` for face-down cards. The
+/// subfolders hold faces redrawn for the other card styles.
+struct CardArt {
+    dir: PathBuf,
+    /// Paths present relative to `dir`, looked up once
+    files: HashSet<String>,
+}
+
+impl CardArt {
+    /// List the artwork in `dir` and its style subfolders
+    fn scan(dir: PathBuf) -> Self {
+        let mut files: HashSet<String> = file_names(&dir, "").collect();
+        for sub in [
+            FOUR_COLOR_DIR,
+            HIGH_CONTRAST_DIR,
+            HIGH_CONTRAST_FOUR_COLOR_DIR,
+        ] {
+            files.extend(file_names(&dir.join(sub), &format!("{}/", sub)));
+        }
+        CardArt { dir, files }
+    }
+
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        self.files.contains(name).then(|| self.dir.join(name))
+    }
+}
+
+/// How cards are drawn, chosen in settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardStyle {
+    /// Diamonds in blue and clubs in green
+    pub four_color: bool,
+    /// Deeper inks, heavier borders, and red suits drawn hollow so color is
+    /// never the only cue
+    pub high_contrast: bool,
+    /// Oversized rank and suit in the corner over a plain center, drawn
+    /// as text since the artwork's indices are small
+    pub large_index: bool,
+}
+
+thread_local! {
+    static STYLE: Cell<CardStyle> = const {
+        Cell::new(CardStyle {
+            four_color: false,
+            high_contrast: false,
+            large_index: false,
+        })
+    };
+    /// Artwork from the player's theme file, tried before the bundled art
+    static THEME_ART: RefCell<Option<CardArt>> = const { RefCell::new(None) };
+}
+
+/// Draw cards in `style` from here on; set once per frame
+pub fn set_style(style: CardStyle) {
+    STYLE.with(|current| current.set(style));
+}
+
+/// Draw faces from the theme folder `dir` where it has them, from here on;
+/// set once per frame. The folder is only listed again when it changes.
+pub fn set_theme_art(dir: Option<&Path>) {
+    THEME_ART.with(|art| {
+        let mut art = art.borrow_mut();
+        if art.as_ref().map(|art| art.dir.as_path()) != dir {
+            *art = dir.map(|dir| CardArt::scan(dir.to_path_buf()));
+        }
+    });
+}
+
+/// Style cards are being drawn in
+pub fn style() -> CardStyle {
+    STYLE.with(Cell::get)
+}
+
+/// Ink for a suit, used by every path that draws cards or suit symbols
+pub fn suit_color(suit: Suit) -> u32 {
+    let style = style();
+    match (suit, style.four_color, style.high_contrast) {
+        (Suit::Diamonds, true, false) => 0x2563EB,
+        (Suit::Diamonds, true, true) => 0x0033CC,
+        (Suit::Clubs, true, false) => 0x15803D,
+        (Suit::Clubs, true, true) => 0x006B00,
+        (Suit::Hearts | Suit::Diamonds, _, false) => 0xDC2626,
+        (Suit::Hearts | Suit::Diamonds, _, true) => 0xB00000,
+        (Suit::Clubs | Suit::Spades, _, false) => 0x111827,
+        (Suit::Clubs | Suit::Spades, _, true) => 0x000000,
+    }
+}
+
+/// Symbol for a suit; in high contrast the red suits are hollow
+pub fn suit_symbol(suit: Suit) -> &'static str {
+    match (suit, style().high_contrast) {
+        (Suit::Hearts, true) => "♡",
+        (Suit::Diamonds, true) => "♢",
+        (suit, _) => suit.symbol(),
+    }
+}
+
+fn file_names(dir: &Path, prefix: &str) -> impl Iterator<Item = String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .map(move |name| format!("{}{}", prefix, name))
+}
+
+/// The artwork folder, beside the executable for an installed build or in
+/// the source tree under `cargo run`. `None` when neither has it, and cards
+/// are drawn as text instead.
+fn card_art() -> Option<&'static CardArt> {
+    static CARD_ART: OnceLock<Option<CardArt>> = OnceLock::new();
+    CARD_ART
+        .get_or_init(|| {
+            let beside_exe = std::env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.parent()?.join("assets").join("cards")));
+            let in_source = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+                .join("cards");
+            beside_exe
+                .into_iter()
+                .chain([in_source])
+                .map(CardArt::scan)
+                .find(|art| !art.files.is_empty())
+        })
+        .as_ref()
+}
+
+/// Artwork for `card` as it lies, face or back, if it is installed
+pub fn image_path(card: Card) -> Option<PathBuf> {
+    if !card.face_up {
+        return back_path();
+    }
+    let style = style();
+    if style.large_index {
+        return None;
+    }
+    let name = format!("{}.svg", card.code());
+    let recolored = style.four_color && matches!(card.suit, Suit::Diamonds | Suit::Clubs);
+    let dir = match (recolored, style.high_contrast) {
+        (true, true) => Some(HIGH_CONTRAST_FOUR_COLOR_DIR),
+        (false, true) => Some(HIGH_CONTRAST_DIR),
+        (true, false) => Some(FOUR_COLOR_DIR),
+        (false, false) => None,
+    };
+    // Without the artwork for the style, the text drawing keeps its colors
+    match dir {
+        Some(dir) => art_file(format!("{}/{}", dir, name)),
+        None => art_file(name),
+    }
+}
+
+/// Artwork for the back of a card, if it is installed
+pub fn back_path() -> Option<PathBuf> {
+    art_file("back.svg".to_string())
+}
+
+fn art_file(name: String) -> Option<PathBuf> {
+    let themed = THEME_ART.with(|art| art.borrow().as_ref().and_then(|art| art.path(&name)));
+    themed.or_else(|| card_art()?.path(&name))
+}
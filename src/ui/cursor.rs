@@ -0,0 +1,329 @@
+use crate::game::pyramid;
+use crate::game::registry::{self, BoardLayout};
+use crate::game::state::{GameState, Position};
+
+/// Arrow key moving the board cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Keyboard focus on the board: the pile Enter acts on and, in a tableau
+/// column, the card. It moves between piles laid out as the board draws
+/// them: stock, waste and foundations in one row, the tableau in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardCursor {
+    pub position: Position,
+}
+
+impl Default for BoardCursor {
+    fn default() -> Self {
+        BoardCursor {
+            position: Position::Stock,
+        }
+    }
+}
+
+impl BoardCursor {
+    /// Move one step in `direction`, staying put at the board's edge
+    pub fn step(self, game_state: &GameState, direction: Direction) -> Self {
+        let position = match self.position {
+            Position::Tableau(col, index) if is_pyramid(game_state) => {
+                step_pyramid(game_state, col, index, direction)
+            }
+            Position::Tableau(col, index) => step_tableau(game_state, col, index, direction),
+            pile => step_top_row(game_state, pile, direction),
+        };
+        BoardCursor { position }.settle(game_state)
+    }
+
+    /// Keep the cursor on the board after it changes: on a face-up card
+    /// of its column, the top of its pile, or the stock if the pile is gone
+    pub fn settle(self, game_state: &GameState) -> Self {
+        let position = match self.position {
+            Position::Tableau(col, index) if col < game_state.tableau.len() => {
+                let column = &game_state.tableau[col];
+                let first_face_up = column
+                    .iter()
+                    .position(|card| card.face_up)
+                    .unwrap_or(column.len().saturating_sub(1));
+                let top = column.len().saturating_sub(1);
+                Position::Tableau(col, index.clamp(first_face_up.min(top), top))
+            }
+            Position::Foundation(foundation) if foundation < game_state.foundations.len() => {
+                Position::Foundation(foundation)
+            }
+            Position::Waste(_) => Position::Waste(game_state.waste.len().saturating_sub(1)),
+            _ => Position::Stock,
+        };
+        BoardCursor { position }
+    }
+
+    /// The next pile one-button scanning stops at, starting over after
+    /// the last
+    pub fn scan_next(self, game_state: &GameState) -> Self {
+        let stops = scan_stops(game_state);
+        let at = stops
+            .iter()
+            .position(|stop| same_pile(*stop, self.position));
+        let position = match at {
+            Some(at) => stops[(at + 1) % stops.len()],
+            None => stops[0],
+        };
+        BoardCursor { position }.settle(game_state)
+    }
+
+    /// Where cards picked up elsewhere land when put down at the cursor,
+    /// or `None` over the stock
+    pub fn drop_target(self, game_state: &GameState) -> Option<Position> {
+        match self.position {
+            Position::Tableau(col, _) => Some(Position::Tableau(
+                col,
+                game_state.tableau.get(col).map_or(0, Vec::len),
+            )),
+            Position::Foundation(foundation) => Some(Position::Foundation(foundation)),
+            Position::Waste(_) => Some(Position::Waste(game_state.waste.len().saturating_sub(1))),
+            Position::Stock => None,
+        }
+    }
+}
+
+/// Piles scanning stops at: the top row left to right, then each tableau
+/// column at its first face-up card, so picking it takes the whole run.
+/// The pyramid's empty slots are left out, as nothing goes there.
+fn scan_stops(game_state: &GameState) -> Vec<Position> {
+    let pyramid = is_pyramid(game_state);
+    let columns = game_state
+        .tableau
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| !(pyramid && column.is_empty()))
+        .map(|(col, column)| {
+            let first_face_up = column.iter().position(|card| card.face_up);
+            Position::Tableau(col, first_face_up.unwrap_or(0))
+        });
+    top_row(game_state).into_iter().chain(columns).collect()
+}
+
+/// Whether two positions are on the same pile
+fn same_pile(a: Position, b: Position) -> bool {
+    match (a, b) {
+        (Position::Tableau(a, _), Position::Tableau(b, _)) => a == b,
+        (Position::Waste(_), Position::Waste(_)) => true,
+        _ => a == b,
+    }
+}
+
+fn is_pyramid(game_state: &GameState) -> bool {
+    matches!(
+        registry::variants().layout(game_state.rules),
+        BoardLayout::Pyramid { .. }
+    )
+}
+
+/// Boards like Golf's draw the tableau above the stock and waste
+fn tableau_on_top(game_state: &GameState) -> bool {
+    registry::variants().layout(game_state.rules) == BoardLayout::StockBelow
+}
+
+/// Piles of the row holding the stock, left to right
+fn top_row(game_state: &GameState) -> Vec<Position> {
+    [Position::Stock, Position::Waste(0)]
+        .into_iter()
+        .chain((0..game_state.foundations.len()).map(Position::Foundation))
+        .collect()
+}
+
+/// Tableau column under a pile of the top row. Foundations line up with
+/// the rightmost columns, as they are drawn.
+fn column_below(game_state: &GameState, pile: Position) -> usize {
+    let columns = game_state.tableau.len();
+    let column = match pile {
+        Position::Waste(_) => 1,
+        Position::Foundation(foundation) => {
+            columns.saturating_sub(game_state.foundations.len()) + foundation
+        }
+        _ => 0,
+    };
+    if is_pyramid(game_state) {
+        // The pyramid's bottom row spans the board
+        return pyramid::slot(
+            pyramid::PYRAMID_ROWS - 1,
+            column.min(pyramid::PYRAMID_ROWS - 1),
+        );
+    }
+    column.min(columns.saturating_sub(1))
+}
+
+/// Top-row pile nearest above tableau column `col`
+fn pile_above(game_state: &GameState, col: usize) -> Position {
+    top_row(game_state)
+        .into_iter()
+        .min_by_key(|pile| column_below(game_state, *pile).abs_diff(col))
+        .unwrap_or(Position::Stock)
+}
+
+fn top_of(game_state: &GameState, col: usize) -> Position {
+    Position::Tableau(col, game_state.tableau[col].len().saturating_sub(1))
+}
+
+fn step_top_row(game_state: &GameState, pile: Position, direction: Direction) -> Position {
+    let row = top_row(game_state);
+    let at = row
+        .iter()
+        .position(|other| match (other, pile) {
+            (Position::Waste(_), Position::Waste(_)) => true,
+            _ => *other == pile,
+        })
+        .unwrap_or(0);
+    let towards_tableau = if tableau_on_top(game_state) {
+        Direction::Up
+    } else {
+        Direction::Down
+    };
+    match direction {
+        Direction::Left => row[at.saturating_sub(1)],
+        Direction::Right => row[(at + 1).min(row.len() - 1)],
+        direction if direction == towards_tableau && !game_state.tableau.is_empty() => {
+            top_of(game_state, column_below(game_state, pile))
+        }
+        _ => pile,
+    }
+}
+
+fn step_tableau(
+    game_state: &GameState,
+    col: usize,
+    index: usize,
+    direction: Direction,
+) -> Position {
+    let columns = game_state.tableau.len();
+    let first_face_up = game_state.tableau[col]
+        .iter()
+        .position(|card| card.face_up)
+        .unwrap_or(index);
+    match direction {
+        Direction::Left => top_of(game_state, col.saturating_sub(1)),
+        Direction::Right => top_of(game_state, (col + 1).min(columns - 1)),
+        // Only Golf's top cards play, so its columns are a single stop
+        Direction::Up if tableau_on_top(game_state) => Position::Tableau(col, index),
+        Direction::Down if tableau_on_top(game_state) => pile_above(game_state, col),
+        // Up and down pick how much of a fanned run to take, leaving the
+        // column for the top row past its first face-up card
+        Direction::Up if index > first_face_up => Position::Tableau(col, index - 1),
+        Direction::Up => pile_above(game_state, col),
+        Direction::Down => Position::Tableau(col, index + 1),
+    }
+}
+
+/// Pyramid slots step along their row, and up and down between rows; the
+/// bottom row leads on to the stock and waste
+fn step_pyramid(
+    game_state: &GameState,
+    col: usize,
+    index: usize,
+    direction: Direction,
+) -> Position {
+    let (row, place) = pyramid::row_and_place(col);
+    let slot = match direction {
+        Direction::Left => pyramid::slot(row, place.saturating_sub(1)),
+        Direction::Right => pyramid::slot(row, (place + 1).min(row)),
+        Direction::Up if row > 0 => pyramid::slot(row - 1, place.min(row - 1)),
+        Direction::Down if row + 1 < pyramid::PYRAMID_ROWS => pyramid::slot(row + 1, place),
+        Direction::Down => return pile_above(game_state, col),
+        Direction::Up => return Position::Tableau(col, index),
+    };
+    top_of(game_state, slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(position: Position) -> BoardCursor {
+        BoardCursor { position }
+    }
+
+    #[test]
+    fn test_cursor_moves_between_rows() {
+        let game_state = GameState::new_with_seed(7);
+        let start = BoardCursor::default();
+        assert_eq!(
+            start.step(&game_state, Direction::Left).position,
+            Position::Stock
+        );
+
+        // Down from the stock lands on the top card of the first column
+        let column = start.step(&game_state, Direction::Down);
+        assert_eq!(column.position, Position::Tableau(0, 0));
+        assert_eq!(
+            column.step(&game_state, Direction::Right).position,
+            Position::Tableau(1, 1)
+        );
+
+        // Foundations sit over the rightmost columns
+        let last = cursor(Position::Tableau(6, 6));
+        assert_eq!(
+            last.step(&game_state, Direction::Up).position,
+            Position::Foundation(3)
+        );
+        assert_eq!(
+            cursor(Position::Foundation(0))
+                .step(&game_state, Direction::Down)
+                .position,
+            Position::Tableau(3, 3)
+        );
+    }
+
+    #[test]
+    fn test_cursor_stays_on_face_up_cards() {
+        let game_state = GameState::new_with_seed(7);
+        // Only the top card of each column starts face up, so up leaves
+        // the column
+        let top = cursor(Position::Tableau(3, 3));
+        assert_eq!(
+            top.step(&game_state, Direction::Up).position,
+            Position::Foundation(0)
+        );
+        assert_eq!(
+            top.step(&game_state, Direction::Down).position,
+            Position::Tableau(3, 3)
+        );
+        assert_eq!(
+            cursor(Position::Tableau(3, 0)).settle(&game_state).position,
+            Position::Tableau(3, 3)
+        );
+        assert_eq!(top.drop_target(&game_state), Some(Position::Tableau(3, 4)));
+        assert_eq!(BoardCursor::default().drop_target(&game_state), None);
+    }
+
+    #[test]
+    fn test_scanning_visits_every_pile() {
+        let game_state = GameState::new_with_seed(7);
+        let mut scan = BoardCursor::default();
+        let mut visited = vec![scan.position];
+        loop {
+            scan = scan.scan_next(&game_state);
+            if scan.position == Position::Stock {
+                break;
+            }
+            visited.push(scan.position);
+        }
+        // Stock, waste, four foundations and seven columns
+        assert_eq!(visited.len(), 13);
+        assert_eq!(visited[1], Position::Waste(0));
+        assert_eq!(visited[2], Position::Foundation(0));
+        assert_eq!(visited[12], Position::Tableau(6, 6));
+
+        // From partway down a column, scanning moves on to the next one
+        assert_eq!(
+            cursor(Position::Tableau(2, 2))
+                .scan_next(&game_state)
+                .position,
+            Position::Tableau(3, 3)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::game::actions::DrawCount;
+use crate::game::deck::Card;
+use crate::game::state::GameState;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The cards of a deal before any move: tableau columns and stock order.
+///
+/// The text form names each pile on its own line, cards separated by spaces,
+/// with `#` starting a comment:
+///
+/// ```text
+/// T1: 7D
+/// T2: 9H 4D
+/// ...
+/// T7: 2S QC 5H 8D AS JH 3C
+/// stock: KD 6S ...
+/// ```
+///
+/// Columns list cards bottom to top; only the top card starts face up. Stock
+/// cards are listed in the order they are dealt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DealLayout {
+    pub tableau: [Vec<Card>; 7],
+    /// Next card to deal first
+    pub stock: Vec<Card>,
+}
+
+impl DealLayout {
+    /// Parse and validate a layout: all 52 cards, each exactly once
+    pub fn parse(text: &str) -> Result<DealLayout, String> {
+        let mut tableau: [Option<Vec<Card>>; 7] = Default::default();
+        let mut stock = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line_error = |message: String| format!("line {}: {}", i + 1, message);
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, cards) = line
+                .split_once(':')
+                .ok_or_else(|| line_error(format!("expected `<pile>: <cards>`, got `{}`", line)))?;
+            let cards = cards
+                .split_whitespace()
+                .map(Card::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(line_error)?;
+
+            let slot = match name.trim().to_ascii_lowercase().as_str() {
+                "stock" => &mut stock,
+                name => {
+                    let column = name
+                        .strip_prefix('t')
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| (1..=7).contains(n))
+                        .ok_or_else(|| line_error(format!("unknown pile `{}`", name)))?;
+                    &mut tableau[column - 1]
+                }
+            };
+            if slot.replace(cards).is_some() {
+                return Err(line_error(format!(
+                    "pile `{}` is listed twice",
+                    name.trim()
+                )));
+            }
+        }
+
+        let mut columns: [Vec<Card>; 7] = Default::default();
+        for (col, cards) in tableau.into_iter().enumerate() {
+            columns[col] = cards.ok_or_else(|| format!("column T{} is missing", col + 1))?;
+            if columns[col].is_empty() {
+                return Err(format!("column T{} has no cards", col + 1));
+            }
+        }
+        let layout = DealLayout {
+            tableau: columns,
+            stock: stock.unwrap_or_default(),
+        };
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Check that the layout holds exactly the 52 cards of one deck
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for card in self.tableau.iter().flatten().chain(&self.stock) {
+            if !seen.insert((card.suit, card.rank)) {
+                return Err(format!("{} appears more than once", card.code()));
+            }
+        }
+        if seen.len() != 52 {
+            return Err(format!("expected 52 cards, found {}", seen.len()));
+        }
+        Ok(())
+    }
+
+    /// Layout of a game that has not been played yet
+    pub fn from_game(game_state: &GameState) -> Result<DealLayout, String> {
+        if game_state.move_count > 0
+            || !game_state.waste.is_empty()
+            || game_state.foundations.iter().any(|pile| !pile.is_empty())
+        {
+            return Err("only an unplayed deal has a layout".to_string());
+        }
+
+        let face_down = |card: &Card| Card {
+            face_up: false,
+            ..*card
+        };
+        Ok(DealLayout {
+            tableau: std::array::from_fn(|col| {
+                game_state.tableau[col].iter().map(face_down).collect()
+            }),
+            stock: game_state.stock.iter().rev().map(face_down).collect(),
+        })
+    }
+
+    /// Build the starting position for this layout
+    pub fn into_game(self, draw_count: DrawCount) -> GameState {
+        let mut game_state = GameState::new_with_seed(0);
+        game_state.draw_count = draw_count;
+        game_state.imported = true;
+        game_state.tableau = self
+            .tableau
+            .map(|mut column| {
+                if let Some(top) = column.last_mut() {
+                    top.face_up = true;
+                }
+                column
+            })
+            .into();
+        // The stock deals from the end of the pile
+        game_state.stock = self.stock.into_iter().rev().collect();
+        game_state
+    }
+}
+
+impl fmt::Display for DealLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let codes = |cards: &[Card]| cards.iter().map(Card::code).collect::<Vec<_>>().join(" ");
+        for (col, column) in self.tableau.iter().enumerate() {
+            writeln!(f, "T{}: {}", col + 1, codes(column))?;
+        }
+        writeln!(f, "stock: {}", codes(&self.stock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::actions::GameAction;
+
+    #[test]
+    fn test_layout_round_trip() {
+        let game_state = GameState::new_with_seed(2036);
+        let layout = DealLayout::from_game(&game_state).unwrap();
+        let parsed = DealLayout::parse(&layout.to_string()).unwrap();
+        assert_eq!(parsed, layout);
+
+        let mut imported = parsed.into_game(game_state.draw_count);
+        assert!(imported.imported);
+        assert_eq!(imported.tableau, game_state.tableau);
+        assert_eq!(imported.stock, game_state.stock);
+
+        // Imported games play like any other
+        let mut original = game_state.clone();
+        original.handle_action(GameAction::DealFromStock).unwrap();
+        imported.handle_action(GameAction::DealFromStock).unwrap();
+        assert_eq!(imported.waste, original.waste);
+    }
+
+    #[test]
+    fn test_layout_accepts_any_column_lengths() {
+        let layout = DealLayout::from_game(&GameState::new_with_seed(5)).unwrap();
+        let mut cards: Vec<Card> = layout.tableau.iter().flatten().copied().collect();
+        cards.extend(&layout.stock);
+
+        let mut text = String::from("# one card per column, rest in stock\n");
+        for (col, card) in cards.iter().take(7).enumerate() {
+            text.push_str(&format!("t{}: {}\n", col + 1, card.code().to_lowercase()));
+        }
+        let stock: Vec<String> = cards[7..].iter().map(Card::code).collect();
+        text.push_str(&format!("Stock: {}\n", stock.join(" ")));
+
+        let game_state = DealLayout::parse(&text).unwrap().into_game(DrawCount::One);
+        assert!(game_state.tableau.iter().all(|column| column.len() == 1));
+        assert!(game_state.tableau.iter().all(|column| column[0].face_up));
+        assert_eq!(game_state.stock.len(), 45);
+    }
+
+    #[test]
+    fn test_layout_validation() {
+        let valid = DealLayout::from_game(&GameState::new_with_seed(8))
+            .unwrap()
+            .to_string();
+
+        // A card listed twice (and so one missing)
+        let first = valid.lines().next().unwrap().to_string();
+        let duplicated = valid.replacen("stock:", &format!("stock: {}", &first[4..]), 1);
+        let error = DealLayout::parse(&duplicated).unwrap_err();
+        assert!(error.contains("more than once"), "{}", error);
+
+        // A card missing from the stock
+        let short = valid.trim_end().rsplit_once(' ').unwrap().0.to_string();
+        let error = DealLayout::parse(&short).unwrap_err();
+        assert!(error.contains("expected 52 cards, found 51"), "{}", error);
+
+        assert!(DealLayout::parse(&valid.replace("T3:", "T8:")).is_err());
+        assert!(DealLayout::parse(&valid.replace("T3:", "T2:")).is_err());
+        assert!(DealLayout::parse(&valid.replacen("T1: ", "T1: ZZ ", 1)).is_err());
+        assert!(DealLayout::parse("").is_err());
+    }
+
+    #[test]
+    fn test_played_games_have_no_layout() {
+        let mut game_state = GameState::new_with_seed(3);
+        game_state.handle_action(GameAction::DealFromStock).unwrap();
+        assert!(DealLayout::from_game(&game_state).is_err());
+    }
+
+    #[test]
+    fn test_imported_deals_cannot_be_replayed_by_seed() {
+        let layout = DealLayout::from_game(&GameState::new_with_seed(4)).unwrap();
+        let mut game_state = layout.into_game(DrawCount::Three);
+        assert!(game_state.summary().contains("Imported deal"));
+        assert!(game_state.handle_action(GameAction::ReplaySeed).is_err());
+    }
+}
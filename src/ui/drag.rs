@@ -0,0 +1,165 @@
+use crate::game::state::Position;
+
+/// Furthest a press on a card can move and still count as a click when it
+/// is released, in pixels
+pub const CLICK_THRESHOLD: f32 = 6.0;
+
+/// The left button going down on a card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Press {
+    pub target: Position,
+    /// Window point it went down at
+    pub origin: (f32, f32),
+}
+
+/// Where picking cards up with the pointer has got to. A press on a card is
+/// a click when let go where it went down, or a drag once the cards are
+/// carried off. A drag ends in a drop on a pile, or is cancelled when let
+/// go anywhere else, and goes back to idle once the board has caught up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum DragState<T> {
+    #[default]
+    Idle,
+    Pressed(Press),
+    /// Carrying the cards described by `dragged`, picked up by `press`
+    /// unless the drag began some other way
+    Dragging {
+        dragged: T,
+        press: Option<Press>,
+    },
+    /// Let go over a pile, the move being played there
+    Dropping(T),
+    /// Let go away from every pile, so nothing moves
+    Cancelled,
+}
+
+impl<T> DragState<T> {
+    /// The pointer went down on the card at `target`
+    pub fn press(&mut self, target: Position, origin: (f32, f32)) {
+        *self = DragState::Pressed(Press { target, origin });
+    }
+
+    /// The pressed cards were carried off
+    pub fn start(&mut self, dragged: T) {
+        let press = match self {
+            DragState::Pressed(press) => Some(*press),
+            _ => None,
+        };
+        *self = DragState::Dragging { dragged, press };
+    }
+
+    /// The cards being carried were let go over a pile. Returns false, and
+    /// changes nothing, when none were.
+    pub fn drop(&mut self) -> bool {
+        match std::mem::take(self) {
+            DragState::Dragging { dragged, .. } => {
+                *self = DragState::Dropping(dragged);
+                true
+            }
+            other => {
+                *self = other;
+                false
+            }
+        }
+    }
+
+    /// The pointer was let go at `point`. Returns the pressed card when that
+    /// makes a click, even if a wobble had started a drag. Cards still being
+    /// carried weren't dropped on a pile, so their drag is cancelled.
+    pub fn release(&mut self, point: (f32, f32)) -> Option<Position> {
+        let clicked = |press: Press| {
+            let moved = (point.0 - press.origin.0).hypot(point.1 - press.origin.1);
+            (moved < CLICK_THRESHOLD).then_some(press.target)
+        };
+        match std::mem::take(self) {
+            DragState::Pressed(press) => clicked(press),
+            DragState::Dragging { press, .. } => {
+                *self = DragState::Cancelled;
+                press.and_then(clicked)
+            }
+            other => {
+                *self = other;
+                None
+            }
+        }
+    }
+
+    /// Cancel a drag that ended without the pointer being let go over the
+    /// board, such as when the window lost the pointer
+    pub fn cancel(&mut self) {
+        if matches!(self, DragState::Dragging { .. }) {
+            *self = DragState::Cancelled;
+        }
+    }
+
+    /// Go back to idle once a drop has been played or a cancelled drag
+    /// undrawn. Returns whether a drag had ended.
+    pub fn finish(&mut self) -> bool {
+        let ended = matches!(self, DragState::Dropping(_) | DragState::Cancelled);
+        if ended {
+            *self = DragState::Idle;
+        }
+        ended
+    }
+
+    /// The cards being carried, while they are
+    pub fn dragging(&self) -> Option<&T> {
+        match self {
+            DragState::Dragging { dragged, .. } => Some(dragged),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_press_released_in_place_clicks() {
+        let card = Position::Tableau(2, 4);
+        let mut drag: DragState<()> = DragState::default();
+        drag.press(card, (100.0, 50.0));
+        assert_eq!(drag.release((103.0, 52.0)), Some(card));
+        assert_eq!(drag, DragState::Idle);
+
+        drag.press(card, (100.0, 50.0));
+        assert_eq!(drag.release((100.0, 70.0)), None);
+        assert_eq!(drag, DragState::Idle);
+
+        // A wobble that started a drag still clicks
+        drag.press(card, (100.0, 50.0));
+        drag.start(());
+        assert_eq!(drag.release((102.0, 50.0)), Some(card));
+        assert_eq!(drag, DragState::Cancelled);
+    }
+
+    #[test]
+    fn test_drag_let_go_away_from_piles_is_cancelled() {
+        let mut drag = DragState::default();
+        drag.press(Position::Waste(0), (10.0, 10.0));
+        drag.start("ace");
+        assert_eq!(drag.dragging(), Some(&"ace"));
+
+        assert_eq!(drag.release((40.0, 10.0)), None);
+        assert_eq!(drag, DragState::Cancelled);
+        assert_eq!(drag.dragging(), None);
+        assert!(!drag.drop());
+        assert!(drag.finish());
+        assert_eq!(drag, DragState::Idle);
+        assert!(!drag.finish());
+    }
+
+    #[test]
+    fn test_drop_on_pile_plays_then_finishes() {
+        let mut drag = DragState::default();
+        drag.start("king");
+        assert!(drag.drop());
+        assert_eq!(drag, DragState::Dropping("king"));
+        // The release that dropped the cards doesn't cancel them
+        assert_eq!(drag.release((0.0, 0.0)), None);
+        assert_eq!(drag, DragState::Dropping("king"));
+        assert!(drag.finish());
+        assert_eq!(drag, DragState::Idle);
+    }
+}
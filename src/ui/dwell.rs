@@ -0,0 +1,90 @@
+use crate::game::clock::Clock;
+use std::time::Duration;
+
+/// Clicking by resting the pointer: a target clicks itself once the
+/// pointer has stayed on it long enough, then waits for the pointer to
+/// leave and come back before clicking again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dwell<T> {
+    target: Option<T>,
+    /// Clock time the pointer arrived on the target
+    since: Duration,
+    clicked: bool,
+}
+
+impl<T> Default for Dwell<T> {
+    fn default() -> Self {
+        Dwell {
+            target: None,
+            since: Duration::ZERO,
+            clicked: false,
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Dwell<T> {
+    /// The pointer is over `target`, or over nothing that clicks. Returns
+    /// true when that starts a new dwell.
+    pub fn hover(&mut self, target: Option<T>, clock: &impl Clock) -> bool {
+        if target == self.target {
+            return false;
+        }
+        *self = Dwell {
+            target,
+            since: clock.now(),
+            clicked: false,
+        };
+        target.is_some()
+    }
+
+    /// The target being dwelt on and how far through `duration` the
+    /// pointer has rested, from 0 to 1
+    pub fn progress(&self, duration: Duration, clock: &impl Clock) -> Option<(T, f32)> {
+        let target = self.target.filter(|_| !self.clicked)?;
+        let rested = clock.now().saturating_sub(self.since);
+        Some((
+            target,
+            (rested.as_secs_f32() / duration.as_secs_f32()).min(1.0),
+        ))
+    }
+
+    /// The target, once the pointer has rested on it for `duration`. Each
+    /// visit clicks only once.
+    pub fn take_click(&mut self, duration: Duration, clock: &impl Clock) -> Option<T> {
+        let (target, progress) = self.progress(duration, clock)?;
+        if progress < 1.0 {
+            return None;
+        }
+        self.clicked = true;
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::FrameClock;
+
+    #[test]
+    fn test_dwell_clicks_once_per_visit() {
+        let second = Duration::from_secs(1);
+        let mut clock = FrameClock::new(10);
+        let mut dwell = Dwell::default();
+        assert!(dwell.hover(Some(3), &clock));
+        assert!(!dwell.hover(Some(3), &clock));
+
+        clock = FrameClock::at_frame(10, 5);
+        assert_eq!(dwell.progress(second, &clock), Some((3, 0.5)));
+        assert_eq!(dwell.take_click(second, &clock), None);
+
+        clock = FrameClock::at_frame(10, 10);
+        assert_eq!(dwell.take_click(second, &clock), Some(3));
+        assert_eq!(dwell.take_click(second, &clock), None);
+        assert_eq!(dwell.progress(second, &clock), None);
+
+        // Moving off and back starts over
+        assert!(!dwell.hover(None, &clock));
+        assert!(dwell.hover(Some(3), &clock));
+        assert_eq!(dwell.progress(second, &clock), Some((3, 0.0)));
+    }
+}
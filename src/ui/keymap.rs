@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Modifiers a binding can hold, before its key. `secondary` is Cmd on
+/// macOS and Ctrl elsewhere.
+const MODIFIERS: [&str; 6] = ["secondary", "ctrl", "alt", "shift", "cmd", "fn"];
+
+/// Something the player can do with a keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    NewGame,
+    Undo,
+    Redo,
+    Hint,
+    Deal,
+    AutoComplete,
+    CollectAll,
+    Mute,
+    StatsHud,
+    NextMove,
+    PreviousMove,
+}
+
+impl Command {
+    pub fn all() -> [Command; 11] {
+        [
+            Command::NewGame,
+            Command::Undo,
+            Command::Redo,
+            Command::Hint,
+            Command::Deal,
+            Command::AutoComplete,
+            Command::CollectAll,
+            Command::Mute,
+            Command::StatsHud,
+            Command::NextMove,
+            Command::PreviousMove,
+        ]
+    }
+
+    /// Name used in the settings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::NewGame => "new_game",
+            Command::Undo => "undo",
+            Command::Redo => "redo",
+            Command::Hint => "hint",
+            Command::Deal => "deal",
+            Command::AutoComplete => "auto_complete",
+            Command::CollectAll => "collect_all",
+            Command::Mute => "mute",
+            Command::StatsHud => "stats_hud",
+            Command::NextMove => "next_move",
+            Command::PreviousMove => "previous_move",
+        }
+    }
+
+    /// Name shown in settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Command::NewGame => "New Game",
+            Command::Undo => "Undo",
+            Command::Redo => "Redo",
+            Command::Hint => "Hint",
+            Command::Deal => "Deal",
+            Command::AutoComplete => "Auto-complete",
+            Command::CollectAll => "Collect All",
+            Command::Mute => "Mute",
+            Command::StatsHud => "Quick Stats",
+            Command::NextMove => "Next Move",
+            Command::PreviousMove => "Previous Move",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Command::NewGame => &["secondary-n"],
+            Command::Undo => &["secondary-z"],
+            Command::Redo => &["secondary-shift-z", "secondary-y"],
+            Command::Hint => &["h"],
+            Command::Deal => &["space"],
+            Command::AutoComplete => &["a"],
+            Command::CollectAll => &["c"],
+            Command::Mute => &["m"],
+            Command::StatsHud => &["tab"],
+            Command::NextMove => &["n"],
+            Command::PreviousMove => &["shift-n"],
+        }
+    }
+}
+
+/// Keystrokes bound to each command, in the form gpui parses, e.g.
+/// `secondary-shift-z`. Stored as a table of command names, each with its
+/// keystrokes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "BTreeMap<String, Vec<String>>",
+    into = "BTreeMap<String, Vec<String>>"
+)]
+pub struct Keymap {
+    bindings: Vec<(Command, Vec<String>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: Command::all()
+                .into_iter()
+                .map(|command| {
+                    let keys = command.default_keys().iter().map(|key| key.to_string());
+                    (command, keys.collect())
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Keystrokes for `command`, the first being the one shown in menus
+    pub fn keys(&self, command: Command) -> &[String] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == command)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+
+    /// Every command with each of its keystrokes
+    pub fn iter(&self) -> impl Iterator<Item = (Command, &str)> {
+        self.bindings
+            .iter()
+            .flat_map(|(command, keys)| keys.iter().map(|key| (*command, key.as_str())))
+    }
+
+    /// Make `keystroke` the only one for `command`, taking it from any
+    /// other command it was bound to
+    pub fn rebind(&mut self, command: Command, keystroke: &str) -> Result<(), String> {
+        check_keystroke(keystroke)?;
+        for (bound, keys) in &mut self.bindings {
+            if *bound == command {
+                *keys = vec![keystroke.to_string()];
+            } else {
+                keys.retain(|key| key != keystroke);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Keymap> for BTreeMap<String, Vec<String>> {
+    fn from(keymap: Keymap) -> Self {
+        keymap
+            .bindings
+            .into_iter()
+            .map(|(command, keys)| (command.name().to_string(), keys))
+            .collect()
+    }
+}
+
+/// Read the commands a stored table binds, keeping the defaults for the rest
+impl TryFrom<BTreeMap<String, Vec<String>>> for Keymap {
+    type Error = String;
+
+    fn try_from(table: BTreeMap<String, Vec<String>>) -> Result<Self, String> {
+        let mut keymap = Keymap::default();
+        for (name, keys) in table {
+            let command = Command::all()
+                .into_iter()
+                .find(|command| command.name() == name)
+                .ok_or_else(|| format!("unknown command `{}`", name))?;
+            for keystroke in &keys {
+                check_keystroke(keystroke)?;
+            }
+            if let Some((_, bound_keys)) = keymap
+                .bindings
+                .iter_mut()
+                .find(|(bound, _)| *bound == command)
+            {
+                *bound_keys = keys;
+            }
+        }
+        Ok(keymap)
+    }
+}
+
+/// Split a keystroke into its modifiers and its key, e.g. `secondary-n`
+/// into `["secondary"]` and `n`. A trailing `-` is the minus key.
+pub fn split_keystroke(keystroke: &str) -> (Vec<&str>, &str) {
+    let (modifiers, key) = match keystroke.strip_suffix('-') {
+        Some(modifiers) if modifiers.is_empty() || modifiers.ends_with('-') => (modifiers, "-"),
+        _ => keystroke.rsplit_once('-').unwrap_or(("", keystroke)),
+    };
+    let modifiers = modifiers.split('-').filter(|part| !part.is_empty());
+    (modifiers.collect(), key)
+}
+
+/// Accept a single keystroke with known modifiers and a key
+fn check_keystroke(keystroke: &str) -> Result<(), String> {
+    let (modifiers, key) = split_keystroke(keystroke);
+    if key.is_empty() || key.contains(' ') {
+        return Err(format!("invalid keystroke `{}`", keystroke));
+    }
+    match modifiers
+        .into_iter()
+        .find(|modifier| !MODIFIERS.contains(modifier))
+    {
+        Some(modifier) => Err(format!(
+            "unknown modifier `{}` in `{}`",
+            modifier, keystroke
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::storage;
+
+    #[test]
+    fn test_rebind_takes_the_key_from_other_commands() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            keymap.keys(Command::Redo),
+            ["secondary-shift-z", "secondary-y"]
+        );
+
+        keymap.rebind(Command::Hint, "a").unwrap();
+        assert_eq!(keymap.keys(Command::Hint), ["a"]);
+        assert!(keymap.keys(Command::AutoComplete).is_empty());
+        assert_eq!(keymap.iter().filter(|(_, key)| *key == "a").count(), 1);
+
+        assert!(keymap.rebind(Command::Hint, "hyper-a").is_err());
+        assert!(keymap.rebind(Command::Hint, "").is_err());
+        assert_eq!(keymap.keys(Command::Hint), ["a"]);
+    }
+
+    #[test]
+    fn test_default_keys_are_not_shared() {
+        let keymap = Keymap::default();
+        let mut keys: Vec<&str> = keymap.iter().map(|(_, keystroke)| keystroke).collect();
+        let count = keys.len();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), count);
+        assert_eq!(keymap.keys(Command::StatsHud), ["tab"]);
+    }
+
+    #[test]
+    fn test_keymap_round_trip() {
+        let mut keymap = Keymap::default();
+        keymap.rebind(Command::Undo, "secondary--").unwrap();
+        keymap.rebind(Command::Deal, "d").unwrap();
+        let text = storage::to_text(&keymap).unwrap();
+        assert_eq!(storage::parse::<Keymap>(&text), Ok(keymap));
+
+        for bad in ["jump = [\"j\"]", "hint = \"h\"", "hint = [\"super-h\"]"] {
+            assert!(storage::parse::<Keymap>(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_split_keystroke() {
+        assert_eq!(split_keystroke("h"), (vec![], "h"));
+        assert_eq!(
+            split_keystroke("secondary-shift-z"),
+            (vec!["secondary", "shift"], "z")
+        );
+        assert_eq!(split_keystroke("ctrl--"), (vec!["ctrl"], "-"));
+        assert_eq!(split_keystroke("-"), (vec![], "-"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+/// Time an action or frame may take before a warning is logged
+pub const DEFAULT_LATENCY_BUDGET: Duration = Duration::from_millis(8);
+/// Most recent samples kept per label for percentiles
+const MAX_SAMPLES: usize = 1024;
+
+/// Percentiles of one label's recent samples, for the diagnostics screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencySummary {
+    pub label: &'static str,
+    /// Samples recorded since startup, including those no longer kept
+    pub count: usize,
+    pub over_budget: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct Series {
+    label: &'static str,
+    /// Ring buffer of the most recent samples
+    samples: Vec<Duration>,
+    next: usize,
+    count: usize,
+    over_budget: usize,
+}
+
+/// Per-label latency samples (e.g. each kind of action, rendering) checked
+/// against a budget
+#[derive(Debug, Clone)]
+pub struct LatencyMonitor {
+    budget: Duration,
+    series: Vec<Series>,
+}
+
+impl LatencyMonitor {
+    pub fn new(budget: Duration) -> Self {
+        LatencyMonitor {
+            budget,
+            series: Vec::new(),
+        }
+    }
+
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Record how long `label` took; returns a warning if it went over budget
+    pub fn record(&mut self, label: &'static str, elapsed: Duration) -> Option<String> {
+        let index = match self.series.iter().position(|series| series.label == label) {
+            Some(index) => index,
+            None => {
+                self.series.push(Series {
+                    label,
+                    samples: Vec::new(),
+                    next: 0,
+                    count: 0,
+                    over_budget: 0,
+                });
+                self.series.len() - 1
+            }
+        };
+        let series = &mut self.series[index];
+
+        if series.samples.len() < MAX_SAMPLES {
+            series.samples.push(elapsed);
+        } else {
+            series.samples[series.next] = elapsed;
+        }
+        series.next = (series.next + 1) % MAX_SAMPLES;
+        series.count += 1;
+
+        if elapsed <= self.budget {
+            return None;
+        }
+        series.over_budget += 1;
+        Some(format!(
+            "{} took {:.1} ms (budget {:.1} ms)",
+            label,
+            elapsed.as_secs_f64() * 1000.0,
+            self.budget.as_secs_f64() * 1000.0
+        ))
+    }
+
+    /// Percentiles for every label, in the order first recorded
+    pub fn summaries(&self) -> Vec<LatencySummary> {
+        self.series
+            .iter()
+            .map(|series| {
+                let mut sorted = series.samples.clone();
+                sorted.sort();
+                LatencySummary {
+                    label: series.label,
+                    count: series.count,
+                    over_budget: series.over_budget,
+                    p50: percentile(&sorted, 50),
+                    p95: percentile(&sorted, 95),
+                    p99: percentile(&sorted, 99),
+                    max: sorted.last().copied().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for LatencyMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_BUDGET)
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warns_only_over_budget() {
+        let mut monitor = LatencyMonitor::default();
+        assert_eq!(monitor.record("move", Duration::from_millis(8)), None);
+        let warning = monitor.record("move", Duration::from_millis(12)).unwrap();
+        assert_eq!(warning, "move took 12.0 ms (budget 8.0 ms)");
+
+        let summary = &monitor.summaries()[0];
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.over_budget, 1);
+    }
+
+    #[test]
+    fn test_percentiles_per_label() {
+        let mut monitor = LatencyMonitor::new(Duration::from_secs(1));
+        for ms in (1..=100).rev() {
+            monitor.record("render", Duration::from_millis(ms));
+        }
+        monitor.record("deal", Duration::from_millis(3));
+
+        let summaries = monitor.summaries();
+        assert_eq!(summaries.len(), 2);
+        let render = &summaries[0];
+        assert_eq!(render.label, "render");
+        assert_eq!(render.p50, Duration::from_millis(50));
+        assert_eq!(render.p95, Duration::from_millis(95));
+        assert_eq!(render.p99, Duration::from_millis(99));
+        assert_eq!(render.max, Duration::from_millis(100));
+        assert_eq!(summaries[1].p50, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_only_recent_samples_are_kept() {
+        let mut monitor = LatencyMonitor::new(Duration::from_secs(1));
+        monitor.record("move", Duration::from_millis(500));
+        for _ in 0..MAX_SAMPLES {
+            monitor.record("move", Duration::from_millis(1));
+        }
+        let summary = &monitor.summaries()[0];
+        assert_eq!(summary.count, MAX_SAMPLES + 1);
+        // The slow first sample has been overwritten
+        assert_eq!(summary.max, Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::ui::{CARD_HEIGHT, CARD_WIDTH, theme};
+use gpui::{Pixels, Size, px, size};
+use std::cell::Cell;
+
+/// Smallest window the game stays playable in, small enough in compact mode
+/// to dock beside other windows
+pub const MIN_WINDOW_WIDTH: f32 = 480.0;
+pub const MIN_WINDOW_HEIGHT: f32 = 400.0;
+/// Windows narrower than this pack the piles closer together
+const NARROW_WIDTH: f32 = 900.0;
+
+/// Smallest and largest the cards are drawn, relative to `CARD_WIDTH`
+const MIN_CARD_SCALE: f32 = 0.5;
+const MAX_CARD_SCALE: f32 = 2.0;
+/// Window space the cards can't use: padding, the header, menu and status
+/// bars, and the gap between the board's two rows
+const CHROME_WIDTH: f32 = 32.0;
+const CHROME_HEIGHT: f32 = 230.0;
+/// Compact mode drops the title, saving this much height
+const TITLE_HEIGHT: f32 = 44.0;
+/// In compact mode, the share of the usual fan step between tableau cards
+/// and of the usual size for the stock, waste and foundations
+const COMPACT_FAN: f32 = 0.6;
+const COMPACT_TOP_ROW: f32 = 0.75;
+/// Cards a tableau column is expected to fan out to when fitting the
+/// height; longer columns scroll
+const FIT_FAN_DEPTH: f32 = 12.0;
+
+/// How the board is laid out for the current window size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    /// How much larger than `CARD_WIDTH` cards are drawn
+    pub card_scale: f32,
+    /// Space between neighbouring piles
+    pub pile_gap: f32,
+    /// Space between the board's rows
+    pub row_gap: f32,
+    pub compact: bool,
+    /// How much larger than normal text is drawn
+    pub text_scale: f32,
+}
+
+impl BoardLayout {
+    /// Fit a board `columns` cards wide, over a tableau fanned
+    /// `FIT_FAN_DEPTH` cards deep, into a window of `window`. Larger text
+    /// makes the bars around the board taller, leaving less for the cards.
+    pub fn fit(window: Size<Pixels>, columns: usize, compact: bool, text_scale: f32) -> Self {
+        let width = f32::from(window.width);
+        let (pile_gap, row_gap) = if compact || width < NARROW_WIDTH {
+            (4.0, 8.0)
+        } else {
+            (8.0, 16.0)
+        };
+
+        let columns = columns.max(1) as f32;
+        let free_width = width - CHROME_WIDTH - pile_gap * (columns - 1.0);
+        let (chrome_height, fan, top_row) = if compact {
+            (CHROME_HEIGHT - TITLE_HEIGHT, COMPACT_FAN, COMPACT_TOP_ROW)
+        } else {
+            (CHROME_HEIGHT, 1.0, 1.0)
+        };
+        let free_height = f32::from(window.height) - chrome_height * text_scale;
+        // The top row is one card high, the tableau one card plus its fan
+        let board_height =
+            (1.0 + top_row) * CARD_HEIGHT + theme::current().tableau_offset * fan * FIT_FAN_DEPTH;
+        let card_scale = (free_width / (columns * CARD_WIDTH)).min(free_height / board_height);
+
+        BoardLayout {
+            card_scale: card_scale.clamp(MIN_CARD_SCALE, MAX_CARD_SCALE),
+            pile_gap,
+            row_gap,
+            compact,
+            text_scale,
+        }
+    }
+
+    /// Layout for the stock, waste and foundations, smaller in compact mode
+    pub fn top_row(self) -> Self {
+        if !self.compact {
+            return self;
+        }
+        BoardLayout {
+            card_scale: self.card_scale * COMPACT_TOP_ROW,
+            ..self
+        }
+    }
+}
+
+/// The smallest window size, for the window options
+pub fn min_window_size() -> Size<Pixels> {
+    size(px(MIN_WINDOW_WIDTH), px(MIN_WINDOW_HEIGHT))
+}
+
+thread_local! {
+    static CURRENT: Cell<BoardLayout> = const {
+        Cell::new(BoardLayout {
+            card_scale: 1.0,
+            pile_gap: 8.0,
+            row_gap: 16.0,
+            compact: false,
+            text_scale: 1.0,
+        })
+    };
+}
+
+/// Lay the board out with `layout` from here on; set once per frame
+pub fn set_current(layout: BoardLayout) {
+    CURRENT.with(|current| current.set(layout));
+}
+
+/// Layout the board is being drawn with
+pub fn current() -> BoardLayout {
+    CURRENT.with(Cell::get)
+}
+
+pub fn card_scale() -> f32 {
+    current().card_scale
+}
+
+pub fn card_width() -> f32 {
+    CARD_WIDTH * card_scale()
+}
+
+pub fn card_height() -> f32 {
+    CARD_HEIGHT * card_scale()
+}
+
+/// `size` in pixels, scaled by the text size setting
+pub fn text_size(size: f32) -> Pixels {
+    px(size * current().text_scale)
+}
+
+/// Text on the cards, which grows with the cards as well as the setting
+pub fn card_text_size(size: f32) -> Pixels {
+    text_size(size * card_scale())
+}
+
+/// Vertical step between the cards fanned down a tableau column
+pub fn tableau_offset() -> f32 {
+    let layout = current();
+    let fan = if layout.compact { COMPACT_FAN } else { 1.0 };
+    theme::current().tableau_offset * fan * layout.card_scale
+}
@@ -1,40 +1,66 @@
 use gpui::{
-    FontWeight, InteractiveElement, IntoElement, ParentElement, Styled, div, px, rgb, white,
+    AnyElement, App, Div, ElementId, FontWeight, InteractiveElement, IntoElement, ParentElement,
+    SharedString, Stateful, StatefulInteractiveElement, Styled, Window, div, img,
+    prelude::FluentBuilder, px, rgb, rgba, white,
 };
+use std::rc::Rc;
+use std::time::Duration;
 
+pub mod animation;
 pub mod app;
+pub mod audio;
+pub mod card_art;
+pub mod cursor;
+pub mod deal_layout;
+pub mod drag;
+pub mod dwell;
+pub mod keymap;
+pub mod latency;
+pub mod layout;
+pub mod motion;
+pub mod music;
+pub mod pile_view;
+pub mod shortcuts;
+pub mod sound;
+pub mod theme;
+pub mod theme_file;
+pub mod toast;
+pub mod tooltip;
+pub mod watch;
 
 use crate::game::deck::Card;
+use crate::game::locale;
 
-// Card dimensions in pixels
+// Card dimensions in pixels at the base scale
 pub const CARD_WIDTH: f32 = 80.0;
 pub const CARD_HEIGHT: f32 = 112.0;
 
 // Layout constants
 pub const TABLEAU_CARD_OFFSET: f32 = 20.0; // Vertical offset for stacked cards
 
+/// Height of the corner index in the large index style, about the fan step
+/// between tableau cards so the whole index shows on covered cards
+const LARGE_INDEX_SIZE: f32 = 20.0;
+
+/// Percentage between neighbouring stops of a slider
+const SLIDER_STEP: usize = 10;
+
 /// Render a single card with optional click handler and hover state
 pub fn render_card_interactive(
     card: Card,
     clickable: bool,
     _on_click: Option<fn()>,
 ) -> impl IntoElement {
-    let card_content = if !card.face_up {
-        // Face-down card - show card back pattern
-        div()
-            .size_full()
-            .bg(rgb(0x1E3A8A)) // Dark blue background
-            .flex()
-            .items_center()
-            .justify_center()
-            .child(div().text_color(white()).text_size(px(24.0)).child("🂠"))
+    let card_content = if let Some(path) = card_art::image_path(card) {
+        // Bundled artwork when installed, text otherwise
+        img(path).size_full().into_any_element()
+    } else if !card.face_up {
+        render_card_back()
+    } else if card_art::style().large_index {
+        render_large_index_face(card)
     } else {
         // Face-up card - show rank and suit
-        let text_color = if card.is_red() {
-            rgb(0xDC2626) // Red color for hearts and diamonds
-        } else {
-            rgb(0x000000) // Black color for clubs and spades
-        };
+        let text_color = rgb(card_art::suit_color(card.suit));
 
         div()
             .size_full()
@@ -45,7 +71,7 @@ pub fn render_card_interactive(
                 div()
                     .text_color(text_color)
                     .font_weight(FontWeight::BOLD)
-                    .text_size(px(14.0))
+                    .text_size(layout::card_text_size(14.0))
                     .child(card.rank.display()),
             )
             .child(
@@ -53,8 +79,8 @@ pub fn render_card_interactive(
                 div().flex_1().flex().items_center().justify_center().child(
                     div()
                         .text_color(text_color)
-                        .text_size(px(32.0))
-                        .child(card.suit.symbol()),
+                        .text_size(layout::card_text_size(32.0))
+                        .child(card_art::suit_symbol(card.suit)),
                 ),
             )
             .child(
@@ -65,16 +91,18 @@ pub fn render_card_interactive(
                     .justify_end()
                     .text_color(text_color)
                     .font_weight(FontWeight::BOLD)
-                    .text_size(px(14.0))
+                    .text_size(layout::card_text_size(14.0))
                     .child(card.rank.display()),
             )
+            .into_any_element()
     };
 
     let mut card_div = div()
-        .w(px(CARD_WIDTH))
-        .h(px(CARD_HEIGHT))
+        .w(px(layout::card_width()))
+        .h(px(layout::card_height()))
         .bg(white())
         .border_2()
+        .when(card_art::style().high_contrast, |card| card.border_4())
         .border_color(rgb(0x000000))
         .rounded_md()
         .shadow_lg();
@@ -94,11 +122,65 @@ pub fn render_card_interactive(
     card_div.child(card_content)
 }
 
+/// Face of a card in the large index style: rank and suit big enough to
+/// read in the strip a fanned card shows, over a single suit symbol
+fn render_large_index_face(card: Card) -> AnyElement {
+    let text_color = rgb(card_art::suit_color(card.suit));
+    div()
+        .size_full()
+        .flex()
+        .flex_col()
+        .px_1()
+        .text_color(text_color)
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .gap_0p5()
+                .font_weight(FontWeight::EXTRA_BOLD)
+                .text_size(layout::card_text_size(LARGE_INDEX_SIZE))
+                .line_height(layout::card_text_size(LARGE_INDEX_SIZE))
+                .child(card.rank.display())
+                .child(card_art::suit_symbol(card.suit)),
+        )
+        .child(
+            div()
+                .flex_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .text_size(layout::card_text_size(40.0))
+                .child(card_art::suit_symbol(card.suit)),
+        )
+        .into_any_element()
+}
+
+/// Render the back of a card, filling its parent: the bundled artwork when
+/// installed, otherwise a plain pattern
+pub fn render_card_back() -> AnyElement {
+    if let Some(path) = card_art::back_path() {
+        return img(path).size_full().into_any_element();
+    }
+    div()
+        .size_full()
+        .bg(rgb(0x1E3A8A)) // Dark blue background
+        .flex()
+        .items_center()
+        .justify_center()
+        .child(
+            div()
+                .text_color(white())
+                .text_size(layout::card_text_size(24.0))
+                .child("🂠"),
+        )
+        .into_any_element()
+}
+
 /// Render an empty pile placeholder with visual indicator
 pub fn render_empty_pile(label: &'static str) -> impl IntoElement {
     div()
-        .w(px(CARD_WIDTH))
-        .h(px(CARD_HEIGHT))
+        .w(px(layout::card_width()))
+        .h(px(layout::card_height()))
         .bg(rgb(0x1F2937)) // Dark gray background
         .border_2()
         .border_color(rgb(0x4B5563)) // Lighter gray border
@@ -110,7 +192,7 @@ pub fn render_empty_pile(label: &'static str) -> impl IntoElement {
         .child(
             div()
                 .text_color(rgb(0x9CA3AF)) // Light gray text
-                .text_size(px(12.0))
+                .text_size(layout::text_size(12.0))
                 .font_weight(FontWeight::MEDIUM)
                 .child(label),
         )
@@ -120,3 +202,129 @@ pub fn render_empty_pile(label: &'static str) -> impl IntoElement {
 pub fn render_card(card: Card) -> impl IntoElement {
     render_card_interactive(card, false, None::<fn()>)
 }
+
+/// Render a small text button for menus and dialogs; attach `.on_click` to make it act
+pub fn render_button(id: impl Into<ElementId>, label: impl Into<SharedString>) -> Stateful<Div> {
+    let theme = theme::current();
+    div()
+        .id(id)
+        .px_3()
+        .py_1()
+        .bg(rgb(theme.control))
+        .border_1()
+        .border_color(rgb(theme.control_border))
+        .rounded_md()
+        .text_sm()
+        .text_color(rgb(theme.text))
+        .cursor_pointer()
+        .hover(move |style| style.border_color(rgb(theme.accent)))
+        .child(label.into())
+}
+
+/// A row of controls, running from the right in right-to-left languages
+pub fn row() -> Div {
+    div()
+        .flex()
+        .when(locale::is_rtl(), |row| row.flex_row_reverse())
+}
+
+/// Render a labelled checkbox-style toggle; attach `.on_click` to flip it
+pub fn render_toggle(
+    id: impl Into<ElementId>,
+    label: impl Into<SharedString>,
+    checked: bool,
+) -> Stateful<Div> {
+    let theme = theme::current();
+    div()
+        .id(id)
+        .flex()
+        .items_center()
+        .gap_2()
+        .text_sm()
+        .text_color(rgb(theme.text))
+        .cursor_pointer()
+        .when(locale::is_rtl(), |toggle| toggle.flex_row_reverse())
+        .child(
+            div()
+                .w(px(16.0))
+                .h(px(16.0))
+                .border_1()
+                .border_color(rgb(theme.muted_text))
+                .rounded_sm()
+                .flex()
+                .items_center()
+                .justify_center()
+                .when(checked, |checkbox| {
+                    checkbox
+                        .bg(rgb(theme.accent))
+                        .text_color(white())
+                        .child(div().text_xs().child("✓"))
+                }),
+        )
+        .child(label.into())
+}
+
+/// Render a slider for a percentage as a track of stops a tenth apart, the
+/// first a narrow one for off; `on_pick` hears the percentage clicked
+pub fn render_slider(
+    id: &str,
+    percent: u32,
+    on_pick: impl Fn(&u32, &mut Window, &mut App) + 'static,
+) -> Div {
+    let theme = theme::current();
+    let on_pick = Rc::new(on_pick);
+    row()
+        .items_center()
+        .gap(px(2.0))
+        .children((0..=100).step_by(SLIDER_STEP).map(|stop: u32| {
+            let on_pick = on_pick.clone();
+            let filled = stop > 0 && stop <= percent;
+            div()
+                .id(ElementId::Name(format!("{}_{}", id, stop).into()))
+                .w(px(if stop == 0 { 6.0 } else { 14.0 }))
+                .h(px(14.0))
+                .rounded_sm()
+                .border_1()
+                .border_color(rgb(theme.control_border))
+                .bg(rgb(if filled { theme.accent } else { theme.control }))
+                .cursor_pointer()
+                .hover(move |style| style.border_color(rgb(theme.accent)))
+                .on_click(move |_event, window, cx| on_pick(&stop, window, cx))
+        }))
+        .child(div().ml_2().text_sm().child(format!("{}%", percent)))
+}
+
+/// Render a modal: dims everything behind it and centers `content` in a panel
+pub fn render_modal(content: impl IntoElement) -> Div {
+    let theme = theme::current();
+    div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(rgba(theme.backdrop))
+        .occlude()
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .p_6()
+                .bg(rgb(theme.panel))
+                .border_2()
+                .border_color(rgb(theme.panel_border))
+                .rounded_lg()
+                .text_color(rgb(theme.text))
+                .when(locale::is_rtl(), |panel| panel.text_right())
+                .child(content),
+        )
+}
+
+/// Format a duration as `m:ss` for status and result displays
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,50 @@
+use std::process::Command;
+
+/// Whether the operating system asks apps to cut down on motion.
+///
+/// Read once at startup from the platform's own tools, without pulling in
+/// an extra crate: `defaults` on macOS and `gsettings` on GNOME-style
+/// Linux desktops. Anywhere else, or if the tool is missing, motion is left
+/// on.
+pub fn system_prefers_reduced_motion() -> bool {
+    let (program, args, reduced) = if cfg!(target_os = "macos") {
+        (
+            "defaults",
+            ["read", "com.apple.universalaccess", "reduceMotion"],
+            "1",
+        )
+    } else if cfg!(target_os = "linux") {
+        (
+            "gsettings",
+            ["get", "org.gnome.desktop.interface", "enable-animations"],
+            "false",
+        )
+    } else {
+        return false;
+    };
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| reply_matches(&output.stdout, reduced))
+}
+
+/// True when a tool's reply is `reduced`, ignoring surrounding whitespace
+fn reply_matches(reply: &[u8], reduced: &str) -> bool {
+    String::from_utf8_lossy(reply).trim() == reduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_matches() {
+        assert!(reply_matches(b"1\n", "1"));
+        assert!(!reply_matches(b"0\n", "1"));
+        assert!(reply_matches(b"false\n", "false"));
+        assert!(!reply_matches(b"true\n", "false"));
+        assert!(!reply_matches(b"", "false"));
+    }
+}
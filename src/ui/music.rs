@@ -0,0 +1,49 @@
+use crate::game::storage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File types background music is played from
+const MUSIC_EXTENSIONS: [&str; 2] = ["ogg", "wav"];
+
+/// Folder players drop music tracks into
+pub fn default_dir() -> PathBuf {
+    storage::config_dir().join("music")
+}
+
+/// Every track in `dir` in name order, played one after another and looped
+pub fn tracks(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut tracks: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| MUSIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_in_name_order() {
+        let dir =
+            std::env::temp_dir().join(format!("vibe-solitaire-test-{}-music", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["b.wav", "a.OGG", "notes.txt", "cover"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(tracks(&dir), [dir.join("a.OGG"), dir.join("b.wav")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(tracks(&dir).is_empty());
+    }
+}
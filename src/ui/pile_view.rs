@@ -0,0 +1,82 @@
+use crate::game::deck::Card;
+use crate::game::settings::{Accommodations, Settings};
+use crate::game::state::{Pile, Position};
+use crate::game::variant::Variant;
+use crate::ui::app::SolitaireApp;
+use crate::ui::layout::BoardLayout;
+use crate::ui::theme::Theme;
+use crate::ui::theme_file::ThemeFile;
+use gpui::{
+    Context, Entity, IntoElement, ParentElement, Render, Styled, Subscription, WeakEntity, Window,
+    div,
+};
+
+/// One pile of the board drawn as a view of its own: a tableau column, a
+/// foundation, the stock or the waste. The app only lets a pile draw again
+/// when what it shows has changed, so a move redraws the piles it touched
+/// instead of the whole board.
+pub struct PileView {
+    pile: Pile,
+    app: WeakEntity<SolitaireApp>,
+    /// What the pile showed when it was last drawn
+    look: Option<PileLook>,
+    /// Draws the pile again whenever the shared settings change
+    _settings_changed: Subscription,
+}
+
+/// Everything a pile is drawn from: its cards as the board shows them, the
+/// highlights on it, and the theme and size cards are drawn with. Settings
+/// aren't part of it: the view redraws itself when they change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PileLook {
+    pub cards: Vec<Card>,
+    /// Whether the cards being dragged may be dropped here
+    pub drop_target: bool,
+    /// Buried card whose fan is opened up, when it is in this pile
+    pub peek: Option<Position>,
+    /// Tooltip of the stock and foundations
+    pub label: String,
+    /// Whether the stock won't come round again once it is dealt
+    pub last_pass: bool,
+    /// Cards of the waste fanned out beside its top card
+    pub fan: usize,
+    pub rules: Variant,
+    pub accommodations: Accommodations,
+    pub theme_file: Option<ThemeFile>,
+    pub theme: Theme,
+    pub layout: BoardLayout,
+}
+
+impl PileView {
+    pub fn new(
+        pile: Pile,
+        app: WeakEntity<SolitaireApp>,
+        settings: &Entity<Settings>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        PileView {
+            pile,
+            app,
+            look: None,
+            _settings_changed: cx.observe(settings, |_view, _settings, cx| cx.notify()),
+        }
+    }
+
+    /// Take `look` as what the pile shows, returning whether it differs
+    /// from what was last drawn
+    pub fn show(&mut self, look: PileLook) -> bool {
+        let changed = self.look.as_ref() != Some(&look);
+        self.look = Some(look);
+        changed
+    }
+}
+
+impl Render for PileView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let pile = self.pile;
+        // The pile's listeners belong to the app, so it draws the pile. Laid
+        // out as a flex box, a column stretches to the tallest one.
+        let element = self.app.update(cx, |app, cx| app.render_pile(pile, cx));
+        div().flex().children(element.ok())
+    }
+}
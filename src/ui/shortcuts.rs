@@ -0,0 +1,148 @@
+use crate::game::locale::tr;
+use crate::ui::keymap::{self, Command, Keymap};
+use gpui::{Action, App, ClickEvent, KeyBinding, Keystroke, Menu, MenuItem, Window, actions};
+
+/// Key context the shortcuts apply in, set on the game's root element
+pub const CONTEXT: &str = "Solitaire";
+
+/// Name of the `secondary` modifier, for showing shortcuts
+pub const SECONDARY: &str = if cfg!(target_os = "macos") {
+    "Cmd"
+} else {
+    "Ctrl"
+};
+
+/// Keys that only modify others, never recorded as a shortcut on their own
+const MODIFIER_KEYS: [&str; 6] = ["shift", "control", "alt", "platform", "function", "cmd"];
+
+// Game commands. Keyboard shortcuts, the window's menu and the menu bar's
+// buttons all dispatch these, and the game's root element handles them.
+actions!(
+    solitaire,
+    [
+        NewGame,
+        Undo,
+        Redo,
+        Hint,
+        Deal,
+        AutoComplete,
+        CollectAll,
+        ToggleMute,
+        ToggleStatsHud,
+        NextMove,
+        PreviousMove
+    ]
+);
+
+/// Bind the game's keyboard shortcuts from `keymap`, replacing any bound
+/// before, and refresh the window's menu to show them. `secondary` is Cmd
+/// on macOS and Ctrl elsewhere.
+pub fn bind_keys(keymap: &Keymap, cx: &mut App) {
+    cx.clear_key_bindings();
+    cx.bind_keys(
+        keymap
+            .iter()
+            .map(|(command, keystroke)| match command {
+                Command::NewGame => KeyBinding::new(keystroke, NewGame, Some(CONTEXT)),
+                Command::Undo => KeyBinding::new(keystroke, Undo, Some(CONTEXT)),
+                Command::Redo => KeyBinding::new(keystroke, Redo, Some(CONTEXT)),
+                Command::Hint => KeyBinding::new(keystroke, Hint, Some(CONTEXT)),
+                Command::Deal => KeyBinding::new(keystroke, Deal, Some(CONTEXT)),
+                Command::AutoComplete => KeyBinding::new(keystroke, AutoComplete, Some(CONTEXT)),
+                Command::CollectAll => KeyBinding::new(keystroke, CollectAll, Some(CONTEXT)),
+                Command::Mute => KeyBinding::new(keystroke, ToggleMute, Some(CONTEXT)),
+                Command::StatsHud => KeyBinding::new(keystroke, ToggleStatsHud, Some(CONTEXT)),
+                Command::NextMove => KeyBinding::new(keystroke, NextMove, Some(CONTEXT)),
+                Command::PreviousMove => KeyBinding::new(keystroke, PreviousMove, Some(CONTEXT)),
+            })
+            .collect::<Vec<_>>(),
+    );
+    cx.set_menus(menus());
+}
+
+/// The window's Game menu, each item showing the shortcut bound to it
+pub fn menus() -> Vec<Menu> {
+    vec![Menu {
+        name: tr("Game").into(),
+        items: vec![
+            item(Command::NewGame, NewGame),
+            MenuItem::separator(),
+            item(Command::Undo, Undo),
+            item(Command::Redo, Redo),
+            MenuItem::separator(),
+            item(Command::Deal, Deal),
+            item(Command::Hint, Hint),
+            item(Command::AutoComplete, AutoComplete),
+            item(Command::CollectAll, CollectAll),
+            MenuItem::separator(),
+            item(Command::Mute, ToggleMute),
+        ],
+    }]
+}
+
+/// Click handler for a button that runs `action`, dispatched from the
+/// focused element just as its keyboard shortcut is
+pub fn dispatch(action: impl Action) -> impl Fn(&ClickEvent, &mut Window, &mut App) + 'static {
+    move |_event, window, cx| window.dispatch_action(action.boxed_clone(), cx)
+}
+
+/// A keymap keystroke as players read it, e.g. `secondary-shift-z` as
+/// "Ctrl+Shift+Z"
+pub fn describe(keystroke: &str) -> String {
+    let (modifiers, key) = keymap::split_keystroke(keystroke);
+    let mut parts: Vec<String> = modifiers
+        .into_iter()
+        .map(|modifier| match modifier {
+            "secondary" => SECONDARY.to_string(),
+            "fn" => "Fn".to_string(),
+            modifier => capitalize(modifier),
+        })
+        .collect();
+    parts.push(capitalize(key));
+    parts.join("+")
+}
+
+/// The keystroke pressed, in the keymap's form, or `None` for a modifier
+/// pressed on its own
+pub fn record(keystroke: &Keystroke) -> Option<String> {
+    let key = keystroke.key.as_str();
+    if MODIFIER_KEYS.contains(&key) {
+        return None;
+    }
+    let modifiers = &keystroke.modifiers;
+    let mac = cfg!(target_os = "macos");
+    let mut parts = Vec::new();
+    if modifiers.secondary() {
+        parts.push("secondary");
+    }
+    if modifiers.control && mac {
+        parts.push("ctrl");
+    }
+    if modifiers.platform && !mac {
+        parts.push("cmd");
+    }
+    if modifiers.alt {
+        parts.push("alt");
+    }
+    if modifiers.shift {
+        parts.push("shift");
+    }
+    if modifiers.function {
+        parts.push("fn");
+    }
+    parts.push(key);
+    Some(parts.join("-"))
+}
+
+/// A menu item running `action`, named after `command`
+fn item(command: Command, action: impl Action) -> MenuItem {
+    MenuItem::action(tr(command.label()), action)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,289 @@
+use crate::game::events::GameEvent;
+use crate::game::storage;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File types a sound pack's sounds are read from, in order of preference
+const SOUND_EXTENSIONS: [&str; 2] = ["wav", "ogg"];
+
+/// Something that happened in play with a sound of its own, so players can
+/// follow the game by ear as well as by eye
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Cards went where they were put
+    Move,
+    /// A move was refused
+    Invalid,
+    /// A face-down card was turned up
+    Flip,
+    /// Cards were dealt from the stock
+    Deal,
+    /// The game was won
+    Win,
+    /// The game was won faster than ever before
+    RecordWin,
+    /// The game was won without undoing a move
+    NoUndoWin,
+}
+
+/// One tone of a cue
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    /// Pitch in hertz
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+const fn note(frequency: f32, millis: u64) -> Note {
+    Note {
+        frequency,
+        duration: Duration::from_millis(millis),
+    }
+}
+
+impl Cue {
+    pub fn all() -> [Cue; 7] {
+        [
+            Cue::Move,
+            Cue::Invalid,
+            Cue::Flip,
+            Cue::Deal,
+            Cue::Win,
+            Cue::RecordWin,
+            Cue::NoUndoWin,
+        ]
+    }
+
+    /// Name used in settings and sound files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cue::Move => "move",
+            Cue::Invalid => "invalid",
+            Cue::Flip => "flip",
+            Cue::Deal => "deal",
+            Cue::Win => "win",
+            Cue::RecordWin => "record_win",
+            Cue::NoUndoWin => "no_undo_win",
+        }
+    }
+
+    /// The built-in tones, played one after another. Each cue differs in
+    /// pitch and rhythm so none is mistaken for another: a single tick for a
+    /// move, a falling buzz for a refusal, a rising pair for a flip, a
+    /// quick run for a deal and an arpeggio for a win, longer and higher for
+    /// the better wins.
+    pub fn notes(&self) -> &'static [Note] {
+        const MOVE: &[Note] = &[note(660.0, 45)];
+        const INVALID: &[Note] = &[note(220.0, 90), note(165.0, 140)];
+        const FLIP: &[Note] = &[note(880.0, 40), note(1175.0, 60)];
+        const DEAL: &[Note] = &[note(440.0, 25), note(523.0, 25), note(587.0, 25)];
+        const WIN: &[Note] = &[
+            note(523.0, 120),
+            note(659.0, 120),
+            note(784.0, 120),
+            note(1047.0, 320),
+        ];
+        const RECORD_WIN: &[Note] = &[
+            note(523.0, 100),
+            note(659.0, 100),
+            note(784.0, 100),
+            note(1047.0, 100),
+            note(1319.0, 100),
+            note(1568.0, 100),
+            note(2093.0, 420),
+        ];
+        const NO_UNDO_WIN: &[Note] = &[
+            note(523.0, 110),
+            note(659.0, 110),
+            note(784.0, 110),
+            note(1047.0, 110),
+            note(1319.0, 360),
+        ];
+        match self {
+            Cue::Move => MOVE,
+            Cue::Invalid => INVALID,
+            Cue::Flip => FLIP,
+            Cue::Deal => DEAL,
+            Cue::Win => WIN,
+            Cue::RecordWin => RECORD_WIN,
+            Cue::NoUndoWin => NO_UNDO_WIN,
+        }
+    }
+
+    /// Fanfare for a win, by how well it went: a record time beats going
+    /// without undo, which beats an ordinary win
+    pub fn fanfare(record_time: bool, undone: bool) -> Cue {
+        if record_time {
+            Cue::RecordWin
+        } else if !undone {
+            Cue::NoUndoWin
+        } else {
+            Cue::Win
+        }
+    }
+
+    /// Cue for a move that did `events`
+    pub fn for_events(events: &[GameEvent]) -> Cue {
+        let happened = |wanted: fn(&GameEvent) -> bool| events.iter().any(wanted);
+        if happened(|event| *event == GameEvent::GameWon) {
+            Cue::Win
+        } else if happened(|event| {
+            matches!(
+                event,
+                GameEvent::StockDealt { .. } | GameEvent::WasteTurnedOver
+            )
+        }) {
+            Cue::Deal
+        } else if happened(|event| matches!(event, GameEvent::CardFlipped { .. })) {
+            Cue::Flip
+        } else {
+            Cue::Move
+        }
+    }
+}
+
+/// Recorded sounds in place of the built-in tones: a folder in the sounds
+/// folder holding a file per cue named after it, e.g. `move.wav` or
+/// `win.ogg`. Cues without a file keep their tone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundPack {
+    /// The folder's name, chosen in settings
+    pub name: String,
+    sounds: Vec<(Cue, PathBuf)>,
+}
+
+impl SoundPack {
+    /// Folder players drop sound packs into
+    pub fn default_dir() -> PathBuf {
+        storage::config_dir().join("sounds")
+    }
+
+    /// Names of the packs in `dir`, in order
+    pub fn names(dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Find the sounds of pack `name` in `dir`
+    pub fn load(dir: &Path, name: &str) -> Result<SoundPack, String> {
+        let folder = dir.join(name);
+        if !folder.is_dir() {
+            return Err(format!("no sound pack `{}` in {}", name, dir.display()));
+        }
+        let sounds: Vec<(Cue, PathBuf)> = Cue::all()
+            .into_iter()
+            .filter_map(|cue| {
+                SOUND_EXTENSIONS
+                    .iter()
+                    .map(|ext| folder.join(format!("{}.{}", cue.name(), ext)))
+                    .find(|path| path.is_file())
+                    .map(|path| (cue, path))
+            })
+            .collect();
+        if sounds.is_empty() {
+            return Err(format!(
+                "sound pack `{}` has none of {}",
+                name,
+                Cue::all().map(|cue| cue.name()).join(", ")
+            ));
+        }
+        Ok(SoundPack {
+            name: name.to_string(),
+            sounds,
+        })
+    }
+
+    /// Every cue the pack has a sound for, with the sound's file
+    pub fn sounds(&self) -> impl Iterator<Item = (Cue, &Path)> {
+        self.sounds.iter().map(|(cue, path)| (*cue, path.as_path()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::{Card, Rank, Suit};
+    use crate::game::state::Pile;
+
+    #[test]
+    fn test_cues_sound_different() {
+        for (i, cue) in Cue::all().into_iter().enumerate() {
+            assert!(!cue.notes().is_empty(), "{}", cue.name());
+            for other in &Cue::all()[i + 1..] {
+                assert_ne!(cue.notes(), other.notes(), "{}", cue.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cue_for_events() {
+        let nine = Card::new(Suit::Spades, Rank::Nine, true);
+        let moved = GameEvent::CardMoved {
+            card: nine,
+            from: Pile::Tableau(1),
+            to: Pile::Tableau(0),
+        };
+        assert_eq!(Cue::for_events(&[moved]), Cue::Move);
+        assert_eq!(
+            Cue::for_events(&[GameEvent::StockDealt { count: 3 }]),
+            Cue::Deal
+        );
+        assert_eq!(Cue::for_events(&[GameEvent::WasteTurnedOver]), Cue::Deal);
+
+        let flipped = GameEvent::CardFlipped {
+            card: nine,
+            pile: Pile::Tableau(1),
+        };
+        assert_eq!(Cue::for_events(&[moved, flipped]), Cue::Flip);
+        assert_eq!(
+            Cue::for_events(&[moved, flipped, GameEvent::GameWon]),
+            Cue::Win
+        );
+    }
+
+    #[test]
+    fn test_fanfare_by_how_the_game_was_won() {
+        assert_eq!(Cue::fanfare(true, true), Cue::RecordWin);
+        assert_eq!(Cue::fanfare(true, false), Cue::RecordWin);
+        assert_eq!(Cue::fanfare(false, false), Cue::NoUndoWin);
+        assert_eq!(Cue::fanfare(false, true), Cue::Win);
+    }
+
+    #[test]
+    fn test_sound_pack_finds_a_file_per_cue() {
+        let dir =
+            std::env::temp_dir().join(format!("vibe-solitaire-test-{}-sounds", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let pack = dir.join("chimes");
+        fs::create_dir_all(&pack).unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        for name in ["move.ogg", "win.wav", "win.ogg", "readme.txt"] {
+            fs::write(pack.join(name), "").unwrap();
+        }
+        fs::write(dir.join("stray.wav"), "").unwrap();
+        assert_eq!(SoundPack::names(&dir), ["chimes", "empty"]);
+
+        let chimes = SoundPack::load(&dir, "chimes").unwrap();
+        assert_eq!(chimes.name, "chimes");
+        let sounds: Vec<(Cue, &Path)> = chimes.sounds().collect();
+        assert_eq!(
+            sounds,
+            [
+                (Cue::Move, pack.join("move.ogg").as_path()),
+                (Cue::Win, pack.join("win.wav").as_path()),
+            ]
+        );
+        assert!(SoundPack::load(&dir, "empty").is_err());
+        assert!(SoundPack::load(&dir, "missing").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
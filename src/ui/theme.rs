@@ -0,0 +1,139 @@
+use crate::game::settings::ThemeChoice;
+use crate::ui::TABLEAU_CARD_OFFSET;
+use crate::ui::theme_file::ThemeFile;
+use gpui::WindowAppearance;
+use std::cell::Cell;
+
+/// Look of the UI chrome (menus, dialogs, buttons and the status bar) and
+/// of the table the cards lie on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Dialog panels and the status bar strip
+    pub panel: u32,
+    pub panel_border: u32,
+    /// Buttons and text fields
+    pub control: u32,
+    pub control_border: u32,
+    pub text: u32,
+    /// Secondary text such as hints and captions
+    pub muted_text: u32,
+    /// Hovered and selected controls
+    pub accent: u32,
+    /// Dims everything behind a modal, as RGBA
+    pub backdrop: u32,
+    pub error: u32,
+    /// Text that calls for attention, like a new record
+    pub highlight: u32,
+    /// The table under the cards
+    pub felt: u32,
+    /// Pixels between the cards fanned down a tableau column
+    pub tableau_offset: f32,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            panel: 0x111827,
+            panel_border: 0x4B5563,
+            control: 0x1F2937,
+            control_border: 0x4B5563,
+            text: 0xFFFFFF,
+            muted_text: 0x9CA3AF,
+            accent: 0x3B82F6,
+            backdrop: 0x000000AA,
+            error: 0xF87171,
+            highlight: 0xFACC15,
+            felt: 0x0F5132,
+            tableau_offset: TABLEAU_CARD_OFFSET,
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            panel: 0xF9FAFB,
+            panel_border: 0xD1D5DB,
+            control: 0xFFFFFF,
+            control_border: 0x9CA3AF,
+            text: 0x111827,
+            muted_text: 0x4B5563,
+            accent: 0x2563EB,
+            backdrop: 0x00000066,
+            error: 0xDC2626,
+            highlight: 0xB45309,
+            felt: 0x0F5132,
+            tableau_offset: TABLEAU_CARD_OFFSET,
+        }
+    }
+
+    /// Accessibility theme: pure black and white with a bright accent
+    pub fn high_contrast() -> Self {
+        Theme {
+            panel: 0x000000,
+            panel_border: 0xFFFFFF,
+            control: 0x000000,
+            control_border: 0xFFFFFF,
+            text: 0xFFFFFF,
+            muted_text: 0xE5E7EB,
+            accent: 0xFFD400,
+            backdrop: 0x000000DD,
+            error: 0xFF8080,
+            highlight: 0xFFD400,
+            felt: 0x0F5132,
+            tableau_offset: TABLEAU_CARD_OFFSET,
+        }
+    }
+
+    /// Theme for the player's choice, asking the window when it follows the OS
+    pub fn resolve(choice: ThemeChoice, appearance: WindowAppearance) -> Self {
+        match choice {
+            ThemeChoice::Dark => Theme::dark(),
+            ThemeChoice::Light => Theme::light(),
+            ThemeChoice::HighContrast => Theme::high_contrast(),
+            ThemeChoice::System => match appearance {
+                WindowAppearance::Light | WindowAppearance::VibrantLight => Theme::light(),
+                WindowAppearance::Dark | WindowAppearance::VibrantDark => Theme::dark(),
+            },
+        }
+    }
+
+    /// A player's theme file laid over the built-in theme it is based on
+    pub fn from_file(file: &ThemeFile, appearance: WindowAppearance) -> Self {
+        let mut theme = Theme::resolve(file.base, appearance);
+        for (key, color) in &file.colors {
+            let field = match key.as_str() {
+                "panel" => &mut theme.panel,
+                "panel_border" => &mut theme.panel_border,
+                "control" => &mut theme.control,
+                "control_border" => &mut theme.control_border,
+                "text" => &mut theme.text,
+                "muted_text" => &mut theme.muted_text,
+                "accent" => &mut theme.accent,
+                "backdrop" => &mut theme.backdrop,
+                "error" => &mut theme.error,
+                "highlight" => &mut theme.highlight,
+                "felt" => &mut theme.felt,
+                _ => continue,
+            };
+            *field = *color;
+        }
+        if let Some(offset) = file.tableau_offset {
+            theme.tableau_offset = offset as f32;
+        }
+        theme
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Theme> = Cell::new(Theme::dark());
+}
+
+/// Theme the chrome is being drawn with. The render helpers read this so
+/// they need no context passed in.
+pub fn current() -> Theme {
+    CURRENT.with(Cell::get)
+}
+
+/// Switch the theme for everything drawn from here on; set once per frame
+pub fn set_current(theme: Theme) {
+    CURRENT.with(|current| current.set(theme));
+}
@@ -0,0 +1,242 @@
+use crate::game::settings::ThemeChoice;
+use crate::game::storage;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension of theme files
+pub const THEME_EXTENSION: &str = "toml";
+
+/// Colors a theme file may set. `backdrop` is RGBA, dimming the board
+/// behind dialogs; the rest are RGB.
+pub const COLOR_KEYS: [&str; 11] = [
+    "panel",
+    "panel_border",
+    "control",
+    "control_border",
+    "text",
+    "muted_text",
+    "accent",
+    "backdrop",
+    "error",
+    "highlight",
+    "felt",
+];
+
+/// A theme a player dropped into the themes folder. Anything it leaves out
+/// comes from the built-in theme it is based on.
+///
+/// ```text
+/// name = "Midnight"
+/// base = "dark"
+///
+/// [colors]
+/// felt = "#1E293B"
+/// accent = "#F59E0B"
+/// backdrop = "#000000CC"
+///
+/// [cards]
+/// art = "midnight-cards"
+///
+/// [spacing]
+/// tableau_offset = 24
+///
+/// [motion]
+/// reduce = true
+/// ```
+///
+/// `art` names a folder of card SVGs laid out like `assets/cards`, relative
+/// to the theme file; faces it lacks are drawn from the bundled artwork.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeFile {
+    /// File stem, which settings refer to the theme by
+    pub id: String,
+    /// Name shown in settings, defaulting to the id
+    pub name: String,
+    pub base: ThemeChoice,
+    /// Colors set by the file, keyed by one of `COLOR_KEYS`
+    pub colors: Vec<(String, u32)>,
+    pub card_art: Option<PathBuf>,
+    /// Pixels between the cards fanned down a tableau column
+    pub tableau_offset: Option<u32>,
+    /// Play without card animations, as if the player had asked for less
+    /// motion
+    pub reduce_motion: bool,
+}
+
+impl ThemeFile {
+    /// Folder players drop theme files into
+    pub fn default_dir() -> PathBuf {
+        storage::config_dir().join("themes")
+    }
+
+    /// Every theme file in `dir` by id, in name order, with the reason for
+    /// any that failed to load
+    pub fn load_all(dir: &Path) -> Vec<(String, Result<ThemeFile, String>)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut themes: Vec<(String, Result<ThemeFile, String>)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == THEME_EXTENSION))
+            .filter_map(|path| {
+                let id = path.file_stem()?.to_str()?.to_string();
+                Some((id, Self::load(&path)))
+            })
+            .collect();
+        themes.sort_by(|a, b| a.0.cmp(&b.0));
+        themes
+    }
+
+    /// Where the theme with `id` is kept in `dir`
+    pub fn path(dir: &Path, id: &str) -> PathBuf {
+        dir.join(format!("{}.{}", id, THEME_EXTENSION))
+    }
+
+    /// Load the theme with `id` from `dir`
+    pub fn load_id(dir: &Path, id: &str) -> Result<ThemeFile, String> {
+        Self::load(&Self::path(dir, id))
+    }
+
+    pub fn load(path: &Path) -> Result<ThemeFile, String> {
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("invalid theme file name {}", path.display()))?;
+        let mut theme = Self::from_source(id, storage::load(path)?)?;
+        // Card art is relative to the theme file
+        if let (Some(art), Some(dir)) = (&theme.card_art, path.parent()) {
+            theme.card_art = Some(dir.join(art));
+        }
+        Ok(theme)
+    }
+
+    /// Read a theme from the text of its file
+    pub fn parse(id: &str, text: &str) -> Result<ThemeFile, String> {
+        Self::from_source(id, storage::parse(text)?)
+    }
+
+    fn from_source(id: &str, source: ThemeSource) -> Result<ThemeFile, String> {
+        let mut colors = Vec::new();
+        for (key, text) in source.colors {
+            if !COLOR_KEYS.contains(&key.as_str()) {
+                return Err(format!("unknown color `{}`", key));
+            }
+            let color = parse_color(&text, key == "backdrop")
+                .ok_or_else(|| format!("invalid color for {}: {}", key, text))?;
+            colors.push((key, color));
+        }
+        if let Some(offset) = source.spacing.tableau_offset
+            && !(4..=60).contains(&offset)
+        {
+            return Err(format!("invalid tableau_offset {}", offset));
+        }
+        Ok(ThemeFile {
+            id: id.to_string(),
+            name: source.name.unwrap_or_else(|| id.to_string()),
+            base: source.base.unwrap_or(ThemeChoice::Dark),
+            colors,
+            card_art: source.cards.art,
+            tableau_offset: source.spacing.tableau_offset,
+            reduce_motion: source.motion.reduce,
+        })
+    }
+
+    /// Color the file sets for `key`, if any
+    pub fn color(&self, key: &str) -> Option<u32> {
+        self.colors
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, color)| *color)
+    }
+}
+
+/// Layout of a theme file, every part of which is optional
+#[derive(Deserialize)]
+struct ThemeSource {
+    name: Option<String>,
+    base: Option<ThemeChoice>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+    #[serde(default)]
+    cards: CardsSection,
+    #[serde(default)]
+    spacing: SpacingSection,
+    #[serde(default)]
+    motion: MotionSection,
+}
+
+#[derive(Default, Deserialize)]
+struct CardsSection {
+    art: Option<PathBuf>,
+}
+
+#[derive(Default, Deserialize)]
+struct SpacingSection {
+    tableau_offset: Option<u32>,
+}
+
+#[derive(Default, Deserialize)]
+struct MotionSection {
+    #[serde(default)]
+    reduce: bool,
+}
+
+/// Parse `#RRGGBB`, or `#RRGGBBAA` when `alpha` is set
+fn parse_color(text: &str, alpha: bool) -> Option<u32> {
+    let hex = text.strip_prefix('#')?;
+    let digits = if alpha { 8 } else { 6 };
+    if hex.len() != digits || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<ThemeFile, String> {
+        ThemeFile::parse("midnight", text)
+    }
+
+    #[test]
+    fn test_theme_file_parses() {
+        let theme = parse(
+            "name = \"Midnight\"\nbase = \"light\"\n\n[colors]\nfelt = \"#1E293B\"\nbackdrop = \"#000000CC\"\n\n[cards]\nart = \"cards\"\n\n[spacing]\ntableau_offset = 24\n\n[motion]\nreduce = true\n",
+        )
+        .unwrap();
+        assert_eq!(theme.name, "Midnight");
+        assert_eq!(theme.base, ThemeChoice::Light);
+        assert_eq!(theme.color("felt"), Some(0x1E293B));
+        assert_eq!(theme.color("backdrop"), Some(0x000000CC));
+        assert_eq!(theme.color("accent"), None);
+        assert_eq!(theme.card_art, Some(PathBuf::from("cards")));
+        assert_eq!(theme.tableau_offset, Some(24));
+        assert!(theme.reduce_motion);
+
+        // Everything is optional
+        let empty = parse("").unwrap();
+        assert_eq!(empty.name, "midnight");
+        assert_eq!(empty.base, ThemeChoice::Dark);
+        assert!(empty.colors.is_empty());
+        assert!(!empty.reduce_motion);
+    }
+
+    #[test]
+    fn test_theme_file_errors() {
+        for bad in [
+            "base = \"sepia\"",
+            "[colors]\nfelt = \"green\"",
+            "[colors]\nfelt = \"#1E293BFF\"",
+            "[colors]\nbackdrop = \"#000000\"",
+            "[colors]\nbackground = \"#000000\"",
+            "[spacing]\ntableau_offset = 500",
+            "[motion]\nreduce = 1",
+        ] {
+            assert!(parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
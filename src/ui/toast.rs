@@ -0,0 +1,119 @@
+use crate::game::clock::Clock;
+use std::time::Duration;
+
+/// How long a toast stays up
+pub const TOAST_DURATION: Duration = Duration::from_millis(3000);
+/// Time a toast takes to fade out at the end of `TOAST_DURATION`
+const TOAST_FADE: Duration = Duration::from_millis(400);
+/// Most toasts shown at once; the oldest make way for new ones
+pub const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    /// Something the player might like to know
+    Info,
+    /// A move or command that was refused, with the reason
+    Error,
+}
+
+/// A short message shown over the board for a few seconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub message: String,
+    /// Clock time the toast was shown at
+    shown: Duration,
+}
+
+impl Toast {
+    fn elapsed(&self, clock: &impl Clock) -> Duration {
+        clock.now().saturating_sub(self.shown)
+    }
+
+    /// 1 while the toast is up, falling to 0 as it fades out
+    pub fn opacity(&self, clock: &impl Clock) -> f32 {
+        let left = TOAST_DURATION.saturating_sub(self.elapsed(clock));
+        (left.as_secs_f32() / TOAST_FADE.as_secs_f32()).min(1.0)
+    }
+
+    pub fn is_finished(&self, clock: &impl Clock) -> bool {
+        self.elapsed(clock) >= TOAST_DURATION
+    }
+}
+
+/// Toasts showing, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    /// Show `message`. The same message again restarts its toast rather
+    /// than stacking a copy, so repeating a refused move doesn't pile up.
+    pub fn push(&mut self, kind: ToastKind, message: impl Into<String>, clock: &impl Clock) {
+        let message = message.into();
+        self.toasts
+            .retain(|toast| toast.kind != kind || toast.message != message);
+        self.toasts.push(Toast {
+            kind,
+            message,
+            shown: clock.now(),
+        });
+        let excess = self.toasts.len().saturating_sub(MAX_TOASTS);
+        self.toasts.drain(..excess);
+    }
+
+    /// Drop the toasts whose time is up
+    pub fn expire(&mut self, clock: &impl Clock) {
+        self.toasts.retain(|toast| !toast.is_finished(clock));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::FrameClock;
+
+    #[test]
+    fn test_toasts_expire_and_fade() {
+        let mut clock = FrameClock::new(10);
+        let mut toasts = Toasts::default();
+        toasts.push(ToastKind::Error, "Invalid move", &clock);
+        assert_eq!(toasts.iter().next().unwrap().opacity(&clock), 1.0);
+
+        // 2.8 seconds in, half way through fading out
+        clock = FrameClock::at_frame(10, 28);
+        let toast = toasts.iter().next().unwrap();
+        assert!((toast.opacity(&clock) - 0.5).abs() < 1e-3);
+        toasts.expire(&clock);
+        assert!(!toasts.is_empty());
+
+        clock.advance();
+        clock.advance();
+        toasts.expire(&clock);
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn test_toasts_restart_repeats_and_drop_the_oldest() {
+        let clock = FrameClock::new(10);
+        let mut toasts = Toasts::default();
+        for message in ["one", "two", "one", "three", "four"] {
+            toasts.push(ToastKind::Info, message, &clock);
+        }
+        let messages: Vec<&str> = toasts.iter().map(|toast| toast.message.as_str()).collect();
+        assert_eq!(messages, ["one", "three", "four"]);
+
+        // Errors and information are kept apart
+        toasts.push(ToastKind::Error, "four", &clock);
+        assert_eq!(toasts.iter().count(), MAX_TOASTS);
+    }
+}
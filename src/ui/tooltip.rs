@@ -0,0 +1,43 @@
+use crate::ui::theme::{self, Theme};
+use gpui::{
+    AnyView, App, Context, IntoElement, ParentElement, Render, SharedString, Styled, Window, div,
+    rgb,
+};
+
+/// A line of text shown by an element while the mouse rests on it
+pub struct Tooltip {
+    text: SharedString,
+    /// Palette when the tooltip was asked for, as it draws outside the frame
+    /// that set the current one
+    theme: Theme,
+}
+
+impl Render for Tooltip {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .bg(rgb(self.theme.panel))
+            .border_1()
+            .border_color(rgb(self.theme.panel_border))
+            .rounded_md()
+            .shadow_md()
+            .text_xs()
+            .text_color(rgb(self.theme.text))
+            .child(self.text.clone())
+    }
+}
+
+/// Builder for an element's `.tooltip`, showing `text`:
+/// `ui::render_button("undo", "Undo").tooltip(tooltip::text("Undo the last move"))`
+pub fn text(text: impl Into<SharedString>) -> impl Fn(&mut Window, &mut App) -> AnyView + 'static {
+    let text = text.into();
+    let theme = theme::current();
+    move |_window, cx| {
+        cx.new(|_cx| Tooltip {
+            text: text.clone(),
+            theme,
+        })
+        .into()
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often watched files are looked at
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a file must be left alone after changing before it is read
+/// again, so an editor saving in several writes causes one reload
+pub const SETTLE_TIME: Duration = Duration::from_millis(300);
+
+/// What a file looked like when last looked at: `None` when it was missing
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Files the app reads its look and settings from, watched for edits made
+/// while it runs. There is no change notification to wait on, so the files
+/// are polled, and a change is only reported once it has settled.
+#[derive(Debug, Default)]
+pub struct FileWatcher {
+    files: Vec<Watched>,
+}
+
+#[derive(Debug)]
+struct Watched {
+    path: PathBuf,
+    stamp: Stamp,
+    /// When the file was last seen to change, until that is reported
+    changed_at: Option<Instant>,
+}
+
+impl FileWatcher {
+    /// Watch `path`, as it is now, if it isn't already watched
+    pub fn watch(&mut self, path: PathBuf) {
+        if self.files.iter().any(|file| file.path == path) {
+            return;
+        }
+        self.files.push(Watched {
+            stamp: stamp(&path),
+            path,
+            changed_at: None,
+        });
+    }
+
+    /// Stop watching `path`
+    pub fn unwatch(&mut self, path: &Path) {
+        self.files.retain(|file| file.path != path);
+    }
+
+    /// Look at every watched file at `now`, returning those whose changes
+    /// have settled since the last poll
+    pub fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut settled = Vec::new();
+        for file in &mut self.files {
+            let stamp = stamp(&file.path);
+            if stamp != file.stamp {
+                file.stamp = stamp;
+                file.changed_at = Some(now);
+            } else if file
+                .changed_at
+                .is_some_and(|changed_at| now.duration_since(changed_at) >= SETTLE_TIME)
+            {
+                file.changed_at = None;
+                settled.push(file.path.clone());
+            }
+        }
+        settled
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_is_reported_once_settled() {
+        let dir =
+            std::env::temp_dir().join(format!("vibe-solitaire-test-{}-watch", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("theme.toml");
+        fs::write(&path, "name = \"Old\"\n").unwrap();
+
+        let mut watcher = FileWatcher::default();
+        watcher.watch(path.clone());
+        watcher.watch(path.clone());
+        let start = Instant::now();
+        assert!(watcher.poll(start).is_empty());
+
+        fs::write(&path, "name = \"Midnight\"\n").unwrap();
+        assert!(watcher.poll(start).is_empty());
+        // Still being written
+        fs::write(&path, "name = \"Midnight\"\nbase = \"dark\"\n").unwrap();
+        assert!(watcher.poll(start + SETTLE_TIME).is_empty());
+        assert!(watcher.poll(start + SETTLE_TIME + POLL_INTERVAL).is_empty());
+        assert_eq!(watcher.poll(start + SETTLE_TIME * 2), vec![path.clone()]);
+        assert!(watcher.poll(start + SETTLE_TIME * 3).is_empty());
+
+        // A file being removed is a change too
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll(start + SETTLE_TIME * 3).is_empty());
+        assert_eq!(watcher.poll(start + SETTLE_TIME * 4), vec![path.clone()]);
+
+        watcher.unwatch(&path);
+        fs::write(&path, "name = \"Back\"\n").unwrap();
+        assert!(watcher.poll(start + SETTLE_TIME * 4).is_empty());
+        assert!(watcher.poll(start + SETTLE_TIME * 5).is_empty());
+    }
+}